@@ -52,6 +52,7 @@ impl StringBuilder {
 fn main() {
     generate_operator_dictionary();
     generate_math_variant_dictionary();
+    generate_bidi_mirroring_dictionary();
 }
 
 #[derive(Hash, Deserialize, Eq, PartialEq)]
@@ -155,10 +156,12 @@ fn operator_def_to_operator(operator_def: &OperatorDef) -> String {
     );
 
     format!("Operator {{\
+        priority: {},
         lspace: {},
         rspace: {},
         properties: {}
     }}",
+        operator_def.priority,
         space_level_int_to_enum(operator_def.lspace),
         space_level_int_to_enum(operator_def.rspace),
         property
@@ -258,4 +261,36 @@ fn generate_math_variant_dictionary() {
     }
     variant_dictionary.build(&mut file).unwrap();
     write!(&mut file, ";\n").unwrap();
-}
\ No newline at end of file
+}
+
+#[derive(Deserialize)]
+struct Mirroring {
+    mirror: Option<u32>,
+    #[allow(dead_code)]
+    name: String
+}
+
+fn generate_bidi_mirroring_dictionary() {
+    let dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let file = Path::new(&dir).join("blobs").join("bidi_mirroring.json");
+    let mut mirroring_dict = String::new();
+    File::open(file).unwrap().read_to_string(&mut mirroring_dict).expect("Cannot read bidi mirroring dictionary");
+    let deserialize: HashMap<String, Mirroring> = serde_json::from_str(&mirroring_dict).unwrap();
+
+    let path = Path::new(&env::var("OUT_DIR").unwrap()).join("codegen_bidi_mirroring_dictionary.rs");
+    let mut file = BufWriter::new(File::create(&path).unwrap());
+    write!(&mut file, "static BIDI_MIRRORING_DICTIONARY: phf::Map<char, Option<char>> = ").unwrap();
+
+    let mut mirroring_dictionary = phf_codegen::Map::new();
+    for (unicode, mirroring) in deserialize {
+        let unicode = std::char::from_u32(unicode.parse::<u32>().unwrap()).unwrap();
+        let mirror = match mirroring.mirror {
+            Some(mirror) => format!("Some({:?})", std::char::from_u32(mirror).unwrap()),
+            None => String::from("None")
+        };
+
+        mirroring_dictionary.entry(unicode, &mirror);
+    }
+    mirroring_dictionary.build(&mut file).unwrap();
+    write!(&mut file, ";\n").unwrap();
+}
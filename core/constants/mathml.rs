@@ -108,6 +108,7 @@ impl OperatorProperty {
 
 #[derive(Clone)]
 pub struct Operator {
+    priority: u32,
     lspace: SpaceLevel,
     rspace: SpaceLevel,
     properties: OperatorProperty
@@ -117,12 +118,17 @@ impl Operator {
 
     pub fn default() -> Operator {
         Operator {
+            priority: 0,
             lspace: SpaceLevel::NoSpace,
             rspace: SpaceLevel::NoSpace,
             properties: OperatorProperty::default()
         }
     }
 
+    pub fn priority(&self) -> u32 {
+        self.priority
+    }
+
     pub fn lspace(&self) -> &SpaceLevel {
         &self.lspace
     }
@@ -229,4 +235,14 @@ mod test {
         let attrs = dictionary.operator_attrs(";", &OperatorForm::Infix).unwrap();
         assert_eq!(attrs.properties().linebreak_style(), "after");
     }
+
+    #[test]
+    fn is_operator_priority_parsed() {
+        let dictionary = OperatorDictionary::new();
+
+        assert_eq!(dictionary.operator_attrs("+", &OperatorForm::Infix).unwrap().priority(), 275);
+        assert_eq!(dictionary.operator_attrs("=", &OperatorForm::Infix).unwrap().priority(), 260);
+        assert_eq!(dictionary.operator_attrs("(", &OperatorForm::Prefix).unwrap().priority(), 20);
+        assert_eq!(dictionary.operator_attrs("!", &OperatorForm::Postfix).unwrap().priority(), 810);
+    }
 }
\ No newline at end of file
@@ -50,6 +50,33 @@ impl MathVariantsDictionary {
     }
 }
 
+/// Characters with Bidi_Mirrored property, as listed in BidiMirroring.txt of unicode
+/// character database.
+pub struct BidiMirroringDictionary {
+    dictionary: &'static phf::Map<char, Option<char>>
+}
+
+include!(concat!(env!("OUT_DIR"), "/codegen_bidi_mirroring_dictionary.rs"));
+
+impl BidiMirroringDictionary {
+    pub fn new() -> BidiMirroringDictionary {
+        BidiMirroringDictionary {
+            dictionary: &BIDI_MIRRORING_DICTIONARY
+        }
+    }
+
+    /// Returns true if the character needs to be mirrored in right to left text.
+    pub fn is_mirrored(&self, c: &char) -> bool {
+        self.dictionary.contains_key(c)
+    }
+
+    /// Character whose glyph is the mirror image of given character. Returns `None` if such
+    /// a character is not present, in which case mirrored glyph has to come from the font.
+    pub fn mirrored_char(&self, c: &char) -> Option<char> {
+        self.dictionary.get(c).and_then(|mirror| *mirror)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -70,6 +97,27 @@ mod test {
         assert_eq!(variant, None);
     }
 
+    #[test]
+    fn if_variant_is_in_letterlike_symbols() {
+        let variants = MathVariantsDictionary::new();
+
+        assert_eq!(variants.variant_for_char(&'R', &MathVariant::DoubleStruck), Some('ℝ'));
+        assert_eq!(variants.variant_for_char(&'C', &MathVariant::Fraktur), Some('ℭ'));
+        assert_eq!(variants.variant_for_char(&'h', &MathVariant::Italic), Some('ℎ'));
+        assert_eq!(variants.variant_for_char(&'e', &MathVariant::Script), Some('ℯ'));
+    }
+
+    #[test]
+    fn if_arabic_variant_is_present() {
+        let variants = MathVariantsDictionary::new();
+
+        assert_eq!(variants.variant_for_char(&'\u{628}', &MathVariant::Initial), Some('\u{1EE21}'));
+        assert_eq!(variants.variant_for_char(&'\u{62C}', &MathVariant::Tailed), Some('\u{1EE42}'));
+        assert_eq!(variants.variant_for_char(&'\u{627}', &MathVariant::Looped), Some('\u{1EE80}'));
+        assert_eq!(variants.variant_for_char(&'\u{628}', &MathVariant::Stretched), Some('\u{1EE61}'));
+        assert_eq!(variants.variant_for_char(&'\u{628}', &MathVariant::DoubleStruck), Some('\u{1EEA1}'));
+    }
+
     #[test]
     fn if_no_variants_are_present() {
         let variants = MathVariantsDictionary::new();
@@ -77,4 +125,17 @@ mod test {
 
         assert_eq!(variant, None);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn if_mirrored_char_is_present() {
+        let mirroring = BidiMirroringDictionary::new();
+
+        assert_eq!(mirroring.mirrored_char(&'('), Some(')'));
+        assert_eq!(mirroring.mirrored_char(&'⟩'), Some('⟨'));
+        assert_eq!(mirroring.mirrored_char(&'∈'), Some('∋'));
+        assert_eq!(mirroring.mirrored_char(&'≤'), Some('≥'));
+        assert!(mirroring.is_mirrored(&'∑'));
+        assert_eq!(mirroring.mirrored_char(&'∑'), None);
+        assert!(!mirroring.is_mirrored(&'+'));
+    }
+}
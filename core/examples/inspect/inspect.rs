@@ -14,125 +14,62 @@
  * limitations under the License.
 */
 
-/*
-pub extern crate cairo;
-pub extern crate gtk;
-pub extern crate akriti_core;
-pub extern crate akriti_measure;
-pub extern crate freetype_sys;
-
-mod canvas;
-mod text_ruler;
-mod math_ruler;
-mod platform;
-
-use gtk::prelude::*;
-use gtk::DrawingArea;
-
-use cairo::{Context, FontSlant, FontWeight};
-use platform::GTKPlatform;
-use canvas::CairoCanvas;
-use akriti_core::paint::{Point};
-use akriti_core::platform::Context as AkritiContext;
-use akriti_core::layout::{MiElement, MrowElement, MnElement, MtextElement};
-use akriti_core::props::{MathVariant, Directionality, Color};
-use akriti_core::layout::Element;
-
-fn main() {
-    if gtk::init().is_err() {
-        println!("Failed to initialize GTK.");
-        return;
-    }
-
-    let platform = GTKPlatform::new();
-
-    let context = AkritiContext::new(Box::new(platform), 64.);
-
-    let mut mrow = MrowElement::new(Directionality::LTR, Color::transparent());
-    mrow.add_element(
-        Box::new(MiElement::new(
-            String::from("i"),
-            MathVariant::Normal,
-            64.,
-            Directionality::LTR,
-            Color::RGB(0, 0, 0),
-            Color::RGB(0, 255, 0),
-        ))
-    );
-    mrow.add_element(
-        Box::new(MiElement::new(
-            String::from(" + "),
-            MathVariant::Normal,
-            64.,
-            Directionality::LTR,
-            Color::RGB(0, 0, 0),
-            Color::RGB(0, 255, 0),
-        ))
-    );
-    mrow.add_element(
-        Box::new(MnElement::new(
-            String::from("2"),
-            MathVariant::Normal,
-            64.,
-            Directionality::LTR,
-            Color::RGB(0, 0, 0),
-            Color::RGB(0, 255, 0),
-        ))
-    );
-    mrow.add_element(
-        Box::new(MiElement::new(
-            String::from("j"),
-            MathVariant::Normal,
-            64.,
-            Directionality::LTR,
-            Color::RGB(0, 0, 0),
-            Color::RGB(0, 255, 0),
-        ))
-    );
-    mrow.add_element(
-        Box::new(MtextElement::new(
-            String::from("hello"),
-            MathVariant::Normal,
-            64.,
-            Directionality::LTR,
-            Color::RGB(0, 0, 0),
-            Color::RGB(0, 255, 0),
-        ))
-    );
 
-    drawable(500, 500, move |_, cr| {
-        cr.select_font_face("STIX Two Math", FontSlant::Normal, FontWeight::Normal);
+//! Renders a sample formula to PNG using the raster backend.
+//!
+//! Usage: inspect [FONT] [OUTPUT]
 
-        let drawing = mrow.layout(&context);
+extern crate akriti_core;
 
-        let canvas = CairoCanvas::new(cr);
-        cr.scale(1., 1.);
+use std::env;
+use std::path::Path;
 
-        drawing.draw(&canvas, &Point::new(0., 0.));
+use akriti_core::backend::raster::RasterPlatform;
+use akriti_core::elements::*;
+use akriti_core::paint::Point;
+use akriti_core::platform::Context;
 
-        Inhibit(false)
-    });
-
-    gtk::main();
-}
-
-pub fn drawable<F>(width: i32, height: i32, draw_fn: F)
-        where F: Fn(&DrawingArea, &Context) -> Inhibit + 'static {
-    let window = gtk::Window::new(gtk::WindowType::Toplevel);
-    let drawing_area = Box::new(DrawingArea::new)();
-
-    drawing_area.connect_draw(draw_fn);
-
-    window.set_default_size(width, height);
-
-    window.connect_delete_event(|_, _| {
-        gtk::main_quit();
-        Inhibit(false)
-    });
-    window.add(&drawing_area);
-    window.set_keep_above(true);
-    window.show_all();
+fn main() {
+    let mut args = env::args().skip(1);
+    let font = args.next().unwrap_or_else(||
+        format!("{}/src/test/assets/STIX2Math.otf", env!("CARGO_MANIFEST_DIR")));
+    let output = args.next().unwrap_or_else(|| String::from("inspect.png"));
+
+    let platform = RasterPlatform::from_file(Path::new(&font))
+        .unwrap_or_else(|err| panic!("Cannot load {}: {}", font, err));
+    let context = Context::new(Box::new(platform), 64.);
+
+    let mut radicand = Mrow::new();
+    radicand.with_child(Box::new(Msup::new(Box::new(Mi::new(String::from("b"))),
+                                           Box::new(Mn::new(String::from("2"))))))
+        .with_child(Box::new(Mo::new(String::from("-"))))
+        .with_child(Box::new(Mn::new(String::from("4"))))
+        .with_child(Box::new(Mi::new(String::from("a"))))
+        .with_child(Box::new(Mi::new(String::from("c"))));
+
+    let mut num = Mrow::new();
+    num.with_child(Box::new(Mo::new(String::from("-"))))
+        .with_child(Box::new(Mi::new(String::from("b"))))
+        .with_child(Box::new(Mo::new(String::from("±"))))
+        .with_child(Box::new(Msqrt::new(Box::new(radicand))));
+
+    let mut den = Mrow::new();
+    den.with_child(Box::new(Mn::new(String::from("2"))))
+        .with_child(Box::new(Mi::new(String::from("a"))));
+
+    let mut formula = Mrow::new();
+    formula.with_child(Box::new(Mi::new(String::from("x"))))
+        .with_child(Box::new(Mo::new(String::from("="))))
+        .with_child(Box::new(Mfrac::new(Box::new(num), Box::new(den))));
+
+    let layout = formula.layout(&context, &Family::new(), &InheritedProps::default(), &None);
+    let drawable = layout.layout(&context);
+
+    let platform = context.platform().as_any().downcast_ref::<RasterPlatform>().unwrap();
+    let canvas = platform.new_canvas(drawable.bounding_box().width(),
+                                     drawable.bounding_box().height());
+    drawable.draw(&canvas, &Point::new(0., 0.));
+
+    canvas.write_png(Path::new(&output))
+        .unwrap_or_else(|err| panic!("Cannot write {}: {}", output, err));
 }
-*/
-
-fn main() {}
\ No newline at end of file
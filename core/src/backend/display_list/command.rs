@@ -0,0 +1,94 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use ::paint::{Point, Rect, FontSynthesis, TypeFace, GlyphRun, Canvas};
+use ::draw::BoundingBox;
+use ::props::{Directionality, Color};
+
+/// Single recorded call of `Canvas`, with the same arguments
+#[derive(Debug, Clone, PartialEq)]
+pub enum DrawCommand {
+    Text {
+        top_left: Point,
+        bound: BoundingBox,
+        text: String,
+        color: Color,
+        size: f32,
+        dir: Directionality,
+        typeface: TypeFace,
+        synthesis: FontSynthesis,
+    },
+    Glyph {
+        top_left: Point,
+        bound: BoundingBox,
+        glyph_index: u32,
+        color: Color,
+        size: f32,
+        dir: Directionality,
+        typeface: TypeFace,
+        synthesis: FontSynthesis,
+    },
+    GlyphRun {
+        top_left: Point,
+        bound: BoundingBox,
+        run: GlyphRun,
+        color: Color,
+        synthesis: FontSynthesis,
+    },
+    Rect {
+        top_left: Point,
+        rect: Rect,
+        color: Color,
+    },
+    RectOutline {
+        top_left: Point,
+        rect: Rect,
+        color: Color,
+        stroke_width: f32,
+    },
+    Line {
+        start: Point,
+        end: Point,
+        color: Color,
+        stroke_width: f32,
+    },
+}
+
+impl DrawCommand {
+    /// Issues the recorded call on `canvas`, with all positions moved by `pen_pos`
+    pub fn draw(&self, canvas: &Canvas, pen_pos: &Point) {
+        let offset = |point: &Point| Point::new(point.x() + pen_pos.x(), point.y() + pen_pos.y());
+
+        match *self {
+            DrawCommand::Text { ref top_left, ref bound, ref text, ref color, size, ref dir,
+                ref typeface, ref synthesis } =>
+                canvas.draw_text(&offset(top_left), bound, text, color, size, dir, typeface,
+                                 synthesis),
+            DrawCommand::Glyph { ref top_left, ref bound, glyph_index, ref color, size, ref dir,
+                ref typeface, ref synthesis } =>
+                canvas.draw_glyph(&offset(top_left), bound, glyph_index, color, size, dir,
+                                  typeface, synthesis),
+            DrawCommand::GlyphRun { ref top_left, ref bound, ref run, ref color, ref synthesis } =>
+                canvas.draw_glyph_run(&offset(top_left), bound, run, color, synthesis),
+            DrawCommand::Rect { ref top_left, ref rect, ref color } =>
+                canvas.draw_rect(&offset(top_left), rect, color),
+            DrawCommand::RectOutline { ref top_left, ref rect, ref color, stroke_width } =>
+                canvas.draw_rect_outline(&offset(top_left), rect, color, stroke_width),
+            DrawCommand::Line { ref start, ref end, ref color, stroke_width } =>
+                canvas.draw_line(&offset(start), &offset(end), color, stroke_width),
+        }
+    }
+}
@@ -0,0 +1,390 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::error::Error;
+use std::fmt;
+use std::str;
+
+use super::DrawCommand;
+use ::paint::{Point, Rect, FontSynthesis, TypeFace, GlyphRun, ShapedGlyph, Ink};
+use ::draw::BoundingBox;
+use ::props::{Directionality, Color};
+
+pub const MAGIC: &[u8; 4] = b"AKDL";
+pub const VERSION: u8 = 4;
+
+const TEXT: u8 = 0;
+const GLYPH: u8 = 1;
+const RECT: u8 = 2;
+const RECT_OUTLINE: u8 = 3;
+const LINE: u8 = 4;
+const GLYPH_RUN: u8 = 5;
+
+const SYNTHESIS_BOLD: u8 = 1;
+const SYNTHESIS_ITALIC: u8 = 2;
+const SYNTHESIS_MIRROR: u8 = 4;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DisplayListError {
+    Truncated,
+    BadMagic,
+    UnsupportedVersion(u8),
+    Malformed(&'static str),
+}
+
+impl fmt::Display for DisplayListError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DisplayListError::Truncated => write!(f, "display list is truncated"),
+            DisplayListError::BadMagic => write!(f, "data is not a display list"),
+            DisplayListError::UnsupportedVersion(version) =>
+                write!(f, "unsupported display list version {}", version),
+            DisplayListError::Malformed(what) => write!(f, "malformed {}", what),
+        }
+    }
+}
+
+impl Error for DisplayListError {
+    fn description(&self) -> &str {
+        "Cannot decode display list"
+    }
+}
+
+/// Writes values in little endian byte order
+pub struct Encoder {
+    bytes: Vec<u8>,
+}
+
+impl Encoder {
+    pub fn new() -> Encoder {
+        Encoder { bytes: Vec::new() }
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    pub fn put_bytes(&mut self, bytes: &[u8]) {
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    pub fn put_u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    pub fn put_u32(&mut self, value: u32) {
+        self.put_bytes(&[value as u8, (value >> 8) as u8, (value >> 16) as u8, (value >> 24) as u8]);
+    }
+
+    pub fn put_f32(&mut self, value: f32) {
+        self.put_u32(value.to_bits());
+    }
+
+    pub fn put_bounding_box(&mut self, bounding_box: &BoundingBox) {
+        self.put_rect(bounding_box.rect());
+        self.put_f32(bounding_box.baseline());
+        self.put_f32(bounding_box.axis());
+        match bounding_box.ink() {
+            Some(ink) => {
+                self.put_u8(1);
+                self.put_f32(ink.left());
+                self.put_f32(ink.right());
+                self.put_f32(ink.ascent());
+                self.put_f32(ink.descent());
+            },
+            None => self.put_u8(0),
+        }
+    }
+
+    pub fn put_command(&mut self, command: &DrawCommand) {
+        match *command {
+            DrawCommand::Text { ref top_left, ref bound, ref text, ref color, size, ref dir,
+                ref typeface, ref synthesis } => {
+                self.put_u8(TEXT);
+                self.put_point(top_left);
+                self.put_bounding_box(bound);
+                self.put_str(text);
+                self.put_font(color, size, dir, typeface, synthesis);
+            },
+            DrawCommand::Glyph { ref top_left, ref bound, glyph_index, ref color, size, ref dir,
+                ref typeface, ref synthesis } => {
+                self.put_u8(GLYPH);
+                self.put_point(top_left);
+                self.put_bounding_box(bound);
+                self.put_u32(glyph_index);
+                self.put_font(color, size, dir, typeface, synthesis);
+            },
+            DrawCommand::GlyphRun { ref top_left, ref bound, ref run, ref color,
+                ref synthesis } => {
+                self.put_u8(GLYPH_RUN);
+                self.put_point(top_left);
+                self.put_bounding_box(bound);
+                self.put_str(run.text());
+                self.put_u32(run.glyphs().len() as u32);
+                for glyph in run.glyphs() {
+                    self.put_u32(glyph.glyph_index());
+                    self.put_f32(glyph.advance());
+                    self.put_f32(glyph.x_offset());
+                    self.put_f32(glyph.y_offset());
+                    self.put_u32(glyph.cluster() as u32);
+                }
+                self.put_font(color, run.size(), run.dir(), run.typeface(), synthesis);
+            },
+            DrawCommand::Rect { ref top_left, ref rect, ref color } => {
+                self.put_u8(RECT);
+                self.put_point(top_left);
+                self.put_rect(rect);
+                self.put_color(color);
+            },
+            DrawCommand::RectOutline { ref top_left, ref rect, ref color, stroke_width } => {
+                self.put_u8(RECT_OUTLINE);
+                self.put_point(top_left);
+                self.put_rect(rect);
+                self.put_color(color);
+                self.put_f32(stroke_width);
+            },
+            DrawCommand::Line { ref start, ref end, ref color, stroke_width } => {
+                self.put_u8(LINE);
+                self.put_point(start);
+                self.put_point(end);
+                self.put_color(color);
+                self.put_f32(stroke_width);
+            },
+        }
+    }
+
+    fn put_str(&mut self, text: &str) {
+        self.put_u32(text.len() as u32);
+        self.put_bytes(text.as_bytes());
+    }
+
+    fn put_point(&mut self, point: &Point) {
+        self.put_f32(point.x());
+        self.put_f32(point.y());
+    }
+
+    fn put_rect(&mut self, rect: &Rect) {
+        self.put_f32(rect.width());
+        self.put_f32(rect.height());
+    }
+
+    /// Colors keep their variant, so that decoded commands compare equal to recorded ones
+    fn put_color(&mut self, color: &Color) {
+        match *color {
+            Color::RGB(r, g, b) => self.put_bytes(&[0, 255, r, g, b]),
+            Color::ARGB(a, r, g, b) => self.put_bytes(&[1, a, r, g, b]),
+        }
+    }
+
+    fn put_font(&mut self, color: &Color, size: f32, dir: &Directionality, typeface: &TypeFace,
+                synthesis: &FontSynthesis) {
+        self.put_color(color);
+        self.put_f32(size);
+        self.put_u8(match *dir {
+            Directionality::LTR => 0,
+            Directionality::RTL => 1,
+        });
+        self.put_str(typeface.font_family());
+        self.put_str(typeface.font_style());
+
+        let mut flags = 0;
+        if synthesis.bold() {
+            flags |= SYNTHESIS_BOLD;
+        }
+        if synthesis.italic() {
+            flags |= SYNTHESIS_ITALIC;
+        }
+        if synthesis.mirror() {
+            flags |= SYNTHESIS_MIRROR;
+        }
+        self.put_u8(flags);
+    }
+}
+
+/// Reads values written by `Encoder`
+pub struct Decoder<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(data: &'a [u8]) -> Decoder<'a> {
+        Decoder { data, pos: 0 }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    pub fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], DisplayListError> {
+        let end = self.pos.checked_add(len).ok_or(DisplayListError::Truncated)?;
+        if end > self.data.len() {
+            return Err(DisplayListError::Truncated);
+        }
+
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, DisplayListError> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32, DisplayListError> {
+        let bytes = self.read_bytes(4)?;
+        Ok(bytes[0] as u32 | (bytes[1] as u32) << 8 | (bytes[2] as u32) << 16 |
+            (bytes[3] as u32) << 24)
+    }
+
+    pub fn read_f32(&mut self) -> Result<f32, DisplayListError> {
+        let value = f32::from_bits(self.read_u32()?);
+        if value.is_finite() {
+            Ok(value)
+        } else {
+            Err(DisplayListError::Malformed("number"))
+        }
+    }
+
+    pub fn read_bounding_box(&mut self) -> Result<BoundingBox, DisplayListError> {
+        let rect = self.read_rect()?;
+        let baseline = self.read_f32()?;
+        let axis = self.read_f32()?;
+        let ink = match self.read_u8()? {
+            0 => None,
+            1 => Some(Ink::new(self.read_f32()?, self.read_f32()?, self.read_f32()?,
+                               self.read_f32()?)),
+            _ => return Err(DisplayListError::Malformed("ink")),
+        };
+        Ok(BoundingBox::new(rect, baseline, axis).with_ink(ink))
+    }
+
+    pub fn read_command(&mut self) -> Result<DrawCommand, DisplayListError> {
+        match self.read_u8()? {
+            TEXT => {
+                let top_left = self.read_point()?;
+                let bound = self.read_bounding_box()?;
+                let text = self.read_string()?;
+                let (color, size, dir, typeface, synthesis) = self.read_font()?;
+                Ok(DrawCommand::Text { top_left, bound, text, color, size, dir, typeface,
+                    synthesis })
+            },
+            GLYPH => {
+                let top_left = self.read_point()?;
+                let bound = self.read_bounding_box()?;
+                let glyph_index = self.read_u32()?;
+                let (color, size, dir, typeface, synthesis) = self.read_font()?;
+                Ok(DrawCommand::Glyph { top_left, bound, glyph_index, color, size, dir, typeface,
+                    synthesis })
+            },
+            GLYPH_RUN => {
+                let top_left = self.read_point()?;
+                let bound = self.read_bounding_box()?;
+                let text = self.read_string()?;
+                let count = self.read_u32()? as usize;
+                let mut glyphs = Vec::with_capacity(count.min(self.data.len()));
+                for _ in 0..count {
+                    let glyph_index = self.read_u32()?;
+                    let advance = self.read_f32()?;
+                    let x_offset = self.read_f32()?;
+                    let y_offset = self.read_f32()?;
+                    let cluster = self.read_u32()? as usize;
+                    if cluster > text.len() {
+                        return Err(DisplayListError::Malformed("glyph cluster"));
+                    }
+                    glyphs.push(ShapedGlyph::new(glyph_index, advance, x_offset, y_offset,
+                                                 cluster));
+                }
+                let (color, size, dir, typeface, synthesis) = self.read_font()?;
+                Ok(DrawCommand::GlyphRun {
+                    top_left,
+                    bound,
+                    run: GlyphRun::new(text, typeface, size, dir, glyphs),
+                    color,
+                    synthesis,
+                })
+            },
+            RECT => Ok(DrawCommand::Rect {
+                top_left: self.read_point()?,
+                rect: self.read_rect()?,
+                color: self.read_color()?,
+            }),
+            RECT_OUTLINE => Ok(DrawCommand::RectOutline {
+                top_left: self.read_point()?,
+                rect: self.read_rect()?,
+                color: self.read_color()?,
+                stroke_width: self.read_f32()?,
+            }),
+            LINE => Ok(DrawCommand::Line {
+                start: self.read_point()?,
+                end: self.read_point()?,
+                color: self.read_color()?,
+                stroke_width: self.read_f32()?,
+            }),
+            _ => Err(DisplayListError::Malformed("command")),
+        }
+    }
+
+    fn read_string(&mut self) -> Result<String, DisplayListError> {
+        let len = self.read_u32()? as usize;
+        str::from_utf8(self.read_bytes(len)?)
+            .map(|text| text.to_string())
+            .map_err(|_| DisplayListError::Malformed("text"))
+    }
+
+    fn read_point(&mut self) -> Result<Point, DisplayListError> {
+        let x = self.read_f32()?;
+        let y = self.read_f32()?;
+        Ok(Point::new(x, y))
+    }
+
+    fn read_rect(&mut self) -> Result<Rect, DisplayListError> {
+        let width = self.read_f32()?;
+        let height = self.read_f32()?;
+        Ok(Rect::new(width, height))
+    }
+
+    fn read_color(&mut self) -> Result<Color, DisplayListError> {
+        let bytes = self.read_bytes(5)?;
+        match bytes[0] {
+            0 => Ok(Color::RGB(bytes[2], bytes[3], bytes[4])),
+            1 => Ok(Color::ARGB(bytes[1], bytes[2], bytes[3], bytes[4])),
+            _ => Err(DisplayListError::Malformed("color")),
+        }
+    }
+
+    fn read_font(&mut self) -> Result<(Color, f32, Directionality, TypeFace, FontSynthesis),
+        DisplayListError> {
+        let color = self.read_color()?;
+        let size = self.read_f32()?;
+        let dir = match self.read_u8()? {
+            0 => Directionality::LTR,
+            1 => Directionality::RTL,
+            _ => return Err(DisplayListError::Malformed("directionality")),
+        };
+        let typeface = TypeFace::new(self.read_string()?, self.read_string()?);
+
+        let flags = self.read_u8()?;
+        if flags & !(SYNTHESIS_BOLD | SYNTHESIS_ITALIC | SYNTHESIS_MIRROR) != 0 {
+            return Err(DisplayListError::Malformed("font synthesis"));
+        }
+        let synthesis = FontSynthesis::new(flags & SYNTHESIS_BOLD != 0,
+                                           flags & SYNTHESIS_ITALIC != 0)
+            .with_mirror(flags & SYNTHESIS_MIRROR != 0);
+
+        Ok((color, size, dir, typeface, synthesis))
+    }
+}
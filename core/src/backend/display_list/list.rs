@@ -0,0 +1,279 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::any::Any;
+use std::cell::{Ref, RefCell};
+
+use super::DrawCommand;
+use super::encoding::{Encoder, Decoder, DisplayListError, MAGIC, VERSION};
+use ::paint::{Point, Rect, FontSynthesis, TypeFace, GlyphRun, Canvas};
+use ::draw::BoundingBox;
+use ::props::{Directionality, Color};
+
+/// Canvas recording draw calls, to be replayed later onto any other canvas. Lists serialize
+/// to a compact byte format, so that layout can happen once and drawing elsewhere, like
+/// across an FFI boundary or from a cache on disk.
+///
+/// The byte format starts with magic `AKDL`, a version byte, the bounding box of the recorded
+/// drawable and the number of commands. Each command is a tag byte followed by its arguments,
+/// with numbers in little endian order.
+#[derive(Debug)]
+pub struct DisplayList {
+    bounding_box: BoundingBox,
+    commands: RefCell<Vec<DrawCommand>>,
+}
+
+impl DisplayList {
+    pub fn new(bounding_box: &BoundingBox) -> DisplayList {
+        DisplayList {
+            bounding_box: bounding_box.clone(),
+            commands: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub fn bounding_box(&self) -> &BoundingBox {
+        &self.bounding_box
+    }
+
+    pub fn commands(&self) -> Ref<'_, Vec<DrawCommand>> {
+        self.commands.borrow()
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.borrow().is_empty()
+    }
+
+    /// Draws all recorded commands in order, moved by `pen_pos`
+    pub fn replay(&self, canvas: &Canvas, pen_pos: &Point) {
+        for command in self.commands.borrow().iter() {
+            command.draw(canvas, pen_pos);
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let commands = self.commands.borrow();
+
+        let mut encoder = Encoder::new();
+        encoder.put_bytes(MAGIC);
+        encoder.put_u8(VERSION);
+        encoder.put_bounding_box(&self.bounding_box);
+        encoder.put_u32(commands.len() as u32);
+        for command in commands.iter() {
+            encoder.put_command(command);
+        }
+
+        encoder.into_bytes()
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<DisplayList, DisplayListError> {
+        let mut decoder = Decoder::new(data);
+        if decoder.read_bytes(MAGIC.len()).map_err(|_| DisplayListError::BadMagic)? != MAGIC {
+            return Err(DisplayListError::BadMagic);
+        }
+
+        let version = decoder.read_u8()?;
+        if version != VERSION {
+            return Err(DisplayListError::UnsupportedVersion(version));
+        }
+
+        let bounding_box = decoder.read_bounding_box()?;
+        let count = decoder.read_u32()?;
+
+        let mut commands = Vec::new();
+        for _ in 0..count {
+            commands.push(decoder.read_command()?);
+        }
+        if !decoder.is_empty() {
+            return Err(DisplayListError::Malformed("trailing data"));
+        }
+
+        Ok(DisplayList { bounding_box, commands: RefCell::new(commands) })
+    }
+
+    fn push(&self, command: DrawCommand) {
+        self.commands.borrow_mut().push(command);
+    }
+}
+
+impl Canvas for DisplayList {
+    fn draw_text(&self, top_left: &Point, bound: &BoundingBox, text: &str, color: &Color, size: f32,
+                 dir: &Directionality, typeface: &TypeFace, synthesis: &FontSynthesis) {
+        self.push(DrawCommand::Text {
+            top_left: top_left.clone(),
+            bound: bound.clone(),
+            text: text.to_string(),
+            color: color.clone(),
+            size,
+            dir: dir.clone(),
+            typeface: typeface.clone(),
+            synthesis: *synthesis,
+        });
+    }
+
+    fn draw_glyph(&self, top_left: &Point, bound: &BoundingBox, glyph_index: u32, color: &Color,
+                  size: f32, dir: &Directionality, typeface: &TypeFace, synthesis: &FontSynthesis) {
+        self.push(DrawCommand::Glyph {
+            top_left: top_left.clone(),
+            bound: bound.clone(),
+            glyph_index,
+            color: color.clone(),
+            size,
+            dir: dir.clone(),
+            typeface: typeface.clone(),
+            synthesis: *synthesis,
+        });
+    }
+
+    fn draw_glyph_run(&self, top_left: &Point, bound: &BoundingBox, run: &GlyphRun, color: &Color,
+                      synthesis: &FontSynthesis) {
+        self.push(DrawCommand::GlyphRun {
+            top_left: top_left.clone(),
+            bound: bound.clone(),
+            run: run.clone(),
+            color: color.clone(),
+            synthesis: *synthesis,
+        });
+    }
+
+    fn draw_rect(&self, top_left: &Point, rect: &Rect, color: &Color) {
+        self.push(DrawCommand::Rect {
+            top_left: top_left.clone(),
+            rect: rect.clone(),
+            color: color.clone(),
+        });
+    }
+
+    fn draw_rect_outline(&self, top_left: &Point, rect: &Rect, color: &Color, stroke_width: f32) {
+        self.push(DrawCommand::RectOutline {
+            top_left: top_left.clone(),
+            rect: rect.clone(),
+            color: color.clone(),
+            stroke_width,
+        });
+    }
+
+    fn draw_line(&self, start: &Point, end: &Point, color: &Color, stroke_width: f32) {
+        self.push(DrawCommand::Line {
+            start: start.clone(),
+            end: end.clone(),
+            color: color.clone(),
+            stroke_width,
+        });
+    }
+
+    fn as_any(&self) -> &Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::elements::*;
+    use ::platform::Context;
+    use ::backend::raster::{RasterPlatform, RasterCanvas};
+    use ::font::FontStack;
+    use ::paint::ShapedGlyph;
+    use ::font::test::stix_math;
+    use ::test::fixture::draw_root;
+
+    fn sample() -> DisplayList {
+        let bound = BoundingBox::new(Rect::new(20., 10.), 2., 5.);
+        let list = DisplayList::new(&bound);
+        let typeface = TypeFace::new(String::from("STIX Two Math"), String::from("Regular"));
+
+        list.draw_text(&Point::new(1., 2.), &bound, "a\u{1D465}", &Color::RGB(1, 2, 3), 12.5,
+                       &Directionality::RTL, &typeface.clone(), &FontSynthesis::new(true, false));
+        list.draw_glyph(&Point::new(-1., 0.), &bound.clone().with_ink(None), 1234,
+                        &Color::ARGB(255, 0, 0, 0), 64., &Directionality::LTR, &TypeFace::default(),
+                        &FontSynthesis::mirrored());
+        let glyphs = vec![ShapedGlyph::new(7, 6.5, 0., 0., 0),
+                          ShapedGlyph::new(12, 0., -3., 1.5, 1)];
+        let run = GlyphRun::new(String::from("a\u{301}"), typeface, 16., Directionality::LTR,
+                                glyphs);
+        list.draw_glyph_run(&Point::new(2., 0.), &bound, &run, &Color::RGB(4, 5, 6),
+                            &FontSynthesis::none());
+        list.draw_rect(&Point::new(0., 0.), &Rect::new(3., 4.), &Color::ARGB(128, 4, 5, 6));
+        list.draw_rect_outline(&Point::new(0.5, 0.5), &Rect::new(3., 4.), &Color::RGB(0, 0, 0),
+                               1.5);
+        list.draw_line(&Point::new(0., 1.), &Point::new(10., 1.), &Color::RGB(9, 9, 9), 0.25);
+        list
+    }
+
+    #[test]
+    fn it_round_trips_bytes() {
+        let list = sample();
+        let bytes = list.to_bytes();
+        assert_eq!(&bytes[..5], b"AKDL\x04");
+
+        let decoded = DisplayList::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.bounding_box(), list.bounding_box());
+        assert_eq!(decoded.len(), 6);
+        assert_eq!(*decoded.commands(), *list.commands());
+        assert_eq!(decoded.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn it_rejects_invalid_bytes() {
+        let bytes = sample().to_bytes();
+
+        assert_eq!(DisplayList::from_bytes(b"PNG").unwrap_err(), DisplayListError::BadMagic);
+        assert_eq!(DisplayList::from_bytes(b"AKDL\x09").unwrap_err(),
+                   DisplayListError::UnsupportedVersion(9));
+        assert_eq!(DisplayList::from_bytes(&bytes[..bytes.len()-1]).unwrap_err(),
+                   DisplayListError::Truncated);
+
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(DisplayList::from_bytes(&trailing).is_err());
+
+        let mut unknown = bytes.clone();
+        unknown[42] = 42;
+        assert_eq!(DisplayList::from_bytes(&unknown).unwrap_err(),
+                   DisplayListError::Malformed("command"));
+    }
+
+    #[test]
+    fn it_replays_drawables() {
+        let platform = RasterPlatform::new(stix_math());
+        let fonts = platform.fonts().clone();
+        let context = Context::new(Box::new(platform), 64.);
+
+        let msqrt = Msqrt::new(Box::new(Mfrac::new(Box::new(Mi::new(String::from("x"))),
+                                                   Box::new(Mn::new(String::from("2"))))));
+        let drawable = draw_root(&msqrt, &context);
+        let bounding_box = drawable.bounding_box();
+
+        let list = DisplayList::new(bounding_box);
+        drawable.draw(&list, &Point::new(0., 0.));
+        let list = DisplayList::from_bytes(&list.to_bytes()).unwrap();
+        assert!(!list.is_empty());
+
+        let width = bounding_box.width().ceil() as u32;
+        let height = bounding_box.height().ceil() as u32;
+        let direct = RasterCanvas::new(FontStack::new(stix_math()), width, height);
+        drawable.draw(&direct, &Point::new(0., 0.));
+        let replayed = RasterCanvas::new(fonts, width, height);
+        list.replay(&replayed, &Point::new(0., 0.));
+
+        assert_eq!(direct.pixels(), replayed.pixels());
+        assert!(direct.pixels().iter().any(|&value| value != 0));
+    }
+}
@@ -0,0 +1,46 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+
+pub mod display_list;
+pub mod pdf;
+pub mod raster;
+pub mod svg;
+
+use ::font::{OpenTypeFont, Outline};
+use ::paint::FontSynthesis;
+
+// Stems are widened by font size divided by this ratio when synthesizing bold
+pub(crate) const FAUX_BOLD_STROKE_RATIO: f32 = 24.;
+// Horizontal shear of synthesized italic, about 12 degrees
+pub(crate) const FAUX_ITALIC_SKEW: f32 = 0.21;
+
+/// Glyph outline in device space with its origin at `x`, `baseline`. Mirroring and italic are
+/// applied to the outline, bold is left to the backend.
+pub(crate) fn device_outline(font: &OpenTypeFont, glyph_index: u32, x: f32, baseline: f32,
+                             size: f32, synthesis: &FontSynthesis) -> Outline {
+    let scale = size / font.units_per_em() as f32;
+    let advance = font.advance_width(glyph_index) as f32;
+
+    let mut outline = Outline::new();
+    outline.extend_transformed(&font.glyph_outline(glyph_index), |gx, gy| {
+        let gx = if synthesis.mirror() { advance - gx } else { gx };
+        let gx = if synthesis.italic() { gx + gy * FAUX_ITALIC_SKEW } else { gx };
+        (x + gx * scale, baseline - gy * scale)
+    });
+
+    outline
+}
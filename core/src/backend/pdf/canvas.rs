@@ -0,0 +1,609 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::char;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write as FmtWrite;
+use std::fs::File;
+use std::io::{Error, Write};
+use std::path::Path;
+
+use super::writer::PdfWriter;
+use super::super::{FAUX_BOLD_STROKE_RATIO, FAUX_ITALIC_SKEW};
+use ::font::{FontStack, Outline, PathCommand};
+use ::paint::{Point, Rect, FontSynthesis, TypeFace, GlyphRun, Canvas};
+use ::draw::BoundingBox;
+use ::props::{Directionality, Color};
+
+// Type 3 fonts address glyphs with single byte character codes
+const GLYPHS_PER_FONT: usize = 256;
+// Limit on mappings in one bfchar block of a ToUnicode CMap
+const MAX_BFCHAR_ENTRIES: usize = 100;
+// Glyph space of Type 3 fonts, to which outlines of fonts with any em size are scaled
+const GLYPH_UNITS_PER_EM: f32 = 1000.;
+
+struct Page {
+    width: f32,
+    height: f32,
+    content: String,
+}
+
+/// Canvas recording draw calls as pages of a PDF document. Every page is sized from the
+/// bounding box of the drawable painted on it, with one point per pixel.
+///
+/// Glyphs are embedded as Type 3 fonts traced from the outlines of the fonts in `fonts`,
+/// holding only the glyphs drawn on some page. Translucent colors are painted with constant
+/// alpha graphics states.
+pub struct PdfCanvas {
+    fonts: FontStack,
+    pages: RefCell<Vec<Page>>,
+    subset: RefCell<Vec<(usize, u32)>>,
+    subset_positions: RefCell<HashMap<(usize, u32), usize>>,
+    type3_fonts: RefCell<BTreeSet<(usize, bool)>>,
+    alphas: RefCell<BTreeSet<u8>>,
+}
+
+impl PdfCanvas {
+    pub fn new(fonts: FontStack, bounding_box: &BoundingBox) -> PdfCanvas {
+        let canvas = PdfCanvas {
+            fonts,
+            pages: RefCell::new(Vec::new()),
+            subset: RefCell::new(Vec::new()),
+            subset_positions: RefCell::new(HashMap::new()),
+            type3_fonts: RefCell::new(BTreeSet::new()),
+            alphas: RefCell::new(BTreeSet::new()),
+        };
+        canvas.add_page(bounding_box);
+        canvas
+    }
+
+    /// Starts a new page, which receives all following draw calls
+    pub fn add_page(&self, bounding_box: &BoundingBox) {
+        let width = bounding_box.width().max(1.);
+        let height = bounding_box.height().max(1.);
+
+        self.pages.borrow_mut().push(Page {
+            width,
+            height,
+            // Flips the y axis so that the origin is at top left of the page
+            content: format!("1 0 0 -1 0 {} cm\n", num(height)),
+        });
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.borrow().len()
+    }
+
+    pub fn to_pdf(&self) -> Vec<u8> {
+        let mut writer = PdfWriter::new();
+        let catalog = writer.reserve();
+        let page_tree = writer.reserve();
+        let resources = writer.reserve();
+
+        let mut fonts = String::new();
+        for &(font, bold) in self.type3_fonts.borrow().iter() {
+            let id = self.write_font(&mut writer, font, bold);
+            write!(fonts, " /{} {} 0 R", font_name(font, bold), id).unwrap();
+        }
+
+        let mut graphics_states = String::new();
+        for &alpha in self.alphas.borrow().iter() {
+            write!(graphics_states, " /A{} << /ca {a} /CA {a} >>", alpha,
+                   a = num(alpha as f32 / 255.)).unwrap();
+        }
+
+        writer.set(resources, format!("<< /Font <<{} >> /ExtGState <<{} >> >>", fonts,
+                                      graphics_states));
+
+        let mut kids = Vec::new();
+        for page in self.pages.borrow().iter() {
+            let contents = writer.add_stream("", page.content.as_bytes());
+            let id = writer.add(format!("<< /Type /Page /Parent {} 0 R /MediaBox [0 0 {} {}] \
+                                         /Resources {} 0 R /Contents {} 0 R >>", page_tree,
+                                        num(page.width), num(page.height), resources, contents));
+            kids.push(format!("{} 0 R", id));
+        }
+
+        writer.set(page_tree, format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "),
+                                      kids.len()));
+        writer.set(catalog, format!("<< /Type /Catalog /Pages {} 0 R >>", page_tree));
+        writer.finish(catalog)
+    }
+
+    pub fn write_pdf(&self, path: &Path) -> Result<(), Error> {
+        File::create(path)?.write_all(&self.to_pdf())
+    }
+
+    fn push(&self, content: &str) {
+        let mut pages = self.pages.borrow_mut();
+        pages.last_mut().expect("PDF canvas has no page").content.push_str(content);
+    }
+
+    /// Operators setting fill and stroke color, through a graphics state for translucent colors
+    fn paint(&self, color: &Color) -> String {
+        let mut operators = String::new();
+        if color.a() < 255 {
+            self.alphas.borrow_mut().insert(color.a());
+            write!(operators, "/A{} gs ", color.a()).unwrap();
+        }
+
+        let rgb = format!("{} {} {}", num(color.r() as f32 / 255.), num(color.g() as f32 / 255.),
+                          num(color.b() as f32 / 255.));
+        write!(operators, "{rgb} rg {rgb} RG ", rgb = rgb).unwrap();
+        operators
+    }
+
+    /// Font resource name and character code of a glyph from the font at `source` in the font
+    /// stack, adding it to the subset on first use
+    fn glyph_code(&self, source: usize, glyph_index: u32, bold: bool) -> (String, u8) {
+        let key = (source, glyph_index);
+        let position = *self.subset_positions.borrow_mut().entry(key).or_insert_with(|| {
+            let mut subset = self.subset.borrow_mut();
+            subset.push(key);
+            subset.len() - 1
+        });
+
+        let font = position / GLYPHS_PER_FONT;
+        self.type3_fonts.borrow_mut().insert((font, bold));
+        (font_name(font, bold), (position % GLYPHS_PER_FONT) as u8)
+    }
+
+    /// Text operators showing a glyph with its origin at `x`, `baseline`. The text matrix
+    /// applies synthesized styles the same way `device_outline` does.
+    fn show_glyph(&self, source: usize, glyph_index: u32, x: f32, baseline: f32, size: f32,
+                  synthesis: &FontSynthesis) -> String {
+        let source_font = &self.fonts.fonts()[source];
+        if source_font.glyph_outline(glyph_index).is_empty() {
+            return String::new();
+        }
+
+        let (font, code) = self.glyph_code(source, glyph_index, synthesis.bold());
+        let (scale_x, x) = if synthesis.mirror() {
+            let advance = source_font.advance_width(glyph_index) as f32 * size /
+                source_font.units_per_em() as f32;
+            (-size, x + advance)
+        } else {
+            (size, x)
+        };
+        let skew = if synthesis.italic() { FAUX_ITALIC_SKEW * size } else { 0. };
+
+        format!("/{} 1 Tf {} 0 {} {} {} {} Tm <{:02x}> Tj\n", font, num(scale_x), num(skew),
+                num(-size), num(x), num(baseline), code)
+    }
+
+    /// Writes a Type 3 font drawing the subset glyphs addressed through `font`. Outlines are
+    /// scaled from the em size of their source font to the glyph space. Faux bold fonts also
+    /// stroke the outlines.
+    fn write_font(&self, writer: &mut PdfWriter, font: usize, bold: bool) -> usize {
+        let subset = self.subset.borrow();
+        let glyphs = &subset[font * GLYPHS_PER_FONT..subset.len().min((font + 1) * GLYPHS_PER_FONT)];
+        let half = if bold { GLYPH_UNITS_PER_EM / FAUX_BOLD_STROKE_RATIO / 2. } else { 0. };
+
+        let mut char_procs = String::new();
+        let mut differences = String::from("0");
+        let mut widths = Vec::with_capacity(glyphs.len());
+        let mut font_bbox: Option<(f32, f32, f32, f32)> = None;
+
+        for &(source, glyph) in glyphs.iter() {
+            let source_font = &self.fonts.fonts()[source];
+            let units_per_em = source_font.units_per_em() as f32;
+            let scale = GLYPH_UNITS_PER_EM / units_per_em;
+            let outline = source_font.glyph_outline(glyph);
+            let advance = source_font.advance_width(glyph) as f32 * scale;
+            let bbox = outline.bounds()
+                .map(|b| (b.x_min() * scale - half, b.y_min() * scale - half,
+                          b.x_max() * scale + half, b.y_max() * scale + half))
+                .unwrap_or((0., 0., 0., 0.));
+            font_bbox = Some(match font_bbox {
+                Some((l, b, r, t)) => (l.min(bbox.0), b.min(bbox.1), r.max(bbox.2), t.max(bbox.3)),
+                None => bbox,
+            });
+
+            let mut procedure = format!("{} 0 {} {} {} {} d1\n", num(advance), num(bbox.0),
+                                        num(bbox.1), num(bbox.2), num(bbox.3));
+            if scale != 1. {
+                writeln!(procedure, "{s} 0 0 {s} 0 0 cm", s = num(scale)).unwrap();
+            }
+            procedure.push_str(&path_operators(&outline));
+            if bold {
+                writeln!(procedure, "{} w B", num(units_per_em / FAUX_BOLD_STROKE_RATIO)).unwrap();
+            } else {
+                procedure.push_str("f\n");
+            }
+
+            let id = writer.add_stream("", procedure.as_bytes());
+            write!(char_procs, " /{} {} 0 R", glyph_name(source, glyph), id).unwrap();
+            write!(differences, " /{}", glyph_name(source, glyph)).unwrap();
+            widths.push(num(advance));
+        }
+
+        let to_unicode = writer.add_stream("", self.to_unicode(glyphs).as_bytes());
+        let (left, bottom, right, top) = font_bbox.unwrap_or((0., 0., 0., 0.));
+        let matrix = 1. / GLYPH_UNITS_PER_EM as f64;
+
+        writer.add(format!("<< /Type /Font /Subtype /Type3 /FontBBox [{} {} {} {}] \
+                            /FontMatrix [{m} 0 0 {m} 0 0] /CharProcs <<{} >> \
+                            /Encoding << /Type /Encoding /Differences [{}] >> \
+                            /FirstChar 0 /LastChar {} /Widths [{}] /ToUnicode {} 0 R \
+                            /Resources << >> >>",
+                           num(left), num(bottom), num(right), num(top), char_procs, differences,
+                           glyphs.len() - 1, widths.join(" "), to_unicode, m = matrix))
+    }
+
+    /// CMap mapping character codes back to text for search and copying
+    fn to_unicode(&self, glyphs: &[(usize, u32)]) -> String {
+        let mappings: Vec<(usize, char)> = glyphs.iter().enumerate()
+            .filter_map(|(code, &(source, glyph))| {
+                self.fonts.fonts()[source].glyph_unicode(glyph).and_then(char::from_u32)
+                    .map(|c| (code, c))
+            })
+            .collect();
+
+        let mut cmap = String::from("/CIDInit /ProcSet findresource begin\n12 dict begin\n\
+                                     begincmap\n/CIDSystemInfo << /Registry (Adobe) \
+                                     /Ordering (UCS) /Supplement 0 >> def\n\
+                                     /CMapName /Adobe-Identity-UCS def\n/CMapType 2 def\n\
+                                     1 begincodespacerange\n<00> <ff>\nendcodespacerange\n");
+        for block in mappings.chunks(MAX_BFCHAR_ENTRIES) {
+            writeln!(cmap, "{} beginbfchar", block.len()).unwrap();
+            for &(code, c) in block.iter() {
+                write!(cmap, "<{:02x}> <", code).unwrap();
+                let mut units = [0; 2];
+                for unit in c.encode_utf16(&mut units).iter() {
+                    write!(cmap, "{:04x}", unit).unwrap();
+                }
+                cmap.push_str(">\n");
+            }
+            cmap.push_str("endbfchar\n");
+        }
+        cmap.push_str("endcmap\nCMapName currentdict /CMap defineresource pop\nend\nend\n");
+        cmap
+    }
+}
+
+impl Canvas for PdfCanvas {
+    fn draw_text(&self, top_left: &Point, bound: &BoundingBox, text: &str, color: &Color, size: f32,
+                 dir: &Directionality, typeface: &TypeFace, synthesis: &FontSynthesis) {
+        if color.a() == 0 {
+            return;
+        }
+
+        let source = self.fonts.index_of(typeface).unwrap_or(0);
+        let font = &self.fonts.fonts()[source];
+        let scale = size / font.units_per_em() as f32;
+        let baseline = top_left.y() + bound.baseline_pos();
+
+        let mut glyphs: Vec<u32> = text.chars()
+            .map(|c| font.glyph_index(c as u32).unwrap_or(0))
+            .collect();
+        if *dir == Directionality::RTL {
+            glyphs.reverse();
+        }
+
+        let mut operators = format!("q {}BT\n", self.paint(color));
+        let mut x = top_left.x();
+        for glyph in glyphs {
+            operators.push_str(&self.show_glyph(source, glyph, x, baseline, size, synthesis));
+            x += font.advance_width(glyph) as f32 * scale;
+        }
+        operators.push_str("ET Q\n");
+        self.push(&operators);
+    }
+
+    fn draw_glyph(&self, top_left: &Point, _: &BoundingBox, glyph_index: u32, color: &Color,
+                  size: f32, _: &Directionality, typeface: &TypeFace, synthesis: &FontSynthesis) {
+        if color.a() == 0 {
+            return;
+        }
+
+        let source = self.fonts.index_of(typeface).unwrap_or(0);
+        let font = &self.fonts.fonts()[source];
+        let scale = size / font.units_per_em() as f32;
+
+        // Placed by ink top, like the raster canvas
+        let baseline = match font.glyph_outline(glyph_index).bounds() {
+            Some(bounds) => top_left.y() + bounds.y_max() * scale,
+            None => return,
+        };
+
+        let glyph = self.show_glyph(source, glyph_index, top_left.x(), baseline, size, synthesis);
+        self.push(&format!("q {}BT\n{}ET Q\n", self.paint(color), glyph));
+    }
+
+    fn draw_glyph_run(&self, top_left: &Point, bound: &BoundingBox, run: &GlyphRun, color: &Color,
+                      synthesis: &FontSynthesis) {
+        if color.a() == 0 {
+            return;
+        }
+
+        let source = self.fonts.index_of(run.typeface()).unwrap_or(0);
+        let baseline = top_left.y() + bound.baseline_pos();
+
+        let mut operators = format!("q {}BT\n", self.paint(color));
+        let mut x = top_left.x();
+        for glyph in run.glyphs() {
+            operators.push_str(&self.show_glyph(source, glyph.glyph_index(), x + glyph.x_offset(),
+                                                baseline - glyph.y_offset(), run.size(),
+                                                synthesis));
+            x += glyph.advance();
+        }
+        operators.push_str("ET Q\n");
+        self.push(&operators);
+    }
+
+    fn draw_rect(&self, top_left: &Point, rect: &Rect, color: &Color) {
+        if color.a() == 0 {
+            return;
+        }
+
+        self.push(&format!("q {}{} {} {} {} re f Q\n", self.paint(color), num(top_left.x()),
+                           num(top_left.y()), num(rect.width()), num(rect.height())));
+    }
+
+    fn draw_rect_outline(&self, top_left: &Point, rect: &Rect, color: &Color, stroke_width: f32) {
+        if color.a() == 0 {
+            return;
+        }
+
+        self.push(&format!("q {}{} w {} {} {} {} re S Q\n", self.paint(color), num(stroke_width),
+                           num(top_left.x()), num(top_left.y()), num(rect.width()),
+                           num(rect.height())));
+    }
+
+    fn draw_line(&self, start: &Point, end: &Point, color: &Color, stroke_width: f32) {
+        if color.a() == 0 {
+            return;
+        }
+
+        self.push(&format!("q {}{} w {} {} m {} {} l S Q\n", self.paint(color), num(stroke_width),
+                           num(start.x()), num(start.y()), num(end.x()), num(end.y())));
+    }
+
+    fn as_any(&self) -> &Any {
+        self
+    }
+}
+
+fn font_name(font: usize, bold: bool) -> String {
+    format!("{}{}", if bold { "B" } else { "F" }, font)
+}
+
+/// Glyph name unique across the font stack, suffixed by the position of fallback fonts
+fn glyph_name(source: usize, glyph_index: u32) -> String {
+    if source == 0 {
+        format!("g{}", glyph_index)
+    } else {
+        format!("g{}.{}", glyph_index, source)
+    }
+}
+
+/// Path construction operators for an outline. PDF has no quadratic segments, so they are
+/// raised to cubic ones.
+fn path_operators(outline: &Outline) -> String {
+    let mut operators = String::new();
+    let mut current = (0., 0.);
+
+    for command in outline.commands().iter() {
+        match *command {
+            PathCommand::MoveTo(x, y) => {
+                writeln!(operators, "{} {} m", num(x), num(y)).unwrap();
+                current = (x, y);
+            },
+            PathCommand::LineTo(x, y) => {
+                writeln!(operators, "{} {} l", num(x), num(y)).unwrap();
+                current = (x, y);
+            },
+            PathCommand::QuadTo(x1, y1, x, y) => {
+                let (x0, y0) = current;
+                writeln!(operators, "{} {} {} {} {} {} c", num(x0 + 2. / 3. * (x1 - x0)),
+                       num(y0 + 2. / 3. * (y1 - y0)), num(x + 2. / 3. * (x1 - x)),
+                       num(y + 2. / 3. * (y1 - y)), num(x), num(y)).unwrap();
+                current = (x, y);
+            },
+            PathCommand::CurveTo(x1, y1, x2, y2, x, y) => {
+                writeln!(operators, "{} {} {} {} {} {} c", num(x1), num(y1), num(x2), num(y2),
+                       num(x), num(y)).unwrap();
+                current = (x, y);
+            },
+            PathCommand::Close => operators.push_str("h\n"),
+        }
+    }
+
+    operators
+}
+
+/// Number with at most three decimals and no trailing zeros, PDF has no exponent notation
+fn num(value: f32) -> String {
+    let text = format!("{:.3}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" || text.is_empty() {
+        String::from("0")
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str;
+    use super::*;
+    use ::elements::*;
+    use ::platform::Context;
+    use ::backend::raster::RasterPlatform;
+    use ::font::test::stix_math;
+    use ::test::fixture::draw_root;
+
+    fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+        haystack.windows(needle.len()).position(|window| window == needle)
+    }
+
+    /// Objects of a PDF file by number, located through its cross reference table
+    fn parse(pdf: &[u8]) -> Vec<String> {
+        assert!(pdf.starts_with(b"%PDF-1.4\n"));
+        assert!(pdf.ends_with(b"%%EOF\n"));
+
+        let start = pdf.windows(10).rposition(|window| window == b"startxref\n").unwrap() + 10;
+        let xref: usize = str::from_utf8(&pdf[start..]).unwrap().lines().next().unwrap()
+            .parse().unwrap();
+
+        let mut lines = str::from_utf8(&pdf[xref..]).unwrap().lines();
+        assert_eq!(lines.next(), Some("xref"));
+        let count: usize = lines.next().unwrap()[2..].parse().unwrap();
+        assert_eq!(lines.next(), Some("0000000000 65535 f "));
+
+        (1..count).zip(lines).map(|(id, line)| {
+            assert_eq!(line.len(), 19);
+            let object = &pdf[line[..10].parse::<usize>().unwrap()..];
+            let header = format!("{} 0 obj\n", id);
+            assert!(object.starts_with(header.as_bytes()), "Bad offset for object {}", id);
+
+            let end = find(object, b"\nendobj").unwrap();
+            String::from_utf8(object[header.len()..end].to_vec()).unwrap()
+        }).collect()
+    }
+
+    /// Object referenced by the first number following `key`
+    fn reference<'a>(objects: &'a [String], object: &str, key: &str) -> &'a str {
+        let start = object.find(key).unwrap_or_else(|| panic!("No {} in {}", key, object));
+        let id: usize = object[start + key.len()..].split_whitespace().next().unwrap()
+            .parse().unwrap();
+        &objects[id - 1]
+    }
+
+    #[test]
+    fn it_writes_document() {
+        let platform = RasterPlatform::new(stix_math());
+        let fonts = platform.fonts().clone();
+        let font = fonts.math_font().clone();
+        let context = Context::new(Box::new(platform), 64.);
+
+        let mfrac = Mfrac::new(Box::new(Mi::new(String::from("x"))),
+                               Box::new(Mi::new(String::from("y"))));
+        let drawable = draw_root(&mfrac, &context);
+        let bounding_box = drawable.bounding_box();
+
+        let canvas = PdfCanvas::new(fonts, bounding_box);
+        drawable.draw(&canvas, &Point::new(0., 0.));
+        let objects = parse(&canvas.to_pdf());
+
+        let catalog = &objects[0];
+        assert!(catalog.contains("/Type /Catalog"));
+        let pages = reference(&objects, catalog, "/Pages");
+        assert!(pages.contains("/Count 1"));
+
+        let page = reference(&objects, pages, "/Kids [");
+        assert!(page.contains(&format!("/MediaBox [0 0 {} {}]", num(bounding_box.width()),
+                                       num(bounding_box.height()))));
+        let content = reference(&objects, page, "/Contents");
+        assert!(content.contains(&format!("1 0 0 -1 0 {} cm", num(bounding_box.height()))));
+        assert_eq!(content.matches(" Tj").count(), 2);
+        assert_eq!(content.matches(" l S").count(), 1);
+
+        let resources = reference(&objects, page, "/Resources");
+        let type3 = reference(&objects, resources, "/F0");
+        let x = font.glyph_index(0x1D465).unwrap();
+        let y = font.glyph_index(0x1D466).unwrap();
+        assert!(type3.contains("/Subtype /Type3"));
+        assert!(type3.contains("/FontMatrix [0.001 0 0 0.001 0 0]"));
+        assert!(type3.contains(&format!("/Differences [0 /g{} /g{}]", x, y)));
+        assert!(type3.contains(&format!("/Widths [{} {}]", font.advance_width(x),
+                                        font.advance_width(y))));
+
+        let procedure = reference(&objects, type3, &format!("/g{}", x));
+        assert!(procedure.contains(&format!("{} 0 ", font.advance_width(x))));
+        assert!(procedure.contains(" d1\n"));
+        assert!(procedure.contains(" c\n"));
+
+        let to_unicode = reference(&objects, type3, "/ToUnicode");
+        assert!(to_unicode.contains("<00> <d835dc65>\n<01> <d835dc66>"));
+    }
+
+    #[test]
+    fn it_maps_alpha_to_graphics_states() {
+        let canvas = PdfCanvas::new(FontStack::new(stix_math()),
+                                    &BoundingBox::new(Rect::new(20., 10.), 0., 5.));
+        canvas.draw_rect(&Point::new(1., 2.), &Rect::new(3., 4.), &Color::ARGB(128, 255, 0, 0));
+        canvas.draw_rect(&Point::new(0., 0.), &Rect::new(3., 4.), &Color::ARGB(0, 255, 0, 0));
+        canvas.add_page(&BoundingBox::new(Rect::new(30., 15.), 0., 5.));
+        canvas.draw_line(&Point::new(0., 1.), &Point::new(30., 1.), &Color::RGB(0, 0, 255), 2.);
+        assert_eq!(canvas.page_count(), 2);
+
+        let objects = parse(&canvas.to_pdf());
+        let pages = reference(&objects, &objects[0], "/Pages");
+        assert!(pages.contains("/Count 2"));
+
+        let first = reference(&objects, pages, "/Kids [");
+        assert!(first.contains("/MediaBox [0 0 20 10]"));
+        let content = reference(&objects, first, "/Contents");
+        assert!(content.ends_with("stream\n1 0 0 -1 0 10 cm\n\
+                                   q /A128 gs 1 0 0 rg 1 0 0 RG 1 2 3 4 re f Q\n\nendstream"));
+
+        let resources = reference(&objects, first, "/Resources");
+        assert_eq!(resources, "<< /Font << >> /ExtGState << /A128 << /ca 0.502 /CA 0.502 >> >> >>");
+
+        let second = reference(&objects, pages, "/Kids [5 0 R");
+        assert!(second.contains("/MediaBox [0 0 30 15]"));
+        assert!(reference(&objects, second, "/Contents").contains("0 0 1 RG 2 w 0 1 m 30 1 l S Q"));
+    }
+
+    #[test]
+    fn it_splits_subset_into_fonts() {
+        let fonts = FontStack::new(stix_math());
+        let font = fonts.math_font().clone();
+        let glyphs: Vec<u32> = (1..u32::from(font.num_glyphs()))
+            .filter(|&glyph| !font.glyph_outline(glyph).is_empty())
+            .take(GLYPHS_PER_FONT + 10)
+            .collect();
+
+        let bound = BoundingBox::new(Rect::new(100., 100.), 0., 50.);
+        let canvas = PdfCanvas::new(fonts.clone(), &bound);
+        for &glyph in glyphs.iter() {
+            canvas.draw_glyph(&Point::new(0., 0.), &bound, glyph, &Color::RGB(0, 0, 0), 32.,
+                              &Directionality::LTR, fonts.math_typeface(),
+                              &FontSynthesis::none());
+        }
+        canvas.draw_glyph(&Point::new(0., 0.), &bound, glyphs[0], &Color::RGB(0, 0, 0), 32.,
+                          &Directionality::LTR, fonts.math_typeface(),
+                          &FontSynthesis::new(true, true));
+
+        let objects = parse(&canvas.to_pdf());
+        let page = reference(&objects, reference(&objects, &objects[0], "/Pages"), "/Kids [");
+        let resources = reference(&objects, page, "/Resources");
+
+        assert!(reference(&objects, resources, "/F0").contains("/LastChar 255"));
+        assert!(reference(&objects, resources, "/F1").contains("/LastChar 9"));
+
+        let bold = reference(&objects, resources, "/B0");
+        assert!(bold.contains("/LastChar 255"));
+        let procedure = reference(&objects, bold, &format!("/g{} ", glyphs[0]));
+        assert!(procedure.contains(" w B\n"));
+
+        let content = reference(&objects, page, "/Contents");
+        assert!(content.contains("/F1 1 Tf 32 0 0 -32 0 "));
+        assert!(content.contains("/B0 1 Tf 32 0 6.72 -32 0 "));
+        assert!(content.contains("<09> Tj"));
+    }
+
+    #[test]
+    fn it_formats_numbers() {
+        assert_eq!(num(1.), "1");
+        assert_eq!(num(-0.0001), "0");
+        assert_eq!(num(0.5019608), "0.502");
+        assert_eq!(num(-12.25), "-12.25");
+    }
+}
@@ -0,0 +1,79 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::fmt::Write;
+
+/// Serializes numbered indirect objects into a PDF file with a cross reference table
+pub struct PdfWriter {
+    objects: Vec<Option<Vec<u8>>>,
+}
+
+impl PdfWriter {
+    pub fn new() -> PdfWriter {
+        PdfWriter { objects: Vec::new() }
+    }
+
+    /// Allocates an object number to be filled later with `set`, for objects which are
+    /// referenced before they can be written
+    pub fn reserve(&mut self) -> usize {
+        self.objects.push(None);
+        self.objects.len()
+    }
+
+    pub fn set(&mut self, id: usize, object: String) {
+        self.objects[id - 1] = Some(object.into_bytes());
+    }
+
+    pub fn add(&mut self, object: String) -> usize {
+        let id = self.reserve();
+        self.set(id, object);
+        id
+    }
+
+    /// Adds a stream object, `entries` are written into its dictionary along with the length
+    pub fn add_stream(&mut self, entries: &str, data: &[u8]) -> usize {
+        let mut object = format!("<< /Length {}{} >>\nstream\n", data.len(), entries).into_bytes();
+        object.extend_from_slice(data);
+        object.extend_from_slice(b"\nendstream");
+
+        self.objects.push(Some(object));
+        self.objects.len()
+    }
+
+    pub fn finish(self, root: usize) -> Vec<u8> {
+        // Binary comment marks the file as binary for transfer programs
+        let mut pdf = b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n".to_vec();
+
+        let mut offsets = Vec::with_capacity(self.objects.len());
+        for (index, object) in self.objects.into_iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.extend_from_slice(format!("{} 0 obj\n", index + 1).as_bytes());
+            pdf.extend_from_slice(&object.expect("Reserved PDF object was never written"));
+            pdf.extend_from_slice(b"\nendobj\n");
+        }
+
+        let xref = pdf.len();
+        let mut trailer = format!("xref\n0 {}\n0000000000 65535 f \n", offsets.len() + 1);
+        for offset in offsets.iter() {
+            writeln!(trailer, "{:010} 00000 n ", offset).unwrap();
+        }
+        write!(trailer, "trailer\n<< /Size {} /Root {} 0 R >>\nstartxref\n{}\n%%EOF\n",
+               offsets.len() + 1, root, xref).unwrap();
+
+        pdf.extend_from_slice(trailer.as_bytes());
+        pdf
+    }
+}
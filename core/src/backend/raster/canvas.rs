@@ -0,0 +1,301 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::{Error, Write};
+use std::path::Path as FilePath;
+
+use super::rasterizer::{Path, Rasterizer};
+use super::png::encode_png;
+use super::super::{device_outline, FAUX_BOLD_STROKE_RATIO};
+use ::font::{OpenTypeFont, FontStack, PathCommand};
+use ::paint::{Point, Rect, FontSynthesis, TypeFace, GlyphRun, Canvas};
+use ::draw::BoundingBox;
+use ::props::{Directionality, Color};
+
+/// Canvas drawing into an in-memory RGBA buffer with non premultiplied alpha. Glyphs are
+/// filled from the outlines of the font in `fonts` matching their typeface.
+pub struct RasterCanvas {
+    fonts: FontStack,
+    width: usize,
+    height: usize,
+    pixels: RefCell<Vec<u8>>,
+}
+
+impl RasterCanvas {
+    pub fn new(fonts: FontStack, width: u32, height: u32) -> RasterCanvas {
+        RasterCanvas {
+            fonts,
+            width: width as usize,
+            height: height as usize,
+            pixels: RefCell::new(vec![0; width as usize * height as usize * 4]),
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width as u32
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height as u32
+    }
+
+    /// RGBA value of a pixel
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let index = (y as usize * self.width + x as usize) * 4;
+        let pixels = self.pixels.borrow();
+        [pixels[index], pixels[index+1], pixels[index+2], pixels[index+3]]
+    }
+
+    pub fn pixels(&self) -> Vec<u8> {
+        self.pixels.borrow().clone()
+    }
+
+    pub fn encode_png(&self) -> Vec<u8> {
+        encode_png(self.width as u32, self.height as u32, &self.pixels.borrow())
+    }
+
+    pub fn write_png(&self, path: &FilePath) -> Result<(), Error> {
+        File::create(path)?.write_all(&self.encode_png())
+    }
+
+    fn fill(&self, path: &Path, color: &Color) {
+        let (left, top, right, bottom) = match path.bounds() {
+            Some(bounds) => bounds,
+            None => return,
+        };
+
+        let x0 = left.floor().max(0.) as usize;
+        let y0 = top.floor().max(0.) as usize;
+        let x1 = (right.ceil().max(0.) as usize).min(self.width);
+        let y1 = (bottom.ceil().max(0.) as usize).min(self.height);
+        if x0 >= x1 || y0 >= y1 {
+            return;
+        }
+
+        let mut rasterizer = Rasterizer::new(x1 - x0, y1 - y0);
+        rasterizer.fill(path, x0 as f32, y0 as f32);
+
+        let mut pixels = self.pixels.borrow_mut();
+        let width = self.width;
+        rasterizer.for_each_pixel(|x, y, coverage| {
+            let index = ((y + y0) * width + x + x0) * 4;
+            blend(&mut pixels[index..index+4], color, coverage);
+        });
+    }
+
+    /// Appends glyph outline scaled to `size` with its origin on the baseline at `origin`.
+    /// Faux bold smears copies of the outline horizontally.
+    fn append_glyph(&self, path: &mut Path, font: &OpenTypeFont, glyph_index: u32,
+                    origin: &Point, size: f32, synthesis: &FontSynthesis) {
+        let bold_width = if synthesis.bold() { size / FAUX_BOLD_STROKE_RATIO } else { 0. };
+        let copies = (bold_width * 2.).ceil() as usize;
+
+        for copy in 0..copies+1 {
+            let offset = if copies == 0 {
+                0.
+            } else {
+                bold_width * (copy as f32 / copies as f32 - 0.5)
+            };
+
+            let outline = device_outline(font, glyph_index, origin.x() + offset, origin.y(), size,
+                                         synthesis);
+            for command in outline.commands().iter() {
+                match *command {
+                    PathCommand::MoveTo(x, y) => path.move_to(x, y),
+                    PathCommand::LineTo(x, y) => path.line_to(x, y),
+                    PathCommand::QuadTo(x1, y1, x, y) => path.quad_to(x1, y1, x, y),
+                    PathCommand::CurveTo(x1, y1, x2, y2, x, y) =>
+                        path.curve_to(x1, y1, x2, y2, x, y),
+                    PathCommand::Close => path.close(),
+                }
+            }
+            path.close();
+        }
+    }
+}
+
+/// Source over compositing of `color` with `coverage` onto a non premultiplied pixel
+fn blend(pixel: &mut [u8], color: &Color, coverage: f32) {
+    let src_alpha = color.a() as f32 / 255. * coverage;
+    let dst_alpha = pixel[3] as f32 / 255.;
+    let out_alpha = src_alpha + dst_alpha * (1. - src_alpha);
+    if out_alpha <= 0. {
+        return;
+    }
+
+    let channel = |src: u8, dst: u8| {
+        let value = (src as f32 * src_alpha + dst as f32 * dst_alpha * (1. - src_alpha)) / out_alpha;
+        value.round().max(0.).min(255.) as u8
+    };
+
+    pixel[0] = channel(color.r(), pixel[0]);
+    pixel[1] = channel(color.g(), pixel[1]);
+    pixel[2] = channel(color.b(), pixel[2]);
+    pixel[3] = (out_alpha * 255.).round() as u8;
+}
+
+impl Canvas for RasterCanvas {
+    fn draw_text(&self, top_left: &Point, bound: &BoundingBox, text: &str, color: &Color, size: f32,
+                 dir: &Directionality, typeface: &TypeFace, synthesis: &FontSynthesis) {
+        let font = self.fonts.font(typeface);
+        let scale = size / font.units_per_em() as f32;
+        let baseline = top_left.y() + bound.baseline_pos();
+
+        let mut glyphs: Vec<u32> = text.chars()
+            .map(|c| font.glyph_index(c as u32).unwrap_or(0))
+            .collect();
+        if *dir == Directionality::RTL {
+            glyphs.reverse();
+        }
+
+        let mut path = Path::new();
+        let mut x = top_left.x();
+        for glyph in glyphs {
+            self.append_glyph(&mut path, font, glyph, &Point::new(x, baseline), size, synthesis);
+            x += font.advance_width(glyph) as f32 * scale;
+        }
+
+        self.fill(&path, color);
+    }
+
+    fn draw_glyph(&self, top_left: &Point, bound: &BoundingBox, glyph_index: u32, color: &Color,
+                  size: f32, _: &Directionality, typeface: &TypeFace, synthesis: &FontSynthesis) {
+        let font = self.fonts.font(typeface);
+        let scale = size / font.units_per_em() as f32;
+
+        // Stretchy parts and size variants are placed by their ink top
+        let baseline = match font.glyph_outline(glyph_index).bounds() {
+            Some(bounds) => top_left.y() + bounds.y_max() * scale,
+            None => top_left.y() + bound.baseline_pos(),
+        };
+
+        let mut path = Path::new();
+        self.append_glyph(&mut path, font, glyph_index, &Point::new(top_left.x(), baseline), size,
+                          synthesis);
+        self.fill(&path, color);
+    }
+
+    fn draw_glyph_run(&self, top_left: &Point, bound: &BoundingBox, run: &GlyphRun, color: &Color,
+                      synthesis: &FontSynthesis) {
+        let font = self.fonts.font(run.typeface());
+        let baseline = top_left.y() + bound.baseline_pos();
+
+        let mut path = Path::new();
+        let mut x = top_left.x();
+        for glyph in run.glyphs() {
+            let origin = Point::new(x + glyph.x_offset(), baseline - glyph.y_offset());
+            self.append_glyph(&mut path, font, glyph.glyph_index(), &origin, run.size(), synthesis);
+            x += glyph.advance();
+        }
+
+        self.fill(&path, color);
+    }
+
+    fn draw_rect(&self, top_left: &Point, rect: &Rect, color: &Color) {
+        let mut path = Path::new();
+        path.rect(top_left.x(), top_left.y(), top_left.x() + rect.width(),
+                  top_left.y() + rect.height(), false);
+        self.fill(&path, color);
+    }
+
+    fn draw_rect_outline(&self, top_left: &Point, rect: &Rect, color: &Color, stroke_width: f32) {
+        let half = stroke_width / 2.;
+        let left = top_left.x();
+        let top = top_left.y();
+        let right = left + rect.width();
+        let bottom = top + rect.height();
+
+        let mut path = Path::new();
+        path.rect(left - half, top - half, right + half, bottom + half, false);
+        if right - left > stroke_width && bottom - top > stroke_width {
+            path.rect(left + half, top + half, right - half, bottom - half, true);
+        }
+        self.fill(&path, color);
+    }
+
+    fn draw_line(&self, start: &Point, end: &Point, color: &Color, stroke_width: f32) {
+        let dx = end.x() - start.x();
+        let dy = end.y() - start.y();
+        let length = (dx*dx + dy*dy).sqrt();
+        if length == 0. {
+            return;
+        }
+
+        let nx = -dy / length * stroke_width / 2.;
+        let ny = dx / length * stroke_width / 2.;
+
+        let mut path = Path::new();
+        path.move_to(start.x() + nx, start.y() + ny);
+        path.line_to(end.x() + nx, end.y() + ny);
+        path.line_to(end.x() - nx, end.y() - ny);
+        path.line_to(start.x() - nx, start.y() - ny);
+        path.close();
+        self.fill(&path, color);
+    }
+
+    fn as_any(&self) -> &Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::font::test::stix_math;
+
+    #[test]
+    fn it_draws_rects_and_lines() {
+        let canvas = RasterCanvas::new(FontStack::new(stix_math()), 10, 10);
+
+        canvas.draw_rect(&Point::new(0., 0.), &Rect::new(4., 4.), &Color::RGB(255, 0, 0));
+        assert_eq!(canvas.pixel(1, 1), [255, 0, 0, 255]);
+        assert_eq!(canvas.pixel(5, 5), [0, 0, 0, 0]);
+
+        canvas.draw_line(&Point::new(0., 8.5), &Point::new(10., 8.5), &Color::ARGB(128, 0, 0, 255), 1.);
+        assert_eq!(canvas.pixel(5, 8), [0, 0, 255, 128]);
+
+        canvas.draw_rect(&Point::new(0., 0.), &Rect::new(2., 2.), &Color::ARGB(128, 0, 255, 0));
+        assert_eq!(canvas.pixel(0, 0), [127, 128, 0, 255]);
+    }
+
+    #[test]
+    fn it_draws_rect_outline() {
+        let canvas = RasterCanvas::new(FontStack::new(stix_math()), 10, 10);
+
+        canvas.draw_rect_outline(&Point::new(1., 1.), &Rect::new(8., 8.), &Color::RGB(0, 0, 0), 2.);
+        assert_eq!(canvas.pixel(0, 5)[3], 255);
+        assert_eq!(canvas.pixel(1, 5)[3], 255);
+        assert_eq!(canvas.pixel(5, 5)[3], 0);
+    }
+
+    #[test]
+    fn it_fills_glyph_outlines() {
+        let fonts = FontStack::new(stix_math());
+        let canvas = RasterCanvas::new(fonts.clone(), 64, 64);
+        let bound = BoundingBox::new(Rect::new(64., 64.), 16., 32.);
+
+        canvas.draw_text(&Point::new(0., 0.), &bound, "\u{25A0}", &Color::RGB(0, 0, 0), 64.,
+                         &Directionality::LTR, fonts.math_typeface(), &FontSynthesis::none());
+
+        let inked = canvas.pixels().chunks(4).filter(|p| p[3] == 255).count();
+        assert!(inked > 500, "Black square has {} opaque pixels", inked);
+        assert_eq!(canvas.pixel(0, 63), [0, 0, 0, 0]);
+    }
+}
@@ -0,0 +1,147 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+
+use std::any::Any;
+use std::path::Path;
+use std::rc::Rc;
+
+use super::canvas::RasterCanvas;
+use ::font::{OpenTypeFont, OpenTypeRuler, FontStack, FontError};
+use ::paint::{TextRuler, MathRuler, TypeFace};
+
+/// Platform measuring with the fonts of a `FontStack`, whose canvases rasterize their outlines
+pub struct RasterPlatform {
+    fonts: FontStack,
+    rulers: Vec<OpenTypeRuler>,
+}
+
+impl RasterPlatform {
+    pub fn new(font: OpenTypeFont) -> RasterPlatform {
+        RasterPlatform::with_fonts(FontStack::new(font))
+    }
+
+    pub fn with_fonts(fonts: FontStack) -> RasterPlatform {
+        RasterPlatform {
+            rulers: fonts.fonts().iter().map(|font| OpenTypeRuler::new(font.clone())).collect(),
+            fonts,
+        }
+    }
+
+    pub fn from_file(path: &Path) -> Result<RasterPlatform, FontError> {
+        Ok(RasterPlatform::new(OpenTypeFont::from_file(path)?))
+    }
+
+    /// Math font
+    pub fn font(&self) -> &Rc<OpenTypeFont> {
+        self.fonts.math_font()
+    }
+
+    pub fn fonts(&self) -> &FontStack {
+        &self.fonts
+    }
+
+    /// Transparent canvas covering at least one pixel
+    pub fn new_canvas(&self, width: f32, height: f32) -> RasterCanvas {
+        RasterCanvas::new(self.fonts.clone(), width.ceil().max(1.) as u32,
+                          height.ceil().max(1.) as u32)
+    }
+
+    fn ruler(&self, typeface: &TypeFace, size: f32) -> &OpenTypeRuler {
+        let ruler = &self.rulers[self.fonts.index_of(typeface).unwrap_or(0)];
+        ruler.set_size(size);
+        ruler
+    }
+}
+
+impl ::platform::Platform for RasterPlatform {
+    fn get_text_ruler(&self, size: f32) -> &TextRuler {
+        self.ruler(self.fonts.text_typeface(), size)
+    }
+
+    fn get_math_ruler(&self, size: f32) -> &MathRuler {
+        self.ruler(self.fonts.math_typeface(), size)
+    }
+
+    fn get_typeface_ruler(&self, typeface: &TypeFace, size: f32) -> &TextRuler {
+        self.ruler(typeface, size)
+    }
+
+    fn math_typeface(&self) -> &TypeFace {
+        self.fonts.math_typeface()
+    }
+
+    fn text_typeface(&self) -> &TypeFace {
+        self.fonts.text_typeface()
+    }
+
+    fn styled_text_typeface(&self, bold: bool, italic: bool) -> Option<&TypeFace> {
+        self.fonts.styled_text_typeface(bold, italic)
+    }
+
+    fn fallback_typefaces(&self) -> &[TypeFace] {
+        self.fonts.fallback_typefaces()
+    }
+
+    fn typeface_has_glyph(&self, typeface: &TypeFace, unicode: u32) -> bool {
+        match self.fonts.index_of(typeface) {
+            Some(index) => self.fonts.fonts()[index].glyph_index(unicode).is_some(),
+            None => false,
+        }
+    }
+
+    fn px_to_du(&self, px: f32) -> f32 {
+        px
+    }
+
+    fn sp_to_du(&self, sp: f32) -> f32 {
+        64.*sp
+    }
+
+    fn dp_to_du(&self, dp: f32) -> f32 {
+        64.*dp
+    }
+
+    fn as_any(&self) -> &Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::platform::Platform;
+    use ::font::test::{stix_math, stix_text};
+
+    #[test]
+    fn it_picks_faces_from_font_stack() {
+        let mut fonts = FontStack::new(stix_math());
+        fonts.with_text_font(stix_text());
+        let platform = RasterPlatform::with_fonts(fonts);
+        let math = platform.math_typeface().clone();
+        let text = platform.text_typeface().clone();
+
+        assert_eq!(platform.get_text_ruler(20.).typeface(), &text);
+        assert_eq!(platform.get_math_ruler(20.).typeface(), &math);
+        assert_eq!(platform.get_typeface_ruler(&text, 12.).font_size(), 12.);
+
+        let unknown = TypeFace::new(String::from("Unknown"), String::from("Regular"));
+        assert!(!platform.typeface_has_glyph(&unknown, 'x' as u32));
+        assert_eq!(platform.typeface_for_char(&text, 'x' as u32), Some(text.clone()));
+        assert_eq!(platform.typeface_for_char(&unknown, 'x' as u32), Some(math));
+        assert_eq!(platform.typeface_for_char(&text, 0x10FFFF), None);
+    }
+}
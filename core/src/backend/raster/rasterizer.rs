@@ -0,0 +1,290 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+
+// Maximum distance in pixels between a curve and its flattened polyline
+const FLATTEN_TOLERANCE: f32 = 0.1;
+const MAX_CURVE_SEGMENTS: usize = 100;
+
+/// Polygon in device space made of flattened closed contours
+#[derive(Debug, Default)]
+pub struct Path {
+    lines: Vec<((f32, f32), (f32, f32))>,
+    start: (f32, f32),
+    current: (f32, f32),
+}
+
+impl Path {
+    pub fn new() -> Path {
+        Path::default()
+    }
+
+    pub fn move_to(&mut self, x: f32, y: f32) {
+        self.close();
+        self.start = (x, y);
+        self.current = (x, y);
+    }
+
+    pub fn line_to(&mut self, x: f32, y: f32) {
+        let from = self.current;
+        self.lines.push((from, (x, y)));
+        self.current = (x, y);
+    }
+
+    pub fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (x0, y0) = self.current;
+        let dd = ((x0 - 2.*x1 + x).powi(2) + (y0 - 2.*y1 + y).powi(2)).sqrt();
+        let segments = segment_count(dd / (8. * FLATTEN_TOLERANCE));
+
+        for i in 1..segments+1 {
+            let t = i as f32 / segments as f32;
+            let mt = 1. - t;
+            self.line_to(mt*mt*x0 + 2.*mt*t*x1 + t*t*x, mt*mt*y0 + 2.*mt*t*y1 + t*t*y);
+        }
+    }
+
+    pub fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (x0, y0) = self.current;
+        let dd = ((x0 - 2.*x1 + x2).powi(2) + (y0 - 2.*y1 + y2).powi(2)).sqrt()
+            .max(((x1 - 2.*x2 + x).powi(2) + (y1 - 2.*y2 + y).powi(2)).sqrt());
+        let segments = segment_count(3. * dd / (4. * FLATTEN_TOLERANCE));
+
+        for i in 1..segments+1 {
+            let t = i as f32 / segments as f32;
+            let mt = 1. - t;
+            let a = mt*mt*mt;
+            let b = 3.*mt*mt*t;
+            let c = 3.*mt*t*t;
+            let d = t*t*t;
+            self.line_to(a*x0 + b*x1 + c*x2 + d*x, a*y0 + b*y1 + c*y2 + d*y);
+        }
+    }
+
+    /// Closes current contour with a line back to its start
+    pub fn close(&mut self) {
+        if self.current != self.start {
+            let (x, y) = self.start;
+            self.line_to(x, y);
+        }
+    }
+
+    /// Adds a closed rectangle, clockwise in device space unless `reverse` is set
+    pub fn rect(&mut self, left: f32, top: f32, right: f32, bottom: f32, reverse: bool) {
+        self.move_to(left, top);
+        if reverse {
+            self.line_to(left, bottom);
+            self.line_to(right, bottom);
+            self.line_to(right, top);
+        } else {
+            self.line_to(right, top);
+            self.line_to(right, bottom);
+            self.line_to(left, bottom);
+        }
+        self.close();
+    }
+
+    /// Bounds as (left, top, right, bottom)
+    pub fn bounds(&self) -> Option<(f32, f32, f32, f32)> {
+        self.lines.iter().fold(None, |bounds, &((x0, y0), (x1, y1))| {
+            let (l, t, r, b) = bounds.unwrap_or((x0, y0, x0, y0));
+            Some((l.min(x0).min(x1), t.min(y0).min(y1), r.max(x0).max(x1), b.max(y0).max(y1)))
+        })
+    }
+}
+
+fn segment_count(value: f32) -> usize {
+    (value.sqrt().ceil() as usize).max(1).min(MAX_CURVE_SEGMENTS)
+}
+
+/// Anti-aliased scanline rasterizer which accumulates signed area covered by each edge in
+/// a pixel. Running sums along a row give coverage for the non-zero fill rule, with
+/// overlapping contours of same winding saturating to full coverage.
+pub struct Rasterizer {
+    width: usize,
+    height: usize,
+    accumulator: Vec<f32>,
+}
+
+impl Rasterizer {
+    pub fn new(width: usize, height: usize) -> Rasterizer {
+        Rasterizer {
+            width,
+            height,
+            // Two extra cells per row take area spilling past the right edge
+            accumulator: vec![0.; (width + 2) * height],
+        }
+    }
+
+    /// Fills path translated by (-dx, -dy)
+    pub fn fill(&mut self, path: &Path, dx: f32, dy: f32) {
+        for &((x0, y0), (x1, y1)) in path.lines.iter() {
+            self.draw_line((x0 - dx, y0 - dy), (x1 - dx, y1 - dy));
+        }
+    }
+
+    fn draw_line(&mut self, from: (f32, f32), to: (f32, f32)) {
+        if from.1 == to.1 {
+            return;
+        }
+
+        let (dir, p0, p1) = if from.1 < to.1 { (1., from, to) } else { (-1., to, from) };
+        let height = self.height as f32;
+        if p1.1 <= 0. || p0.1 >= height {
+            return;
+        }
+
+        let dxdy = (p1.0 - p0.0) / (p1.1 - p0.1);
+        let y_start = p0.1.max(0.);
+        let y_end = p1.1.min(height);
+        let width = self.width as f32;
+        let stride = self.width + 2;
+        let clamp = |x: f32| x.max(0.).min(width);
+
+        let mut x = p0.0 + (y_start - p0.1) * dxdy;
+        for row in (y_start as usize)..(y_end.ceil() as usize) {
+            let row_top = row as f32;
+            let dy = (row_top + 1.).min(y_end) - row_top.max(y_start);
+            let x_next = x + dxdy * dy;
+            let d = dy * dir;
+
+            let (x0, x1) = if x < x_next {
+                (clamp(x), clamp(x_next))
+            } else {
+                (clamp(x_next), clamp(x))
+            };
+            let line = row * stride;
+            let x0_floor = x0.floor();
+            let x0i = x0_floor as usize;
+            let x1_ceil = x1.ceil();
+            let x1i = x1_ceil as usize;
+
+            if x1i <= x0i + 1 {
+                let xmf = 0.5 * (x0 + x1) - x0_floor;
+                self.accumulator[line + x0i] += d - d * xmf;
+                self.accumulator[line + x0i + 1] += d * xmf;
+            } else {
+                let s = 1. / (x1 - x0);
+                let x0f = x0 - x0_floor;
+                let a0 = 0.5 * s * (1. - x0f) * (1. - x0f);
+                let x1f = x1 - x1_ceil + 1.;
+                let am = 0.5 * s * x1f * x1f;
+
+                self.accumulator[line + x0i] += d * a0;
+                if x1i == x0i + 2 {
+                    self.accumulator[line + x0i + 1] += d * (1. - a0 - am);
+                } else {
+                    let a1 = s * (1.5 - x0f);
+                    self.accumulator[line + x0i + 1] += d * (a1 - a0);
+                    for xi in x0i+2..x1i-1 {
+                        self.accumulator[line + xi] += d * s;
+                    }
+                    let a2 = a1 + (x1i - x0i - 3) as f32 * s;
+                    self.accumulator[line + x1i - 1] += d * (1. - a2 - am);
+                }
+                self.accumulator[line + x1i] += d * am;
+            }
+
+            x = x_next;
+        }
+    }
+
+    /// Calls `f(x, y, coverage)` for every pixel with non zero coverage
+    pub fn for_each_pixel<F>(&self, mut f: F) where F: FnMut(usize, usize, f32) {
+        let stride = self.width + 2;
+        for y in 0..self.height {
+            let mut acc = 0.;
+            for x in 0..self.width {
+                acc += self.accumulator[y * stride + x];
+                let coverage = acc.abs().min(1.);
+                if coverage > 1. / 512. {
+                    f(x, y, coverage);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn coverage(path: &Path, width: usize, height: usize) -> Vec<f32> {
+        let mut rasterizer = Rasterizer::new(width, height);
+        rasterizer.fill(path, 0., 0.);
+
+        let mut result = vec![0.; width * height];
+        rasterizer.for_each_pixel(|x, y, c| result[y * width + x] = c);
+        result
+    }
+
+    #[test]
+    fn it_fills_pixel_aligned_rect() {
+        let mut path = Path::new();
+        path.rect(1., 1., 3., 3., false);
+
+        let result = coverage(&path, 4, 4);
+        for y in 0..4 {
+            for x in 0..4 {
+                let expected = if (1..3).contains(&x) && (1..3).contains(&y) { 1. } else { 0. };
+                assert!((result[y * 4 + x] - expected).abs() < 1e-4, "pixel {} {}", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn it_antialiases_partial_pixels() {
+        let mut path = Path::new();
+        path.rect(0.5, 0., 2., 1., false);
+
+        let result = coverage(&path, 3, 1);
+        assert!((result[0] - 0.5).abs() < 1e-4);
+        assert!((result[1] - 1.).abs() < 1e-4);
+        assert!(result[2].abs() < 1e-4);
+    }
+
+    #[test]
+    fn it_cuts_holes_with_reverse_winding() {
+        let mut path = Path::new();
+        path.rect(0., 0., 3., 3., false);
+        path.rect(1., 1., 2., 2., true);
+
+        let result = coverage(&path, 3, 3);
+        assert!(result[4].abs() < 1e-4);
+        assert!((result[0] - 1.).abs() < 1e-4);
+    }
+
+    #[test]
+    fn it_clips_to_bounds() {
+        let mut path = Path::new();
+        path.rect(-5., -5., 1., 10., false);
+
+        let result = coverage(&path, 2, 2);
+        assert_eq!(result, vec![1., 0., 1., 0.]);
+    }
+
+    #[test]
+    fn it_flattens_curves() {
+        let mut path = Path::new();
+        path.move_to(0., 0.);
+        path.curve_to(0., 10., 10., 10., 10., 0.);
+        path.close();
+
+        let (left, top, right, bottom) = path.bounds().unwrap();
+        assert_eq!((left, top, right), (0., 0., 10.));
+        assert!((bottom - 7.5).abs() < 0.1);
+        assert!(path.lines.len() > 4);
+    }
+}
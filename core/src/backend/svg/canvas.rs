@@ -0,0 +1,417 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::fmt::Write as FmtWrite;
+use std::fs::File;
+use std::io::{Error, Write};
+use std::path::Path;
+
+use super::super::{device_outline, FAUX_BOLD_STROKE_RATIO};
+use ::font::{OpenTypeFont, FontStack, PathCommand};
+use ::paint::{Point, Rect, FontSynthesis, TypeFace, GlyphRun, Canvas};
+use ::draw::BoundingBox;
+use ::props::{Directionality, Color};
+
+/// How glyphs are written to the SVG document
+#[derive(Debug, Clone, PartialEq)]
+pub enum SvgGlyphMode {
+    /// `<text>` elements set in the family of their typeface, which the viewer must have
+    /// available, with generic `font_family` as fallback. Glyphs without a code point, like
+    /// size variants and stretchy parts, and runs with glyphs substituted for their characters,
+    /// like dotless letters under accents and script style variants, are still written as paths.
+    Text { font_family: String },
+    /// `<path>` elements traced from font outlines, independent of installed fonts
+    Path,
+}
+
+/// Canvas recording draw calls as elements of a standalone SVG document. The document is
+/// sized from the bounding box of the drawable painted at origin.
+pub struct SvgCanvas {
+    fonts: FontStack,
+    glyph_mode: SvgGlyphMode,
+    width: f32,
+    height: f32,
+    baseline: f32,
+    elements: RefCell<String>,
+}
+
+impl SvgCanvas {
+    pub fn new(fonts: FontStack, bounding_box: &BoundingBox, glyph_mode: SvgGlyphMode)
+        -> SvgCanvas {
+        SvgCanvas {
+            fonts,
+            glyph_mode,
+            width: bounding_box.width(),
+            height: bounding_box.height(),
+            baseline: bounding_box.baseline(),
+            elements: RefCell::new(String::new()),
+        }
+    }
+
+    /// CSS `vertical-align` in px which places the equation baseline on the baseline of
+    /// surrounding text when the SVG is inlined
+    pub fn vertical_align(&self) -> f32 {
+        -self.baseline
+    }
+
+    pub fn to_svg(&self) -> String {
+        let mut svg = String::new();
+        writeln!(svg, "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}px\" height=\"{h}px\" \
+                       viewBox=\"0 0 {w} {h}\" style=\"vertical-align: {va}px\">",
+               w = num(self.width), h = num(self.height), va = num(self.vertical_align()))
+            .unwrap();
+        svg.push_str(&self.elements.borrow());
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    pub fn write_svg(&self, path: &Path) -> Result<(), Error> {
+        File::create(path)?.write_all(self.to_svg().as_bytes())
+    }
+
+    fn push(&self, element: String) {
+        let mut elements = self.elements.borrow_mut();
+        elements.push_str("  ");
+        elements.push_str(&element);
+        elements.push('\n');
+    }
+
+    /// Path of a glyph with its origin on the baseline at `origin`
+    fn glyph_path(&self, font: &OpenTypeFont, glyph_index: u32, origin: &Point, color: &Color,
+                  size: f32, synthesis: &FontSynthesis) {
+        let outline = device_outline(font, glyph_index, origin.x(), origin.y(), size, synthesis);
+        if outline.is_empty() {
+            return;
+        }
+
+        let mut d = String::new();
+        for command in outline.commands().iter() {
+            match *command {
+                PathCommand::MoveTo(x, y) => write!(d, "M{} {}", num(x), num(y)),
+                PathCommand::LineTo(x, y) => write!(d, "L{} {}", num(x), num(y)),
+                PathCommand::QuadTo(x1, y1, x, y) =>
+                    write!(d, "Q{} {} {} {}", num(x1), num(y1), num(x), num(y)),
+                PathCommand::CurveTo(x1, y1, x2, y2, x, y) =>
+                    write!(d, "C{} {} {} {} {} {}", num(x1), num(y1), num(x2), num(y2), num(x),
+                           num(y)),
+                PathCommand::Close => write!(d, "Z"),
+            }.unwrap();
+        }
+
+        let stroke = if synthesis.bold() {
+            format!(" {} stroke-width=\"{}\"", paint("stroke", color),
+                    num(size / FAUX_BOLD_STROKE_RATIO))
+        } else {
+            String::new()
+        };
+
+        self.push(format!("<path d=\"{}\" {}{}/>", d, paint("fill", color), stroke));
+    }
+
+    fn text(&self, text: &str, x: f32, baseline: f32, width: f32, color: &Color, size: f32,
+            dir: &Directionality, typeface: &TypeFace, synthesis: &FontSynthesis,
+            font_family: &str) {
+        let mut attributes = String::new();
+
+        // Right to left runs are anchored at their right edge and laid out in reverse,
+        // matching glyph order of the raster canvas
+        let anchor = if *dir == Directionality::RTL {
+            attributes.push_str(" direction=\"rtl\" unicode-bidi=\"bidi-override\"");
+            x + width
+        } else {
+            x
+        };
+
+        if synthesis.bold() {
+            attributes.push_str(" font-weight=\"bold\"");
+        }
+        if synthesis.italic() {
+            attributes.push_str(" font-style=\"italic\"");
+        }
+        if synthesis.mirror() {
+            write!(attributes, " transform=\"matrix(-1 0 0 1 {} 0)\"", num(2.*x + width)).unwrap();
+        }
+
+        let font_family = format!("'{}', {}", typeface.font_family(), font_family);
+        self.push(format!("<text x=\"{}\" y=\"{}\" font-family=\"{}\" font-size=\"{}\" {}{}>{}</text>",
+                          num(anchor), num(baseline), escape(&font_family), num(size),
+                          paint("fill", color), attributes, escape(text)));
+    }
+}
+
+impl Canvas for SvgCanvas {
+    fn draw_text(&self, top_left: &Point, bound: &BoundingBox, text: &str, color: &Color, size: f32,
+                 dir: &Directionality, typeface: &TypeFace, synthesis: &FontSynthesis) {
+        if color.a() == 0 {
+            return;
+        }
+
+        let font = self.fonts.font(typeface);
+        let baseline = top_left.y() + bound.baseline_pos();
+        match self.glyph_mode {
+            SvgGlyphMode::Text { ref font_family } => {
+                self.text(text, top_left.x(), baseline, bound.width(), color, size, dir,
+                          typeface, synthesis, font_family);
+            },
+            SvgGlyphMode::Path => {
+                let scale = size / font.units_per_em() as f32;
+                let mut glyphs: Vec<u32> = text.chars()
+                    .map(|c| font.glyph_index(c as u32).unwrap_or(0))
+                    .collect();
+                if *dir == Directionality::RTL {
+                    glyphs.reverse();
+                }
+
+                let mut x = top_left.x();
+                for glyph in glyphs {
+                    self.glyph_path(font, glyph, &Point::new(x, baseline), color, size, synthesis);
+                    x += font.advance_width(glyph) as f32 * scale;
+                }
+            }
+        }
+    }
+
+    fn draw_glyph(&self, top_left: &Point, _: &BoundingBox, glyph_index: u32, color: &Color,
+                  size: f32, dir: &Directionality, typeface: &TypeFace, synthesis: &FontSynthesis) {
+        if color.a() == 0 {
+            return;
+        }
+
+        let font = self.fonts.font(typeface);
+        let scale = size / font.units_per_em() as f32;
+
+        // Placed by ink top, like the raster canvas
+        let baseline = match font.glyph_outline(glyph_index).bounds() {
+            Some(bounds) => top_left.y() + bounds.y_max() * scale,
+            None => return,
+        };
+
+        match (&self.glyph_mode, font.glyph_unicode(glyph_index)) {
+            (&SvgGlyphMode::Text { ref font_family }, Some(unicode)) => {
+                let text = ::std::char::from_u32(unicode).map(|c| c.to_string())
+                    .unwrap_or_default();
+                let width = font.advance_width(glyph_index) as f32 * scale;
+                self.text(&text, top_left.x(), baseline, width, color, size, dir, typeface,
+                          synthesis, font_family);
+            },
+            _ => self.glyph_path(font, glyph_index, &Point::new(top_left.x(), baseline), color,
+                                 size, synthesis),
+        }
+    }
+
+    fn draw_glyph_run(&self, top_left: &Point, bound: &BoundingBox, run: &GlyphRun, color: &Color,
+                      synthesis: &FontSynthesis) {
+        if color.a() == 0 {
+            return;
+        }
+
+        let baseline = top_left.y() + bound.baseline_pos();
+        let font = self.fonts.font(run.typeface());
+        match self.glyph_mode {
+            // Viewer shapes the text again, positions of the run are kept only in path mode
+            SvgGlyphMode::Text { ref font_family } if !has_substitutions(font, run) => {
+                self.text(run.text(), top_left.x(), baseline, run.width(), color, run.size(),
+                          run.dir(), run.typeface(), synthesis, font_family);
+            },
+            _ => {
+                let mut x = top_left.x();
+                for glyph in run.glyphs() {
+                    let origin = Point::new(x + glyph.x_offset(), baseline - glyph.y_offset());
+                    self.glyph_path(font, glyph.glyph_index(), &origin, color, run.size(),
+                                    synthesis);
+                    x += glyph.advance();
+                }
+            }
+        }
+    }
+
+    fn draw_rect(&self, top_left: &Point, rect: &Rect, color: &Color) {
+        if color.a() == 0 {
+            return;
+        }
+
+        self.push(format!("<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" {}/>",
+                          num(top_left.x()), num(top_left.y()), num(rect.width()),
+                          num(rect.height()), paint("fill", color)));
+    }
+
+    fn draw_rect_outline(&self, top_left: &Point, rect: &Rect, color: &Color, stroke_width: f32) {
+        if color.a() == 0 {
+            return;
+        }
+
+        self.push(format!("<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" {} \
+                           stroke-width=\"{}\"/>", num(top_left.x()), num(top_left.y()),
+                          num(rect.width()), num(rect.height()), paint("stroke", color),
+                          num(stroke_width)));
+    }
+
+    fn draw_line(&self, start: &Point, end: &Point, color: &Color, stroke_width: f32) {
+        if color.a() == 0 {
+            return;
+        }
+
+        self.push(format!("<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" {} stroke-width=\"{}\"/>",
+                          num(start.x()), num(start.y()), num(end.x()), num(end.y()),
+                          paint("stroke", color), num(stroke_width)));
+    }
+
+    fn as_any(&self) -> &Any {
+        self
+    }
+}
+
+/// Number with at most two decimals and no trailing zeros
+// Runs drawn with other glyphs than those of their characters, which a viewer shaping the text
+// would not draw
+fn has_substitutions(font: &OpenTypeFont, run: &GlyphRun) -> bool {
+    let mut shaped: Vec<u32> = run.glyphs().iter().map(|glyph| glyph.glyph_index()).collect();
+    let mut mapped: Vec<u32> = run.text().chars()
+        .map(|c| font.glyph_index(c as u32).unwrap_or(0))
+        .collect();
+
+    // Right to left runs hold glyphs in drawing order
+    shaped.sort();
+    mapped.sort();
+    shaped != mapped
+}
+
+fn num(value: f32) -> String {
+    let text = format!("{:.2}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" || text.is_empty() {
+        String::from("0")
+    } else {
+        text.to_string()
+    }
+}
+
+/// Color attribute, with opacity for translucent colors
+fn paint(attribute: &str, color: &Color) -> String {
+    let mut value = format!("{}=\"#{:02x}{:02x}{:02x}\"", attribute, color.r(), color.g(),
+                            color.b());
+    if color.a() < 255 {
+        write!(value, " {}-opacity=\"{}\"", attribute, num(color.a() as f32 / 255.)).unwrap();
+    }
+    value
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::elements::*;
+    use ::platform::Context;
+    use ::backend::raster::RasterPlatform;
+    use ::font::test::stix_math;
+    use ::props::Accent;
+    use ::test::fixture::draw_root;
+
+    fn render(element: &Element, glyph_mode: SvgGlyphMode) -> SvgCanvas {
+        let platform = RasterPlatform::new(stix_math());
+        let fonts = platform.fonts().clone();
+        let context = Context::new(Box::new(platform), 64.);
+
+        let drawable = draw_root(element, &context);
+
+        let canvas = SvgCanvas::new(fonts, drawable.bounding_box(), glyph_mode);
+        drawable.draw(&canvas, &Point::new(0., 0.));
+        canvas
+    }
+
+    #[test]
+    fn it_writes_outlines() {
+        let mfrac = Mfrac::new(Box::new(Mi::new(String::from("x"))),
+                               Box::new(Mi::new(String::from("y"))));
+        let canvas = render(&mfrac, SvgGlyphMode::Path);
+        let svg = canvas.to_svg();
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.contains(&format!("viewBox=\"0 0 {} {}\"", num(canvas.width),
+                                      num(canvas.height))));
+        assert!(canvas.baseline > 0.);
+        assert!(svg.contains(&format!("vertical-align: {}px", num(-canvas.baseline))));
+        assert_eq!(svg.matches("<path ").count(), 2);
+        assert_eq!(svg.matches("<line ").count(), 1);
+        assert!(!svg.contains("<text"));
+        assert!(svg.ends_with("</svg>\n"));
+    }
+
+    #[test]
+    fn it_writes_text() {
+        let mut mrow = Mrow::new();
+        mrow.with_child(Box::new(Mi::new(String::from("a"))))
+            .with_child(Box::new(Mo::new(String::from("<"))))
+            .with_child(Box::new(Mi::new(String::from("b"))));
+
+        let svg = render(&mrow, SvgGlyphMode::Text { font_family: String::from("serif") })
+            .to_svg();
+
+        assert_eq!(svg.matches("<text ").count(), 3);
+        assert!(svg.contains("font-family=\"'STIX Two Math', serif\""));
+        assert!(svg.contains(">&lt;</text>"));
+        assert!(svg.contains(">\u{1D44E}</text>"));
+        assert!(!svg.contains("<path"));
+    }
+
+    #[test]
+    fn it_writes_substituted_glyphs_as_paths() {
+        let text_mode = || SvgGlyphMode::Text { font_family: String::from("serif") };
+
+        // Dotless i under an accent
+        let mut accented = Munderover::new(Box::new(Mi::new(String::from("i"))),
+                                           Box::new(Mo::new(String::from("\u{307}"))),
+                                           Box::new(Mempty::new()));
+        accented.with_accent(Some(Accent::True));
+        let svg = render(&accented, text_mode()).to_svg();
+        assert_eq!(svg.matches("<path ").count(), 1);
+        assert!(!svg.contains(">\u{1D456}</text>"));
+
+        // Script style variant of the superscript
+        let msup = Msup::new(Box::new(Mi::new(String::from("a"))),
+                             Box::new(Mi::new(String::from("x"))));
+        let svg = render(&msup, text_mode()).to_svg();
+        assert_eq!(svg.matches("<text ").count(), 1);
+        assert!(svg.contains(">\u{1D44E}</text>"));
+        assert_eq!(svg.matches("<path ").count(), 1);
+    }
+
+    #[test]
+    fn it_formats_attributes() {
+        assert_eq!(num(1.), "1");
+        assert_eq!(num(-0.001), "0");
+        assert_eq!(num(2.25), "2.25");
+        assert_eq!(paint("fill", &Color::RGB(255, 0, 16)), "fill=\"#ff0010\"");
+        assert_eq!(paint("stroke", &Color::ARGB(128, 0, 0, 0)),
+                   "stroke=\"#000000\" stroke-opacity=\"0.5\"");
+    }
+}
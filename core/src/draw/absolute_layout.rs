@@ -15,28 +15,33 @@
 */
 
 
-use super::{Drawable, BoundingBox, MeasureMode};
+use super::{Drawable, BoundingBox, MeasureMode, HitResult, Highlight, BoxSnapshot};
 use ::platform::Context;
-use ::paint::{Canvas, Point, Rect};
+use ::paint::{Canvas, Point, Rect, Ink};
+use ::props::Directionality;
+use ::elements::InstanceId;
 use std::cmp::Ordering;
 use std::slice::{Iter, IterMut};
 
-pub struct AbsoluteLayout<'a> {
-    children: Vec<Child<'a>>,
+/// Places children at given positions, which may overlap. Children are drawn in the order they
+/// are added, later children over earlier ones.
+pub struct AbsoluteLayout {
+    children: Vec<Child>,
     bounding_box: BoundingBox,
 
     baseline: Option<f32>,
     axis: Option<f32>,
 
     calculate_child_bounds: bool,
+    dir: Directionality,
 }
 
-pub struct Child<'a> {
-    drawable: Box<Drawable+'a>,
+pub struct Child {
+    drawable: Box<Drawable>,
     params: AbsoluteLayoutParams
 }
 
-impl<'a> Child<'a> {
+impl Child {
     pub fn drawable(&self) -> &Drawable {
         self.drawable.as_ref()
     }
@@ -56,13 +61,42 @@ impl AbsoluteLayoutParams {
     }
 }
 
-impl<'a> Drawable for AbsoluteLayout<'a> {
+impl Drawable for AbsoluteLayout {
     fn draw(&self, canvas: &Canvas, pen_pos: &Point) {
         for child in &self.children {
-            child.drawable.draw(canvas, &(pen_pos+&child.params.position))
+            child.drawable.draw(canvas, &(pen_pos+&self.child_position(child)))
         }
     }
 
+    fn draw_highlighted(&self, canvas: &Canvas, pen_pos: &Point, highlight: &Highlight,
+                        selected: bool) {
+        for child in &self.children {
+            child.drawable.draw_highlighted(canvas, &(pen_pos+&self.child_position(child)),
+                                            highlight, selected)
+        }
+    }
+
+    // Later children are drawn over earlier ones, so they are hit first
+    fn hit_test(&self, pen_pos: &Point, point: &Point) -> Option<HitResult> {
+        self.children.iter().rev()
+            .filter_map(|child| child.drawable.hit_test(&(pen_pos+&self.child_position(child)),
+                                                        point))
+            .next()
+    }
+
+    fn locate(&self, pen_pos: &Point, instance_id: &InstanceId) -> Option<(Point, &Drawable)> {
+        self.children.iter()
+            .filter_map(|child| child.drawable.locate(&(pen_pos+&self.child_position(child)),
+                                                      instance_id))
+            .next()
+    }
+
+    fn to_snapshot(&self) -> BoxSnapshot {
+        BoxSnapshot::group(self.bounding_box.clone(), self.children.iter()
+            .map(|child| (self.child_position(child), child.drawable.to_snapshot()))
+            .collect())
+    }
+
     fn calculate(&mut self, context: &Context, _: &MeasureMode, _: &MeasureMode) {
         if self.calculate_child_bounds {
             for child in self.children.iter_mut() {
@@ -88,11 +122,18 @@ impl<'a> Drawable for AbsoluteLayout<'a> {
             Some(end_y_calc(c))
         }).unwrap_or(0f32);
 
-        self.bounding_box = BoundingBox {
-            rect: Rect::new(width, height),
-            baseline: self.baseline.unwrap_or(height),
-            axis: self.axis.unwrap_or(height/2f32),
-        }
+        let baseline = self.baseline.unwrap_or(height);
+        self.bounding_box = BoundingBox::new(
+            Rect::new(width, height),
+            baseline,
+            self.axis.unwrap_or(height/2f32),
+        );
+
+        // Mirrored positions depend on the width computed above
+        let ink = Ink::union_all(self.children.iter().filter_map(|child|
+            child.drawable.bounding_box().ink_in_parent(&self.child_position(child),
+                                                        height - baseline)));
+        self.bounding_box = self.bounding_box.clone().with_ink(ink);
     }
 
     fn bounding_box(&self) -> &BoundingBox {
@@ -100,18 +141,34 @@ impl<'a> Drawable for AbsoluteLayout<'a> {
     }
 }
 
-impl<'a> AbsoluteLayout<'a> {
-    pub fn new() -> AbsoluteLayout<'a> {
+impl AbsoluteLayout {
+    pub fn new() -> AbsoluteLayout {
         AbsoluteLayout {
             children: Vec::new(),
             bounding_box: BoundingBox::default(),
             baseline: None,
             axis: None,
-            calculate_child_bounds: true
+            calculate_child_bounds: true,
+            dir: Directionality::LTR,
+        }
+    }
+
+    /// Positions of children are mirrored horizontally when direction is right to left
+    pub fn set_dir(&mut self, dir: Directionality) {
+        self.dir = dir;
+    }
+
+    pub fn child_position(&self, child: &Child) -> Point {
+        match self.dir {
+            Directionality::LTR => child.params.position.clone(),
+            Directionality::RTL => Point::new(
+                self.bounding_box.width() - child.params.position.x() - child.drawable.bounding_box().width(),
+                child.params.position.y()
+            )
         }
     }
 
-    pub fn add_child(&mut self, child: Box<Drawable + 'a>, params: AbsoluteLayoutParams) {
+    pub fn add_child(&mut self, child: Box<Drawable>, params: AbsoluteLayoutParams) {
         self.children.push(Child { drawable: child, params });
     }
 
@@ -137,11 +194,11 @@ impl<'a> AbsoluteLayout<'a> {
         }
     }
 
-    pub fn iter(&self) -> Iter<Child<'a>> {
+    pub fn iter(&self) -> Iter<Child> {
         self.children.iter()
     }
 
-    pub fn iter_mut(&mut self) -> IterMut<Child<'a>> {
+    pub fn iter_mut(&mut self) -> IterMut<Child> {
         self.children.iter_mut()
     }
 
@@ -152,4 +209,65 @@ impl<'a> AbsoluteLayout<'a> {
     pub fn is_calculating_child_bounds(&self) -> bool {
         self.calculate_child_bounds
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::*;
+    use ::platform::test::test_context;
+    use ::backend::display_list::{DisplayList, DrawCommand};
+    use ::props::Color;
+
+    #[test]
+    fn it_draws_overlapping_children_in_order() {
+        let test_context = test_context();
+        let mut al = AbsoluteLayout::new();
+
+        let mut first = Fixed::new(20., 10., 5., 5.);
+        first.background = Color::RGB(255, 0, 0);
+        let mut last = Fixed::new(10., 10., 5., 5.);
+        last.background = Color::RGB(0, 0, 255);
+
+        al.add_child(Box::new(first), AbsoluteLayoutParams::new(Point::new(0., 0.)));
+        al.add_child(Box::new(Space::new(-4., 0., 0., 0.)),
+                     AbsoluteLayoutParams::new(Point::new(12., 0.)));
+        al.add_child(Box::new(last), AbsoluteLayoutParams::new(Point::new(15., 0.)));
+        al.calculate(&test_context, &MeasureMode::Wrap, &MeasureMode::Wrap);
+
+        // Negative extents end before the position of the child, within the other children
+        assert_eq!(al.bounding_box().width(), 25.);
+        assert_eq!(al.bounding_box().height(), 10.);
+
+        let list = DisplayList::new(al.bounding_box());
+        al.draw(&list, &Point::new(0., 0.));
+        let colors = list.commands().iter().filter_map(|command| match *command {
+            DrawCommand::Rect { ref color, .. } => Some(color.clone()),
+            _ => None,
+        }).collect::<Vec<Color>>();
+        assert_eq!(colors, vec![Color::RGB(255, 0, 0), Color::RGB(0, 0, 255)]);
+    }
+
+    #[test]
+    fn it_mirrors_children_in_rtl() {
+        let test_context = test_context();
+        let mut al = AbsoluteLayout::new();
+
+        al.add_child(Box::new(Fixed::new(10., 20., 10., 10.)),
+                     AbsoluteLayoutParams::new(Point::new(0., 0.)));
+        al.add_child(Box::new(Fixed::new(20., 10., 5., 5.)),
+                     AbsoluteLayoutParams::new(Point::new(15., 5.)));
+        al.calculate(&test_context, &MeasureMode::Wrap, &MeasureMode::Wrap);
+
+        let positions = |al: &AbsoluteLayout| al.iter().map(|c| al.child_position(c)).collect::<Vec<Point>>();
+
+        assert_eq!(al.bounding_box().width(), 35.);
+        assert_eq!(positions(&al), vec![Point::new(0., 0.), Point::new(15., 5.)]);
+
+        al.set_dir(Directionality::RTL);
+        al.calculate(&test_context, &MeasureMode::Wrap, &MeasureMode::Wrap);
+
+        assert_eq!(al.bounding_box().width(), 35.);
+        assert_eq!(positions(&al), vec![Point::new(25., 0.), Point::new(0., 5.)]);
+    }
+}
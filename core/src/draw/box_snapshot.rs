@@ -0,0 +1,438 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+
+
+use super::{Drawable, BoundingBox, MeasureMode, HitTarget, HitResult, Highlight};
+use ::backend::display_list::{DisplayList, DrawCommand};
+use ::elements::InstanceId;
+use ::paint::{Canvas, Point};
+use ::platform::Context;
+use ::props::Color;
+
+/// Snapshot of a calculated drawable, holding everything the drawable needs to draw and answer
+/// queries, and snapshots of inner drawables at their positions. Drawables from layout are owned
+/// and can be measured again, while a snapshot is fixed at the size it was calculated with, so
+/// it can be cloned and sent to other threads but cannot be stretched by its parent.
+///
+/// Snapshots are drawables themselves, which draw, hit test and locate elements like the
+/// drawables they were taken of.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoxSnapshot {
+    bounding_box: BoundingBox,
+    content: BoxContent,
+    text: Option<TextStops>,
+
+    // Children with positions of their top left corners in this box, in drawing order
+    children: Vec<(Point, BoxSnapshot)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum BoxContent {
+    /// Box of an element, filled with its background and hit by points not in inner elements
+    Element { target: Option<HitTarget>, background: Color },
+    /// Box an element is located at but never hit, like an empty slot
+    Slot { target: Option<HitTarget> },
+    /// Drawing calls recorded from a drawable drawn at the top left corner of the box
+    Paint(Vec<DrawCommand>),
+    /// Box drawing only its children
+    Group,
+}
+
+/// Positions of characters of token text in a box, for hit testing and carets
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextStops {
+    glyphs: Vec<GlyphStop>,
+    // Caret after the text
+    end: f32,
+}
+
+/// Glyph of token text, with its offset in characters and the caret before them. Positions are
+/// from the left of the box.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlyphStop {
+    left: f32,
+    advance: f32,
+    offset: usize,
+    caret: f32,
+}
+
+impl BoxSnapshot {
+    /// Snapshot recording what `drawable` draws, for drawables without inner drawables
+    pub(crate) fn painted<T: Drawable + ?Sized>(drawable: &T) -> BoxSnapshot {
+        let list = DisplayList::new(drawable.bounding_box());
+        drawable.draw(&list, &Point::new(0., 0.));
+        let commands = list.commands().clone();
+        BoxSnapshot::new(drawable.bounding_box().clone(), BoxContent::Paint(commands))
+    }
+
+    /// Snapshot drawing `children` placed at their positions
+    pub(crate) fn group(bounding_box: BoundingBox, children: Vec<(Point, BoxSnapshot)>)
+                        -> BoxSnapshot {
+        let mut snapshot = BoxSnapshot::new(bounding_box, BoxContent::Group);
+        snapshot.children = children;
+        snapshot
+    }
+
+    /// Snapshot of the element `target` with its background, around `content`
+    pub(crate) fn element(bounding_box: BoundingBox, target: Option<HitTarget>, background: Color,
+                          content: Option<BoxSnapshot>) -> BoxSnapshot {
+        let mut snapshot = BoxSnapshot::new(bounding_box,
+                                            BoxContent::Element { target, background });
+        if let Some(content) = content {
+            snapshot.text = content.text.clone();
+            snapshot.children.push((Point::new(0., 0.), content));
+        }
+        snapshot
+    }
+
+    /// Empty snapshot the element `target` is located at
+    pub(crate) fn slot(bounding_box: BoundingBox, target: Option<HitTarget>) -> BoxSnapshot {
+        BoxSnapshot::new(bounding_box, BoxContent::Slot { target })
+    }
+
+    /// Sets text stops of the snapshot, in its own coordinates
+    pub(crate) fn with_text(mut self, text: Option<TextStops>) -> BoxSnapshot {
+        self.text = text;
+        self
+    }
+
+    pub fn children(&self) -> &[(Point, BoxSnapshot)] {
+        &self.children
+    }
+
+    /// Text stops of the snapshot, when it is a token or the box of its text
+    pub fn text(&self) -> Option<&TextStops> {
+        self.text.as_ref()
+    }
+
+    /// Element the snapshot was taken for
+    pub fn hit_target(&self) -> Option<&HitTarget> {
+        match self.content {
+            BoxContent::Element { ref target, .. } | BoxContent::Slot { ref target } =>
+                target.as_ref(),
+            _ => None,
+        }
+    }
+
+    fn new(bounding_box: BoundingBox, content: BoxContent) -> BoxSnapshot {
+        BoxSnapshot { bounding_box, content, text: None, children: Vec::new() }
+    }
+}
+
+impl Drawable for BoxSnapshot {
+    fn draw(&self, canvas: &Canvas, pen_pos: &Point) {
+        self.draw_highlighted(canvas, pen_pos, &Highlight::none(), false);
+    }
+
+    fn draw_highlighted(&self, canvas: &Canvas, pen_pos: &Point, highlight: &Highlight,
+                        selected: bool) {
+        let mut selected = selected;
+        match self.content {
+            BoxContent::Element { ref target, ref background } => {
+                let is_selected = !selected && target.as_ref()
+                    .map_or(false, |target| highlight.is_selected(target.instance_id()));
+
+                if !self.bounding_box.rect().is_empty() {
+                    canvas.draw_rect(pen_pos, self.bounding_box.rect(), background);
+                    if is_selected || (selected && background.a() > 0) {
+                        canvas.draw_rect(pen_pos, self.bounding_box.rect(), highlight.color());
+                    }
+                }
+                selected = selected || is_selected;
+            },
+            BoxContent::Paint(ref commands) => {
+                for command in commands.iter() {
+                    command.draw(canvas, pen_pos);
+                }
+            },
+            BoxContent::Slot { .. } | BoxContent::Group => {},
+        }
+
+        for (position, child) in self.children.iter() {
+            child.draw_highlighted(canvas, &(pen_pos + position), highlight, selected);
+        }
+    }
+
+    fn calculate(&mut self, _: &Context, _: &MeasureMode, _: &MeasureMode) {
+        // do nothing, snapshots are taken of calculated drawables
+    }
+
+    fn bounding_box(&self) -> &BoundingBox {
+        &self.bounding_box
+    }
+
+    // Later children are drawn over earlier ones, so they are hit first
+    fn hit_test(&self, pen_pos: &Point, point: &Point) -> Option<HitResult> {
+        let hit = self.children.iter().rev()
+            .filter_map(|(position, child)| child.hit_test(&(pen_pos + position), point))
+            .next();
+        if hit.is_some() {
+            return hit;
+        }
+
+        match self.content {
+            BoxContent::Element { target: Some(ref target), .. }
+            if self.bounding_box.contains(pen_pos, point) => {
+                let offset = if target.element_type().is_token() {
+                    self.text_offset(pen_pos, point)
+                } else {
+                    None
+                };
+                Some(HitResult::new(target.clone(), pen_pos.clone(), self.bounding_box.clone(),
+                                    offset))
+            },
+            _ => None
+        }
+    }
+
+    fn text_offset(&self, pen_pos: &Point, point: &Point) -> Option<usize> {
+        self.text.as_ref().and_then(|text| text.offset_at(point.x() - pen_pos.x()))
+    }
+
+    fn locate(&self, pen_pos: &Point, instance_id: &InstanceId) -> Option<(Point, &Drawable)> {
+        let found = self.children.iter()
+            .filter_map(|(position, child)| child.locate(&(pen_pos + position),
+                                                                  instance_id))
+            .next();
+        if found.is_some() {
+            return found;
+        }
+
+        match self.hit_target() {
+            Some(target) if target.instance_id() == instance_id => Some((pen_pos.clone(), self)),
+            _ => None
+        }
+    }
+
+    fn caret_x(&self, pen_pos: &Point, offset: usize) -> Option<f32> {
+        self.text.as_ref().map(|text| pen_pos.x() + text.caret_x(offset))
+    }
+
+    fn to_snapshot(&self) -> BoxSnapshot {
+        self.clone()
+    }
+}
+
+impl TextStops {
+    pub(crate) fn new(glyphs: Vec<GlyphStop>, end: f32) -> TextStops {
+        TextStops { glyphs, end }
+    }
+
+    /// Offset of the characters of the glyph nearest to `x`, if there is any glyph
+    pub fn offset_at(&self, x: f32) -> Option<usize> {
+        let mut nearest: Option<(f32, usize)> = None;
+        for glyph in self.glyphs.iter() {
+            let distance = if x < glyph.left {
+                glyph.left - x
+            } else {
+                (x - glyph.left - glyph.advance).max(0.)
+            };
+
+            if nearest.map(|(nearest, _)| distance < nearest).unwrap_or(true) {
+                nearest = Some((distance, glyph.offset));
+            }
+        }
+
+        nearest.map(|(_, offset)| offset)
+    }
+
+    /// Position of a caret before the character at `offset`, or after the text when no glyph
+    /// starts there
+    pub fn caret_x(&self, offset: usize) -> f32 {
+        self.glyphs.iter()
+            .find(|glyph| glyph.offset == offset)
+            .map_or(self.end, |glyph| glyph.caret)
+    }
+
+    /// Stops moved right by `dx`
+    pub(crate) fn translate(&self, dx: f32) -> TextStops {
+        TextStops {
+            glyphs: self.glyphs.iter()
+                .map(|glyph| GlyphStop { left: glyph.left + dx, caret: glyph.caret + dx, ..*glyph })
+                .collect(),
+            end: self.end + dx,
+        }
+    }
+}
+
+impl GlyphStop {
+    pub(crate) fn new(left: f32, advance: f32, offset: usize, caret: f32) -> GlyphStop {
+        GlyphStop { left, advance, offset, caret }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::thread;
+    use ::elements::*;
+    use ::props::{Accent, Directionality};
+    use ::test::fixture::{stix_context, layout_root};
+
+    fn equation(dir: Directionality) -> Mrow {
+        let mut sum = Munderover::new(Box::new(Mo::new(String::from("∑"))),
+                                      Box::new(Mi::new(String::from("n"))),
+                                      Box::new(Mn::new(String::from("0"))));
+        sum.with_accent(Some(Accent::False));
+
+        let mut scripts = Mmultiscripts::new(Box::new(Mi::new(String::from("T"))));
+        scripts.with_prescript(Mmultiscript {
+            superscript: Box::new(Mn::new(String::from("1"))),
+            subscript: Box::new(Mempty::new()),
+        });
+
+        let mut mrow = Mrow::new();
+        mrow.with_dir(Some(dir))
+            .with_child(Box::new(sum))
+            .with_child(Box::new(Mfrac::new(Box::new(Mi::new(String::from("xy"))),
+                                            Box::new(Msqrt::new(Box::new(Placeholder::new()))))))
+            .with_child(Box::new(Mo::new(String::from("→"))))
+            .with_child(Box::new(Msubsup::new(Box::new(scripts),
+                                              Box::new(Mn::new(String::from("2"))),
+                                              Box::new(Mtext::new(String::from("ab"))))))
+            .with_child(Box::new(Mpadded::new(Box::new(Mn::new(String::from("123"))))));
+        mrow
+    }
+
+    fn instance_ids(element: &Element, ids: &mut Vec<InstanceId>) {
+        ids.push(element.instance_id().clone());
+        for child in element.child_elements() {
+            instance_ids(child, ids);
+        }
+    }
+
+    fn commands(drawable: &Drawable, highlight: &Highlight) -> Vec<DrawCommand> {
+        let list = DisplayList::new(drawable.bounding_box());
+        drawable.draw_highlighted(&list, &Point::new(3., 5.), highlight, false);
+        let commands = list.commands().clone();
+        commands
+    }
+
+    // Commands with positions moved to the origin, and the positions, which are summed in
+    // another order when drawn from snapshots
+    fn split_positions(command: &DrawCommand) -> (DrawCommand, Vec<Point>) {
+        let mut command = command.clone();
+        let positions = match command {
+            DrawCommand::Text { ref mut top_left, .. } |
+            DrawCommand::Glyph { ref mut top_left, .. } |
+            DrawCommand::GlyphRun { ref mut top_left, .. } |
+            DrawCommand::Rect { ref mut top_left, .. } |
+            DrawCommand::RectOutline { ref mut top_left, .. } =>
+                vec![::std::mem::replace(top_left, Point::new(0., 0.))],
+            DrawCommand::Line { ref mut start, ref mut end, .. } =>
+                vec![::std::mem::replace(start, Point::new(0., 0.)),
+                     ::std::mem::replace(end, Point::new(0., 0.))],
+        };
+        (command, positions)
+    }
+
+    fn assert_same_drawing(left: &[DrawCommand], right: &[DrawCommand]) {
+        assert_eq!(left.len(), right.len());
+        for (left, right) in left.iter().zip(right.iter()) {
+            let (left, left_positions) = split_positions(left);
+            let (right, right_positions) = split_positions(right);
+            assert_eq!(left, right);
+            for (left, right) in left_positions.iter().zip(right_positions.iter()) {
+                assert!((left.x() - right.x()).abs() < 1e-3 && (left.y() - right.y()).abs() < 1e-3,
+                        "{:?} != {:?}", left, right);
+            }
+        }
+    }
+
+    #[test]
+    fn it_keeps_drawables_behavior() {
+        let context = stix_context();
+
+        for dir in [Directionality::LTR, Directionality::RTL].iter() {
+            let mrow = equation(dir.clone());
+            let layout = layout_root(&mrow, &context);
+            let drawable = layout.layout(&context);
+
+            // Snapshots outlive their layouts and move across threads
+            let snapshot = thread::spawn({
+                let snapshot = layout.snapshot(&context);
+                move || snapshot
+            }).join().unwrap();
+            assert_eq!(snapshot.bounding_box(), drawable.bounding_box());
+
+            let mut ids = Vec::new();
+            instance_ids(&mrow, &mut ids);
+            let selection = Highlight::new(vec![ids[1].clone()], Color::RGB(255, 0, 0));
+            for highlight in [Highlight::none(), selection].iter() {
+                assert_same_drawing(&commands(&snapshot, highlight),
+                                    &commands(drawable.as_ref(), highlight));
+            }
+
+            let pen_pos = Point::new(3., 5.);
+            for id in ids.iter() {
+                let expected = drawable.locate(&pen_pos, id)
+                    .map(|(pos, found)| (pos, found.bounding_box().clone(),
+                                         found.caret_x(&pen_pos, 1)));
+                let located = snapshot.locate(&pen_pos, id)
+                    .map(|(pos, found)| (pos, found.bounding_box().clone(),
+                                         found.caret_x(&pen_pos, 1)));
+                assert_eq!(located, expected);
+            }
+
+            let bounds = drawable.bounding_box();
+            let mut text_hits = 0;
+            for x in (0..(bounds.width() as i32 + 10)).step_by(2) {
+                for y in (0..(bounds.height() as i32 + 10)).step_by(3) {
+                    let point = Point::new(x as f32, y as f32);
+                    let hit = drawable.hit_test(&pen_pos, &point);
+                    if hit.as_ref().and_then(|hit| hit.offset()).unwrap_or(0) > 0 {
+                        text_hits += 1;
+                    }
+                    assert_eq!(snapshot.hit_test(&pen_pos, &point), hit);
+                }
+            }
+            assert!(text_hits > 0);
+        }
+    }
+
+    #[test]
+    fn it_keeps_drawables_after_layout() {
+        let context = stix_context();
+        let mrow = equation(Directionality::LTR);
+        let layout = layout_root(&mrow, &context);
+        let snapshot = layout.snapshot(&context);
+
+        // Drawables own what they draw and are measured again after elements are dropped
+        let mut drawable = layout.layout(&context);
+        drop(layout);
+        drop(mrow);
+        drawable.calculate(&context, &MeasureMode::Wrap, &MeasureMode::Wrap);
+
+        assert_eq!(drawable.bounding_box(), snapshot.bounding_box());
+        assert_same_drawing(&commands(drawable.as_ref(), &Highlight::none()),
+                            &commands(&snapshot, &Highlight::none()));
+    }
+
+    #[test]
+    fn it_finds_text_stops() {
+        let stops = TextStops::new(vec![GlyphStop::new(0., 10., 0, 0.),
+                                        GlyphStop::new(10., 5., 2, 10.)], 15.);
+
+        assert_eq!(stops.offset_at(-4.), Some(0));
+        assert_eq!(stops.offset_at(12.), Some(2));
+        assert_eq!(stops.offset_at(40.), Some(2));
+        assert_eq!(stops.caret_x(2), 10.);
+        assert_eq!(stops.caret_x(3), 15.);
+        assert_eq!(stops.translate(5.).caret_x(0), 5.);
+        assert_eq!(TextStops::new(Vec::new(), 0.).offset_at(1.), None);
+    }
+}
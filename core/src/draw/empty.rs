@@ -15,17 +15,24 @@
 */
 
 
-use super::{Drawable, BoundingBox, MeasureMode};
+use super::{Drawable, BoundingBox, MeasureMode, HitTarget, BoxSnapshot};
 use ::paint::{Point, Canvas};
 use ::platform::Context;
+use ::elements::InstanceId;
 
 pub struct Empty {
     bounding_box: BoundingBox,
+    hit_target: Option<HitTarget>,
 }
 
 impl Empty {
     pub fn new() -> Empty {
-        Empty { bounding_box: BoundingBox::default() }
+        Empty { bounding_box: BoundingBox::default(), hit_target: None }
+    }
+
+    /// Element of an empty slot, which is located though it has no size
+    pub fn set_hit_target(&mut self, target: Option<HitTarget>) {
+        self.hit_target = target;
     }
 }
 
@@ -41,4 +48,16 @@ impl Drawable for Empty {
     fn bounding_box(&self) -> &BoundingBox {
         &self.bounding_box
     }
+
+    fn locate(&self, pen_pos: &Point, instance_id: &InstanceId) -> Option<(Point, &Drawable)> {
+        match self.hit_target {
+            Some(ref target) if target.instance_id() == instance_id =>
+                Some((pen_pos.clone(), self)),
+            _ => None
+        }
+    }
+
+    fn to_snapshot(&self) -> BoxSnapshot {
+        BoxSnapshot::slot(self.bounding_box.clone(), self.hit_target.clone())
+    }
 }
\ No newline at end of file
@@ -57,11 +57,7 @@ impl Drawable for Fixed {
             self.height
         };
 
-        self.bounding_box = BoundingBox {
-            rect: Rect::new(width, height),
-            baseline: self.baseline,
-            axis: self.axis,
-        }
+        self.bounding_box = BoundingBox::new(Rect::new(width, height), self.baseline, self.axis);
     }
 
     fn bounding_box(&self) -> &BoundingBox {
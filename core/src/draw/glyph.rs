@@ -18,8 +18,7 @@
 use std::char::from_u32;
 
 use super::{Drawable, BoundingBox, MeasureMode};
-use ::paint::{Point, Canvas, Rect};
-use ::layout::Layout;
+use ::paint::{Point, Canvas, Rect, FontSynthesis, TypeFace, MathRuler};
 use ::platform::Context;
 use ::props::{Color, Directionality};
 
@@ -32,64 +31,93 @@ pub enum GlyphIndex {
     Index(u32),
 }
 
-pub struct Glyph<'a, T: Layout + 'a> {
+pub struct Glyph {
     glyph_index: GlyphIndex,
-    element: &'a T,
-    size_reader: SizeReader<T>,
-    dir_reader: DirReader<T>,
-    color_reader: ColorReader<T>,
+    element_size: f32,
+    dir: Directionality,
+    color: Color,
 
     bounding_box: BoundingBox,
 
     advance: Option<f32>,
+    size: Option<f32>,
+    synthesis: FontSynthesis,
+    typeface: TypeFace,
+    script_level: u32,
+    flattened_accent: bool,
+    substitute: Option<u32>,
 }
 
-impl<'a, T: Layout + 'a> Drawable for Glyph<'a, T> {
+impl Drawable for Glyph {
     fn draw(&self, canvas: &Canvas, pen_pos: &Point) {
-        match self.glyph_index {
-            GlyphIndex::Index(index) => {
+        match (self.substitute, &self.glyph_index) {
+            (Some(index), _) | (None, &GlyphIndex::Index(index)) => {
                 canvas.draw_glyph(pen_pos, &self.bounding_box, index,
-                                  (self.color_reader)(self.element),
-                                  (self.size_reader)(self.element),
-                                  (self.dir_reader)(self.element));
+                                  &self.color,
+                                  self.size(),
+                                  &self.dir,
+                                  &self.typeface,
+                                  &self.synthesis);
             },
-            GlyphIndex::Char(unicode) => {
+            (None, &GlyphIndex::Char(unicode)) => {
                 canvas.draw_text(pen_pos, &self.bounding_box,
                                  &from_u32(unicode).unwrap().to_string(),
-                                 (self.color_reader)(self.element),
-                                 (self.size_reader)(self.element),
-                                 (self.dir_reader)(self.element));
+                                 &self.color,
+                                 self.size(),
+                                 &self.dir,
+                                 &self.typeface,
+                                 &self.synthesis);
             }
         }
     }
 
     fn calculate(&mut self, context: &Context, _: &MeasureMode, _: &MeasureMode) {
-        let base_size = (self.size_reader)(self.element);
-        let ruler = context.platform().get_math_ruler(base_size);
-        let dir = (self.dir_reader)(self.element);
-
-        let bounds = match self.glyph_index {
-            GlyphIndex::Char(unicode) => ruler.measure_char(unicode, dir),
-            GlyphIndex::Index(index) => ruler.measure_glyph(index, dir),
+        let base_size = self.size();
+        let platform = context.platform();
+        let dir = &self.dir;
+
+        // Glyph indices are those of the math font, characters may come from any face having them
+        let math_typeface = platform.math_typeface();
+        self.typeface = match self.glyph_index {
+            GlyphIndex::Char(unicode) => platform.typeface_for_char(math_typeface, unicode)
+                .unwrap_or_else(|| math_typeface.clone()),
+            GlyphIndex::Index(_) => math_typeface.clone(),
         };
 
-        let factor = if bounds.height() > base_size {
-            bounds.height() / base_size
+        let ruler = platform.get_math_ruler(base_size);
+        self.substitute = if self.typeface == *math_typeface {
+            self.math_font_substitute(ruler, dir)
         } else {
-            1.
+            None
         };
 
-        let bounds = if let Some(advance) = self.advance {
-            Rect::new(bounds.width(), advance)
-        } else {
-            bounds
+        let (width, ink) = match (self.substitute, &self.glyph_index) {
+            (Some(index), _) | (None, &GlyphIndex::Index(index)) =>
+                (ruler.measure_glyph(index, dir).width(), ruler.glyph_ink(index)),
+            (None, &GlyphIndex::Char(unicode)) => {
+                let char_ruler = platform.get_typeface_ruler(&self.typeface, base_size);
+                let run = char_ruler.shape(&from_u32(unicode).unwrap().to_string(), dir, None);
+                (run.width(), char_ruler.run_ink(&run))
+            },
         };
 
-        self.bounding_box = BoundingBox {
-            rect: bounds,
-            baseline: -ruler.descent()*factor,
-            axis: (ruler.axis_height()-ruler.descent())*factor,
+        let ink_ascent = ink.as_ref().map(|ink| ink.ascent()).unwrap_or(0.);
+        let ink_descent = ink.as_ref().map(|ink| ink.descent()).unwrap_or(0.);
+
+        // Parts of a vertical assembly fill their advance from the bottom of their ink, other
+        // glyphs span the font ascent and descent and any ink beyond them
+        let (height, depth) = if let Some(advance) = self.advance {
+            (advance, -ink_descent)
+        } else {
+            (ruler.ascent().max(ink_ascent) - ruler.descent().min(ink_descent),
+             -ruler.descent().min(ink_descent))
         };
+
+        self.bounding_box = BoundingBox::new(
+            Rect::new(width, height),
+            depth,
+            ruler.axis_height() + depth,
+        ).with_ink(ink);
     }
 
     fn bounding_box(&self) -> &BoundingBox {
@@ -97,21 +125,75 @@ impl<'a, T: Layout + 'a> Drawable for Glyph<'a, T> {
     }
 }
 
-impl<'a, T: Layout + 'a> Glyph<'a, T> {
-    pub fn new(element: &'a T, glyph_index: GlyphIndex, size_reader: SizeReader<T>,
-               color_reader: ColorReader<T>, dir_reader: DirReader<T>) -> Glyph<'a, T> {
+impl Glyph {
+    pub fn new<T>(element: &T, glyph_index: GlyphIndex, size_reader: SizeReader<T>,
+                  color_reader: ColorReader<T>, dir_reader: DirReader<T>) -> Glyph {
         Glyph {
             glyph_index,
-            element,
-            size_reader,
-            dir_reader,
-            color_reader,
+            element_size: size_reader(element),
+            dir: dir_reader(element).clone(),
+            color: color_reader(element).clone(),
             bounding_box: BoundingBox::default(),
-            advance: None
+            advance: None,
+            size: None,
+            synthesis: FontSynthesis::none(),
+            typeface: TypeFace::default(),
+            script_level: 0,
+            flattened_accent: false,
+            substitute: None,
         }
     }
 
     pub fn set_advance(&mut self, advance: Option<f32>) {
         self.advance = advance;
     }
+
+    pub fn set_synthesis(&mut self, synthesis: FontSynthesis) {
+        self.synthesis = synthesis;
+    }
+
+    /// Font size overriding the size read from element, for glyphs scaled to a size
+    pub fn set_size(&mut self, size: Option<f32>) {
+        self.size = size;
+    }
+
+    /// Script level selecting the script style variant of the glyph
+    pub fn set_script_level(&mut self, script_level: u32) {
+        self.script_level = script_level;
+    }
+
+    /// Draws the flattened form of an accent, for accents over tall bases
+    pub fn set_flattened_accent(&mut self, flattened_accent: bool) {
+        self.flattened_accent = flattened_accent;
+    }
+
+    // Flattened accent and script style forms of a glyph of the math font, none when it is
+    // drawn as is
+    fn math_font_substitute(&self, ruler: &MathRuler, dir: &Directionality) -> Option<u32> {
+        if !self.flattened_accent && self.script_level == 0 {
+            return None;
+        }
+
+        let nominal = match self.glyph_index {
+            GlyphIndex::Index(index) => index,
+            GlyphIndex::Char(unicode) => ruler.shape(&from_u32(unicode)?.to_string(), dir, None)
+                .glyphs().first()?.glyph_index(),
+        };
+
+        let mut glyph_index = nominal;
+        if self.flattened_accent {
+            glyph_index = ruler.flattened_accent_glyph(glyph_index).unwrap_or(glyph_index);
+        }
+        glyph_index = ruler.script_style_glyph(glyph_index, self.script_level).unwrap_or(glyph_index);
+
+        if glyph_index == nominal {
+            None
+        } else {
+            Some(glyph_index)
+        }
+    }
+
+    fn size(&self) -> f32 {
+        self.size.unwrap_or(self.element_size)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,57 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+
+
+use super::Drawable;
+use ::elements::InstanceId;
+use ::props::Color;
+use ::paint::{Canvas, Point};
+
+/// Selection painted while drawing, over the backgrounds of the selected elements and under
+/// their content
+pub struct Highlight {
+    instance_ids: Vec<InstanceId>,
+    color: Color,
+}
+
+impl Highlight {
+    pub fn new(instance_ids: Vec<InstanceId>, color: Color) -> Highlight {
+        Highlight { instance_ids, color }
+    }
+
+    /// Highlight which selects nothing
+    pub fn none() -> Highlight {
+        Highlight::new(Vec::new(), Color::transparent())
+    }
+
+    pub fn instance_ids(&self) -> &[InstanceId] {
+        &self.instance_ids
+    }
+
+    pub fn color(&self) -> &Color {
+        &self.color
+    }
+
+    pub fn is_selected(&self, instance_id: &InstanceId) -> bool {
+        self.instance_ids.contains(instance_id)
+    }
+
+    /// Draws `drawable` at `pen_pos` with this highlight
+    pub fn draw(&self, drawable: &Drawable, canvas: &Canvas, pen_pos: &Point) {
+        drawable.draw_highlighted(canvas, pen_pos, self, false);
+    }
+}
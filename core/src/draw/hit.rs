@@ -0,0 +1,79 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+
+
+use super::BoundingBox;
+use ::elements::{InstanceId, ElementType};
+use ::paint::Point;
+
+/// Element a drawable was laid out for, reported when it is hit
+#[derive(Debug, Clone, PartialEq)]
+pub struct HitTarget {
+    instance_id: InstanceId,
+    element_type: ElementType,
+}
+
+impl HitTarget {
+    pub fn new(instance_id: InstanceId, element_type: ElementType) -> HitTarget {
+        HitTarget { instance_id, element_type }
+    }
+
+    pub fn instance_id(&self) -> &InstanceId {
+        &self.instance_id
+    }
+
+    pub fn element_type(&self) -> ElementType {
+        self.element_type
+    }
+}
+
+/// Innermost element under a point, with its box in the coordinates the root was drawn in
+#[derive(Debug, Clone, PartialEq)]
+pub struct HitResult {
+    target: HitTarget,
+    top_left: Point,
+    bounding_box: BoundingBox,
+    offset: Option<usize>,
+}
+
+impl HitResult {
+    pub fn new(target: HitTarget, top_left: Point, bounding_box: BoundingBox,
+               offset: Option<usize>) -> HitResult {
+        HitResult { target, top_left, bounding_box, offset }
+    }
+
+    pub fn instance_id(&self) -> &InstanceId {
+        self.target.instance_id()
+    }
+
+    pub fn element_type(&self) -> ElementType {
+        self.target.element_type()
+    }
+
+    pub fn top_left(&self) -> &Point {
+        &self.top_left
+    }
+
+    pub fn bounding_box(&self) -> &BoundingBox {
+        &self.bounding_box
+    }
+
+    /// Offset in characters of the character under the point, for token elements drawing text
+    pub fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+}
@@ -16,7 +16,6 @@
 
 
 use super::{Drawable, BoundingBox, MeasureMode};
-use ::layout::{Layout};
 use ::paint::{Canvas, Point, Rect};
 use ::platform::Context;
 use ::props::{Color};
@@ -29,24 +28,23 @@ pub enum LineParam {
     Horizontal { y: f32 },
 }
 
-pub struct Line<'a, T: Layout + 'a> {
+pub struct Line {
     param: LineParam,
 
-    element: &'a T,
     stroke_width: f32,
-    color_reader: ColorReader<T>,
+    color: Color,
 
     bounding_box: BoundingBox,
     start: Point,
     end: Point,
 }
 
-impl<'a, T: Layout + 'a> Drawable for Line<'a, T> {
+impl Drawable for Line {
     fn draw(&self, canvas: &Canvas, pen_pos: &Point) {
         canvas.draw_line(
             &(&self.start+pen_pos),
             &(&self.end+pen_pos),
-            (self.color_reader)(self.element),
+            &self.color,
             self.stroke_width,
         )
     }
@@ -91,11 +89,7 @@ impl<'a, T: Layout + 'a> Drawable for Line<'a, T> {
         self.start = &start+&Point::new(x_diff, y_diff);
         self.end = &end+&Point::new(x_diff, y_diff);
 
-        self.bounding_box = BoundingBox {
-            rect: Rect::new(width, height),
-            baseline: height/2.,
-            axis: height/2.,
-        }
+        self.bounding_box = BoundingBox::new(Rect::new(width, height), height/2., height/2.);
     }
 
     fn bounding_box(&self) -> &BoundingBox {
@@ -103,14 +97,13 @@ impl<'a, T: Layout + 'a> Drawable for Line<'a, T> {
     }
 }
 
-impl<'a, T: Layout + 'a> Line<'a, T> {
-    pub fn new(param: LineParam, element: &'a T, stroke_width: f32,
-               color_reader: ColorReader<T>) -> Line<'a, T> {
+impl Line {
+    pub fn new<T>(param: LineParam, element: &T, stroke_width: f32,
+                  color_reader: ColorReader<T>) -> Line {
         Line {
             param,
-            element,
             stroke_width,
-            color_reader,
+            color: color_reader(element).clone(),
             bounding_box: BoundingBox::default(),
             start: Point::new(0., 0.),
             end: Point::new(0., 0.),
@@ -123,12 +116,13 @@ mod test {
     use std::any::Any;
 
     use super::*;
+    use ::layout::Layout;
     use ::platform::test::test_context;
-    use ::test::skia::Snapshot;
+    use ::test::snapshot::Snapshot;
 
     struct MockElement;
     impl Layout for MockElement {
-        fn layout<'a>(&'a self, _: &Context) -> Box<Drawable + 'a> {
+        fn layout(&self, _: &Context) -> Box<Drawable> {
             unimplemented!()
         }
 
@@ -136,7 +130,7 @@ mod test {
             unimplemented!()
         }
 
-        fn as_any_mut(&mut self) -> &mut Any {
+        fn as_any_mut(&mut self) -> Option<&mut Any> {
             unimplemented!()
         }
     }
@@ -194,7 +188,7 @@ mod test {
 
     #[test]
     fn test_line() {
-        let skia = Snapshot::default();
+        let snapshot = Snapshot::default();
 
         let element = MockElement { };
 
@@ -205,7 +199,7 @@ mod test {
             |_| &Color::RGB(0, 0, 0)
         );
 
-        skia.snap_drawable(&mut line, &MeasureMode::Wrap, &MeasureMode::Wrap,
+        snapshot.snap_drawable(&mut line, &MeasureMode::Wrap, &MeasureMode::Wrap,
                       "line_45deg");
 
         let mut line = Line::new(
@@ -215,7 +209,7 @@ mod test {
             |_| &Color::RGB(0, 0, 0)
         );
 
-        skia.snap_drawable(&mut line, &MeasureMode::Wrap, &MeasureMode::Wrap,
+        snapshot.snap_drawable(&mut line, &MeasureMode::Wrap, &MeasureMode::Wrap,
                       "line_inclined");
 
         let mut line = Line::new(
@@ -225,7 +219,7 @@ mod test {
             |_| &Color::RGB(0, 0, 0)
         );
 
-        skia.snap_drawable(&mut line, &MeasureMode::Wrap, &MeasureMode::UpTo(100.),
+        snapshot.snap_drawable(&mut line, &MeasureMode::Wrap, &MeasureMode::UpTo(100.),
                       "line_vertical");
 
         let mut line = Line::new(
@@ -235,7 +229,7 @@ mod test {
             |_| &Color::RGB(0, 0, 0)
         );
 
-        skia.snap_drawable(&mut line, &MeasureMode::UpTo(100.), &MeasureMode::Wrap,
+        snapshot.snap_drawable(&mut line, &MeasureMode::UpTo(100.), &MeasureMode::Wrap,
                       "line_horizontal");
     }
 }
\ No newline at end of file
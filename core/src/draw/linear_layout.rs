@@ -17,12 +17,16 @@
 
 use std::f32;
 
-use super::{Drawable, BoundingBox, MeasureMode};
+use super::{Drawable, BoundingBox, MeasureMode, HitResult, Highlight, BoxSnapshot};
 use ::platform::Context;
-use ::paint::{Canvas, Point, Rect};
-
-pub struct LinearLayout<'a> {
-    children: Vec<Child<'a>>,
+use ::paint::{Canvas, Point, Rect, Ink};
+use ::elements::InstanceId;
+
+/// Places children one after another along the main axis. A child with negative extent moves the
+/// pen backwards, so the next child overlaps it. Children are drawn in the order they are added,
+/// later children over earlier ones.
+pub struct LinearLayout {
+    children: Vec<Child>,
     pub gravity: Gravity,
     pub layout_align: Align,
 
@@ -81,8 +85,8 @@ impl LinearLayoutParams {
     }
 }
 
-pub struct Child<'a> {
-    drawable: Box<Drawable + 'a>,
+pub struct Child {
+    drawable: Box<Drawable>,
     params: LinearLayoutParams,
     point: Point
 }
@@ -121,13 +125,40 @@ impl AxisParams {
     }
 }
 
-impl<'a> Drawable for LinearLayout<'a> {
+impl Drawable for LinearLayout {
     fn draw(&self, canvas: &Canvas, pen_pos: &Point) {
         for child in self.children.iter() {
             child.drawable.draw(canvas, &(pen_pos + &child.point));
         }
     }
 
+    fn draw_highlighted(&self, canvas: &Canvas, pen_pos: &Point, highlight: &Highlight,
+                        selected: bool) {
+        for child in self.children.iter() {
+            child.drawable.draw_highlighted(canvas, &(pen_pos + &child.point), highlight,
+                                            selected);
+        }
+    }
+
+    // Later children are drawn over earlier ones, so they are hit first
+    fn hit_test(&self, pen_pos: &Point, point: &Point) -> Option<HitResult> {
+        self.children.iter().rev()
+            .filter_map(|child| child.drawable.hit_test(&(pen_pos + &child.point), point))
+            .next()
+    }
+
+    fn locate(&self, pen_pos: &Point, instance_id: &InstanceId) -> Option<(Point, &Drawable)> {
+        self.children.iter()
+            .filter_map(|child| child.drawable.locate(&(pen_pos + &child.point), instance_id))
+            .next()
+    }
+
+    fn to_snapshot(&self) -> BoxSnapshot {
+        BoxSnapshot::group(self.bounding_box.clone(), self.children.iter()
+            .map(|child| (child.point.clone(), child.drawable.to_snapshot()))
+            .collect())
+    }
+
     // TODO optimize time complexity to at least O(n^2)
     fn calculate(&mut self, context: &Context, width_mode: &MeasureMode, height_mode: &MeasureMode) {
         if self.children.len() == 0 {
@@ -349,11 +380,15 @@ impl<'a> Drawable for LinearLayout<'a> {
             Gravity::Vertical => (cross_axis_length, main_axis_pen),
         };
 
-        self.bounding_box = BoundingBox {
-            rect: Rect::new(width, height),
-            baseline: cross_axis_length - axis_params.baseline_pos(),
-            axis: cross_axis_length - axis_params.axis_pos(),
-        };
+        let baseline = cross_axis_length - axis_params.baseline_pos();
+        let ink = Ink::union_all(self.children.iter().filter_map(|child|
+            child.drawable.bounding_box().ink_in_parent(&child.point, height - baseline)));
+
+        self.bounding_box = BoundingBox::new(
+            Rect::new(width, height),
+            baseline,
+            cross_axis_length - axis_params.axis_pos(),
+        ).with_ink(ink);
     }
 
     fn bounding_box(&self) -> &BoundingBox {
@@ -361,8 +396,8 @@ impl<'a> Drawable for LinearLayout<'a> {
     }
 }
 
-impl<'a> LinearLayout<'a> {
-    pub fn new() -> LinearLayout<'a> {
+impl LinearLayout {
+    pub fn new() -> LinearLayout {
         LinearLayout {
             children: Vec::new(),
             gravity: Gravity::Horizontal,
@@ -386,7 +421,7 @@ impl<'a> LinearLayout<'a> {
         alignment
     }
 
-    pub fn add_child(&mut self, drawable: Box<Drawable + 'a>, params: LinearLayoutParams) {
+    pub fn add_child(&mut self, drawable: Box<Drawable>, params: LinearLayoutParams) {
         self.children.push(Child { drawable, params, point: Point::new(0., 0.) })
     }
 }
@@ -439,6 +474,40 @@ mod test {
         assert_eq!(ll.children[2].point, Point::new(30., 15.));
     }
 
+    #[test]
+    fn it_moves_back_for_negative_extents() {
+        let test_context = test_context();
+        let mut ll = LinearLayout::new();
+        ll.layout_align = Align::Baseline;
+
+        ll.add_child(Box::new(Fixed::new(10., 20., 10., 10.)), LinearLayoutParams::new());
+        ll.add_child(Box::new(Space::new(-4., 0., 0., 0.)), LinearLayoutParams::new());
+        ll.add_child(Box::new(Fixed::new(10., 20., 10., 10.)), LinearLayoutParams::new());
+        ll.calculate(&test_context, &MeasureMode::Wrap, &MeasureMode::Wrap);
+
+        assert_eq!(ll.bounding_box().width(), 16.);
+        assert_eq!(ll.bounding_box().height(), 20.);
+        assert_eq!(ll.children[1].point, Point::new(10., 10.));
+        assert_eq!(ll.children[2].point, Point::new(6., 0.));
+    }
+
+    #[test]
+    fn it_unites_ink_of_children() {
+        let test_context = test_context();
+        let mut ll = LinearLayout::new();
+        ll.layout_align = Align::Baseline;
+
+        ll.add_child(Box::new(Fixed::new(10., 20., 5., 10.)), LinearLayoutParams::new());
+        ll.add_child(Box::new(Space::new(30., 40., 10., 10.)), LinearLayoutParams::new());
+        ll.add_child(Box::new(Fixed::new(10., 10., 0., 5.)), LinearLayoutParams::new());
+        ll.calculate(&test_context, &MeasureMode::Wrap, &MeasureMode::Wrap);
+
+        assert_eq!(ll.bounding_box().height(), 40.);
+        assert_eq!(ll.bounding_box().ink(), Some(&Ink::new(0., 50., 15., -5.)));
+        assert_eq!(ll.bounding_box().ink_ascent(), 15.);
+        assert_eq!(ll.bounding_box().ink_descent(), -5.);
+    }
+
     #[test]
     fn it_aligns_to_axis() {
         let test_context = test_context();
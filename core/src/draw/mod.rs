@@ -27,20 +27,34 @@ mod line;                   pub use self::line::*;
 mod space;                  pub use self::space::*;
 mod empty;                  pub use self::empty::*;
 mod phantom;                pub use self::phantom::*;
+mod hit;                    pub use self::hit::*;
+mod highlight;              pub use self::highlight::*;
+mod box_snapshot;           pub use self::box_snapshot::*;
 
-use ::paint::{Canvas, Point, Rect};
+use ::paint::{Canvas, Point, Rect, Ink};
 use ::platform::Context;
+use ::elements::InstanceId;
 
-#[derive(Debug, Clone)]
+/// Logical box of a drawable, which positions it among its neighbors, together with the ink it
+/// paints. Ink may extend outside the logical box, and is `None` when nothing is painted.
+#[derive(Debug, Clone, PartialEq)]
 pub struct BoundingBox {
     rect: Rect,
     baseline: f32,
-    axis: f32
+    axis: f32,
+    ink: Option<Ink>,
 }
 
 impl BoundingBox {
+    /// Box whose ink fills its logical box
     pub fn new(rect: Rect, baseline: f32, axis: f32) -> BoundingBox {
-        BoundingBox { rect, baseline, axis }
+        let ink = Some(BoundingBox::logical_ink(&rect, baseline));
+        BoundingBox { rect, baseline, axis, ink }
+    }
+
+    pub fn with_ink(mut self, ink: Option<Ink>) -> BoundingBox {
+        self.ink = ink;
+        self
     }
 
     pub fn rect(&self) -> &Rect {
@@ -74,6 +88,40 @@ impl BoundingBox {
     pub fn axis_pos(&self) -> f32 {
         self.height()-self.axis()
     }
+
+    /// Ink around the left end of the baseline
+    pub fn ink(&self) -> Option<&Ink> {
+        self.ink.as_ref()
+    }
+
+    /// Height of ink above the baseline, zero when nothing is painted
+    pub fn ink_ascent(&self) -> f32 {
+        self.ink.as_ref().map(|ink| ink.ascent()).unwrap_or(0.)
+    }
+
+    /// Depth of ink below the baseline, negative like `descent` and zero when nothing is painted
+    pub fn ink_descent(&self) -> f32 {
+        self.ink.as_ref().map(|ink| ink.descent()).unwrap_or(0.)
+    }
+
+    /// Ink around the baseline of a parent box, when this box is placed at `top_left` in it and
+    /// the parent baseline is at `baseline_pos` from its top
+    pub fn ink_in_parent(&self, top_left: &Point, baseline_pos: f32) -> Option<Ink> {
+        self.ink.as_ref().map(|ink| ink.translate(
+            top_left.x(), baseline_pos - (top_left.y() + self.baseline_pos())))
+    }
+
+    /// Whether `point` is in the logical box, when the box is placed at `top_left`
+    pub fn contains(&self, top_left: &Point, point: &Point) -> bool {
+        let (x, y) = (point.x() - top_left.x(), point.y() - top_left.y());
+        x >= self.width().min(0.) && x <= self.width().max(0.) &&
+            y >= self.height().min(0.) && y <= self.height().max(0.)
+    }
+
+    fn logical_ink(rect: &Rect, baseline: f32) -> Ink {
+        Ink::new(rect.width().min(0.), rect.width().max(0.),
+                 (rect.height()-baseline).max(-baseline), (rect.height()-baseline).min(-baseline))
+    }
 }
 
 impl Default for BoundingBox {
@@ -82,11 +130,12 @@ impl Default for BoundingBox {
             rect: Rect::new(0., 0.),
             baseline: 0.0,
             axis: 0.0,
+            ink: None,
         }
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum MeasureMode {
     UpTo(f32),
     Wrap
@@ -97,4 +146,41 @@ pub trait Drawable {
     fn calculate(&mut self, context: &Context, width_mode: &MeasureMode, height_mode: &MeasureMode);
 
     fn bounding_box(&self) -> &BoundingBox;
+
+    /// Draws with the boxes of elements selected by `highlight` painted in its color. `selected`
+    /// is set when drawn inside a selected element.
+    fn draw_highlighted(&self, canvas: &Canvas, pen_pos: &Point, _highlight: &Highlight,
+                        _selected: bool) {
+        self.draw(canvas, pen_pos);
+    }
+
+    /// Innermost element under `point`, when drawn at `pen_pos`. Both points are in the
+    /// coordinates of the root drawable.
+    fn hit_test(&self, _pen_pos: &Point, _point: &Point) -> Option<HitResult> {
+        None
+    }
+
+    /// Offset in characters of the character under `point` in the token text, when drawn at
+    /// `pen_pos`
+    fn text_offset(&self, _pen_pos: &Point, _point: &Point) -> Option<usize> {
+        None
+    }
+
+    /// Innermost drawable laid out for the element `instance_id` and the position it is drawn
+    /// at, when this drawable is drawn at `pen_pos`
+    fn locate(&self, _pen_pos: &Point, _instance_id: &InstanceId) -> Option<(Point, &Drawable)> {
+        None
+    }
+
+    /// Horizontal position of a caret before the character at `offset` in the token text, when
+    /// drawn at `pen_pos`
+    fn caret_x(&self, _pen_pos: &Point, _offset: usize) -> Option<f32> {
+        None
+    }
+
+    /// Owned snapshot of this drawable, once it is calculated. Drawables with inner drawables
+    /// take snapshots of them, and others are recorded as they draw.
+    fn to_snapshot(&self) -> BoxSnapshot {
+        BoxSnapshot::painted(self)
+    }
 }
\ No newline at end of file
@@ -15,71 +15,106 @@
 */
 
 
-use super::{Drawable, BoundingBox, MeasureMode};
+use super::{Drawable, BoundingBox, MeasureMode, HitResult, Highlight, BoxSnapshot};
 use ::paint::{Canvas, Point, Rect};
 use ::platform::Context;
+use ::elements::InstanceId;
 
 pub type PaddingReader<T> = fn (&T) -> f32;
 
-pub struct PaddingBox<'a, T: 'a, U: Drawable> {
+pub struct PaddingBox<U: Drawable> {
     wrapped: Option<U>,
-    props: &'a T,
-    padding_left_reader: PaddingReader<T>,
-    padding_right_reader: PaddingReader<T>,
-    padding_top_reader: PaddingReader<T>,
-    padding_bottom_reader: PaddingReader<T>,
+    padding_left: f32,
+    padding_right: f32,
+    padding_top: f32,
+    padding_bottom: f32,
 
     bounding_box: BoundingBox
 }
 
-impl<'a, T, U: Drawable> Drawable for PaddingBox<'a, T, U> {
+impl<U: Drawable> Drawable for PaddingBox<U> {
     fn draw(&self, canvas: &Canvas, pen_pos: &Point) {
         if let Some(ref wrapped) = self.wrapped {
-            let point = pen_pos+&Point::new((self.padding_left_reader)(self.props),
-                                            (self.padding_top_reader)(self.props));
-            wrapped.draw(canvas, &point);
+            wrapped.draw(canvas, &self.content_pos(pen_pos));
+        }
+    }
+
+    fn draw_highlighted(&self, canvas: &Canvas, pen_pos: &Point, highlight: &Highlight,
+                        selected: bool) {
+        if let Some(ref wrapped) = self.wrapped {
+            wrapped.draw_highlighted(canvas, &self.content_pos(pen_pos), highlight, selected);
         }
     }
 
     fn calculate(&mut self, context: &Context, width_mode: &MeasureMode, height_mode: &MeasureMode) {
-        let padding_left = (self.padding_left_reader)(self.props);
-        let padding_right = (self.padding_right_reader)(self.props);
-        let padding_top = (self.padding_top_reader)(self.props);
-        let padding_bottom = (self.padding_bottom_reader)(self.props);
+        let padding_left = self.padding_left;
+        let padding_right = self.padding_right;
+        let padding_top = self.padding_top;
+        let padding_bottom = self.padding_bottom;
 
         self.bounding_box = if let Some(ref mut val) = self.wrapped {
             val.calculate(context, width_mode, height_mode);
-            BoundingBox {
-                rect: Rect::new(val.bounding_box().width()+padding_left+padding_right,
-                                val.bounding_box().height()+padding_top+padding_bottom),
-                baseline: val.bounding_box().baseline()+padding_bottom,
-                axis: val.bounding_box().axis()+padding_bottom,
-            }
+            BoundingBox::new(
+                Rect::new(val.bounding_box().width()+padding_left+padding_right,
+                          val.bounding_box().height()+padding_top+padding_bottom),
+                val.bounding_box().baseline()+padding_bottom,
+                val.bounding_box().axis()+padding_bottom,
+            ).with_ink(val.bounding_box().ink().map(|ink| ink.translate(padding_left, 0.)))
         } else {
-            BoundingBox {
-                rect: Rect::new(padding_left+padding_right, padding_top+padding_bottom),
-                baseline: 0.0,
-                axis: 0.0,
-            }
+            BoundingBox::new(
+                Rect::new(padding_left+padding_right, padding_top+padding_bottom),
+                0.0,
+                0.0,
+            ).with_ink(None)
         }
     }
 
     fn bounding_box(&self) -> &BoundingBox {
         &self.bounding_box
     }
+
+    fn hit_test(&self, pen_pos: &Point, point: &Point) -> Option<HitResult> {
+        self.wrapped.as_ref()
+            .and_then(|wrapped| wrapped.hit_test(&self.content_pos(pen_pos), point))
+    }
+
+    fn text_offset(&self, pen_pos: &Point, point: &Point) -> Option<usize> {
+        self.wrapped.as_ref()
+            .and_then(|wrapped| wrapped.text_offset(&self.content_pos(pen_pos), point))
+    }
+
+    fn locate(&self, pen_pos: &Point, instance_id: &InstanceId) -> Option<(Point, &Drawable)> {
+        self.wrapped.as_ref()
+            .and_then(|wrapped| wrapped.locate(&self.content_pos(pen_pos), instance_id))
+    }
+
+    fn caret_x(&self, pen_pos: &Point, offset: usize) -> Option<f32> {
+        self.wrapped.as_ref()
+            .and_then(|wrapped| wrapped.caret_x(&self.content_pos(pen_pos), offset))
+    }
+
+    fn to_snapshot(&self) -> BoxSnapshot {
+        let content_pos = self.content_pos(&Point::new(0., 0.));
+        let content = self.wrapped.as_ref().map(|wrapped| wrapped.to_snapshot());
+        let text = content.as_ref()
+            .and_then(|content| content.text())
+            .map(|text| text.translate(content_pos.x()));
+
+        let children = content.into_iter().map(|content| (content_pos.clone(), content)).collect();
+        BoxSnapshot::group(self.bounding_box.clone(), children).with_text(text)
+    }
 }
 
-impl<'a, T, U: Drawable> PaddingBox<'a, T, U> {
-    pub fn new(props: &'a T, padding_left_reader: PaddingReader<T>, padding_right_reader: PaddingReader<T>,
-               padding_top_reader: PaddingReader<T>, padding_bottom_reader: PaddingReader<T>)
-               -> PaddingBox<'a, T, U> {
+impl<U: Drawable> PaddingBox<U> {
+    pub fn new<T>(props: &T, padding_left_reader: PaddingReader<T>, padding_right_reader: PaddingReader<T>,
+                  padding_top_reader: PaddingReader<T>, padding_bottom_reader: PaddingReader<T>)
+                  -> PaddingBox<U> {
         PaddingBox {
             wrapped: None,
-            props,
-            padding_left_reader,
-            padding_right_reader,
-            padding_top_reader,
-            padding_bottom_reader,
+            padding_left: padding_left_reader(props),
+            padding_right: padding_right_reader(props),
+            padding_top: padding_top_reader(props),
+            padding_bottom: padding_bottom_reader(props),
             bounding_box: BoundingBox::default(),
         }
     }
@@ -87,6 +122,10 @@ impl<'a, T, U: Drawable> PaddingBox<'a, T, U> {
     pub fn wrap(&mut self, drawable: U) {
         self.wrapped = Some(drawable);
     }
+
+    fn content_pos(&self, pen_pos: &Point) -> Point {
+        pen_pos+&Point::new(self.padding_left, self.padding_top)
+    }
 }
 
 #[cfg(test)]
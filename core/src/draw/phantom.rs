@@ -18,23 +18,23 @@ use super::{Drawable, BoundingBox, MeasureMode};
 use ::platform::Context;
 use ::paint::{Canvas, Point};
 
-pub struct Phantom<'a> {
-    wrapped: Option<Box<Drawable + 'a>>,
+pub struct Phantom {
+    wrapped: Option<Box<Drawable>>,
 
     bounding_box: BoundingBox,
 }
 
-impl<'a> Phantom<'a> {
-    pub fn new() -> Phantom<'a> {
+impl Phantom {
+    pub fn new() -> Phantom {
         Phantom { wrapped: None, bounding_box: BoundingBox::default() }
     }
 
-    pub fn wrap(&mut self, wrapped: Option<Box<Drawable + 'a>>) {
+    pub fn wrap(&mut self, wrapped: Option<Box<Drawable>>) {
         self.wrapped = wrapped;
     }
 }
 
-impl<'a> Drawable for Phantom<'a> {
+impl Drawable for Phantom {
     fn draw(&self, _: &Canvas, _: &Point) {
         // do nothing
     }
@@ -34,11 +34,8 @@ impl Drawable for Space {
     }
 
     fn calculate(&mut self, _: &Context, _: &MeasureMode, _: &MeasureMode) {
-        self.bounding_box = BoundingBox {
-            rect: Rect::new(self.width, self.height),
-            baseline: self.baseline,
-            axis: self.axis,
-        }
+        self.bounding_box = BoundingBox::new(Rect::new(self.width, self.height), self.baseline,
+                                             self.axis).with_ink(None);
     }
 
     fn bounding_box(&self) -> &BoundingBox {
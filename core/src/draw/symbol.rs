@@ -17,11 +17,11 @@
 
 use std::f32;
 
-use super::{Drawable, MeasureMode, BoundingBox, AbsoluteLayout, AbsoluteLayoutParams, Glyph, GlyphIndex, Text};
+use super::{Drawable, MeasureMode, BoundingBox, AbsoluteLayout, AbsoluteLayoutParams, Glyph, GlyphIndex, Text,
+            Line, LineParam, BoxSnapshot, TextStops, GlyphStop};
 use ::platform::Context;
-use ::paint::{Point, Canvas, GlyphConstructionDirection, MathRuler, GlyphAssembly, GlyphAssemblyPart};
+use ::paint::{Point, Canvas, GlyphConstructionDirection, MathRuler, GlyphAssembly, GlyphAssemblyPart, FontSynthesis};
 use ::props::{Color, Directionality, MathVariant};
-use ::layout::Layout;
 
 type SymbolReader<T> = fn(&T) -> &str;
 type SymmetricReader<T> = fn(&T) -> bool;
@@ -30,45 +30,83 @@ type DirReader<T> = fn(&T) -> &Directionality;
 type ColorReader<T> = fn(&T) -> &Color;
 type VariantReader<T> = fn(&T) -> &MathVariant;
 
-pub struct Symbol<'a, T: Layout + 'a> {
-    props: &'a T,
-    symbol_reader: SymbolReader<T>,
-    math_variant_reader: VariantReader<T>,
-    symmetric_reader: SymmetricReader<T>,
-    base_size_reader: SizeReader<T>,
-    max_size_reader: SizeReader<T>,
-    min_size_reader: SizeReader<T>,
-    dir_reader: DirReader<T>,
-    color_reader: ColorReader<T>,
+pub struct Symbol {
+    symbol: String,
+    math_variant: MathVariant,
+    base_size: f32,
+    max_size: f32,
+    min_size: f32,
+    dir: Directionality,
+    color: Color,
 
     bounding_box: BoundingBox,
-    layout: AbsoluteLayout<'a>,
+    layout: AbsoluteLayout,
+    synthesis: FontSynthesis,
+    script_level: u32,
+    flattened_accent: bool,
 }
 
-impl<'a, T: Layout + 'a> Drawable for Symbol<'a, T> {
+impl Drawable for Symbol {
     fn draw(&self, canvas: &Canvas, pen_pos: &Point) {
         self.layout.draw(canvas, pen_pos);
     }
 
+    // Symbols of more than one character are set as text at the origin of the layout
+    fn text_offset(&self, pen_pos: &Point, point: &Point) -> Option<usize> {
+        if self.symbol.chars().count() > 1 {
+            self.layout.iter().next().and_then(|child| child.drawable().text_offset(pen_pos, point))
+        } else {
+            Some(0)
+        }
+    }
+
+    fn caret_x(&self, pen_pos: &Point, offset: usize) -> Option<f32> {
+        if self.symbol.chars().count() > 1 {
+            self.layout.iter().next().and_then(|child| child.drawable().caret_x(pen_pos, offset))
+        } else {
+            // Caret after the symbol is on its left in right to left layout
+            let rtl = self.dir == Directionality::RTL;
+            if (offset > 0) != rtl {
+                Some(pen_pos.x() + self.bounding_box.width())
+            } else {
+                Some(pen_pos.x())
+            }
+        }
+    }
+
     fn calculate(&mut self, context: &Context, width_mode: &MeasureMode, height_mode: &MeasureMode) {
-        let base_size = (self.base_size_reader)(self.props);
-        let symbol = (self.symbol_reader)(self.props);
+        let base_size = self.base_size;
         let ruler = context.platform().get_math_ruler(base_size);
 
-        let chars: Vec<char> = symbol.chars().collect();
+        let chars: Vec<char> = self.symbol.chars().collect();
 
         if chars.len() > 1 {
             self.set_text(context);
             return;
         }
 
-        let symbol = chars[0] as u32;
+        let mut symbol = chars[0] as u32;
+        let mut mirrored_glyph = None;
+        self.synthesis = FontSynthesis::none();
+
+        // Symbols are mirrored in right to left math. Mirrored glyph is taken from the mirroring
+        // character pair, then from the font and is synthesized if both are not present.
+        if self.dir == Directionality::RTL {
+            let mirroring = context.bidi_mirroring_dictionary();
+
+            if let Some(mirror) = mirroring.mirrored_char(&chars[0]) {
+                symbol = mirror as u32;
+            } else if mirroring.is_mirrored(&chars[0]) {
+                mirrored_glyph = ruler.rtl_mirrored_glyph(symbol);
+                self.synthesis = FontSynthesis::mirrored();
+            }
+        }
 
         if let MeasureMode::UpTo(height) = *height_mode {
             let stretch_dir = GlyphConstructionDirection::Vertical;
             let stretched_size = base_size
-                .max((self.min_size_reader)(self.props))
-                .min((self.max_size_reader)(self.props))
+                .max(self.min_size)
+                .min(self.max_size)
                 .max(height);
 
             if self.try_stretch_symbol(context,symbol, ruler, stretched_size, &stretch_dir) {
@@ -79,8 +117,8 @@ impl<'a, T: Layout + 'a> Drawable for Symbol<'a, T> {
         if let MeasureMode::UpTo(width) = *width_mode {
             let stretch_dir = GlyphConstructionDirection::Horizontal;
             let stretched_size = base_size
-                .max((self.min_size_reader)(self.props))
-                .min((self.max_size_reader)(self.props))
+                .max(self.min_size)
+                .min(self.max_size)
                 .max(width);
 
             if self.try_stretch_symbol(context,symbol, ruler, stretched_size, &stretch_dir) {
@@ -88,45 +126,87 @@ impl<'a, T: Layout + 'a> Drawable for Symbol<'a, T> {
             }
         }
 
-        self.set_single_glyph(context,GlyphIndex::Char(symbol));
+        match mirrored_glyph {
+            Some(glyph_index) => self.set_single_glyph(context, GlyphIndex::Index(glyph_index),
+                                                       FontSynthesis::none(), None),
+            None => {
+                let synthesis = self.synthesis;
+                self.set_single_glyph(context, GlyphIndex::Char(symbol), synthesis, None)
+            }
+        }
     }
 
     fn bounding_box(&self) -> &BoundingBox {
         &self.bounding_box
     }
+
+    fn to_snapshot(&self) -> BoxSnapshot {
+        let content = self.layout.to_snapshot();
+        let text = if self.symbol.chars().count() > 1 {
+            content.children().first().and_then(|(position, text)|
+                text.text().map(|stops| stops.translate(position.x())))
+        } else {
+            let width = self.bounding_box.width();
+            let (before, after) = match self.dir {
+                Directionality::LTR => (0., width),
+                Directionality::RTL => (width, 0.),
+            };
+            Some(TextStops::new(vec![GlyphStop::new(0., width, 0, before)], after))
+        };
+
+        BoxSnapshot::group(self.bounding_box.clone(), vec![(Point::new(0., 0.), content)])
+            .with_text(text)
+    }
 }
 
-impl<'a, T: Layout + 'a> Symbol<'a, T> {
-    pub fn new(props: &'a T, symbol_reader: SymbolReader<T>, math_variant_reader: VariantReader<T>,
-               symmetric_reader: SymmetricReader<T>,
-               base_size_reader: SizeReader<T>, max_size_reader: SizeReader<T>,
-               min_size_reader: SizeReader<T>, dir_reader: DirReader<T>,
-               color_reader: ColorReader<T>) -> Symbol<'a, T> {
+impl Symbol {
+    pub fn new<T>(props: &T, symbol_reader: SymbolReader<T>, math_variant_reader: VariantReader<T>,
+                  _symmetric_reader: SymmetricReader<T>,
+                  base_size_reader: SizeReader<T>, max_size_reader: SizeReader<T>,
+                  min_size_reader: SizeReader<T>, dir_reader: DirReader<T>,
+                  color_reader: ColorReader<T>) -> Symbol {
         Symbol {
-            props,
-            symbol_reader,
-            math_variant_reader,
-            symmetric_reader,
-            base_size_reader,
-            max_size_reader,
-            min_size_reader,
-            dir_reader,
-            color_reader,
+            symbol: String::from(symbol_reader(props)),
+            math_variant: math_variant_reader(props).clone(),
+            base_size: base_size_reader(props),
+            max_size: max_size_reader(props),
+            min_size: min_size_reader(props),
+            dir: dir_reader(props).clone(),
+            color: color_reader(props).clone(),
             bounding_box: BoundingBox::default(),
             layout: AbsoluteLayout::new(),
+            synthesis: FontSynthesis::none(),
+            script_level: 0,
+            flattened_accent: false,
         }
     }
 
-    fn set_single_glyph(&mut self, context: &Context, glyph: GlyphIndex) {
-        let glyph = Glyph::new(
-            self.props,
+    /// Script level selecting script style variants of the symbol glyphs
+    pub fn set_script_level(&mut self, script_level: u32) {
+        self.script_level = script_level;
+    }
+
+    /// Draws the flattened form of the symbol, for accents over tall bases
+    pub fn set_flattened_accent(&mut self, flattened_accent: bool) {
+        self.flattened_accent = flattened_accent;
+    }
+
+    fn set_single_glyph(&mut self, context: &Context, glyph: GlyphIndex, synthesis: FontSynthesis,
+                        size: Option<f32>) {
+        let mut glyph = Glyph::new(
+            self,
             glyph,
-            self.base_size_reader,
-            self.color_reader,
-            self.dir_reader
+            Symbol::base_size,
+            Symbol::color,
+            Symbol::dir
         );
+        glyph.set_synthesis(synthesis);
+        glyph.set_size(size);
+        glyph.set_script_level(self.script_level);
+        glyph.set_flattened_accent(self.flattened_accent);
 
         self.layout.clear();
+        self.layout.set_dir(Directionality::LTR);
         self.layout.add_child(Box::new(glyph),
                               AbsoluteLayoutParams::new(Point::new(0., 0.)));
 
@@ -141,7 +221,9 @@ impl<'a, T: Layout + 'a> Symbol<'a, T> {
 
         for variant in variants {
             if variant.advance() >= stretched_size {
-                self.set_single_glyph(context, GlyphIndex::Index(variant.glyph_index()));
+                let synthesis = self.synthesis;
+                self.set_single_glyph(context, GlyphIndex::Index(variant.glyph_index()), synthesis,
+                                      None);
                 return true;
             }
         }
@@ -152,14 +234,153 @@ impl<'a, T: Layout + 'a> Symbol<'a, T> {
             return true;
         }
 
-        return false;
+        self.try_synthesize_symbol(context, symbol, ruler, stretched_size, stretch_dir)
+    }
+
+    /// Builds stretched symbols from rules and scaled glyphs, for fonts which have no variants
+    /// or assembly for the symbol, like fonts without a MATH table
+    fn try_synthesize_symbol(&mut self, context: &Context, symbol: u32, ruler: &MathRuler,
+                             stretched_size: f32, stretch_dir: &GlyphConstructionDirection) -> bool {
+        let natural = ruler.measure_char(symbol, &self.dir);
+        let thickness = ruler.fraction_rule_thickness();
+
+        match *stretch_dir {
+            GlyphConstructionDirection::Vertical => {
+                if stretched_size <= natural.height() {
+                    return false;
+                }
+
+                match vertical_rules(symbol, natural.width(), stretched_size, thickness) {
+                    Some(rules) => self.set_rules(context, rules, natural.width()),
+                    None => {
+                        // Scaled uniformly, which also widens the glyph
+                        let size = self.base_size * stretched_size /
+                            natural.height();
+                        let synthesis = self.synthesis;
+                        self.set_single_glyph(context, GlyphIndex::Char(symbol), synthesis,
+                                              Some(size));
+                    }
+                }
+
+                true
+            },
+            GlyphConstructionDirection::Horizontal => {
+                if stretched_size <= natural.width() {
+                    return false;
+                }
+
+                match symbol {
+                    0x5F | 0xAF | 0x203E => {
+                        let rule = (Point::new(0., thickness/2.),
+                                    Point::new(stretched_size, thickness/2.), thickness);
+                        self.set_rules(context, vec![rule], stretched_size);
+                        true
+                    },
+                    _ => self.try_set_arrow(context, symbol, stretched_size, thickness),
+                }
+            },
+        }
+    }
+
+    /// Lays out rules given as center lines with stroke width, mirrored within `width` when the
+    /// symbol is mirrored
+    fn set_rules(&mut self, context: &Context, rules: Vec<(Point, Point, f32)>, width: f32) {
+        self.layout.clear();
+        self.layout.set_dir(Directionality::LTR);
+
+        let mirror = self.synthesis.mirror();
+        for (start, end, thickness) in rules {
+            let (start, end) = if mirror {
+                (Point::new(width - start.x(), start.y()), Point::new(width - end.x(), end.y()))
+            } else {
+                (start, end)
+            };
+
+            // Line offsets its center line by half the stroke, which is undone by its position
+            let angle = ((start.y()-end.y()).abs() / (start.x()-end.x()).abs()).atan();
+            let x_diff = angle.sin()*thickness/2.;
+            let y_diff = angle.cos()*thickness/2.;
+
+            let left = start.x().min(end.x());
+            let top = start.y().min(end.y());
+            let line = Line::new(
+                LineParam::Fixed {
+                    start: Point::new(start.x() - left, start.y() - top),
+                    end: Point::new(end.x() - left, end.y() - top),
+                },
+                self,
+                thickness,
+                Symbol::color
+            );
+
+            self.layout.add_child(Box::new(line),
+                                  AbsoluteLayoutParams::new(Point::new(left - x_diff, top - y_diff)));
+        }
+
+        self.layout.calculate(context, &MeasureMode::Wrap, &MeasureMode::Wrap);
+        self.bounding_box = self.layout.bounding_box().clone();
+    }
+
+    /// Stretches arrows by drawing their heads as glyphs joined with a rule on math axis
+    fn try_set_arrow(&mut self, context: &Context, symbol: u32, stretched_size: f32,
+                     thickness: f32) -> bool {
+        let (left_head, right_head) = match symbol {
+            0x2190 | 0x27F5 => (true, false),
+            0x2192 | 0x27F6 => (false, true),
+            0x2194 | 0x27F7 => (true, true),
+            _ => return false,
+        };
+
+        self.layout.clear();
+        self.layout.set_dir(Directionality::LTR);
+
+        let mut head_box = BoundingBox::default();
+        let mut shaft_start = 0.;
+        let mut shaft_end = stretched_size;
+
+        for &(is_head, unicode, is_left) in [(left_head, 0x2190, true), (right_head, 0x2192, false)]
+            .iter() {
+            if !is_head {
+                continue;
+            }
+
+            let mut head = Glyph::new(self, GlyphIndex::Char(unicode), Symbol::base_size,
+                                      Symbol::color, Symbol::dir);
+            head.calculate(context, &MeasureMode::Wrap, &MeasureMode::Wrap);
+            head_box = head.bounding_box().clone();
+
+            let x = if is_left {
+                shaft_start = head_box.width() / 2.;
+                0.
+            } else {
+                shaft_end = stretched_size - head_box.width() / 2.;
+                stretched_size - head_box.width()
+            };
+            self.layout.add_child(Box::new(head), AbsoluteLayoutParams::new(Point::new(x, 0.)));
+        }
+
+        let shaft = Line::new(
+            LineParam::Fixed { start: Point::new(0., 0.), end: Point::new(shaft_end - shaft_start, 0.) },
+            self,
+            thickness,
+            Symbol::color
+        );
+        self.layout.add_child(Box::new(shaft), AbsoluteLayoutParams::new(
+            Point::new(shaft_start, head_box.axis_pos() - thickness/2.)));
+
+        self.layout.calculate(context, &MeasureMode::Wrap, &MeasureMode::Wrap);
+        // Ink of the layout is around its bottom, which the baseline of the heads is above
+        let ink = self.layout.bounding_box().ink()
+            .map(|ink| ink.translate(0., -head_box.baseline()));
+        self.bounding_box = BoundingBox::new(self.layout.bounding_box().rect().clone(),
+                                             head_box.baseline(), head_box.axis()).with_ink(ink);
+
+        true
     }
 
     fn set_glyph_assembly(&mut self, context: &Context, assembly: GlyphAssembly,
                           stretch_dir: &GlyphConstructionDirection,
                           stretched_size: f32, ruler: &MathRuler) {
-        let min_connector_overlap = ruler.minimum_connector_overlap(stretch_dir);
-
         let mut num_extenders = 0;
         let mut total_extender_advance = 0f32;
 
@@ -191,6 +412,11 @@ impl<'a, T: Layout + 'a> Symbol<'a, T> {
         let num_extenders = num_extenders as f32;
         let num_non_extenders = num_non_extenders as f32;
 
+        // Connectors of some parts are shorter than the minimum overlap of the font, like the
+        // arrow head of U+2192 in STIX, those parts can only overlap by their connector length
+        let min_connector_overlap = ruler.minimum_connector_overlap(stretch_dir)
+            .min(max_connector_overlap);
+
         // Number of times extenders needs to be repeated to get size >= stretched size
         let extender_multiplier = ((stretched_size-total_non_extender_advance-
             min_connector_overlap*(1.-num_non_extenders)) /
@@ -206,8 +432,6 @@ impl<'a, T: Layout + 'a> Symbol<'a, T> {
 
         #[cfg(debug_assertions)]
         {
-            assert!(max_connector_overlap >= min_connector_overlap);
-
             let size_with_overlap_correction = extender_multiplier*total_extender_advance
                 +total_non_extender_advance -
                 (overlap*(num_non_extenders+(extender_multiplier*num_extenders)-1.));
@@ -223,6 +447,13 @@ impl<'a, T: Layout + 'a> Symbol<'a, T> {
 
         self.layout.clear();
 
+        // Mirroring each part is not enough, parts has to be placed in mirrored positions
+        self.layout.set_dir(if self.synthesis.mirror() {
+            Directionality::RTL
+        } else {
+            Directionality::LTR
+        });
+
         let mut pen_pos = 0f32;
 
         // Ordering of parts in GlyphAssembly for vertical construction is from bottom to top
@@ -258,16 +489,18 @@ impl<'a, T: Layout + 'a> Symbol<'a, T> {
 
         for _ in 0..num_iters {
             let mut glyph = Glyph::new(
-                self.props,
+                self,
                 GlyphIndex::Index(part.glyph_index()),
-                self.base_size_reader,
-                self.color_reader,
-                self.dir_reader);
+                Symbol::base_size,
+                Symbol::color,
+                Symbol::dir);
 
             if *stretch_dir == GlyphConstructionDirection::Vertical {
                 glyph.set_advance(Some(part.full_advance()));
             }
 
+            glyph.set_synthesis(self.synthesis);
+
             self.layout.add_child(
                 Box::new(glyph),
                 AbsoluteLayoutParams::new(match *stretch_dir {
@@ -283,16 +516,18 @@ impl<'a, T: Layout + 'a> Symbol<'a, T> {
     }
 
     fn set_text(&mut self, context: &Context) {
-        let text = Text::new(
-            self.props,
-            self.symbol_reader,
-            self.base_size_reader,
-            self.math_variant_reader,
-            self.dir_reader,
-            self.color_reader
+        let mut text = Text::new(
+            self,
+            Symbol::symbol,
+            Symbol::base_size,
+            Symbol::math_variant,
+            Symbol::dir,
+            Symbol::color
         );
+        text.set_script_level(self.script_level);
 
         self.layout.clear();
+        self.layout.set_dir(Directionality::LTR);
         self.layout.add_child(Box::new(text),
                               AbsoluteLayoutParams::new(Point::new(0., 0.)));
 
@@ -301,24 +536,103 @@ impl<'a, T: Layout + 'a> Symbol<'a, T> {
         self.bounding_box = self.layout.iter().next().unwrap().drawable().bounding_box().clone();
     }
 
-    pub fn get_layout(&self) -> &AbsoluteLayout<'a> {
+    pub fn get_layout(&self) -> &AbsoluteLayout {
         &self.layout
     }
 
+    // Readers of parts and text set for the symbol
+    fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    fn math_variant(&self) -> &MathVariant {
+        &self.math_variant
+    }
+
+    fn base_size(&self) -> f32 {
+        self.base_size
+    }
+
+    fn dir(&self) -> &Directionality {
+        &self.dir
+    }
+
+    fn color(&self) -> &Color {
+        &self.color
+    }
+
 }
 
+/// Center lines and stroke widths of rules drawing a vertical symbol of `height`, for fences
+/// and radicals which are made of straight strokes
+fn vertical_rules(symbol: u32, width: f32, height: f32, thickness: f32)
+    -> Option<Vec<(Point, Point, f32)>> {
+    let half = thickness / 2.;
+    let vertical = |x: f32| (Point::new(x, 0.), Point::new(x, height), thickness);
+    let bracket = |top: bool, bottom: bool| {
+        let (stem, serif) = (width * 0.3, width * 0.8);
+        let mut rules = vec![vertical(stem)];
+        if top {
+            rules.push((Point::new(stem - half, half), Point::new(serif, half), thickness));
+        }
+        if bottom {
+            rules.push((Point::new(stem - half, height - half), Point::new(serif, height - half),
+                        thickness));
+        }
+        rules
+    };
+    let angle = || {
+        let (tip, end) = (width * 0.2, width * 0.8);
+        vec![(Point::new(end, 0.), Point::new(tip, height / 2.), thickness),
+             (Point::new(tip, height / 2.), Point::new(end, height), thickness)]
+    };
+    let mirrored = |rules: Vec<(Point, Point, f32)>| rules.into_iter()
+        .map(|(start, end, thickness)| (Point::new(width - start.x(), start.y()),
+                                        Point::new(width - end.x(), end.y()), thickness))
+        .collect();
+
+    let rules = match symbol {
+        0x7C | 0x2223 => vec![vertical(width / 2.)],
+        0x2016 | 0x2225 => vec![vertical(width / 3.), vertical(width * 2. / 3.)],
+        0x5B => bracket(true, true),
+        0x5D => mirrored(bracket(true, true)),
+        0x2308 => bracket(true, false),
+        0x2309 => mirrored(bracket(true, false)),
+        0x230A => bracket(false, true),
+        0x230B => mirrored(bracket(false, true)),
+        0x27E8 | 0x2329 => angle(),
+        0x27E9 | 0x232A => mirrored(angle()),
+        0x221A => {
+            // Short tick rising to a heavy down stroke, then a hairline up to the top right
+            // corner where the overbar of the radical begins
+            let tick = Point::new(half, height - width * 0.5);
+            let tick_top = Point::new(width * 0.3, height - width * 0.6);
+            let bottom = Point::new(width * 0.55, height);
+            let top = Point::new(width - half, half);
+            vec![(tick, tick_top.clone(), thickness), (tick_top, bottom.clone(), thickness * 2.),
+                 (bottom, top, thickness)]
+        },
+        _ => return None,
+    };
+
+    Some(rules)
+}
 
 #[cfg(test)]
 mod test {
     use std::any::Any;
 
     use super::*;
-    use ::test::skia::Snapshot;
+    use ::layout::Layout;
+    use ::test::snapshot::Snapshot;
+    use ::font::test::{stix_math, stix_without_math};
+    use ::backend::raster::RasterPlatform;
+    use ::backend::display_list::{DisplayList, DrawCommand};
 
     struct Test;
 
     impl Layout for Test {
-        fn layout<'a>(&'a self, _: &Context) -> Box<Drawable + 'a> {
+        fn layout(&self, _: &Context) -> Box<Drawable> {
             unimplemented!()
         }
 
@@ -326,7 +640,7 @@ mod test {
             unimplemented!()
         }
 
-        fn as_any_mut(&mut self) -> &mut Any {
+        fn as_any_mut(&mut self) -> Option<&mut Any> {
             unimplemented!()
         }
     }
@@ -410,4 +724,108 @@ mod test {
         Snapshot::default().snap_drawable(&mut symbol, &MeasureMode::UpTo(1000.),
                       &MeasureMode::Wrap, "symbol_text");
     }
-}
\ No newline at end of file
+
+    fn symbol_with_dir(test_element: &Test, symbol_reader: SymbolReader<Test>,
+                  dir_reader: DirReader<Test>) -> Symbol {
+        Symbol::new(
+            test_element,
+            symbol_reader,
+            |_| &MathVariant::Normal,
+            |_| true,
+            |_| 64.,
+            |_| f32::INFINITY,
+            |_| 64.,
+            dir_reader,
+            |_| &Color::RGB(0, 0, 0)
+        )
+    }
+
+    // Glyphs drawn for symbol stretched to 1000 units height, with their synthesis
+    fn drawn_glyphs(symbol: &mut Symbol) -> Vec<(u32, FontSynthesis)> {
+        let context = Context::new(Box::new(RasterPlatform::new(stix_math())), 64.);
+        symbol.calculate(&context, &MeasureMode::Wrap, &MeasureMode::UpTo(1000.));
+
+        let list = DisplayList::new(symbol.bounding_box());
+        symbol.draw(&list, &Point::new(0., 0.));
+
+        let glyphs = list.commands().iter()
+            .filter_map(|command| match *command {
+                DrawCommand::Glyph { glyph_index, synthesis, .. } => Some((glyph_index, synthesis)),
+                _ => None,
+            })
+            .collect();
+        glyphs
+    }
+
+    #[test]
+    fn test_rtl_mirroring() {
+        let test_element = Test {};
+
+        let mut symbol = symbol_with_dir(&test_element, |_| "(", |_| &Directionality::RTL);
+        Snapshot::default().snap_drawable(&mut symbol, &MeasureMode::Wrap,
+                      &MeasureMode::UpTo(1000.), "symbol_paren_rtl");
+
+        // Parenthesis has a mirroring pair, which is drawn without synthesis
+        let rtl = drawn_glyphs(&mut symbol);
+        let closing = drawn_glyphs(&mut symbol_with_dir(&test_element, |_| ")", |_| &Directionality::LTR));
+        assert!(!rtl.is_empty());
+        assert_eq!(rtl, closing);
+
+        let mut symbol = symbol_with_dir(&test_element, |_| "√", |_| &Directionality::RTL);
+        Snapshot::default().snap_drawable(&mut symbol, &MeasureMode::Wrap,
+                      &MeasureMode::UpTo(1000.), "symbol_sqrt_rtl");
+
+        // Radical sign has no mirroring pair, its glyphs are mirrored while drawing
+        let rtl = drawn_glyphs(&mut symbol);
+        let ltr = drawn_glyphs(&mut symbol_with_dir(&test_element, |_| "√", |_| &Directionality::LTR));
+        assert!(!rtl.is_empty());
+        assert!(rtl.iter().all(|&(_, synthesis)| synthesis.mirror()));
+        assert!(ltr.iter().all(|&(_, synthesis)| !synthesis.mirror()));
+        assert_eq!(rtl.iter().map(|glyph| glyph.0).collect::<Vec<u32>>(),
+                   ltr.iter().map(|glyph| glyph.0).collect::<Vec<u32>>());
+    }
+
+    fn synthesized_symbol(test_element: &Test, symbol_reader: SymbolReader<Test>) -> Symbol {
+        Symbol::new(
+            test_element,
+            symbol_reader,
+            |_| &MathVariant::Normal,
+            |_| true,
+            |_| 64.,
+            |_| f32::INFINITY,
+            |_| 64.,
+            |_| &Directionality::LTR,
+            |_| &Color::RGB(0, 0, 0)
+        )
+    }
+
+    #[test]
+    fn it_synthesizes_stretchy_symbols() {
+        let test_element = Test {};
+        let snapshot = Snapshot::with_font(stix_without_math(), 64.);
+
+        let vertical: [(SymbolReader<Test>, usize, &str); 5] = [
+            (|_| "(", 1, "paren"),
+            (|_| "[", 3, "bracket"),
+            (|_| "\u{221A}", 3, "sqrt"),
+            (|_| "|", 1, "bar"),
+            (|_| "\u{27E8}", 2, "angle"),
+        ];
+        for &(symbol_reader, children, name) in vertical.iter() {
+            let mut symbol = synthesized_symbol(&test_element, symbol_reader);
+            snapshot.snap_drawable(&mut symbol, &MeasureMode::Wrap, &MeasureMode::UpTo(300.),
+                                   &format!("symbol_synthesized_{}", name));
+
+            assert_eq!(symbol.get_layout().iter().count(), children, "{}", name);
+            assert!(symbol.bounding_box().height() >= 300., "{} is {} high", name,
+                    symbol.bounding_box().height());
+        }
+
+        let mut arrow = synthesized_symbol(&test_element, |_| "\u{2194}");
+        snapshot.snap_drawable(&mut arrow, &MeasureMode::UpTo(400.), &MeasureMode::Wrap,
+                               "symbol_synthesized_arrow");
+        assert_eq!(arrow.get_layout().iter().count(), 3);
+        assert!((arrow.bounding_box().width() - 400.).abs() < 1.);
+        assert!(arrow.bounding_box().height() < 100.);
+    }
+}
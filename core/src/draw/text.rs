@@ -17,11 +17,12 @@
 
 use std::borrow::Cow;
 
-use super::{Drawable, BoundingBox, MeasureMode};
+use super::{Drawable, BoundingBox, MeasureMode, BoxSnapshot, TextStops, GlyphStop};
 use ::props::{MathVariant, Directionality, Color};
-use ::paint::{Canvas, Point, Rect};
+use ::paint::{Canvas, Point, Rect, FontSynthesis, TypeFace, TextRuler, GlyphRun, ShapedGlyph,
+              Ink};
 use ::platform::Context;
-use ::layout::Layout;
+use ::constants::MathVariantsDictionary;
 
 pub type TextReader<T> = fn(&T) -> &str;
 pub type MathSizeReader<T> = fn(&T) -> f32;
@@ -29,87 +30,482 @@ pub type MathVariantReader<T> = fn(&T) -> &MathVariant;
 pub type DirectionalityReader<T> = fn(&T) -> &Directionality;
 pub type MathColorReader<T> = fn(&T) -> &Color;
 
-pub struct Text<'a, T: Layout + 'a> {
-    props: &'a T,
+/// Font text is set in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextFont {
+    /// Math font, with math variants mapped to Mathematical Alphanumeric Symbols
+    Math,
+    /// Text font of the platform, with bold and italic variants set in its styled faces and
+    /// vertical metrics of its own
+    Text,
+}
+
+struct TextRun<'a> {
+    text: Cow<'a, str>,
+    synthesis: FontSynthesis,
+    typeface: TypeFace,
+    glyphs: Option<GlyphRun>,
+    width: f32,
+}
 
-    text_reader: TextReader<T>,
-    math_size_reader: MathSizeReader<T>,
-    math_variant_reader: MathVariantReader<T>,
-    dir_reader: DirectionalityReader<T>,
-    math_color_reader: MathColorReader<T>,
+pub struct Text {
+    text: String,
+    math_size: f32,
+    math_variant: MathVariant,
+    dir: Directionality,
+    math_color: Color,
 
     bounding_box: BoundingBox,
+    font: TextFont,
+    script_level: u32,
+    dotless: bool,
 
-    variant_text: Cow<'a, str>
+    runs: Vec<TextRun<'static>>
 }
 
-impl<'a, T: Layout> Drawable for Text<'a, T> {
+impl<'a> TextRun<'a> {
+    fn into_owned(self) -> TextRun<'static> {
+        TextRun {
+            text: Cow::Owned(self.text.into_owned()),
+            synthesis: self.synthesis,
+            typeface: self.typeface,
+            glyphs: self.glyphs,
+            width: self.width,
+        }
+    }
+}
+
+impl Drawable for Text {
     fn draw(&self, canvas: &Canvas, pen_pos: &Point) {
-        canvas.draw_text(
-            pen_pos,
-            &self.bounding_box,
-            self.variant_text.as_ref(),
-            (self.math_color_reader)(self.props),
-            (self.math_size_reader)(self.props),
-            (self.dir_reader)(self.props)
-        )
+        let dir = &self.dir;
+        let mut offset = 0f32;
+
+        for run in self.runs.iter() {
+            let x = match *dir {
+                Directionality::LTR => offset,
+                Directionality::RTL => self.bounding_box.width() - offset - run.width,
+            };
+
+            if let Some(ref glyphs) = run.glyphs {
+                canvas.draw_glyph_run(
+                    &Point::new(pen_pos.x() + x, pen_pos.y()),
+                    &self.bounding_box,
+                    glyphs,
+                    &self.math_color,
+                    &run.synthesis
+                );
+            }
+
+            offset += run.width;
+        }
     }
 
     fn calculate(&mut self, context: &Context, _: &MeasureMode, _: &MeasureMode) {
-        let size = (self.math_size_reader)(self.props);
+        let size = self.math_size;
+        let dir = &self.dir;
+
+        let text = &self.text;
+        let math_variant = &self.math_variant;
+        let platform = context.platform();
+
+        let dict = context.math_variants_dictionary();
+
+        let typeface = match (self.font, text_style(math_variant)) {
+            (TextFont::Text, Some((bold, italic))) => {
+                // Styles without a face of their own are synthesized on the regular face
+                let synthesis = FontSynthesis::new(bold, italic);
+                let (typeface, face_synthesis) = match platform.styled_text_typeface(bold, italic) {
+                    Some(typeface) => (typeface, FontSynthesis::none()),
+                    None => (platform.text_typeface(), synthesis),
+                };
+
+                self.runs = get_runs(dict, text, &MathVariant::Normal, typeface,
+                                     |unicode| platform.typeface_for_char(typeface, unicode))
+                    .into_iter().map(TextRun::into_owned).collect();
+                for run in self.runs.iter_mut() {
+                    run.synthesis = if run.typeface == *typeface {
+                        face_synthesis
+                    } else {
+                        synthesis
+                    };
+                }
+                typeface
+            },
+            (font, _) => {
+                let typeface = match font {
+                    TextFont::Math => platform.math_typeface(),
+                    TextFont::Text => platform.text_typeface(),
+                };
+
+                self.runs = get_runs(dict, text, math_variant, typeface,
+                                     |unicode| platform.typeface_for_char(typeface, unicode))
+                    .into_iter().map(TextRun::into_owned).collect();
+                typeface
+            }
+        };
+
+        let mut width = 0f32;
+        let mut run_inks = Vec::new();
+
+        // Runs are drawn with the glyphs they were measured with
+        for run in self.runs.iter_mut() {
+            let ruler = platform.get_typeface_ruler(&run.typeface, size);
+            let mut glyphs = ruler.shape(run.text.as_ref(), dir, None);
+
+            // Script style variants are features of the math font
+            let script_level = if run.typeface == *platform.math_typeface() {
+                self.script_level
+            } else {
+                0
+            };
+            if self.dotless || script_level > 0 {
+                let math_ruler = platform.get_math_ruler(size);
+                let dotless = self.dotless;
+                glyphs = substitute_glyphs(glyphs, ruler, dir, |glyph| {
+                    let glyph = if dotless {
+                        ruler.dotless_glyph(glyph).unwrap_or(glyph)
+                    } else {
+                        glyph
+                    };
+                    math_ruler.script_style_glyph(glyph, script_level).unwrap_or(glyph)
+                });
+            }
+
+            run.width = glyphs.width();
+            run_inks.push((width, run.width, ruler.run_ink(&glyphs)));
+            run.glyphs = Some(glyphs);
+            width += run.width;
+        }
 
-        let text = (self.text_reader)(self.props);
+        let ink = Ink::union_all(run_inks.into_iter().filter_map(|(offset, run_width, ink)| {
+            let x = match *dir {
+                Directionality::LTR => offset,
+                Directionality::RTL => width - offset - run_width,
+            };
+            ink.map(|ink| ink.translate(x, 0.))
+        }));
 
-        self.variant_text = get_variant_text(
-            context,
-            text,
-            (self.math_variant_reader)(self.props)
-        );
+        let axis_height = platform.get_math_ruler(size).axis_height();
+        let ruler: &TextRuler = match self.font {
+            TextFont::Math => platform.get_math_ruler(size),
+            TextFont::Text => platform.get_typeface_ruler(typeface, size),
+        };
 
-        let ruler = context.platform().get_math_ruler(size);
-        let width = ruler.measure(self.variant_text.as_ref(), (self.dir_reader)(self.props)).width();
+        // Text font has its own ascent and descent but shares the math axis
         let height = ruler.ascent() - ruler.descent();
         let baseline = ruler.descent().abs();
-        let axis = ruler.axis_height() + baseline;
+        let axis = axis_height + baseline;
 
-        self.bounding_box = BoundingBox {
-            rect: Rect::new(width, height),
-            baseline,
-            axis,
-        }
+        self.bounding_box = BoundingBox::new(Rect::new(width, height), baseline, axis)
+            .with_ink(ink);
     }
 
     fn bounding_box(&self) -> &BoundingBox {
         &self.bounding_box
     }
+
+    // Runs are in logical order with their glyphs in visual order. The glyph nearest to the
+    // point is hit, counting characters of runs before it.
+    fn text_offset(&self, pen_pos: &Point, point: &Point) -> Option<usize> {
+        let dir = &self.dir;
+        let x = point.x() - pen_pos.x();
+        let mut offset = 0f32;
+        let mut chars_before = 0;
+        let mut nearest: Option<(f32, usize)> = None;
+
+        for run in self.runs.iter() {
+            let mut glyph_x = match *dir {
+                Directionality::LTR => offset,
+                Directionality::RTL => self.bounding_box.width() - offset - run.width,
+            };
+
+            for glyph in run.glyphs.iter().flat_map(|glyphs| glyphs.glyphs().iter()) {
+                let distance = if x < glyph_x {
+                    glyph_x - x
+                } else {
+                    (x - glyph_x - glyph.advance()).max(0.)
+                };
+
+                if nearest.map(|(nearest, _)| distance < nearest).unwrap_or(true) {
+                    let cluster = run.text.get(..glyph.cluster()).map(|text| text.chars().count())
+                        .unwrap_or(0);
+                    nearest = Some((distance, chars_before + cluster));
+                }
+                glyph_x += glyph.advance();
+            }
+
+            offset += run.width;
+            chars_before += run.text.chars().count();
+        }
+
+        nearest.map(|(_, offset)| offset)
+    }
+
+    // Caret before a character is on the left of its glyph in left to right text and on the
+    // right in right to left text
+    fn caret_x(&self, pen_pos: &Point, offset: usize) -> Option<f32> {
+        let dir = &self.dir;
+        let mut run_offset = 0f32;
+        let mut chars_before = 0;
+
+        for run in self.runs.iter() {
+            let mut glyph_x = match *dir {
+                Directionality::LTR => run_offset,
+                Directionality::RTL => self.bounding_box.width() - run_offset - run.width,
+            };
+
+            for glyph in run.glyphs.iter().flat_map(|glyphs| glyphs.glyphs().iter()) {
+                let cluster = run.text.get(..glyph.cluster()).map(|text| text.chars().count())
+                    .unwrap_or(0);
+                if chars_before + cluster == offset {
+                    return Some(pen_pos.x() + match *dir {
+                        Directionality::LTR => glyph_x,
+                        Directionality::RTL => glyph_x + glyph.advance(),
+                    });
+                }
+                glyph_x += glyph.advance();
+            }
+
+            run_offset += run.width;
+            chars_before += run.text.chars().count();
+        }
+
+        Some(pen_pos.x() + match *dir {
+            Directionality::LTR => self.bounding_box.width(),
+            Directionality::RTL => 0.,
+        })
+    }
+
+    fn to_snapshot(&self) -> BoxSnapshot {
+        BoxSnapshot::painted(self).with_text(Some(self.text_stops()))
+    }
 }
 
-fn get_variant_text<'a>(context: &Context, text: &'a str, math_variant: &MathVariant) -> Cow<'a, str> {
-    if *math_variant == MathVariant::Normal {
-        return Cow::Borrowed(text);
+// Splits text into runs of variant characters drawn with the same face. Characters for which
+// the variant is not present in the fonts are kept as is and the variant is synthesized where
+// possible. Characters without the variant in unicode, like digits or operators in italic, are
+// kept as is without synthesis. Characters are drawn with `typeface` unless `typeface_for` finds
+// another face of the font stack having them.
+fn get_runs<'a, F>(variant_dict: &MathVariantsDictionary, text: &'a str, math_variant: &MathVariant,
+                   typeface: &TypeFace, typeface_for: F) -> Vec<TextRun<'a>>
+    where F: Fn(u32) -> Option<TypeFace> {
+    let fallback_synthesis = synthesis_for_variant(math_variant);
+    let mut runs: Vec<TextRun<'a>> = Vec::new();
+    let mut run_start = 0;
+
+    for (offset, c) in text.char_indices() {
+        let variant = if *math_variant == MathVariant::Normal {
+            None
+        } else {
+            variant_dict.variant_for_char(&c, math_variant)
+        };
+        let variant_face = variant
+            .and_then(|variant| typeface_for(variant as u32).map(|face| (variant, face)));
+
+        let (mapped, synthesis, face) = match variant_face {
+            Some((variant, face)) => (variant, FontSynthesis::none(), face),
+            None => (c, if variant.is_some() { fallback_synthesis } else { FontSynthesis::none() },
+                     typeface_for(c as u32).unwrap_or_else(|| typeface.clone())),
+        };
+        let end = offset + c.len_utf8();
+
+        if let Some(run) = runs.last_mut() {
+            if run.synthesis == synthesis && run.typeface == face {
+                match run.text {
+                    Cow::Borrowed(_) if mapped == c =>
+                        run.text = Cow::Borrowed(&text[run_start..end]),
+                    _ => run.text.to_mut().push(mapped),
+                }
+                continue;
+            }
+        }
+
+        run_start = offset;
+        let run_text = if mapped == c {
+            Cow::Borrowed(&text[offset..end])
+        } else {
+            Cow::Owned(mapped.to_string())
+        };
+        runs.push(TextRun { text: run_text, synthesis, typeface: face, glyphs: None, width: 0. });
     }
 
-    let variant_dict = context.math_variants_dictionary();
-    let variant = text.chars()
-        .map(|c| if let Some(val) = variant_dict.variant_for_char(&c, math_variant) { val } else { c })
-        .collect::<String>();
+    runs
+}
+
+// Replaces glyphs of a shaped run with their substitutes, which take their own advance
+fn substitute_glyphs<F>(run: GlyphRun, ruler: &TextRuler, dir: &Directionality, substitute: F)
+    -> GlyphRun where F: Fn(u32) -> u32 {
+    let glyphs = run.glyphs().iter()
+        .map(|glyph| {
+            let glyph_index = substitute(glyph.glyph_index());
+            if glyph_index == glyph.glyph_index() {
+                return glyph.clone();
+            }
+
+            ShapedGlyph::new(glyph_index, ruler.measure_glyph(glyph_index, dir).width(),
+                             glyph.x_offset(), glyph.y_offset(), glyph.cluster())
+        })
+        .collect();
 
-    return Cow::Owned(variant);
+    GlyphRun::new(run.text().to_string(), run.typeface().clone(), run.size(), run.dir().clone(),
+                  glyphs)
+}
+
+/// Bold and italic flags of variants set in faces of the text font
+fn text_style(math_variant: &MathVariant) -> Option<(bool, bool)> {
+    match *math_variant {
+        MathVariant::Normal => Some((false, false)),
+        MathVariant::Bold => Some((true, false)),
+        MathVariant::Italic => Some((false, true)),
+        MathVariant::BoldItalic => Some((true, true)),
+        _ => None
+    }
+}
+
+fn synthesis_for_variant(math_variant: &MathVariant) -> FontSynthesis {
+    match *math_variant {
+        MathVariant::Bold | MathVariant::BoldFraktur | MathVariant::BoldScript |
+        MathVariant::BoldSansSerif => FontSynthesis::new(true, false),
+        MathVariant::Italic | MathVariant::SansSerifItalic => FontSynthesis::new(false, true),
+        MathVariant::BoldItalic | MathVariant::SansSerifBoldItalic => FontSynthesis::new(true, true),
+        _ => FontSynthesis::none()
+    }
 }
 
-impl<'a, T: Layout> Text<'a, T> {
-    pub fn new(props: &'a T, text_reader: TextReader<T>, math_size_reader: MathSizeReader<T>,
-               math_variant_reader: MathVariantReader<T>, dir_reader: DirectionalityReader<T>,
-               math_color_reader: MathColorReader<T>) -> Text<'a, T> {
+impl Text {
+    pub fn new<T>(props: &T, text_reader: TextReader<T>, math_size_reader: MathSizeReader<T>,
+                  math_variant_reader: MathVariantReader<T>, dir_reader: DirectionalityReader<T>,
+                  math_color_reader: MathColorReader<T>) -> Text {
         Text {
-            props,
-            text_reader,
-            math_size_reader,
-            math_variant_reader,
-            dir_reader,
-            math_color_reader,
+            text: String::from(text_reader(props)),
+            math_size: math_size_reader(props),
+            math_variant: math_variant_reader(props).clone(),
+            dir: dir_reader(props).clone(),
+            math_color: math_color_reader(props).clone(),
             bounding_box: BoundingBox::default(),
-            variant_text: Cow::default(),
+            font: TextFont::Math,
+            script_level: 0,
+            dotless: false,
+            runs: Vec::new(),
         }
     }
-}
\ No newline at end of file
+
+    pub fn set_font(&mut self, font: TextFont) {
+        self.font = font;
+    }
+
+    /// Script level selecting script style variants of math font glyphs
+    pub fn set_script_level(&mut self, script_level: u32) {
+        self.script_level = script_level;
+    }
+
+    /// Draws letters in their dotless forms, for bases carrying accents
+    pub fn set_dotless(&mut self, dotless: bool) {
+        self.dotless = dotless;
+    }
+
+    // Glyphs at the same positions as in hit testing and carets above
+    fn text_stops(&self) -> TextStops {
+        let dir = &self.dir;
+        let mut glyph_stops = Vec::new();
+        let mut run_offset = 0f32;
+        let mut chars_before = 0;
+
+        for run in self.runs.iter() {
+            let mut glyph_x = match *dir {
+                Directionality::LTR => run_offset,
+                Directionality::RTL => self.bounding_box.width() - run_offset - run.width,
+            };
+
+            for glyph in run.glyphs.iter().flat_map(|glyphs| glyphs.glyphs().iter()) {
+                let cluster = run.text.get(..glyph.cluster()).map(|text| text.chars().count())
+                    .unwrap_or(0);
+                let caret = match *dir {
+                    Directionality::LTR => glyph_x,
+                    Directionality::RTL => glyph_x + glyph.advance(),
+                };
+                glyph_stops.push(GlyphStop::new(glyph_x, glyph.advance(), chars_before + cluster,
+                                                caret));
+                glyph_x += glyph.advance();
+            }
+
+            run_offset += run.width;
+            chars_before += run.text.chars().count();
+        }
+
+        TextStops::new(glyph_stops, match *dir {
+            Directionality::LTR => self.bounding_box.width(),
+            Directionality::RTL => 0.,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn typeface(family: &str) -> TypeFace {
+        TypeFace::new(String::from(family), String::from("Regular"))
+    }
+
+    fn runs(text: &str, math_variant: &MathVariant, has_glyph: fn(u32) -> bool) -> Vec<(String, FontSynthesis)> {
+        let math = typeface("Math");
+        get_runs(&MathVariantsDictionary::new(), text, math_variant, &math,
+                 |unicode| if has_glyph(unicode) { Some(math.clone()) } else { None })
+            .into_iter()
+            .map(|run| (String::from(run.text.as_ref()), run.synthesis))
+            .collect()
+    }
+
+    #[test]
+    fn it_uses_variant_glyphs() {
+        assert_eq!(runs("xR", &MathVariant::DoubleStruck, |_| true),
+                   vec![(String::from("𝕩ℝ"), FontSynthesis::none())]);
+        assert_eq!(runs("ab", &MathVariant::Normal, |_| false),
+                   vec![(String::from("ab"), FontSynthesis::none())]);
+    }
+
+    #[test]
+    fn it_synthesizes_missing_glyphs() {
+        assert_eq!(runs("x1", &MathVariant::BoldItalic, |_| false),
+                   vec![(String::from("x"), FontSynthesis::new(true, true)),
+                        (String::from("1"), FontSynthesis::none())]);
+        assert_eq!(runs("x9", &MathVariant::Bold, |unicode| unicode == '𝐱' as u32),
+                   vec![(String::from("𝐱"), FontSynthesis::none()),
+                        (String::from("9"), FontSynthesis::new(true, false))]);
+    }
+
+    #[test]
+    fn it_keeps_chars_without_variant() {
+        assert_eq!(runs("9", &MathVariant::Fraktur, |_| true),
+                   vec![(String::from("9"), FontSynthesis::none())]);
+        assert_eq!(runs("a+2", &MathVariant::Italic, |unicode| unicode < 0x80),
+                   vec![(String::from("a"), FontSynthesis::new(false, true)),
+                        (String::from("+2"), FontSynthesis::none())]);
+    }
+
+    #[test]
+    fn it_splits_runs_by_font_coverage() {
+        let math = typeface("Math");
+        let fallback = typeface("Fallback");
+        let typeface_for = |unicode: u32| match unicode {
+            0x4E2D => Some(fallback.clone()),
+            0x1F600 => None,
+            _ => Some(math.clone()),
+        };
+
+        let dict = MathVariantsDictionary::new();
+        let runs: Vec<(String, TypeFace, bool)> =
+            get_runs(&dict, "ab\u{4E2D}\u{1F600}", &MathVariant::Normal, &math, typeface_for)
+                .into_iter()
+                .map(|run| {
+                    let borrowed = matches!(run.text, Cow::Borrowed(_));
+                    (String::from(run.text.as_ref()), run.typeface, borrowed)
+                })
+                .collect();
+
+        assert_eq!(runs, vec![(String::from("ab"), math.clone(), true),
+                              (String::from("\u{4E2D}"), fallback.clone(), true),
+                              (String::from("\u{1F600}"), math.clone(), true)]);
+    }
+}
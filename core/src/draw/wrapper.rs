@@ -15,30 +15,46 @@
 */
 
 
-use super::{Drawable, BoundingBox, MeasureMode};
+use super::{Drawable, BoundingBox, MeasureMode, HitTarget, HitResult, Highlight, BoxSnapshot};
 use ::props::Color;
-use ::paint::{Canvas, Point, Rect};
+use ::paint::{Canvas, Point};
 use ::platform::Context;
+use ::elements::InstanceId;
 
 pub type MathBackgroundReader<T> = fn (&T) -> &Color;
 
-pub struct Wrapper<'a, T: 'a, U: Drawable> {
+pub struct Wrapper<U: Drawable> {
     wrapped: Option<U>,
-    props: &'a T,
-    math_background_reader: MathBackgroundReader<T>,
+    math_background: Color,
+    hit_target: Option<HitTarget>,
 
     bounding_box: BoundingBox
 }
 
-impl<'a, T, U: Drawable> Drawable for Wrapper<'a, T, U> {
+impl<U: Drawable> Drawable for Wrapper<U> {
     fn draw(&self, canvas: &Canvas, pen_pos: &Point) {
-        canvas.draw_rect(
-            pen_pos,
-            self.bounding_box.rect(),
-            (self.math_background_reader)(self.props)
-        );
+        self.draw_highlighted(canvas, pen_pos, &Highlight::none(), false);
+    }
+
+    fn draw_highlighted(&self, canvas: &Canvas, pen_pos: &Point, highlight: &Highlight,
+                        selected: bool) {
+        let is_selected = !selected && match self.hit_target {
+            Some(ref target) => highlight.is_selected(target.instance_id()),
+            None => false,
+        };
+
+        if !self.bounding_box.rect().is_empty() {
+            let background = &self.math_background;
+            canvas.draw_rect(pen_pos, self.bounding_box.rect(), background);
+
+            // Selection goes over the background of a selected element, and again over any
+            // opaque background inside it, so that only content is drawn over it
+            if is_selected || (selected && background.a() > 0) {
+                canvas.draw_rect(pen_pos, self.bounding_box.rect(), highlight.color());
+            }
+        }
         if let Some(ref wrapped) = self.wrapped {
-            wrapped.draw(canvas, pen_pos);
+            wrapped.draw_highlighted(canvas, pen_pos, highlight, selected || is_selected);
         }
     }
 
@@ -46,37 +62,71 @@ impl<'a, T, U: Drawable> Drawable for Wrapper<'a, T, U> {
         if let Some(val) = self.wrapped.as_mut() {
             val.calculate(context, width_mode, height_mode);
         }
-        self.bounding_box = BoundingBox {
-            rect: if let Some(ref val) = self.wrapped {
-                val.bounding_box().rect().clone()
-            } else {
-                Rect::new(0., 0.)
-            },
-            baseline: if let Some(ref val) = self.wrapped {
-                val.bounding_box().baseline()
-            } else {
-                0.
-            },
-            axis: if let Some(ref val) = self.wrapped {
-                val.bounding_box().axis()
-            } else {
-                0.
-            },
+        self.bounding_box = if let Some(ref val) = self.wrapped {
+            val.bounding_box().clone()
+        } else {
+            BoundingBox::default()
         }
     }
 
     fn bounding_box(&self) -> &BoundingBox {
         &self.bounding_box
     }
+
+    fn hit_test(&self, pen_pos: &Point, point: &Point) -> Option<HitResult> {
+        let wrapped = self.wrapped.as_ref();
+        if let Some(hit) = wrapped.and_then(|wrapped| wrapped.hit_test(pen_pos, point)) {
+            return Some(hit);
+        }
+
+        match self.hit_target {
+            Some(ref target) if self.bounding_box.contains(pen_pos, point) => {
+                let offset = if target.element_type().is_token() {
+                    wrapped.and_then(|wrapped| wrapped.text_offset(pen_pos, point))
+                } else {
+                    None
+                };
+                Some(HitResult::new(target.clone(), pen_pos.clone(), self.bounding_box.clone(),
+                                    offset))
+            },
+            _ => None
+        }
+    }
+
+    fn text_offset(&self, pen_pos: &Point, point: &Point) -> Option<usize> {
+        self.wrapped.as_ref().and_then(|wrapped| wrapped.text_offset(pen_pos, point))
+    }
+
+    fn locate(&self, pen_pos: &Point, instance_id: &InstanceId) -> Option<(Point, &Drawable)> {
+        if let Some(found) = self.wrapped.as_ref()
+            .and_then(|wrapped| wrapped.locate(pen_pos, instance_id)) {
+            return Some(found);
+        }
+
+        match self.hit_target {
+            Some(ref target) if target.instance_id() == instance_id =>
+                Some((pen_pos.clone(), self)),
+            _ => None
+        }
+    }
+
+    fn caret_x(&self, pen_pos: &Point, offset: usize) -> Option<f32> {
+        self.wrapped.as_ref().and_then(|wrapped| wrapped.caret_x(pen_pos, offset))
+    }
+
+    fn to_snapshot(&self) -> BoxSnapshot {
+        BoxSnapshot::element(self.bounding_box.clone(), self.hit_target.clone(),
+                           self.math_background.clone(),
+                           self.wrapped.as_ref().map(|wrapped| wrapped.to_snapshot()))
+    }
 }
 
-impl<'a, T, U: Drawable> Wrapper<'a, T, U> {
-    pub fn new(props: &'a T, math_background_reader: MathBackgroundReader<T>)
-               -> Wrapper<'a, T, U> {
+impl<U: Drawable> Wrapper<U> {
+    pub fn new<T>(props: &T, math_background_reader: MathBackgroundReader<T>) -> Wrapper<U> {
         Wrapper {
             wrapped: None,
-            props,
-            math_background_reader,
+            math_background: math_background_reader(props).clone(),
+            hit_target: None,
             bounding_box: BoundingBox::default()
         }
     }
@@ -84,4 +134,9 @@ impl<'a, T, U: Drawable> Wrapper<'a, T, U> {
     pub fn wrap(&mut self, drawable: U) {
         self.wrapped = Some(drawable);
     }
+
+    /// Element reported by hit tests of points in this box and not in any inner element
+    pub fn set_hit_target(&mut self, target: Option<HitTarget>) {
+        self.hit_target = target;
+    }
 }
\ No newline at end of file
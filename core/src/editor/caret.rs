@@ -0,0 +1,524 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+
+
+use std::cmp::Ordering;
+
+use ::elements::{Element, ElementType, GeneralLayout, ScriptsAndLimits, Miscellaneous,
+                 Mmultiscripts};
+use ::draw::Drawable;
+use ::paint::{Point, Rect};
+
+/// Position of an editing caret in an element tree. The caret is in the element reached from
+/// the root by `path`, indices into `Element::child_elements`, at `offset`:
+///
+/// - in token text, before the character at the offset
+/// - in rows, before the child at the offset
+/// - in empty slots, at offset zero
+/// - around other elements, before them at offset zero and after them at offset one
+///
+/// Edges of row children are the places between them, so carets there are kept in the row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Caret {
+    path: Vec<usize>,
+    offset: usize,
+}
+
+impl Caret {
+    pub fn new(path: Vec<usize>, offset: usize) -> Caret {
+        Caret { path, offset }
+    }
+
+    /// Caret before the content of `root`
+    pub fn start(root: &Element) -> Caret {
+        Caret::new(Vec::new(), 0).normalize(root)
+    }
+
+    pub fn path(&self) -> &[usize] {
+        &self.path
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Element the caret is in
+    pub fn element<'e>(&self, root: &'e Element) -> Option<&'e Element> {
+        element_at(root, &self.path)
+    }
+
+    /// Caret one place to the left in reading order, or `None` at the start of `root`
+    pub fn move_left(&self, root: &Element) -> Option<Caret> {
+        self.step(root, Caret::step_left)
+    }
+
+    /// Caret one place to the right in reading order, or `None` at the end of `root`
+    pub fn move_right(&self, root: &Element) -> Option<Caret> {
+        self.step(root, Caret::step_right)
+    }
+
+    /// Caret in the slot above, such as the numerator from the denominator or a superscript
+    /// from its base, nearest to this caret in `drawable` laid out for `root`
+    pub fn move_up(&self, root: &Element, drawable: &Drawable) -> Option<Caret> {
+        self.move_vertically(root, drawable, true)
+    }
+
+    /// Caret in the slot below, nearest to this caret in `drawable` laid out for `root`
+    pub fn move_down(&self, root: &Element, drawable: &Drawable) -> Option<Caret> {
+        self.move_vertically(root, drawable, false)
+    }
+
+    /// Top left of the caret and its zero width rectangle, spanning the element it is next to,
+    /// in the coordinates `drawable` laid out for `root` is drawn in from the origin
+    pub fn rect(&self, root: &Element, drawable: &Drawable) -> Option<(Point, Rect)> {
+        let caret = self.normalize(root);
+        let element = element_at(root, &caret.path)?;
+
+        if element.type_info().is_mrow() {
+            let children = element.child_elements();
+            let rtl = is_right_to_left(drawable, &children);
+
+            return match children.get(caret.offset) {
+                Some(child) => edge_rect(drawable, *child, rtl),
+                None => match children.last() {
+                    Some(child) => edge_rect(drawable, *child, !rtl),
+                    None => edge_rect(drawable, element, false),
+                },
+            };
+        }
+
+        if element.text().is_some() {
+            let (top_left, found) = drawable.locate(&Point::new(0., 0.), element.instance_id())?;
+            if let Some(x) = found.caret_x(&top_left, caret.offset) {
+                return Some((Point::new(x, top_left.y()),
+                             Rect::new(0., found.bounding_box().height())));
+            }
+        }
+
+        edge_rect(drawable, element, caret.offset > 0)
+    }
+
+    // Same place expressed in the outermost row
+    pub(crate) fn normalize(&self, root: &Element) -> Caret {
+        let mut caret = self.clone();
+
+        while let Some((&index, parent_path)) = caret.path.split_last() {
+            let element = match element_at(root, &caret.path) {
+                Some(element) => element,
+                None => break,
+            };
+            match element_at(root, parent_path) {
+                Some(parent) if parent.type_info().is_mrow() => {},
+                _ => break,
+            }
+
+            let offset = if caret.offset == 0 {
+                index
+            } else if caret.offset >= max_offset(element) {
+                index + 1
+            } else {
+                break;
+            };
+            caret = Caret::new(parent_path.to_vec(), offset);
+        }
+
+        caret
+    }
+
+    // Steps until the caret is at a different place
+    fn step<F>(&self, root: &Element, step: F) -> Option<Caret>
+        where F: Fn(&Caret, &Element) -> Option<Caret> {
+        let current = self.normalize(root);
+        let mut caret = self.clone();
+
+        loop {
+            caret = step(&caret, root)?;
+            let next = caret.normalize(root);
+            if next != current {
+                return Some(next);
+            }
+        }
+    }
+
+    fn step_right(&self, root: &Element) -> Option<Caret> {
+        let element = element_at(root, &self.path)?;
+        let max = max_offset(element);
+
+        if self.offset < max {
+            return Some(if element.type_info().is_mrow() {
+                child_caret(&self.path, self.offset, 0)
+            } else if element.text().is_some() {
+                Caret::new(self.path.clone(), self.offset + 1)
+            } else if element.child_elements().is_empty() {
+                Caret::new(self.path.clone(), max)
+            } else {
+                // Carets before elements with slots move into the first slot
+                child_caret(&self.path, slot_order(element)[0], 0)
+            });
+        }
+
+        // Carets at the end leave the element into the next slot or past its parent
+        let (&index, parent_path) = self.path.split_last()?;
+        let parent = element_at(root, parent_path)?;
+
+        if parent.type_info().is_mrow() {
+            return Some(Caret::new(parent_path.to_vec(), index + 1));
+        }
+        let order = slot_order(parent);
+        let next = order.iter().position(|&slot| slot == index).and_then(|i| order.get(i + 1));
+
+        Some(match next {
+            Some(&next) => child_caret(parent_path, next, 0),
+            None => Caret::new(parent_path.to_vec(), 1),
+        })
+    }
+
+    fn step_left(&self, root: &Element) -> Option<Caret> {
+        let element = element_at(root, &self.path)?;
+        let offset = self.offset.min(max_offset(element));
+
+        if offset > 0 {
+            let children = element.child_elements();
+            return Some(if element.type_info().is_mrow() {
+                child_caret(&self.path, offset - 1, max_offset(children[offset - 1]))
+            } else if element.text().is_some() {
+                Caret::new(self.path.clone(), offset - 1)
+            } else {
+                // Carets after elements with slots move into the last slot
+                match slot_order(element).last() {
+                    Some(&last) => child_caret(&self.path, last, max_offset(children[last])),
+                    None => Caret::new(self.path.clone(), 0),
+                }
+            });
+        }
+
+        // Carets at the start leave the element into the previous slot or before its parent
+        let (&index, parent_path) = self.path.split_last()?;
+        let parent = element_at(root, parent_path)?;
+
+        if parent.type_info().is_mrow() {
+            return Some(Caret::new(parent_path.to_vec(), index));
+        }
+        let order = slot_order(parent);
+        let previous = order.iter().position(|&slot| slot == index)
+            .and_then(|i| i.checked_sub(1)).map(|i| order[i]);
+
+        Some(match previous {
+            Some(previous) => child_caret(parent_path, previous,
+                                          max_offset(parent.child_elements()[previous])),
+            None => Caret::new(parent_path.to_vec(), 0),
+        })
+    }
+
+    // Moves into the slot above or below of the innermost element having one
+    fn move_vertically(&self, root: &Element, drawable: &Drawable, up: bool) -> Option<Caret> {
+        let caret = self.normalize(root);
+        let x = caret.rect(root, drawable).map(|(top_left, _)| top_left.x());
+
+        for depth in (0..caret.path.len()).rev() {
+            let ancestor = element_at(root, &caret.path[..depth])?;
+            let count = ancestor.child_elements().len();
+            let slot = match vertical_slot(ancestor.type_info(), caret.path[depth], count, up) {
+                Some(slot) => slot,
+                None => continue,
+            };
+
+            let positions = positions_in(root, &child_caret(&caret.path[..depth], slot, 0).path);
+            let nearest = x.and_then(|x| positions.iter()
+                .filter_map(|position| position.rect(root, drawable)
+                    .map(|(top_left, _)| ((top_left.x() - x).abs(), position)))
+                .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal))
+                .map(|(_, position)| position.clone()));
+
+            return nearest.or_else(|| positions.into_iter().next());
+        }
+
+        None
+    }
+}
+
+pub(crate) fn element_at<'e>(root: &'e Element, path: &[usize]) -> Option<&'e Element> {
+    let mut element = root;
+    for &index in path {
+        element = match element.child_elements().get(index) {
+            Some(child) => *child,
+            None => return None,
+        };
+    }
+    Some(element)
+}
+
+fn child_caret(path: &[usize], index: usize, offset: usize) -> Caret {
+    let mut path = path.to_vec();
+    path.push(index);
+    Caret::new(path, offset)
+}
+
+// Offset of a caret at the end of `element`
+fn max_offset(element: &Element) -> usize {
+    match element.text() {
+        Some(text) => text.chars().count(),
+        None if element.type_info().is_mrow() => element.child_elements().len(),
+        // Empty slots hold no content to step over
+        None if element.type_info() == ElementType::Miscellaneous(Miscellaneous::Mempty) ||
+            element.type_info() == ElementType::Miscellaneous(Miscellaneous::Placeholder) => 0,
+        None => 1,
+    }
+}
+
+// Indices of the children of `element` in the order carets move through them, which is the
+// order of `Element::child_elements` except for prescripts, placed before the base
+fn slot_order(element: &Element) -> Vec<usize> {
+    let count = element.child_elements().len();
+    match element.as_any().downcast_ref::<Mmultiscripts>() {
+        Some(multiscripts) => {
+            let prescripts = 1 + 2 * multiscripts.postscript().len();
+            (prescripts..count).chain(0..prescripts).collect()
+        },
+        None => (0..count).collect(),
+    }
+}
+
+// Carets in the element at `path`, from its start to its end
+fn positions_in(root: &Element, path: &[usize]) -> Vec<Caret> {
+    let mut positions = Vec::new();
+    let mut caret = Some(Caret::new(path.to_vec(), 0).normalize(root));
+
+    while let Some(current) = caret {
+        if !current.path.starts_with(path) {
+            break;
+        }
+        caret = current.move_right(root);
+        positions.push(current);
+    }
+
+    positions
+}
+
+// Child a caret moves to from child `index` of an element with `count` children, when moving
+// up or down
+fn vertical_slot(element_type: ElementType, index: usize, count: usize, up: bool) -> Option<usize> {
+    let slot = match element_type {
+        // First child is over the second
+        ElementType::GeneralLayout(GeneralLayout::Mfrac) |
+        ElementType::GeneralLayout(GeneralLayout::Mroot) |
+        ElementType::ScriptsAndLimits(ScriptsAndLimits::Munder) |
+        ElementType::ScriptsAndLimits(ScriptsAndLimits::Msub) => match (index, up) {
+            (1, true) => Some(0),
+            (0, false) => Some(1),
+            _ => None,
+        },
+        // Second child is over the first
+        ElementType::ScriptsAndLimits(ScriptsAndLimits::Msup) => match (index, up) {
+            (0, true) => Some(1),
+            (1, false) => Some(0),
+            _ => None,
+        },
+        // Overscript over base over underscript
+        ElementType::ScriptsAndLimits(ScriptsAndLimits::Munderover) => match (index, up) {
+            (0, true) => Some(2),
+            (1, true) => Some(0),
+            (0, false) => Some(1),
+            (2, false) => Some(0),
+            _ => None,
+        },
+        // Subscript and superscript pairs following the base
+        ElementType::ScriptsAndLimits(ScriptsAndLimits::Msubsup) |
+        ElementType::ScriptsAndLimits(ScriptsAndLimits::Mmutliscripts) => match (index, up) {
+            (0, true) => Some(2),
+            (0, false) => Some(1),
+            (index, true) if index % 2 == 1 => Some(index + 1),
+            (index, false) if index % 2 == 0 => Some(index - 1),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    slot.filter(|&slot| slot < count)
+}
+
+// Children of right to left rows are laid out from the right
+fn is_right_to_left(drawable: &Drawable, children: &[&Element]) -> bool {
+    let origin = Point::new(0., 0.);
+    let x = |child: &&Element| drawable.locate(&origin, child.instance_id())
+        .map(|(top_left, _)| top_left.x());
+
+    match (children.first().and_then(&x), children.last().and_then(&x)) {
+        (Some(first), Some(last)) => first > last,
+        _ => false,
+    }
+}
+
+// Caret on the left or right edge of the box laid out for `element`
+fn edge_rect(drawable: &Drawable, element: &Element, right: bool) -> Option<(Point, Rect)> {
+    let (top_left, found) = drawable.locate(&Point::new(0., 0.), element.instance_id())?;
+    let bounding_box = found.bounding_box();
+    let x = if right { top_left.x() + bounding_box.width() } else { top_left.x() };
+
+    Some((Point::new(x, top_left.y()), Rect::new(0., bounding_box.height())))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::elements::*;
+    use ::test::fixture::{stix_context, draw_root};
+
+    fn walk_right(root: &Element) -> Vec<Caret> {
+        let mut carets = vec![Caret::start(root)];
+        while let Some(next) = carets.last().unwrap().move_right(root) {
+            carets.push(next);
+        }
+        carets
+    }
+
+    fn walk_left(root: &Element, end: &Caret) -> Vec<Caret> {
+        let mut carets = vec![end.clone()];
+        while let Some(previous) = carets.last().unwrap().move_left(root) {
+            carets.push(previous);
+        }
+        carets.reverse();
+        carets
+    }
+
+    #[test]
+    fn it_moves_through_rows_and_tokens() {
+        let mut mrow = Mrow::new();
+        mrow.with_child(Box::new(Mn::new(String::from("12"))))
+            .with_child(Box::new(Mo::new(String::from("+"))))
+            .with_child(Box::new(Mi::new(String::from("x"))));
+
+        let carets = walk_right(&mrow);
+        assert_eq!(carets, vec![
+            Caret::new(vec![], 0),
+            Caret::new(vec![0], 1),
+            Caret::new(vec![], 1),
+            Caret::new(vec![], 2),
+            Caret::new(vec![], 3),
+        ]);
+        assert_eq!(walk_left(&mrow, carets.last().unwrap()), carets);
+    }
+
+    #[test]
+    fn it_moves_through_slots() {
+        let mut mrow = Mrow::new();
+        mrow.with_child(Box::new(Mfrac::new(Box::new(Mi::new(String::from("a"))),
+                                            Box::new(Mempty::new()))))
+            .with_child(Box::new(Mi::new(String::from("b"))));
+
+        let carets = walk_right(&mrow);
+        assert_eq!(carets, vec![
+            Caret::new(vec![], 0),
+            Caret::new(vec![0, 0], 0),
+            Caret::new(vec![0, 0], 1),
+            Caret::new(vec![0, 1], 0),
+            Caret::new(vec![], 1),
+            Caret::new(vec![], 2),
+        ]);
+        assert_eq!(walk_left(&mrow, carets.last().unwrap()), carets);
+    }
+
+    #[test]
+    fn it_moves_through_prescripts_before_base() {
+        let mi = |text: &str| Box::new(Mi::new(String::from(text))) as Box<Element>;
+        let mut multiscripts = Mmultiscripts::new(mi("x"));
+        multiscripts.with_postscript(Mmultiscript { subscript: mi("a"), superscript: mi("b") })
+            .with_prescript(Mmultiscript { subscript: mi("c"), superscript: mi("d") });
+        let mut mrow = Mrow::new();
+        mrow.with_child(Box::new(multiscripts));
+
+        // Children are the base, postscripts and prescripts, which are laid out first
+        let carets = walk_right(&mrow);
+        assert_eq!(carets, vec![
+            Caret::new(vec![], 0),
+            Caret::new(vec![0, 3], 0),
+            Caret::new(vec![0, 3], 1),
+            Caret::new(vec![0, 4], 0),
+            Caret::new(vec![0, 4], 1),
+            Caret::new(vec![0, 0], 0),
+            Caret::new(vec![0, 0], 1),
+            Caret::new(vec![0, 1], 0),
+            Caret::new(vec![0, 1], 1),
+            Caret::new(vec![0, 2], 0),
+            Caret::new(vec![0, 2], 1),
+            Caret::new(vec![], 1),
+        ]);
+        assert_eq!(walk_left(&mrow, carets.last().unwrap()), carets);
+    }
+
+    #[test]
+    fn it_moves_between_numerator_and_denominator() {
+        let context = stix_context();
+
+        let mut numerator = Mrow::new();
+        numerator.with_child(Box::new(Mi::new(String::from("a"))))
+            .with_child(Box::new(Mo::new(String::from("+"))))
+            .with_child(Box::new(Mi::new(String::from("b"))));
+        let mfrac = Mfrac::new(Box::new(numerator), Box::new(Mn::new(String::from("2"))));
+
+        let drawable = draw_root(&mfrac, &context);
+
+        // Denominator is centered, so the end of numerator is nearest to its end
+        let end_of_numerator = Caret::new(vec![0], 3);
+        assert_eq!(end_of_numerator.move_down(&mfrac, drawable.as_ref()),
+                   Some(Caret::new(vec![1], 1)));
+        assert_eq!(end_of_numerator.move_up(&mfrac, drawable.as_ref()), None);
+
+        let up = Caret::new(vec![1], 0).move_up(&mfrac, drawable.as_ref()).unwrap();
+        assert_eq!(up.path(), &[0]);
+        assert!(up.offset() > 0 && up.offset() < 3);
+    }
+
+    #[test]
+    fn it_moves_into_scripts() {
+        let context = stix_context();
+
+        let mut mrow = Mrow::new();
+        mrow.with_child(Box::new(Msup::new(Box::new(Mi::new(String::from("x"))),
+                                           Box::new(Mn::new(String::from("2"))))))
+            .with_child(Box::new(Mi::new(String::from("y"))));
+
+        let drawable = draw_root(&mrow, &context);
+
+        let after_base = Caret::new(vec![0, 0], 1);
+        let up = after_base.move_up(&mrow, drawable.as_ref()).unwrap();
+        assert_eq!(up, Caret::new(vec![0, 1], 0));
+        assert_eq!(up.move_down(&mrow, drawable.as_ref()), Some(after_base.clone()));
+        assert_eq!(Caret::new(vec![], 2).move_up(&mrow, drawable.as_ref()), None);
+    }
+
+    #[test]
+    fn it_places_carets_between_characters() {
+        let context = stix_context();
+
+        let mtext = Mtext::new(String::from("abc"));
+        let drawable = draw_root(&mtext, &context);
+        let bounding_box = drawable.bounding_box();
+
+        let xs: Vec<f32> = (0..4).map(|offset| {
+            let (top_left, rect) = Caret::new(vec![], offset).rect(&mtext, drawable.as_ref())
+                .unwrap();
+            assert_eq!(top_left.y(), 0.);
+            assert_eq!(rect.height(), bounding_box.height());
+            top_left.x()
+        }).collect();
+
+        assert_eq!(xs[0], 0.);
+        assert!(xs[0] < xs[1] && xs[1] < xs[2] && xs[2] < xs[3]);
+        assert!((xs[3] - bounding_box.width()).abs() < 0.01);
+    }
+}
@@ -0,0 +1,694 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+
+
+use std::error::Error;
+use std::fmt;
+use std::mem;
+
+use ::elements::{Element, ElementType, GeneralLayout, TokenElement, Miscellaneous, InstanceId, Mrow,
+                 Mo, Mempty};
+
+/// Edit of an element tree, addressed by the instance ids of its elements. Applying a command
+/// returns its inverse, which undoes the edit when applied to the edited tree.
+///
+/// Content of wrappers is the slot reached from the wrapper by a path of indices into
+/// `Element::child_elements`, or the wrapper itself when the path is empty. See
+/// [`content_path`](fn.content_path.html) for the slot used by default.
+pub enum Command {
+    /// Inserts `element` in `row` before the child at `index`
+    Insert { row: InstanceId, index: usize, element: Box<Element> },
+    /// Removes `target` from the row it is in
+    Delete { target: InstanceId },
+    /// Puts `element` in the place of `target`
+    Replace { target: InstanceId, element: Box<Element> },
+    /// Replaces children `start..end` of `row` with `wrapper`, moving them to its content. A
+    /// content row gets the children appended, any other slot takes the only child when it is not
+    /// a row and a new row of the children otherwise.
+    Wrap { row: InstanceId, start: usize, end: usize, wrapper: Box<Element>, content: Vec<usize> },
+    /// Replaces `target`, which must be in a row, with its content. Children of a content row
+    /// from `start` on are moved out and any other content is moved out entirely. The slot is then
+    /// given `restore` when present, otherwise a content row keeps its remaining children and any
+    /// other slot is left with an `Mempty`.
+    Unwrap { target: InstanceId, content: Vec<usize>, start: usize, restore: Option<Box<Element>> },
+    /// Moves children of `row` from `index` on to the row `tail`, placed after `row` in the row
+    /// it is in
+    Split { row: InstanceId, index: usize, tail: Box<Element> },
+    /// Moves children of the row after `row`, in the row it is in, to the end of `row` and
+    /// removes the emptied row
+    Join { row: InstanceId },
+    /// Moves `target` from the row it is in to `row` before the child at `index`, counted with
+    /// `target` removed
+    Move { target: InstanceId, row: InstanceId, index: usize },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EditError {
+    NotFound(InstanceId),
+    NotRow(InstanceId),
+    NotInRow(InstanceId),
+    OutOfBounds(usize),
+    NoContent,
+    IsRoot,
+    IntoItself(InstanceId),
+}
+
+impl fmt::Display for EditError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            EditError::NotFound(ref id) => write!(f, "no element {:?} in tree", id),
+            EditError::NotRow(ref id) => write!(f, "element {:?} is not a row", id),
+            EditError::NotInRow(ref id) => write!(f, "element {:?} is not in a row", id),
+            EditError::OutOfBounds(index) => write!(f, "index {} is out of bounds", index),
+            EditError::NoContent => write!(f, "wrapper has no content slot at path"),
+            EditError::IsRoot => write!(f, "root element cannot be removed"),
+            EditError::IntoItself(ref id) =>
+                write!(f, "element {:?} cannot be moved into itself", id),
+        }
+    }
+}
+
+impl Error for EditError {
+    fn description(&self) -> &str {
+        "Cannot edit element tree"
+    }
+}
+
+impl Command {
+    /// Wraps children `start..end` of `row` in the default content slot of `wrapper`
+    pub fn wrap(row: InstanceId, start: usize, end: usize, wrapper: Box<Element>) -> Command {
+        let content = content_path(wrapper.as_ref());
+        Command::Wrap { row, start, end, wrapper, content }
+    }
+
+    /// Wraps children `start..end` of `row` in a row between `open` and `close` fence
+    /// operators
+    pub fn wrap_in_fence(row: InstanceId, start: usize, end: usize, open: &str, close: &str)
+                         -> Command {
+        let mut fence = Mrow::new();
+        fence.with_child(Box::new(Mo::new(String::from(open))))
+            .with_child(Box::new(Mrow::new()))
+            .with_child(Box::new(Mo::new(String::from(close))));
+        Command::Wrap { row, start, end, wrapper: Box::new(fence), content: vec![1] }
+    }
+
+    /// Unwraps the default content slot of `target`
+    pub fn unwrap(target: &Element) -> Command {
+        Command::Unwrap { target: target.instance_id().clone(), content: content_path(target),
+                          start: 0, restore: None }
+    }
+
+    /// Splits `row` before the child at `index` into a new row
+    pub fn split(row: InstanceId, index: usize) -> Command {
+        Command::Split { row, index, tail: Box::new(Mrow::new()) }
+    }
+
+    /// Edits `root` and returns the command undoing the edit. The tree is left unchanged on
+    /// errors.
+    pub fn apply(self, root: &mut Element) -> Result<Command, EditError> {
+        self.try_apply(root).map_err(|(_, error)| error)
+    }
+
+    // Like `apply`, but gives the command back with errors, so that it can be kept to apply later
+    pub(crate) fn try_apply(self, root: &mut Element) -> Result<Command, (Command, EditError)> {
+        match self {
+            Command::Insert { row, index, element } => {
+                let checked = find_row(root, &row).and_then(|mrow| {
+                    if index > mrow.children().len() {
+                        return Err(EditError::OutOfBounds(index));
+                    }
+                    Ok(mrow)
+                });
+                let mrow = match checked {
+                    Ok(mrow) => mrow,
+                    Err(error) => return Err((Command::Insert { row, index, element }, error)),
+                };
+
+                let target = element.instance_id().clone();
+                mrow.insert_child(index, element);
+                Ok(Command::Delete { target })
+            },
+
+            Command::Delete { target } => {
+                let (mrow, index) = match find_in_row(root, &target) {
+                    Ok(found) => found,
+                    Err(error) => return Err((Command::Delete { target }, error)),
+                };
+                let element = mrow.remove_child(index);
+                Ok(Command::Insert { row: mrow.instance_id().clone(), index, element })
+            },
+
+            Command::Replace { target, element } => {
+                let (parent, index) = match find_parent(root, &target) {
+                    Ok(found) => found,
+                    Err(error) => return Err((Command::Replace { target, element }, error)),
+                };
+                let slot = parent.child_elements_mut().into_iter().nth(index)
+                    .expect("Parent has no slot of its child");
+
+                let new_target = element.instance_id().clone();
+                let element = mem::replace(slot, element);
+                Ok(Command::Replace { target: new_target, element })
+            },
+
+            Command::Wrap { row, start, end, mut wrapper, content } => {
+                let checked = if content_slot(&mut wrapper, &content).is_none() {
+                    Err(EditError::NoContent)
+                } else {
+                    find_row(root, &row).and_then(|mrow| {
+                        if start > end || end > mrow.children().len() {
+                            return Err(EditError::OutOfBounds(end));
+                        }
+                        Ok(mrow)
+                    })
+                };
+                let mrow = match checked {
+                    Ok(mrow) => mrow,
+                    Err(error) =>
+                        return Err((Command::Wrap { row, start, end, wrapper, content }, error)),
+                };
+
+                let children = (start..end).map(|_| mrow.remove_child(start)).collect();
+                let (content_start, restore) = fill_content(&mut wrapper, &content, children);
+
+                let target = wrapper.instance_id().clone();
+                mrow.insert_child(start, wrapper);
+                Ok(Command::Unwrap { target, content, start: content_start, restore })
+            },
+
+            Command::Unwrap { target, content, start, restore } => {
+                let checked = find_in_row(root, &target).and_then(|(mrow, index)| {
+                    match content_slot(&mut mrow.children_mut()[index], &content) {
+                        Some(slot) => if let Some(row) = as_row(slot.as_mut()) {
+                            if start > row.children().len() {
+                                return Err(EditError::OutOfBounds(start));
+                            }
+                        },
+                        None => return Err(EditError::NoContent),
+                    }
+                    Ok((mrow, index))
+                });
+                let (mrow, index) = match checked {
+                    Ok(found) => found,
+                    Err(error) =>
+                        return Err((Command::Unwrap { target, content, start, restore }, error)),
+                };
+
+                let children = take_content(&mut mrow.children_mut()[index], &content, start,
+                                            restore);
+                let count = children.len();
+                let wrapper = mrow.remove_child(index);
+                for (offset, child) in children.into_iter().enumerate() {
+                    mrow.insert_child(index + offset, child);
+                }
+
+                Ok(Command::Wrap { row: mrow.instance_id().clone(), start: index,
+                                   end: index + count, wrapper, content })
+            },
+
+            Command::Split { row, index, mut tail } => {
+                let checked = if as_row(tail.as_mut()).is_none() {
+                    Err(EditError::NotRow(tail.instance_id().clone()))
+                } else {
+                    find_in_row(root, &row).and_then(|(parent, position)| {
+                        let len = as_row(parent.children_mut()[position].as_mut())
+                            .ok_or_else(|| EditError::NotRow(row.clone()))?
+                            .children().len();
+                        if index > len {
+                            return Err(EditError::OutOfBounds(index));
+                        }
+                        Ok((parent, position))
+                    })
+                };
+                let (parent, position) = match checked {
+                    Ok(found) => found,
+                    Err(error) => return Err((Command::Split { row, index, tail }, error)),
+                };
+                {
+                    let first = as_row(parent.children_mut()[position].as_mut())
+                        .expect("Split element is not a row");
+                    let tail = as_row(tail.as_mut()).expect("Tail is not a row");
+                    while first.children().len() > index {
+                        let child = first.remove_child(index);
+                        tail.with_child(child);
+                    }
+                }
+
+                parent.insert_child(position + 1, tail);
+                Ok(Command::Join { row })
+            },
+
+            Command::Join { row } => {
+                let checked = find_in_row(root, &row).and_then(|(parent, position)| {
+                    if as_row(parent.children_mut()[position].as_mut()).is_none() {
+                        return Err(EditError::NotRow(row.clone()));
+                    }
+                    match parent.children_mut().get_mut(position + 1) {
+                        Some(next) => if as_row(next.as_mut()).is_none() {
+                            return Err(EditError::NotRow(next.instance_id().clone()));
+                        },
+                        None => return Err(EditError::OutOfBounds(position + 1)),
+                    }
+                    Ok((parent, position))
+                });
+                let (parent, position) = match checked {
+                    Ok(found) => found,
+                    Err(error) => return Err((Command::Join { row }, error)),
+                };
+
+                let mut tail = parent.remove_child(position + 1);
+                let first = as_row(parent.children_mut()[position].as_mut())
+                    .expect("Joined element is not a row");
+                let index = first.children().len();
+                {
+                    let tail = as_row(tail.as_mut()).expect("Next element is not a row");
+                    while !tail.children().is_empty() {
+                        first.with_child(tail.remove_child(0));
+                    }
+                }
+
+                Ok(Command::Split { row, index, tail })
+            },
+
+            Command::Move { target, row, index } => {
+                let (from, from_index) = match check_move(root, &target, &row, index) {
+                    Ok(found) => found,
+                    Err(error) => return Err((Command::Move { target, row, index }, error)),
+                };
+
+                let element = find_row(root, &from).expect("Moved element is not in a row")
+                    .remove_child(from_index);
+                find_row(root, &row).expect("Destination is not a row")
+                    .insert_child(index, element);
+                Ok(Command::Move { target, row: from, index: from_index })
+            },
+        }
+    }
+}
+
+// Row that `target` is moved from and its index in the row
+fn check_move(root: &mut Element, target: &InstanceId, row: &InstanceId, index: usize)
+              -> Result<(InstanceId, usize), EditError> {
+    let (from, from_index) = {
+        let (mrow, from_index) = find_in_row(root, target)?;
+        if find(mrow.children()[from_index].as_ref(), row).is_some() {
+            return Err(EditError::IntoItself(target.clone()));
+        }
+        (mrow.instance_id().clone(), from_index)
+    };
+
+    let len = find_row(root, row)?.children().len();
+    let len = if *row == from { len - 1 } else { len };
+    if index > len {
+        return Err(EditError::OutOfBounds(index));
+    }
+    Ok((from, from_index))
+}
+
+/// Path to the slot of `element` that wrapping puts content in: the radicand of roots, the
+/// content between the fences of rows made by `Command::wrap_in_fence`, the row itself for
+/// other rows and the first child for other elements with children
+pub fn content_path(element: &Element) -> Vec<usize> {
+    match element.type_info() {
+        ElementType::GeneralLayout(GeneralLayout::Mroot) => vec![1],
+        ElementType::GeneralLayout(GeneralLayout::Mrow) if is_fence(element) => vec![1],
+        ElementType::GeneralLayout(GeneralLayout::Mrow) => Vec::new(),
+        _ => vec![0],
+    }
+}
+
+fn is_fence(element: &Element) -> bool {
+    let children = element.child_elements();
+    let is_mo = |child: &Element|
+        child.type_info() == ElementType::TokenElement(TokenElement::Mo);
+
+    children.len() == 3 && is_mo(children[0]) && children[1].type_info().is_mrow() &&
+        is_mo(children[2])
+}
+
+fn as_row(element: &mut Element) -> Option<&mut Mrow> {
+    element.as_any_mut().downcast_mut::<Mrow>()
+}
+
+fn find<'e>(element: &'e Element, id: &InstanceId) -> Option<&'e Element> {
+    if element.instance_id() == id {
+        return Some(element);
+    }
+    element.child_elements().into_iter().filter_map(|child| find(child, id)).next()
+}
+
+fn find_mut<'e>(element: &'e mut Element, id: &InstanceId) -> Option<&'e mut Element> {
+    if element.instance_id() == id {
+        return Some(element);
+    }
+    for child in element.child_elements_mut() {
+        if let Some(found) = find_mut(child.as_mut(), id) {
+            return Some(found);
+        }
+    }
+    None
+}
+
+// Element with `id` as a child and the index of the child
+fn find_parent<'e>(root: &'e mut Element, id: &InstanceId)
+                   -> Result<(&'e mut Element, usize), EditError> {
+    if root.instance_id() == id {
+        return Err(EditError::IsRoot);
+    }
+    parent_of(root, id).ok_or_else(|| EditError::NotFound(id.clone()))
+}
+
+fn parent_of<'e>(element: &'e mut Element, id: &InstanceId) -> Option<(&'e mut Element, usize)> {
+    let index = element.child_elements().iter().position(|child| child.instance_id() == id);
+    if let Some(index) = index {
+        return Some((element, index));
+    }
+    for child in element.child_elements_mut() {
+        if let Some(found) = parent_of(child.as_mut(), id) {
+            return Some(found);
+        }
+    }
+    None
+}
+
+fn find_row<'e>(root: &'e mut Element, id: &InstanceId) -> Result<&'e mut Mrow, EditError> {
+    let element = find_mut(root, id).ok_or_else(|| EditError::NotFound(id.clone()))?;
+    as_row(element).ok_or_else(|| EditError::NotRow(id.clone()))
+}
+
+// Row with `id` as a child and the index of the child
+fn find_in_row<'e>(root: &'e mut Element, id: &InstanceId)
+                   -> Result<(&'e mut Mrow, usize), EditError> {
+    let (parent, index) = find_parent(root, id)?;
+    let row = as_row(parent).ok_or_else(|| EditError::NotInRow(id.clone()))?;
+    Ok((row, index))
+}
+
+// Slot holding the content of `wrapper`, which is the wrapper itself for an empty path to a row
+fn content_slot<'e>(wrapper: &'e mut Box<Element>, path: &[usize])
+                    -> Option<&'e mut Box<Element>> {
+    if path.is_empty() && !wrapper.type_info().is_mrow() {
+        return None;
+    }
+    slot_at(wrapper, path)
+}
+
+fn slot_at<'e>(element: &'e mut Box<Element>, path: &[usize]) -> Option<&'e mut Box<Element>> {
+    match path.split_first() {
+        Some((&index, rest)) => {
+            let slot = element.child_elements_mut().into_iter().nth(index)?;
+            slot_at(slot, rest)
+        },
+        None => Some(element),
+    }
+}
+
+// Moves `children` to the content slot of `wrapper`. Returns the index of the first child in a
+// content row and the element replaced in any other slot. Empty slots are not returned, so that
+// unwrapping leaves the row made for the children, which is reused when wrapping again.
+fn fill_content(wrapper: &mut Box<Element>, path: &[usize], mut children: Vec<Box<Element>>)
+                -> (usize, Option<Box<Element>>) {
+    let slot = content_slot(wrapper, path).expect("Wrapper has no content slot");
+
+    if let Some(row) = as_row(slot.as_mut()) {
+        let start = row.children().len();
+        for child in children {
+            row.with_child(child);
+        }
+        return (start, None);
+    }
+
+    let content = if children.len() == 1 && !children[0].type_info().is_mrow() {
+        children.remove(0)
+    } else {
+        let mut row = Mrow::new();
+        for child in children {
+            row.with_child(child);
+        }
+        Box::new(row)
+    };
+    let replaced = mem::replace(slot, content);
+    if replaced.type_info() == ElementType::Miscellaneous(Miscellaneous::Mempty) {
+        (0, None)
+    } else {
+        (0, Some(replaced))
+    }
+}
+
+// Moves content out of the content slot of `wrapper`, the children of a content row from `start`
+// on, and puts `restore` in the slot
+fn take_content(wrapper: &mut Box<Element>, path: &[usize], start: usize,
+                restore: Option<Box<Element>>) -> Vec<Box<Element>> {
+    let slot = content_slot(wrapper, path).expect("Wrapper has no content slot");
+
+    let children = if let Some(row) = as_row(slot.as_mut()) {
+        (start..row.children().len()).map(|_| row.remove_child(start)).collect()
+    } else {
+        vec![mem::replace(slot, Box::new(Mempty::new()))]
+    };
+
+    if let Some(restore) = restore {
+        *slot = restore;
+    }
+    children
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::History;
+    use ::elements::*;
+
+    // Text of tokens, `_` for empty slots and children of other elements in brackets
+    fn describe(element: &Element) -> String {
+        match element.text() {
+            Some(text) => String::from(text),
+            None if element.type_info() == ElementType::Miscellaneous(Miscellaneous::Mempty) =>
+                String::from("_"),
+            None => format!("[{}]", element.child_elements().iter()
+                .map(|child| describe(*child))
+                .collect::<Vec<String>>()
+                .join(" ")),
+        }
+    }
+
+    fn mi(text: &str) -> Box<Element> {
+        Box::new(Mi::new(String::from(text)))
+    }
+
+    fn sum() -> Mrow {
+        let mut mrow = Mrow::new();
+        mrow.with_child(mi("a"))
+            .with_child(Box::new(Mo::new(String::from("+"))))
+            .with_child(mi("b"));
+        mrow
+    }
+
+    fn id(element: &Element, path: &[usize]) -> InstanceId {
+        let mut element = element;
+        for &index in path {
+            element = element.child_elements()[index];
+        }
+        element.instance_id().clone()
+    }
+
+    #[test]
+    fn it_inserts_deletes_and_replaces() {
+        let mut mrow = sum();
+        let row = mrow.instance_id().clone();
+
+        let undo = Command::Insert { row: row.clone(), index: 3, element: mi("c") }
+            .apply(&mut mrow).unwrap();
+        assert_eq!(describe(&mrow), "[a + b c]");
+        undo.apply(&mut mrow).unwrap();
+        assert_eq!(describe(&mrow), "[a + b]");
+
+        let target = id(&mrow, &[1]);
+        let undo = Command::Delete { target }.apply(&mut mrow).unwrap();
+        assert_eq!(describe(&mrow), "[a b]");
+        undo.apply(&mut mrow).unwrap();
+        assert_eq!(describe(&mrow), "[a + b]");
+
+        mrow.with_child(Box::new(Mfrac::new(mi("x"), Box::new(Mempty::new()))));
+        let target = id(&mrow, &[3, 1]);
+        let undo = Command::Replace { target, element: mi("y") }.apply(&mut mrow).unwrap();
+        assert_eq!(describe(&mrow), "[a + b [x y]]");
+        undo.apply(&mut mrow).unwrap();
+        assert_eq!(describe(&mrow), "[a + b [x _]]");
+
+        let root = mrow.instance_id().clone();
+        assert_eq!(Command::Delete { target: root }.apply(&mut mrow).err(),
+                   Some(EditError::IsRoot));
+        assert_eq!(Command::Insert { row: row.clone(), index: 9, element: mi("c") }
+                       .apply(&mut mrow).err(), Some(EditError::OutOfBounds(9)));
+    }
+
+    #[test]
+    fn it_wraps_and_unwraps() {
+        let mut mrow = sum();
+        let row = mrow.instance_id().clone();
+
+        let fraction = Mfrac::new(Box::new(Mempty::new()), mi("y"));
+        let undo = Command::wrap(row.clone(), 0, 3, Box::new(fraction)).apply(&mut mrow).unwrap();
+        assert_eq!(describe(&mrow), "[[[a + b] y]]");
+        let numerator = id(&mrow, &[0, 0]);
+
+        let redo = undo.apply(&mut mrow).unwrap();
+        assert_eq!(describe(&mrow), "[a + b]");
+        redo.apply(&mut mrow).unwrap();
+        assert_eq!(describe(&mrow), "[[[a + b] y]]");
+        assert_eq!(id(&mrow, &[0, 0]), numerator);
+
+        let mut mrow = sum();
+        let undo = Command::wrap(row.clone(), 2, 3, Box::new(Msqrt::new(Box::new(Mempty::new()))))
+            .apply(&mut mrow);
+        assert_eq!(undo.err(), Some(EditError::NotFound(row)));
+
+        let row = mrow.instance_id().clone();
+        Command::wrap_in_fence(row.clone(), 1, 3, "(", ")").apply(&mut mrow).unwrap();
+        assert_eq!(describe(&mrow), "[a [( [+ b] )]]");
+        let undo = Command::unwrap(mrow.child_elements()[1]).apply(&mut mrow).unwrap();
+        assert_eq!(describe(&mrow), "[a + b]");
+        undo.apply(&mut mrow).unwrap();
+        assert_eq!(describe(&mrow), "[a [( [+ b] )]]");
+
+        let undo = Command::wrap(row, 0, 1, Box::new(Mroot::new(Box::new(Mempty::new()), mi("3"))))
+            .apply(&mut mrow).unwrap();
+        assert_eq!(describe(&mrow), "[[3 a] [( [+ b] )]]");
+        undo.apply(&mut mrow).unwrap();
+        assert_eq!(describe(&mrow), "[a [( [+ b] )]]");
+    }
+
+    #[test]
+    fn it_restores_content_replaced_by_wrap() {
+        let wrapper_of = |command: &Command| match *command {
+            Command::Wrap { ref wrapper, .. } => describe(wrapper.as_ref()),
+            _ => panic!("Inverse of unwrap is not a wrap"),
+        };
+
+        // Content of a slot which is not a row is put back
+        let mut mrow = sum();
+        let row = mrow.instance_id().clone();
+        let fraction = Mfrac::new(mi("x"), mi("y"));
+        let undo = Command::wrap(row.clone(), 0, 1, Box::new(fraction)).apply(&mut mrow).unwrap();
+        assert_eq!(describe(&mrow), "[[a y] + b]");
+        let redo = undo.apply(&mut mrow).unwrap();
+        assert_eq!(describe(&mrow), "[a + b]");
+        assert_eq!(wrapper_of(&redo), "[x y]");
+
+        let undo = redo.apply(&mut mrow).unwrap();
+        assert_eq!(describe(&mrow), "[[a y] + b]");
+        undo.apply(&mut mrow).unwrap();
+        assert_eq!(describe(&mrow), "[a + b]");
+
+        // Only the wrapped children are moved out of a content row which had children
+        let mut content = Mrow::new();
+        content.with_child(mi("c")).with_child(mi("d"));
+        let undo = Command::wrap(row.clone(), 1, 3, Box::new(content)).apply(&mut mrow).unwrap();
+        assert_eq!(describe(&mrow), "[a [c d + b]]");
+        let redo = undo.apply(&mut mrow).unwrap();
+        assert_eq!(describe(&mrow), "[a + b]");
+        assert_eq!(wrapper_of(&redo), "[c d]");
+
+        redo.apply(&mut mrow).unwrap();
+        assert_eq!(describe(&mrow), "[a [c d + b]]");
+
+        let result = Command::Unwrap { target: id(&mrow, &[1]), content: Vec::new(), start: 5,
+                                       restore: None }.apply(&mut mrow);
+        assert_eq!(result.err(), Some(EditError::OutOfBounds(5)));
+        assert_eq!(describe(&mrow), "[a [c d + b]]");
+    }
+
+    #[test]
+    fn it_splits_joins_and_moves() {
+        let mut mrow = Mrow::new();
+        mrow.with_child(mi("x")).with_child(Box::new(sum()));
+        let inner = id(&mrow, &[1]);
+
+        let undo = Command::split(inner.clone(), 1).apply(&mut mrow).unwrap();
+        assert_eq!(describe(&mrow), "[x [a] [+ b]]");
+        let redo = undo.apply(&mut mrow).unwrap();
+        assert_eq!(describe(&mrow), "[x [a + b]]");
+        redo.apply(&mut mrow).unwrap();
+        assert_eq!(describe(&mrow), "[x [a] [+ b]]");
+        Command::Join { row: inner.clone() }.apply(&mut mrow).unwrap();
+
+        let target = id(&mrow, &[0]);
+        let undo = Command::Move { target, row: inner.clone(), index: 3 }
+            .apply(&mut mrow).unwrap();
+        assert_eq!(describe(&mrow), "[[a + b x]]");
+        undo.apply(&mut mrow).unwrap();
+        assert_eq!(describe(&mrow), "[x [a + b]]");
+
+        let result = Command::Move { target: inner.clone(), row: inner.clone(), index: 0 }
+            .apply(&mut mrow);
+        assert_eq!(result.err(), Some(EditError::IntoItself(inner)));
+        assert_eq!(describe(&mrow), "[x [a + b]]");
+    }
+
+    #[test]
+    fn it_undoes_and_redoes() {
+        let mut mrow = sum();
+        let row = mrow.instance_id().clone();
+        let mut history = History::new();
+
+        history.apply(&mut mrow, Command::wrap(row.clone(), 2, 3, Box::new(
+            Msup::new(Box::new(Mempty::new()), mi("2"))))).unwrap();
+        let base = id(&mrow, &[2, 0]);
+        history.apply(&mut mrow, Command::Replace { target: base, element: mi("c") }).unwrap();
+        assert_eq!(describe(&mrow), "[a + [c 2]]");
+
+        assert_eq!(history.undo(&mut mrow), Ok(true));
+        assert_eq!(history.undo(&mut mrow), Ok(true));
+        assert_eq!(history.undo(&mut mrow), Ok(false));
+        assert_eq!(describe(&mrow), "[a + b]");
+
+        assert_eq!(history.redo(&mut mrow), Ok(true));
+        assert_eq!(history.redo(&mut mrow), Ok(true));
+        assert_eq!(describe(&mrow), "[a + [c 2]]");
+        assert!(history.can_undo() && !history.can_redo());
+    }
+
+    #[test]
+    fn it_keeps_history_when_undo_fails() {
+        let mut mrow = sum();
+        let row = mrow.instance_id().clone();
+        let mut history = History::new();
+
+        history.apply(&mut mrow, Command::Insert { row: row.clone(), index: 3, element: mi("c") })
+            .unwrap();
+        let added = id(&mrow, &[3]);
+
+        // Undoing deletes the added element, which is not in the tree while it is taken out
+        let taken = Command::Delete { target: added.clone() }.apply(&mut mrow).unwrap();
+        assert_eq!(history.undo(&mut mrow), Err(EditError::NotFound(added)));
+        assert!(history.can_undo() && !history.can_redo());
+
+        taken.apply(&mut mrow).unwrap();
+        assert_eq!(history.undo(&mut mrow), Ok(true));
+        assert_eq!(describe(&mrow), "[a + b]");
+
+        // Redoing inserts at the end of the row, which is out of bounds while it is shorter
+        let taken = Command::Delete { target: id(&mrow, &[2]) }.apply(&mut mrow).unwrap();
+        assert_eq!(history.redo(&mut mrow), Err(EditError::OutOfBounds(3)));
+        assert!(!history.can_undo() && history.can_redo());
+
+        taken.apply(&mut mrow).unwrap();
+        assert_eq!(history.redo(&mut mrow), Ok(true));
+        assert_eq!(describe(&mrow), "[a + b c]");
+    }
+}
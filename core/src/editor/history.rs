@@ -0,0 +1,84 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+
+
+use super::{Command, EditError};
+use ::elements::Element;
+
+/// Commands undoing and redoing edits of an element tree
+pub struct History {
+    undo: Vec<Command>,
+    redo: Vec<Command>,
+}
+
+impl History {
+    pub fn new() -> History {
+        History { undo: Vec::new(), redo: Vec::new() }
+    }
+
+    /// Applies `command` to `root` as a new edit, after which undone edits cannot be redone
+    pub fn apply(&mut self, root: &mut Element, command: Command) -> Result<(), EditError> {
+        let inverse = command.apply(root)?;
+        self.undo.push(inverse);
+        self.redo.clear();
+        Ok(())
+    }
+
+    /// Undoes the last edit of `root`, returning `false` when there is none. The edit is kept to
+    /// undo later when it cannot be undone.
+    pub fn undo(&mut self, root: &mut Element) -> Result<bool, EditError> {
+        match self.undo.pop() {
+            Some(command) => match command.try_apply(root) {
+                Ok(inverse) => {
+                    self.redo.push(inverse);
+                    Ok(true)
+                },
+                Err((command, error)) => {
+                    self.undo.push(command);
+                    Err(error)
+                },
+            },
+            None => Ok(false),
+        }
+    }
+
+    /// Redoes the last undone edit of `root`, returning `false` when there is none. The edit is
+    /// kept to redo later when it cannot be redone.
+    pub fn redo(&mut self, root: &mut Element) -> Result<bool, EditError> {
+        match self.redo.pop() {
+            Some(command) => match command.try_apply(root) {
+                Ok(inverse) => {
+                    self.undo.push(inverse);
+                    Ok(true)
+                },
+                Err((command, error)) => {
+                    self.redo.push(command);
+                    Err(error)
+                },
+            },
+            None => Ok(false),
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
@@ -0,0 +1,187 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+
+
+use super::{Caret, element_at};
+use ::elements::Element;
+use ::draw::Highlight;
+use ::props::Color;
+
+/// Part of an element tree selected in an editor, with paths as in `Caret`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Selection {
+    /// Children `start..end` of the row at `path`
+    Range { path: Vec<usize>, start: usize, end: usize },
+    /// Element at `path` with all its content
+    Subtree { path: Vec<usize> },
+}
+
+impl Selection {
+    /// Smallest selection with everything between `anchor` and `focus`, or `None` when they are
+    /// at the same place. Carets in different children of a row select the children between
+    /// them, and carets in different slots of any other element select the whole element.
+    pub fn between(root: &Element, anchor: &Caret, focus: &Caret) -> Option<Selection> {
+        let anchor = anchor.normalize(root);
+        let focus = focus.normalize(root);
+        if anchor == focus {
+            return None;
+        }
+
+        let path: Vec<usize> = anchor.path().iter().zip(focus.path().iter())
+            .take_while(|&(a, f)| a == f)
+            .map(|(a, _)| *a)
+            .collect();
+
+        if !element_at(root, &path)?.type_info().is_mrow() {
+            return Some(Selection::Subtree { path });
+        }
+
+        let (anchor_start, anchor_end) = row_span(&anchor, path.len());
+        let (focus_start, focus_end) = row_span(&focus, path.len());
+        Some(Selection::Range {
+            start: anchor_start.min(focus_start),
+            end: anchor_end.max(focus_end),
+            path,
+        })
+    }
+
+    /// Selection of the element at `path` in `root`, as a range of one child when it is in a row
+    pub fn element(root: &Element, path: Vec<usize>) -> Selection {
+        match path.split_last() {
+            Some((&index, parent_path)) => match element_at(root, parent_path) {
+                Some(parent) if parent.type_info().is_mrow() => return Selection::Range {
+                    path: parent_path.to_vec(),
+                    start: index,
+                    end: index + 1,
+                },
+                _ => {},
+            },
+            None => {},
+        }
+
+        Selection::Subtree { path }
+    }
+
+    pub fn path(&self) -> &[usize] {
+        match *self {
+            Selection::Range { ref path, .. } | Selection::Subtree { ref path } => path,
+        }
+    }
+
+    /// Selected elements in reading order, each with all its content
+    pub fn elements<'e>(&self, root: &'e Element) -> Vec<&'e Element> {
+        let element = match element_at(root, self.path()) {
+            Some(element) => element,
+            None => return Vec::new(),
+        };
+
+        match *self {
+            Selection::Range { start, end, .. } => element.child_elements().into_iter()
+                .skip(start)
+                .take(end.saturating_sub(start))
+                .collect(),
+            Selection::Subtree { .. } => vec![element],
+        }
+    }
+
+    /// Highlight painting the boxes of selected elements in `color`, for drawables laid out
+    /// for `root`
+    pub fn highlight(&self, root: &Element, color: Color) -> Highlight {
+        Highlight::new(self.elements(root).iter()
+                           .map(|element| element.instance_id().clone())
+                           .collect(), color)
+    }
+}
+
+// Children of the row at depth `depth` of the caret path that a selection to the caret includes
+fn row_span(caret: &Caret, depth: usize) -> (usize, usize) {
+    match caret.path().get(depth) {
+        Some(&index) => (index, index + 1),
+        None => (caret.offset(), caret.offset()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::elements::*;
+    use ::backend::display_list::{DisplayList, DrawCommand};
+    use ::paint::Point;
+    use ::test::fixture::{stix_context, draw_root};
+
+    fn sum() -> Mrow {
+        let mut mrow = Mrow::new();
+        mrow.with_child(Box::new(Mn::new(String::from("123"))))
+            .with_child(Box::new(Mo::new(String::from("+"))))
+            .with_child(Box::new(Mi::new(String::from("x"))));
+        mrow
+    }
+
+    #[test]
+    fn it_selects_between_carets() {
+        let mrow = sum();
+
+        assert_eq!(Selection::between(&mrow, &Caret::new(vec![], 3), &Caret::new(vec![], 1)),
+                   Some(Selection::Range { path: vec![], start: 1, end: 3 }));
+        assert_eq!(Selection::between(&mrow, &Caret::new(vec![0], 1), &Caret::new(vec![], 2)),
+                   Some(Selection::Range { path: vec![], start: 0, end: 2 }));
+        assert_eq!(Selection::between(&mrow, &Caret::new(vec![0], 1), &Caret::new(vec![0], 2)),
+                   Some(Selection::Subtree { path: vec![0] }));
+        assert_eq!(Selection::between(&mrow, &Caret::new(vec![0], 3), &Caret::new(vec![], 1)),
+                   None);
+
+        let frac = Mfrac::new(Box::new(sum()), Box::new(Mi::new(String::from("y"))));
+        assert_eq!(Selection::between(&frac, &Caret::new(vec![0], 1), &Caret::new(vec![1], 0)),
+                   Some(Selection::Subtree { path: vec![] }));
+        assert_eq!(Selection::element(&frac, vec![0, 2]),
+                   Selection::Range { path: vec![0], start: 2, end: 3 });
+    }
+
+    #[test]
+    fn it_paints_selection_over_backgrounds() {
+        let context = stix_context();
+        let selected = Color::RGB(255, 0, 0);
+        let background = Color::RGB(0, 0, 255);
+
+        let mut mi = Mi::new(String::from("x"));
+        mi.with_math_background(Some(background.clone()));
+        let mut mrow = Mrow::new();
+        mrow.with_child(Box::new(Mn::new(String::from("1"))))
+            .with_child(Box::new(Mfrac::new(Box::new(mi), Box::new(Mn::new(String::from("2"))))));
+
+        let drawable = draw_root(&mrow, &context);
+        let list = DisplayList::new(drawable.bounding_box());
+        let highlight = Selection::element(&mrow, vec![1]).highlight(&mrow, selected.clone());
+        highlight.draw(drawable.as_ref(), &list, &Point::new(0., 0.));
+
+        let commands = list.commands();
+        let painted: Vec<Option<Color>> = commands.iter()
+            .filter_map(|command| match *command {
+                DrawCommand::Rect { ref color, .. } if color.a() > 0 => Some(Some(color.clone())),
+                DrawCommand::Text { .. } | DrawCommand::Glyph { .. } |
+                DrawCommand::GlyphRun { .. } => Some(None),
+                _ => None,
+            })
+            .collect();
+
+        // Content of the first child, then the fraction highlighted and the numerator background
+        // covered again, under the fraction content
+        assert_eq!(painted[..4].to_vec(),
+                   vec![None, Some(selected.clone()), Some(background), Some(selected)]);
+        assert!(painted[4..].iter().all(Option::is_none));
+    }
+}
@@ -16,10 +16,44 @@
 
 
 use super::{Element};
+use ::utils::is_space_like;
+
+/// Position of an element among the children of the row it is laid out in. For inferred groups
+/// of an `Mrow` these are the members of the group instead of the children of the element.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowPosition {
+    /// Number of children in the row
+    pub len: usize,
+    /// Number of children in the row which are not space like
+    pub non_space_like: usize,
+    /// Element is the first child which is not space like
+    pub first: bool,
+    /// Element is the last child which is not space like
+    pub last: bool,
+}
+
+impl RowPosition {
+    /// Position of `element` among `children`
+    pub fn in_row(children: &[&Element], element: &Element) -> RowPosition {
+        let non_space_like: Vec<&&Element> = children.iter()
+            .filter(|child| !is_space_like(**child))
+            .collect();
+        let is_element = |child: Option<&&&Element>|
+            child.map_or(false, |child| child.instance_id() == element.instance_id());
+
+        RowPosition {
+            len: children.len(),
+            non_space_like: non_space_like.len(),
+            first: is_element(non_space_like.first()),
+            last: is_element(non_space_like.last()),
+        }
+    }
+}
 
 pub struct Family<'a> {
     grand_parent: Option<&'a Family<'a>>,
-    parent: Option<&'a Element>
+    parent: Option<&'a Element>,
+    position: Option<RowPosition>,
 }
 
 impl<'a> Family<'a> {
@@ -27,6 +61,7 @@ impl<'a> Family<'a> {
         Family {
             grand_parent: None,
             parent: None,
+            position: None,
         }
     }
 
@@ -34,6 +69,16 @@ impl<'a> Family<'a> {
         Family {
             grand_parent: Some(self),
             parent: Some(element),
+            position: None,
+        }
+    }
+
+    /// Adds a row `element` as parent, with the `position` of the child laid out in it
+    pub fn add_at(&'a self, element: &'a Element, position: RowPosition) -> Family<'a> {
+        Family {
+            grand_parent: Some(self),
+            parent: Some(element),
+            position: Some(position),
         }
     }
 
@@ -44,6 +89,10 @@ impl<'a> Family<'a> {
     pub fn grand_parent(&'a self) -> Option<&'a Family<'a>> {
         self.grand_parent.clone()
     }
+
+    pub fn position(&self) -> Option<&RowPosition> {
+        self.position.as_ref()
+    }
 }
 
 #[cfg(test)]
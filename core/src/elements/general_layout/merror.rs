@@ -67,6 +67,14 @@ impl Element for Merror {
     fn instance_id(&self) -> &InstanceId {
         &self.instance_id
     }
+
+    fn child_elements(&self) -> Vec<&Element> {
+        vec![self.child.as_ref()]
+    }
+
+    fn child_elements_mut(&mut self) -> Vec<&mut Box<Element>> {
+        vec![&mut self.child]
+    }
 }
 
 impl PresentationPrivate<Merror> for Merror {
@@ -86,7 +94,7 @@ mod test {
     use super::*;
     use ::elements::*;
     use ::props::*;
-    use ::test::skia::Snapshot;
+    use ::test::snapshot::Snapshot;
 
     #[test]
     fn it_works() {
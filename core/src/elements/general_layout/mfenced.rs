@@ -21,7 +21,9 @@ use super::super::{Element, ElementType, GeneralLayout, InheritedProps, StylePro
                    Presentation, PresentationPrivate, SpecifiedPresentationProps,
                    PropertyCalculator, Property, EmptyComputeCtx, Mrow, Mo, Token};
 use ::platform::Context;
-use ::layout::{Layout};
+use ::layout::{Layout, MrowLayout};
+use ::props::Directionality;
+use ::utils::Grouping;
 
 #[allow(const_err)]
 const PROP_OPEN: Property<String, Mfenced, EmptyComputeCtx> = Property::Specified {
@@ -41,12 +43,22 @@ const PROP_SEPARATORS: Property<String, Mfenced, EmptyComputeCtx> = Property::Sp
     reader: |i| i.mfenced_separators(),
 };
 
+#[allow(const_err)]
+const PROP_DIRECTIONALITY: Property<Directionality, Mfenced, EmptyComputeCtx> = Property::Inherited {
+    reader: |i| i.dir(),
+    writer: |v, fork| fork.dir(v)
+};
+
 pub struct Mfenced {
     open: Option<String>,
     close: Option<String>,
     separators: Option<String>,
 
-    placeholders: RefCell<Mrow>,
+    children: Vec<Box<Element>>,
+
+    // Opening and closing fences followed by separators, made from the props calculated in
+    // layout. Operators are kept while their text is the same, so that their layouts are reused.
+    operators: RefCell<Vec<Mo>>,
 
     presentation_props: SpecifiedPresentationProps,
     instance_id: InstanceId,
@@ -54,22 +66,12 @@ pub struct Mfenced {
 
 impl Mfenced {
     pub fn new() -> Mfenced {
-        let mut mrow = Mrow::new();
-
-        // Opening fence placeholder
-        mrow.with_child(Box::new(Mo::new(String::new())));
-
-        // Content placeholder
-        mrow.with_child(Box::new(Mrow::new()));
-
-        // Closing fence placeholder
-        mrow.with_child(Box::new(Mo::new(String::new())));
-
         Mfenced {
             open: None,
             close: None,
             separators: None,
-            placeholders: RefCell::new(mrow),
+            children: Vec::new(),
+            operators: RefCell::new(Vec::new()),
 
             presentation_props: SpecifiedPresentationProps::default(),
             instance_id: InstanceId::new(),
@@ -77,17 +79,7 @@ impl Mfenced {
     }
 
     pub fn with_child<'a>(&'a mut self, child: Box<Element>) -> &'a mut Mfenced {
-        // Scope for mutable borrow of self
-        {
-            let content = &mut self.placeholders.get_mut().children_mut()[1];
-            let content: &mut Mrow = content.as_any_mut().downcast_mut::<Mrow>().unwrap();
-
-            if content.children().len() > 0 {
-                // Separator placeholder
-                content.with_child(Box::new(Mo::new(String::new())));
-            }
-            content.with_child(child);
-        }
+        self.children.push(child);
         self
     }
     
@@ -117,6 +109,20 @@ impl Mfenced {
     pub fn separators(&self) -> Option<&String> {
         self.separators.as_ref()
     }
+
+    // Replaces operators whose text is changed
+    fn update_operators(&self, texts: Vec<String>) {
+        let mut operators = self.operators.borrow_mut();
+        operators.truncate(texts.len());
+
+        for (index, text) in texts.into_iter().enumerate() {
+            if index == operators.len() {
+                operators.push(Mo::new(text));
+            } else if operators[index].get_text() != text {
+                operators[index] = Mo::new(text);
+            }
+        }
+    }
 }
 
 impl Element for Mfenced {
@@ -126,6 +132,7 @@ impl Element for Mfenced {
             context, self, family, inherited, style.clone());
 
         let presentation_layout = self.layout_presentation(&mut calculator);
+        let dir = calculator.calculate(&PROP_DIRECTIONALITY, None);
         let open = calculator.calculate(&PROP_OPEN, self.open.as_ref());
         let close = calculator.calculate(&PROP_CLOSE, self.close.as_ref());
 
@@ -137,53 +144,42 @@ impl Element for Mfenced {
         let inherited_fork = calculator.make_fork().copy();
         let new_family = family.add(self);
 
-        // Scope for mutating placeholders based on calculated props
-        {
-            let mut placeholders = self.placeholders.borrow_mut();
-
-            // Updated math background since it is not inherited
-            placeholders.with_math_background(Some(presentation_layout.math_background));
-
-            let placeholders: &mut Mrow = placeholders.as_any_mut().downcast_mut::<Mrow>().unwrap();
-            let children = placeholders.children_mut();
-
-            // Scope for opening fence operator mutable borrow
-            {
-                let open_fence_op: &mut Mo = children[0].as_any_mut().downcast_mut::<Mo>()
-                    .expect("Cannot find opening fence placeholder");
-                open_fence_op.with_text(open);
-            }
-
-            // Scope for closing fence operator mutable borrow
-            {
-                let close_fence_op: &mut Mo = children[2].as_any_mut().downcast_mut::<Mo>()
-                    .expect("Cannot find closing fence placeholder");
-                close_fence_op.with_text(close);
-            }
-
-            // Scope for content mutable borrow
-            {
-                let content: &mut Mrow = children[1].as_any_mut().downcast_mut::<Mrow>()
-                    .expect("Cannot find contents");
-
-                let mut latest_separator = separators[0];
-                let mut separator_index = 0usize;
-                for (index, child) in content.children_mut().iter_mut().enumerate() {
-                    if index % 2 != 0 {
-                        let separator: &mut Mo = child.as_any_mut().downcast_mut::<Mo>()
-                            .expect(&format!("Expected operator at index {}", index));
-                        latest_separator = *separators.get(separator_index).unwrap_or(&latest_separator);
-                        separator.with_text(latest_separator.to_string());
-                        separator_index += 1;
-                    }
-                }
+        // Separators after the last one repeat it, and there are none when it is empty
+        let num_separators = if separators.is_empty() {
+            0
+        } else {
+            self.children.len().saturating_sub(1)
+        };
+        let mut texts = vec![open, close];
+        texts.extend((0..num_separators).map(|index|
+            separators.get(index).unwrap_or(&separators[separators.len() - 1]).to_string()));
+        self.update_operators(texts);
+
+        // Laid out as an mrow of fences around an inferred mrow of children and separators
+        let operators = self.operators.borrow();
+        let mut elements: Vec<&Element> = vec![&operators[0]];
+        for (index, child) in self.children.iter().enumerate() {
+            if index > 0 && index <= num_separators {
+                elements.push(&operators[index + 1]);
             }
+            elements.push(child.as_ref());
         }
-
-        let row = self.placeholders.borrow();
-        let layout = row.layout(context, &new_family, &inherited_fork, style);
-
-        return layout;
+        elements.push(&operators[1]);
+
+        let content = (1..elements.len() - 1).map(Grouping::Child).collect();
+        let groups = [Grouping::Child(0), Grouping::Row(content),
+            Grouping::Child(elements.len() - 1)];
+
+        let row = Mrow::new();
+        let mut layout = MrowLayout {
+            presentation_element: presentation_layout,
+            dir,
+            elements: Vec::new(),
+        };
+        layout.elements = Mrow::layout_groups(&row, &elements, &groups, context, &new_family,
+                                              &inherited_fork, style, &layout);
+
+        Box::new(layout)
     }
 
     fn type_info(&self) -> ElementType {
@@ -201,6 +197,14 @@ impl Element for Mfenced {
     fn instance_id(&self) -> &InstanceId {
         &self.instance_id
     }
+
+    fn child_elements(&self) -> Vec<&Element> {
+        self.children.iter().map(|child| child.as_ref()).collect()
+    }
+
+    fn child_elements_mut(&mut self) -> Vec<&mut Box<Element>> {
+        self.children.iter_mut().collect()
+    }
 }
 
 impl PresentationPrivate<Mfenced> for Mfenced {
@@ -218,7 +222,7 @@ impl Presentation<Mfenced> for Mfenced {}
 
 #[cfg(test)]
 mod test {
-    use ::test::skia::Snapshot;
+    use ::test::snapshot::Snapshot;
     use super::*;
     use elements::*;
     use ::props::*;
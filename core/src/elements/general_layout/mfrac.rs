@@ -19,7 +19,7 @@ use std::any::Any;
 
 use super::super::{Element, Presentation, PresentationPrivate, ElementType, GeneralLayout,
                    SpecifiedPresentationProps, InheritedProps, StyleProps, Property,
-                   PropertyCalculator, InstanceId, Family, EmptyComputeCtx};
+                   PropertyCalculator, InstanceId, Family, EmptyComputeCtx, layout_child};
 use ::props::*;
 use ::platform::Context;
 use ::layout::{Layout, MfracLayout};
@@ -167,11 +167,16 @@ impl Element for Mfrac {
         }
         let fork = fork.copy();
 
+        let mut denominator_fork = fork.copier();
+        denominator_fork.cramped(true);
+        let denominator_fork = denominator_fork.copy();
+
         let new_family = family.add(self);
 
         Box::new(MfracLayout {
-            numerator: self.numerator.layout(context, &new_family, &fork, style),
-            denominator: self.denominator.layout(context, &new_family, &fork, style),
+            numerator: layout_child(self.numerator.as_ref(), context, &new_family, &fork, style),
+            denominator: layout_child(self.denominator.as_ref(), context, &new_family,
+                                      &denominator_fork, style),
             dir,
             line_thickness,
             num_align,
@@ -196,6 +201,14 @@ impl Element for Mfrac {
     fn instance_id(&self) -> &InstanceId {
         &self.instance_id
     }
+
+    fn child_elements(&self) -> Vec<&Element> {
+        vec![self.numerator.as_ref(), self.denominator.as_ref()]
+    }
+
+    fn child_elements_mut(&mut self) -> Vec<&mut Box<Element>> {
+        vec![&mut self.numerator, &mut self.denominator]
+    }
 }
 
 impl PresentationPrivate<Mfrac> for Mfrac {
@@ -215,7 +228,92 @@ impl Presentation<Mfrac> for Mfrac {}
 mod test {
     use super::*;
     use ::elements::*;
-    use ::test::skia::Snapshot;
+    use ::test::snapshot::Snapshot;
+    use ::backend::display_list::{DisplayList, DrawCommand};
+    use ::paint::Point;
+    use ::test::fixture::{stix_context, draw_root};
+
+    #[test]
+    fn it_stacks_without_rule() {
+        let context = stix_context();
+
+        let mut mfrac = Mfrac::new(
+            Box::new(Mi::new(String::from("n"))),
+            Box::new(Mi::new(String::from("k"))));
+        mfrac.with_line_thickness(Some(LineThickness::PX(0.)));
+
+        let drawable = draw_root(&mfrac, &context);
+        let list = DisplayList::new(drawable.bounding_box());
+        drawable.draw(&list, &Point::new(0., 0.));
+
+        let commands = list.commands();
+        assert!(commands.iter().all(|command| match *command {
+            DrawCommand::Line { .. } => false,
+            _ => true,
+        }), "Rule drawn for zero thickness");
+
+        let runs: Vec<(f32, f32)> = commands.iter().filter_map(|command| match *command {
+            DrawCommand::GlyphRun { ref top_left, ref bound, .. } =>
+                Some((top_left.y(), top_left.y() + bound.height())),
+            _ => None,
+        }).collect();
+        assert_eq!(runs.len(), 2);
+
+        let gap = (runs[1].0 - runs[0].1).max(runs[0].0 - runs[1].1);
+        let gap_min = context.platform().get_math_ruler(32.).stack_display_style_gap_min();
+        assert!(gap >= gap_min - 0.01, "Gap {} is below {}", gap, gap_min);
+    }
+
+    // Ink gaps between the rule and the numerator and denominator drawn by a fraction
+    fn rule_gaps(context: &Context, mfrac: &Mfrac, display_style: bool) -> (f32, f32) {
+        let mut inherited = InheritedProps::default().copier();
+        inherited.display_style(display_style);
+        let layout = mfrac.layout(context, &Family::new(), &inherited.copy(), &None);
+        let drawable = layout.layout(context);
+        let list = DisplayList::new(drawable.bounding_box());
+        drawable.draw(&list, &Point::new(0., 0.));
+
+        let commands = list.commands();
+        let (rule_top, rule_bottom) = commands.iter().filter_map(|command| match *command {
+            DrawCommand::Line { ref start, stroke_width, .. } =>
+                Some((start.y() - stroke_width/2., start.y() + stroke_width/2.)),
+            _ => None,
+        }).next().expect("Rule not drawn");
+
+        let inks: Vec<(f32, f32)> = commands.iter().filter_map(|command| match *command {
+            DrawCommand::GlyphRun { ref top_left, ref bound, .. } => {
+                let baseline = top_left.y() + bound.baseline_pos();
+                Some((baseline - bound.ink_ascent(), baseline - bound.ink_descent()))
+            },
+            _ => None,
+        }).collect();
+        assert_eq!(inks.len(), 2);
+
+        (rule_top - inks[0].1, inks[1].0 - rule_bottom)
+    }
+
+    #[test]
+    fn it_measures_gaps_to_ink() {
+        let context = stix_context();
+        let ruler = context.platform().get_math_ruler(32.);
+
+        let mut num = Mi::new(String::from("y"));
+        num.with_math_size(Some(MathSize::BIG));
+        let mut denom = Mi::new(String::from("b"));
+        denom.with_math_size(Some(MathSize::BIG));
+        let mfrac = Mfrac::new(Box::new(num), Box::new(denom));
+        let (num_gap, denom_gap) = rule_gaps(&context, &mfrac, true);
+
+        // Large glyphs are kept apart by the minimum gaps rather than the baseline shifts
+        assert!((num_gap - ruler.fraction_num_display_style_gap_min()).abs() < 0.01,
+                "{} is not the numerator gap", num_gap);
+        assert!((denom_gap - ruler.fraction_denominator_display_style_gap_min()).abs() < 0.01,
+                "{} is not the denominator gap", denom_gap);
+
+        let (num_gap, denom_gap) = rule_gaps(&context, &mfrac, false);
+        assert!(num_gap >= ruler.fraction_numerator_gap_min());
+        assert!(denom_gap >= ruler.fraction_denominator_gap_min());
+    }
 
     #[test]
     fn it_works() {
@@ -295,6 +393,13 @@ mod test {
         snap.snap_element(
             &mfrac,
             "mfrac_bevelled");
+
+        let mut rtl = Mrow::new();
+        rtl.with_dir(Some(Directionality::RTL))
+            .with_child(Box::new(mfrac));
+        snap.snap_element(
+            &rtl,
+            "mfrac_bevelled_rtl");
     }
 
     #[test]
@@ -325,4 +430,40 @@ mod test {
         snap.snap_element(&nested, "mfrac_nested");
     }
 
-}
\ No newline at end of file
+
+    #[test]
+    fn it_mirrors_in_rtl() {
+        let snap = Snapshot::default();
+
+        let numerator = Mi::new(String::from("a"));
+        let denominator = Mn::new(String::from("123"));
+        let ids = [numerator.instance_id().clone(), denominator.instance_id().clone()];
+        let ids = [&ids[0], &ids[1]];
+
+        let mut mfrac = Mfrac::new(Box::new(numerator), Box::new(denominator));
+        mfrac.with_num_align(Some(HAlign::Left));
+        let mut row = Mrow::new();
+        row.with_child(Box::new(mfrac));
+
+        // Numerator aligned to the left is aligned to the right in right to left layout
+        let ltr = snap.positions(&row, &ids);
+        row.with_dir(Some(Directionality::RTL));
+        let rtl = snap.positions(&row, &ids);
+        snap.snap_element(&row, "mfrac_num_left_rtl");
+
+        assert!((ltr[0].x() - ltr[1].x()).abs() < 0.01);
+        assert!(rtl[0].x() > rtl[1].x());
+        assert!((ltr[0].y() - rtl[0].y()).abs() < 0.01);
+
+        // Bevelled fraction has numerator on the right of denominator in right to left layout
+        row.children_mut()[0].as_any_mut().downcast_mut::<Mfrac>().unwrap()
+            .with_bevelled(Some(true));
+        let rtl = snap.positions(&row, &ids);
+        row.with_dir(Some(Directionality::LTR));
+        let ltr = snap.positions(&row, &ids);
+
+        assert!(ltr[0].x() < ltr[1].x());
+        assert!(rtl[0].x() > rtl[1].x());
+        assert!(rtl[0].y() < rtl[1].y());
+    }
+}
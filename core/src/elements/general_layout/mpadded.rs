@@ -19,7 +19,7 @@ use std::any::Any;
 
 use super::super::{Element, InstanceId, Family, InheritedProps, StyleProps, ElementType, GeneralLayout,
                    PropertyCalculator, PresentationPrivate, Presentation, SpecifiedPresentationProps,
-                   EmptyComputeCtx, Property};
+                   EmptyComputeCtx, Property, layout_child};
 use ::layout::{Layout, MpaddedLayout};
 use ::props::{PseudoLength, PropertyModifier};
 use ::platform::Context;
@@ -157,7 +157,8 @@ impl Element for Mpadded {
             depth,
             lspace,
             voffset,
-            child_layout: self.child.layout(context, &new_family, &inherited_fork, style),
+            child_layout: layout_child(self.child.as_ref(), context, &new_family,
+                                       &inherited_fork, style),
             presentation_layout,
         })
     }
@@ -177,6 +178,14 @@ impl Element for Mpadded {
     fn instance_id(&self) -> &InstanceId {
         &self.instance_id
     }
+
+    fn child_elements(&self) -> Vec<&Element> {
+        vec![self.child.as_ref()]
+    }
+
+    fn child_elements_mut(&mut self) -> Vec<&mut Box<Element>> {
+        vec![&mut self.child]
+    }
 }
 
 impl PresentationPrivate<Mpadded> for Mpadded {
@@ -196,8 +205,50 @@ impl Presentation<Mpadded> for Mpadded {}
 mod test {
     use super::*;
     use ::elements::*;
-    use ::test::skia::Snapshot;
+    use ::test::snapshot::Snapshot;
     use ::props::*;
+    use ::backend::display_list::{DisplayList, DrawCommand};
+    use ::paint::Point;
+    use ::test::fixture::{stix_context, draw_root};
+
+    #[test]
+    fn it_overlaps_neighbors_with_negative_width() {
+        let context = stix_context();
+
+        let x = Mi::new(String::from("x"));
+        let mut mpadded = Mpadded::new(Box::new(Mi::new(String::from("y"))));
+        mpadded.with_width(Some(PropertyModifier::Set(PseudoLength::DU(-10.))))
+            .with_math_background(Some(Color::RGB(255, 0, 0)));
+        let z = Mi::new(String::from("z"));
+        let (x_id, mpadded_id, z_id) = (x.instance_id().clone(), mpadded.instance_id().clone(),
+                                        z.instance_id().clone());
+
+        let mut mrow = Mrow::new();
+        mrow.with_child(Box::new(x))
+            .with_child(Box::new(mpadded))
+            .with_child(Box::new(z));
+
+        let drawable = draw_root(&mrow, &context);
+        let origin = Point::new(0., 0.);
+        let (_, x) = drawable.locate(&origin, &x_id).unwrap();
+        let (padded_pos, padded) = drawable.locate(&origin, &mpadded_id).unwrap();
+        let (z_pos, z) = drawable.locate(&origin, &z_id).unwrap();
+        let x_width = x.bounding_box().width();
+
+        // Content after the padded box is drawn over it, moved back by its width
+        assert_eq!(padded.bounding_box().width(), -10.);
+        assert!((padded_pos.x() - x_width).abs() < 0.01);
+        assert!((z_pos.x() - (x_width - 10.)).abs() < 0.01);
+        assert!((drawable.bounding_box().width() - (x_width - 10. + z.bounding_box().width()))
+            .abs() < 0.01);
+
+        let list = DisplayList::new(drawable.bounding_box());
+        drawable.draw(&list, &origin);
+        assert!(list.commands().iter().all(|command| match *command {
+            DrawCommand::Rect { ref color, .. } => *color != Color::RGB(255, 0, 0),
+            _ => true,
+        }), "Background drawn for negative width");
+    }
 
     #[test]
     fn it_works() {
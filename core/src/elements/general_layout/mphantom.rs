@@ -17,7 +17,8 @@
 use std::any::Any;
 
 use super::super::{Element, ElementType, GeneralLayout, InstanceId, InheritedProps, StyleProps,
-                   Family, Presentation, PresentationPrivate, SpecifiedPresentationProps, PropertyCalculator};
+                   Family, Presentation, PresentationPrivate, SpecifiedPresentationProps,
+                   PropertyCalculator, layout_child};
 use ::platform::Context;
 use ::layout::{Layout, MphatomLayout};
 
@@ -61,7 +62,7 @@ impl Element for Mphantom {
 
         Box::new(MphatomLayout {
             presentation_layout,
-            child_layout: self.child.layout(context, &new_family, &fork, style)
+            child_layout: layout_child(self.child.as_ref(), context, &new_family, &fork, style)
         })
     }
 
@@ -80,6 +81,14 @@ impl Element for Mphantom {
     fn instance_id(&self) -> &InstanceId {
         &self.instance_id
     }
+
+    fn child_elements(&self) -> Vec<&Element> {
+        vec![self.child.as_ref()]
+    }
+
+    fn child_elements_mut(&mut self) -> Vec<&mut Box<Element>> {
+        vec![&mut self.child]
+    }
 }
 
 impl PresentationPrivate<Mphantom> for Mphantom {
@@ -98,7 +107,7 @@ impl Presentation<Mphantom> for Mphantom {}
 mod test {
     use super::*;
     use ::elements::*;
-    use ::test::skia::Snapshot;
+    use ::test::snapshot::Snapshot;
     use ::props::*;
 
     #[test]
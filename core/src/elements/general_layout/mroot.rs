@@ -19,7 +19,7 @@ use std::any::Any;
 
 use super::super::{Element, Presentation, PresentationPrivate, ElementType, GeneralLayout,
                    SpecifiedPresentationProps, InheritedProps, StyleProps, Property,
-                   PropertyCalculator, InstanceId, Family, EmptyComputeCtx};
+                   PropertyCalculator, InstanceId, Family, EmptyComputeCtx, layout_child};
 use ::props::*;
 use ::platform::Context;
 use ::layout::{Layout, MrootLayout};
@@ -106,9 +106,16 @@ impl Element for Mroot {
             );
         let degree_inherited_props = inherited_props_copier.copy();
 
+        // Radicand is set in cramped style
+        let mut child_copier = fork.copier();
+        child_copier.cramped(true);
+        let child_inherited_props = child_copier.copy();
+
         Box::new(MrootLayout {
-            child: self.child.layout(context, &new_family, &fork, style),
-            degree: self.degree.layout(context, &new_family, &degree_inherited_props, style),
+            child: layout_child(self.child.as_ref(), context, &new_family,
+                                &child_inherited_props, style),
+            degree: layout_child(self.degree.as_ref(), context, &new_family,
+                                 &degree_inherited_props, style),
             base_size,
 
             presentation_element: presentation_layout,
@@ -131,6 +138,15 @@ impl Element for Mroot {
     fn instance_id(&self) -> &InstanceId {
         &self.instance_id
     }
+
+    fn child_elements(&self) -> Vec<&Element> {
+        // Degree is read before the radicand
+        vec![self.degree.as_ref(), self.child.as_ref()]
+    }
+
+    fn child_elements_mut(&mut self) -> Vec<&mut Box<Element>> {
+        vec![&mut self.degree, &mut self.child]
+    }
 }
 
 impl PresentationPrivate<Mroot> for Mroot {
@@ -150,7 +166,40 @@ impl Presentation<Mroot> for Mroot {}
 mod test {
     use super::*;
     use ::elements::*;
-    use ::test::skia::Snapshot;
+    use ::props::Directionality;
+    use ::test::snapshot::Snapshot;
+    use ::backend::display_list::{DisplayList, DrawCommand};
+    use ::paint::Point;
+    use ::test::fixture::{stix_context, draw_root};
+
+    #[test]
+    fn it_keeps_radicand_ink_below_rule() {
+        let context = stix_context();
+        let ruler = context.platform().get_math_ruler(32.);
+
+        let root = Mroot::new(Box::new(Mi::new(String::from("x"))),
+                              Box::new(Mn::new(String::from("3"))));
+        let drawable = draw_root(&root, &context);
+        let list = DisplayList::new(drawable.bounding_box());
+        drawable.draw(&list, &Point::new(0., 0.));
+
+        let commands = list.commands();
+        let rule_bottom = commands.iter().filter_map(|command| match *command {
+            DrawCommand::Line { ref start, stroke_width, .. } => Some(start.y() + stroke_width/2.),
+            _ => None,
+        }).next().expect("Rule not drawn");
+
+        // Radicand is drawn last
+        let radicand_top = commands.iter().filter_map(|command| match *command {
+            DrawCommand::GlyphRun { ref top_left, ref bound, .. } =>
+                Some(top_left.y() + bound.baseline_pos() - bound.ink_ascent()),
+            _ => None,
+        }).last().expect("Radicand not drawn");
+
+        let gap = radicand_top - rule_bottom;
+        assert!((gap - ruler.radical_display_style_vertical_gap()).abs() < 0.01,
+                "{} is not the radical gap", gap);
+    }
 
     #[test]
     fn it_works() {
@@ -212,4 +261,27 @@ mod test {
         ).as_ref(), "mroot_nested")
 
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn it_works_rtl() {
+        let snap = Snapshot::default();
+
+        let base = Mi::new(String::from("x"));
+        let degree = Mn::new(String::from("3"));
+        let (base_id, degree_id) = (base.instance_id().clone(), degree.instance_id().clone());
+
+        let mut mroot = Mroot::new(Box::new(base), Box::new(degree));
+        let ltr = snap.positions(&mroot, &[&base_id, &degree_id]);
+
+        mroot.with_dir(Some(Directionality::RTL));
+        let rtl = snap.positions(&mroot, &[&base_id, &degree_id]);
+
+        snap.snap_element(&mroot, "mroot_rtl");
+
+        // Degree moves from the left of the radical to its right, at the same height
+        assert!(ltr[1].x() < ltr[0].x());
+        assert!(rtl[1].x() > rtl[0].x());
+        assert!((ltr[1].y() - rtl[1].y()).abs() < 0.01);
+        assert!((ltr[0].y() - rtl[0].y()).abs() < 0.01);
+    }
+}
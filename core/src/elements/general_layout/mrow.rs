@@ -19,10 +19,12 @@ use std::any::Any;
 
 use super::super::{Element, Presentation, PresentationPrivate, ElementType, GeneralLayout,
                    SpecifiedPresentationProps, InheritedProps, StyleProps, Property,
-                   PropertyCalculator, InstanceId, Family, EmptyComputeCtx};
+                   PropertyCalculator, InstanceId, Family, RowPosition, EmptyComputeCtx, layout_child};
 use ::props::*;
 use ::platform::Context;
-use ::layout::{Layout, MrowLayout};
+use ::layout::{Layout, MrowLayout, PresentationLayout};
+use ::constants::OperatorDictionary;
+use ::utils::{group_by_precedence, infer_groups, is_space_like, Grouping};
 
 #[allow(const_err)]
 const PROP_DIRECTIONALITY: Property<Directionality, Mrow, EmptyComputeCtx> = Property::Inherited {
@@ -36,6 +38,7 @@ pub struct Mrow {
     children: Vec<Box<Element>>,
 
     dir: Option<Directionality>,
+    inferred_grouping: bool,
     presentation_props: SpecifiedPresentationProps
 }
 
@@ -45,6 +48,7 @@ impl Mrow {
             instance_id: InstanceId::new(),
             children: Vec::new(),
             dir: None,
+            inferred_grouping: false,
             presentation_props: SpecifiedPresentationProps::default(),
         }
     }
@@ -58,6 +62,19 @@ impl Mrow {
         self.dir.as_ref()
     }
 
+    /// Sets whether children are laid out in the rows inferred from operator priority, as
+    /// [`infer_grouping`](#method.infer_grouping) would group them, without changing the
+    /// children. Operator forms and stretchy sizes then follow the inferred rows, so that a fence
+    /// stretches to its contents only. Disabled by default, laying out children as given.
+    pub fn with_inferred_grouping<'a>(&'a mut self, inferred_grouping: bool) -> &'a mut Mrow {
+        self.inferred_grouping = inferred_grouping;
+        self
+    }
+
+    pub fn is_inferred_grouping(&self) -> bool {
+        self.inferred_grouping
+    }
+
     pub fn with_child<'a>(&'a mut self, child: Box<Element>) -> &'a mut Mrow {
         self.children.push(child);
         self
@@ -68,6 +85,25 @@ impl Mrow {
     }
 
     pub fn children_mut(&mut self) -> &mut [Box<Element>] { &mut self.children[..] }
+
+    /// Inserts `child` before the child at `index`. Panics if `index` is greater than the number
+    /// of children.
+    pub fn insert_child(&mut self, index: usize, child: Box<Element>) {
+        self.children.insert(index, child);
+    }
+
+    /// Removes and returns the child at `index`. Panics if `index` is out of bounds.
+    pub fn remove_child(&mut self, index: usize) -> Box<Element> {
+        self.children.remove(index)
+    }
+
+    /// Regroups children into nested rows based on operator priority. See
+    /// [`group_by_precedence`](../../utils/fn.group_by_precedence.html).
+    pub fn infer_grouping<'a>(&'a mut self, dictionary: &OperatorDictionary) -> &'a mut Mrow {
+        let children = ::std::mem::replace(&mut self.children, Vec::new());
+        self.children = group_by_precedence(children, dictionary);
+        self
+    }
 }
 
 impl Element for Mrow {
@@ -80,14 +116,23 @@ impl Element for Mrow {
         let dir = calculator.calculate(&PROP_DIRECTIONALITY, self.get_dir());
 
         let fork = calculator.make_fork().copy();
-        let new_family = family.add(self);
 
-        Box::new(MrowLayout {
+        let children = self.child_elements();
+        let groups = if self.inferred_grouping {
+            infer_groups(&children, context.operator_dictionary())
+        } else {
+            (0..children.len()).map(Grouping::Child).collect()
+        };
+
+        let mut layout = MrowLayout {
             presentation_element: presentation_layout,
             dir,
-            elements: self.children.iter().map(|e|
-                e.layout(context, &new_family, &fork, style)).collect(),
-        })
+            elements: Vec::new(),
+        };
+        layout.elements = Mrow::layout_groups(self, &children, &groups, context, family, &fork,
+                                              style, &layout);
+
+        Box::new(layout)
     }
 
     fn type_info(&self) -> ElementType {
@@ -105,6 +150,56 @@ impl Element for Mrow {
     fn instance_id(&self) -> &InstanceId {
         &self.instance_id
     }
+
+    fn child_elements(&self) -> Vec<&Element> {
+        self.children.iter().map(|child| child.as_ref()).collect()
+    }
+
+    fn child_elements_mut(&mut self) -> Vec<&mut Box<Element>> {
+        self.children.iter_mut().collect()
+    }
+}
+
+impl Mrow {
+    /// Lays out `groups` of `children` of row element `parent` in `row`, for rows made of other
+    /// elements than mrow children. Inferred rows are neither hit tested nor painted with
+    /// background.
+    pub(crate) fn layout_groups<'a>(parent: &Element, children: &[&Element], groups: &[Grouping],
+                                    context: &Context, family: &Family<'a>,
+                                    inherited: &InheritedProps, style: &Option<&StyleProps>,
+                                    row: &MrowLayout) -> Vec<Box<Layout>> {
+        // Inferred rows always hold an operand or operator
+        let is_non_space_like = |group: &Grouping| match *group {
+            Grouping::Child(index) => !is_space_like(children[index]),
+            Grouping::Row(_) => true,
+        };
+        let non_space_like = groups.iter().filter(|group| is_non_space_like(*group)).count();
+        let first = groups.iter().position(&is_non_space_like);
+        let last = groups.iter().rposition(&is_non_space_like);
+
+        groups.iter().enumerate().map(|(position, group)| match *group {
+            Grouping::Child(index) => {
+                let family = family.add_at(parent, RowPosition {
+                    len: groups.len(),
+                    non_space_like,
+                    first: first == Some(position),
+                    last: last == Some(position),
+                });
+                layout_child(children[index], context, &family, inherited, style)
+            },
+            Grouping::Row(ref groups) => {
+                let mut inferred = MrowLayout {
+                    presentation_element: PresentationLayout::new(
+                        row.presentation_element.math_color.clone(), Color::transparent()),
+                    dir: row.dir.clone(),
+                    elements: Vec::new(),
+                };
+                inferred.elements = Mrow::layout_groups(parent, children, groups, context, family,
+                                                        inherited, style, row);
+                Box::new(inferred) as Box<Layout>
+            },
+        }).collect()
+    }
 }
 
 impl PresentationPrivate<Mrow> for Mrow {
@@ -124,7 +219,10 @@ impl Presentation<Mrow> for Mrow {}
 mod test {
     use super::*;
     use ::elements::*;
-    use ::test::skia::Snapshot;
+    use ::test::snapshot::Snapshot;
+    use ::paint::Point;
+    use ::layout::MoLayout;
+    use ::test::fixture::{stix_context, layout_root, draw_root};
 
     #[test]
     fn it_works() {
@@ -198,4 +296,98 @@ mod test {
         );
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn it_hit_tests_children() {
+        let context = stix_context();
+
+        let mi = Mi::new(String::from("x"));
+        let mtext = Mtext::new(String::from("abc"));
+        let (mi_id, mtext_id) = (mi.instance_id().clone(), mtext.instance_id().clone());
+
+        let mut mrow = Mrow::new();
+        mrow.with_child(Box::new(mi)).with_child(Box::new(mtext));
+
+        let drawable = draw_root(&mrow, &context);
+        let origin = Point::new(10., 20.);
+        let width = drawable.bounding_box().width();
+        let baseline_y = origin.y() + drawable.bounding_box().baseline_pos();
+
+        let hit = drawable.hit_test(&origin, &Point::new(origin.x() + 1., baseline_y))
+            .expect("Identifier not hit");
+        assert_eq!(hit.instance_id(), &mi_id);
+        assert!(hit.element_type().is_mi());
+        assert_eq!(hit.offset(), Some(0));
+        assert_eq!(hit.top_left(), &origin);
+
+        let hit = drawable.hit_test(&origin, &Point::new(origin.x() + width - 1., baseline_y))
+            .expect("Text not hit");
+        assert_eq!(hit.instance_id(), &mtext_id);
+        assert!(hit.element_type().is_mtext());
+        assert_eq!(hit.offset(), Some(2));
+        assert!((hit.top_left().x() + hit.bounding_box().width() - (origin.x() + width)).abs()
+            < 0.01);
+
+        assert_eq!(drawable.hit_test(&origin, &Point::new(origin.x() - 1., baseline_y)), None);
+    }
+
+    #[test]
+    fn it_infers_operator_forms_from_grouping() {
+        let context = stix_context();
+
+        let minus_lspace = |inferred_grouping: bool| {
+            let mut mrow = Mrow::new();
+            mrow.with_child(Box::new(Mi::new(String::from("a"))))
+                .with_child(Box::new(Mo::new(String::from("+"))))
+                .with_child(Box::new(Mo::new(String::from("-"))))
+                .with_child(Box::new(Mi::new(String::from("b"))))
+                .with_inferred_grouping(inferred_grouping);
+
+            let layout = layout_root(&mrow, &context);
+            let row: &MrowLayout = layout.as_any().downcast_ref::<MrowLayout>().unwrap();
+            if inferred_grouping {
+                assert_eq!(row.elements.len(), 3);
+                let group: &MrowLayout = row.elements[2].as_any().downcast_ref::<MrowLayout>()
+                    .expect("Prefix operator is not grouped with its operand");
+                group.elements[0].as_any().downcast_ref::<MoLayout>().unwrap().lspace
+            } else {
+                assert_eq!(row.elements.len(), 4);
+                row.elements[2].as_any().downcast_ref::<MoLayout>().unwrap().lspace
+            }
+        };
+
+        // Infix minus is spaced, prefix minus starting the group of its operand is not
+        assert!(minus_lspace(false) > 0.);
+        assert_eq!(minus_lspace(true), 0.);
+    }
+
+    #[test]
+    fn it_stretches_fences_to_inferred_group() {
+        let context = stix_context();
+
+        let paren_height = |inferred_grouping: bool| {
+            let open = Mo::new(String::from("("));
+            let open_id = open.instance_id().clone();
+
+            let mut mrow = Mrow::new();
+            mrow.with_child(Box::new(open))
+                .with_child(Box::new(Mi::new(String::from("a"))))
+                .with_child(Box::new(Mo::new(String::from(")"))))
+                .with_child(Box::new(Mo::new(String::from("+"))))
+                .with_child(Box::new(Mfrac::new(
+                    Box::new(Mi::new(String::from("x"))),
+                    Box::new(Mi::new(String::from("y")))
+                )))
+                .with_inferred_grouping(inferred_grouping);
+
+            let drawable = draw_root(&mrow, &context);
+            let (_, paren) = drawable.locate(&Point::new(0., 0.), &open_id).unwrap();
+            (paren.bounding_box().height(), drawable.bounding_box().height())
+        };
+
+        // Fence stretches over the fraction in a flat row, but only over its contents when grouped
+        let (flat, row_height) = paren_height(false);
+        let (grouped, _) = paren_height(true);
+        assert!(flat >= row_height * 0.9);
+        assert!(grouped < flat);
+    }
+}
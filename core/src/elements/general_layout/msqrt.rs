@@ -20,7 +20,7 @@ use super::super::{Mroot, Mempty, Element, ElementType, GeneralLayout, Inherited
                    StyleProps, Family, InstanceId, Presentation, PresentationPrivate,
                    SpecifiedPresentationProps};
 use ::platform::Context;
-use ::layout::{Layout};
+use ::layout::{Layout, with_hit_target};
 
 
 pub struct Msqrt {
@@ -38,7 +38,7 @@ impl Msqrt {
 impl Element for Msqrt {
     fn layout<'a>(&self, context: &Context, family: &Family<'a>, inherited: &InheritedProps,
                   style: &Option<&StyleProps>) -> Box<Layout> {
-        self.root.layout(context, family, inherited, style)
+        with_hit_target(self.root.layout(context, family, inherited, style), self)
     }
 
     fn type_info(&self) -> ElementType {
@@ -56,6 +56,15 @@ impl Element for Msqrt {
     fn instance_id(&self) -> &InstanceId {
         self.root.instance_id()
     }
+
+    fn child_elements(&self) -> Vec<&Element> {
+        vec![self.root.get_child().as_ref()]
+    }
+
+    fn child_elements_mut(&mut self) -> Vec<&mut Box<Element>> {
+        // Degree of the root it is laid out as is not a slot of a square root
+        self.root.child_elements_mut().split_off(1)
+    }
 }
 
 impl PresentationPrivate<Msqrt> for Msqrt {
@@ -75,7 +84,24 @@ impl Presentation<Msqrt> for Msqrt {}
 mod test {
     use super::*;
     use ::elements::*;
-    use ::test::skia::Snapshot;
+    use ::test::snapshot::Snapshot;
+    use ::paint::Point;
+    use ::test::fixture::{stix_context, draw_root};
+
+    #[test]
+    fn it_is_hit_as_msqrt() {
+        let context = stix_context();
+
+        let msqrt = Msqrt::new(Box::new(Mi::new(String::from("x"))));
+        let drawable = draw_root(&msqrt, &context);
+
+        // Radical sign is on the left of the radicand
+        let point = Point::new(1., drawable.bounding_box().baseline_pos());
+        let hit = drawable.hit_test(&Point::new(0., 0.), &point).expect("Radical not hit");
+        assert_eq!(hit.instance_id(), msqrt.instance_id());
+        assert!(hit.element_type().is_msqrt());
+        assert_eq!(hit.offset(), None);
+    }
 
     #[test]
     fn test_nested() {
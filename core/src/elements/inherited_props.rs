@@ -17,14 +17,15 @@
 
 use ::props::*;
 
-#[derive(Immutable, Clone)]
+#[derive(Immutable, Clone, Debug, PartialEq)]
 pub struct InheritedProps {
     display_style: bool,
+    cramped: CrampedStyle,
     math_size: MathSize,
     script_level: ScriptLevel,
     dir: Directionality,
     math_color: Color,
-    script_size_multiplier: f32,
+    script_size_multiplier: ScriptSizeMultiplier,
     script_min_size: f32,
     linebreak_mult_char: String,
     indent_align:IndentAlign,
@@ -45,11 +46,12 @@ impl Default for InheritedProps {
     fn default() -> InheritedProps {
         InheritedProps {
             display_style: true,
+            cramped: false,
             script_level: ScriptLevel::new(0, 64.),
             math_size: MathSize::NORMAL,
             dir: Directionality::LTR,
             math_color: Color::parse("#000000").unwrap(),
-            script_size_multiplier: 0.707,
+            script_size_multiplier: None,
             script_min_size: 8.0,
             linebreak_mult_char: String::from("\u{2062}"),
             indent_align: IndentAlign::Auto,
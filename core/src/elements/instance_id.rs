@@ -21,7 +21,7 @@ lazy_static! {
     static ref INSTANCE_COUNT: AtomicUsize = AtomicUsize::new(0);
 }
 
-#[derive(Debug, PartialOrd, PartialEq)]
+#[derive(Debug, Clone, PartialOrd, PartialEq, Eq, Hash)]
 pub struct InstanceId {
     id: usize
 }
@@ -0,0 +1,469 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+
+
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use super::{Element, ElementType, Family, InheritedProps, InstanceId, RowPosition, StyleProps};
+use ::layout::Layout;
+use ::draw::{Drawable, BoxSnapshot, BoundingBox, MeasureMode, Highlight, HitResult};
+use ::paint::{Canvas, Point};
+use ::platform::Context;
+
+/// Layouts of elements kept between layouts of a tree, so that after an edit only the changed
+/// elements and their ancestors are laid out again. A layout is reused when its element is laid
+/// out with the same inherited props, at the same kind of place among its siblings and below
+/// ancestors at the same kind of places, which is what operator forms are inferred from.
+///
+/// Drawables of cached layouts are measured once for each way their parents calculate them, and
+/// reused as [snapshots](../draw/struct.BoxSnapshot.html) afterwards, so that glyph variants
+/// and assemblies of reused layouts are not searched again.
+///
+/// Changes to elements are not seen by the cache, so they must be marked with
+/// [`invalidate`](#method.invalidate). The cache is used for the elements laid out by other
+/// elements, once set on the context with `Context::set_layout_cache`.
+pub struct LayoutCache {
+    entries: RefCell<HashMap<InstanceId, Entry>>,
+
+    // Places of the elements being laid out, outermost first
+    placements: RefCell<Vec<Placement>>,
+
+    hits: Cell<usize>,
+    misses: Cell<usize>,
+    measures: Cell<usize>,
+}
+
+struct Entry {
+    inherited: InheritedProps,
+    placements: Vec<Placement>,
+    styled: bool,
+    layout: Rc<Box<Layout>>,
+    measured: Rc<RefCell<Vec<Measured>>>,
+}
+
+// Snapshot of the drawable of a cached layout calculated with the measure modes, or as laid out
+// when there are none
+struct Measured {
+    modes: Option<(MeasureMode, MeasureMode)>,
+    snapshot: BoxSnapshot,
+}
+
+// Place of an element among its siblings, as far as operator form inference looks at it
+#[derive(Debug, Clone, PartialEq)]
+enum Placement {
+    Root,
+    Row { only: bool, under_script: bool, spaced: bool, first: bool, last: bool },
+    Other(ElementType),
+}
+
+impl LayoutCache {
+    pub fn new() -> LayoutCache {
+        LayoutCache {
+            entries: RefCell::new(HashMap::new()),
+            placements: RefCell::new(Vec::new()),
+            hits: Cell::new(0),
+            misses: Cell::new(0),
+            measures: Cell::new(0),
+        }
+    }
+
+    /// Marks `id` in the tree at `root` as changed in its props, text or children. Its
+    /// ancestors, whose layouts hold its layout, and its descendants, whose layouts may read
+    /// props it passes down, are laid out again along with it.
+    pub fn invalidate(&self, root: &Element, id: &InstanceId) {
+        let mut entries = self.entries.borrow_mut();
+        entries.remove(id);
+
+        let mut path = Vec::new();
+        if path_to(root, id, &mut path) {
+            for element in path.iter() {
+                entries.remove(element.instance_id());
+            }
+            if let Some(element) = path.last() {
+                let mut descendants = HashSet::new();
+                collect_ids(*element, &mut descendants);
+                for descendant in descendants.iter() {
+                    entries.remove(descendant);
+                }
+            }
+        }
+    }
+
+    /// Drops layouts of elements not in the tree at `root`
+    pub fn prune(&self, root: &Element) {
+        let mut ids = HashSet::new();
+        collect_ids(root, &mut ids);
+        self.entries.borrow_mut().retain(|id, _| ids.contains(id));
+    }
+
+    pub fn clear(&self) {
+        self.entries.borrow_mut().clear();
+    }
+
+    /// Number of layouts reused
+    pub fn hits(&self) -> usize {
+        self.hits.get()
+    }
+
+    /// Number of layouts made
+    pub fn misses(&self) -> usize {
+        self.misses.get()
+    }
+
+    /// Number of times drawables of cached layouts were measured
+    pub fn measures(&self) -> usize {
+        self.measures.get()
+    }
+
+    fn layout<'a>(&self, element: &Element, context: &Context, family: &Family<'a>,
+                  inherited: &InheritedProps, style: &Option<&StyleProps>) -> Box<Layout> {
+        let mut placements = self.placements.borrow().clone();
+        placements.push(placement(element, family));
+
+        let cached = self.entries.borrow().get(element.instance_id())
+            .filter(|entry| entry.inherited == *inherited && entry.placements == placements &&
+                entry.styled == style.is_some())
+            .map(|entry| (entry.layout.clone(), entry.measured.clone()));
+        if let Some((layout, measured)) = cached {
+            self.hits.set(self.hits.get() + 1);
+            return Box::new(SharedLayout { layout, measured });
+        }
+
+        self.misses.set(self.misses.get() + 1);
+        let outer = self.placements.replace(placements.clone());
+        let layout = Rc::new(element.layout(context, family, inherited, style));
+        let measured = Rc::new(RefCell::new(Vec::new()));
+        self.placements.replace(outer);
+
+        self.entries.borrow_mut().insert(element.instance_id().clone(), Entry {
+            inherited: inherited.clone(),
+            placements,
+            styled: style.is_some(),
+            layout: layout.clone(),
+            measured: measured.clone(),
+        });
+        Box::new(SharedLayout { layout, measured })
+    }
+}
+
+/// Lays out `element` as a child of the last element in `family`, reusing its layout from the
+/// layout cache of `context` when there is one. Parents that change layouts of their children
+/// lay them out with `Element::layout` instead, since cached layouts are shared and give no
+/// `as_any_mut` to change them through.
+pub fn layout_child<'a>(element: &Element, context: &Context, family: &Family<'a>,
+                        inherited: &InheritedProps, style: &Option<&StyleProps>) -> Box<Layout> {
+    match context.layout_cache() {
+        Some(cache) => cache.layout(element, context, family, inherited, style),
+        None => element.layout(context, family, inherited, style),
+    }
+}
+
+// Layout shared with the cache, which cannot be downcast with `as_any_mut` and changed
+struct SharedLayout {
+    layout: Rc<Box<Layout>>,
+    measured: Rc<RefCell<Vec<Measured>>>,
+}
+
+impl Layout for SharedLayout {
+    fn layout(&self, context: &Context) -> Box<Drawable> {
+        let mut drawable = SharedDrawable {
+            layout: self.layout.clone(),
+            measured: self.measured.clone(),
+            snapshot: BoxSnapshot::group(BoundingBox::default(), Vec::new()),
+        };
+        drawable.measure(context, None);
+        Box::new(drawable)
+    }
+
+    fn as_any(&self) -> &Any {
+        self.layout.as_any()
+    }
+
+    fn as_any_mut(&mut self) -> Option<&mut Any> {
+        None
+    }
+}
+
+// Drawable of a cached layout, drawn from the snapshot taken when it was last calculated
+struct SharedDrawable {
+    layout: Rc<Box<Layout>>,
+    measured: Rc<RefCell<Vec<Measured>>>,
+    snapshot: BoxSnapshot,
+}
+
+impl SharedDrawable {
+    // Takes the snapshot measured with `modes`, measuring the drawable if it was not before
+    fn measure(&mut self, context: &Context, modes: Option<(MeasureMode, MeasureMode)>) {
+        let measured = self.measured.borrow().iter()
+            .find(|measured| measured.modes == modes)
+            .map(|measured| measured.snapshot.clone());
+
+        self.snapshot = match measured {
+            Some(snapshot) => snapshot,
+            None => {
+                if let Some(cache) = context.layout_cache() {
+                    cache.measures.set(cache.measures.get() + 1);
+                }
+
+                let mut drawable = self.layout.layout(context);
+                if let Some((ref width_mode, ref height_mode)) = modes {
+                    drawable.calculate(context, width_mode, height_mode);
+                }
+                let snapshot = drawable.to_snapshot();
+                self.measured.borrow_mut().push(Measured { modes, snapshot: snapshot.clone() });
+                snapshot
+            }
+        };
+    }
+}
+
+impl Drawable for SharedDrawable {
+    fn draw(&self, canvas: &Canvas, pen_pos: &Point) {
+        self.snapshot.draw(canvas, pen_pos);
+    }
+
+    fn calculate(&mut self, context: &Context, width_mode: &MeasureMode, height_mode: &MeasureMode) {
+        self.measure(context, Some((width_mode.clone(), height_mode.clone())));
+    }
+
+    fn bounding_box(&self) -> &BoundingBox {
+        self.snapshot.bounding_box()
+    }
+
+    fn draw_highlighted(&self, canvas: &Canvas, pen_pos: &Point, highlight: &Highlight,
+                        selected: bool) {
+        self.snapshot.draw_highlighted(canvas, pen_pos, highlight, selected);
+    }
+
+    fn hit_test(&self, pen_pos: &Point, point: &Point) -> Option<HitResult> {
+        self.snapshot.hit_test(pen_pos, point)
+    }
+
+    fn text_offset(&self, pen_pos: &Point, point: &Point) -> Option<usize> {
+        self.snapshot.text_offset(pen_pos, point)
+    }
+
+    fn locate(&self, pen_pos: &Point, instance_id: &InstanceId) -> Option<(Point, &Drawable)> {
+        self.snapshot.locate(pen_pos, instance_id)
+    }
+
+    fn caret_x(&self, pen_pos: &Point, offset: usize) -> Option<f32> {
+        self.snapshot.caret_x(pen_pos, offset)
+    }
+
+    fn to_snapshot(&self) -> BoxSnapshot {
+        self.snapshot.clone()
+    }
+}
+
+fn placement<'a>(element: &Element, family: &Family<'a>) -> Placement {
+    let parent = match family.parent() {
+        Some(parent) => parent,
+        None => return Placement::Root,
+    };
+    if !parent.type_info().is_mrow() {
+        return Placement::Other(parent.type_info());
+    }
+
+    let position = family.position().cloned()
+        .unwrap_or_else(|| RowPosition::in_row(&parent.child_elements(), element));
+    let under_script = family.grand_parent()
+        .and_then(|grand_parent| grand_parent.parent())
+        .map_or(false, |grand_parent| grand_parent.type_info().is_scrips_or_limits());
+
+    Placement::Row {
+        only: position.len == 1,
+        under_script: position.len == 1 && under_script,
+        spaced: position.non_space_like >= 2,
+        first: position.first,
+        last: position.last,
+    }
+}
+
+// Elements from `element` to the one with `id`, if it is in the tree
+fn path_to<'e>(element: &'e Element, id: &InstanceId, path: &mut Vec<&'e Element>) -> bool {
+    path.push(element);
+    if element.instance_id() == id {
+        return true;
+    }
+    for child in element.child_elements() {
+        if path_to(child, id, path) {
+            return true;
+        }
+    }
+    path.pop();
+    false
+}
+
+fn collect_ids(element: &Element, ids: &mut HashSet<InstanceId>) {
+    ids.insert(element.instance_id().clone());
+    for child in element.child_elements() {
+        collect_ids(child, ids);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::elements::*;
+    use ::backend::display_list::{DisplayList, DrawCommand};
+    use ::layout::MiLayout;
+    use ::props::Color;
+    use ::test::fixture::stix_context;
+
+    fn draw(mrow: &Mrow, context: &Context) -> Vec<DrawCommand> {
+        let layout = mrow.layout(context, &Family::new(), &InheritedProps::default(), &None);
+        let drawable = layout.layout(context);
+        let list = DisplayList::new(drawable.bounding_box());
+        drawable.draw(&list, &::paint::Point::new(0., 0.));
+        let commands = list.commands().clone();
+        commands
+    }
+
+    fn equation() -> Mrow {
+        let fraction = Mfrac::new(Box::new(Mi::new(String::from("x"))),
+                                  Box::new(Mn::new(String::from("2"))));
+        let mut mrow = Mrow::new();
+        mrow.with_child(Box::new(Mo::new(String::from("-"))))
+            .with_child(Box::new(fraction))
+            .with_child(Box::new(Mo::new(String::from("="))))
+            .with_child(Box::new(Mn::new(String::from("1"))));
+        mrow
+    }
+
+    #[test]
+    fn it_reuses_unchanged_layouts() {
+        let uncached = stix_context();
+        let mut context = stix_context();
+        context.set_layout_cache(Some(LayoutCache::new()));
+
+        let mut mrow = equation();
+        assert_eq!(draw(&mrow, &context), draw(&mrow, &uncached));
+        let misses = context.layout_cache().unwrap().misses();
+        assert_eq!(context.layout_cache().unwrap().hits(), 0);
+
+        assert_eq!(draw(&mrow, &context), draw(&mrow, &uncached));
+        assert!(context.layout_cache().unwrap().hits() > 0);
+        assert_eq!(context.layout_cache().unwrap().misses(), misses);
+
+        // Removing the last child makes "=" a postfix operator
+        mrow.remove_child(3);
+        context.layout_cache().unwrap().invalidate(&mrow, mrow.instance_id());
+        assert_eq!(draw(&mrow, &context), draw(&mrow, &uncached));
+
+        mrow.insert_child(0, Box::new(Mi::new(String::from("y"))));
+        context.layout_cache().unwrap().invalidate(&mrow, mrow.instance_id());
+        assert_eq!(draw(&mrow, &context), draw(&mrow, &uncached));
+    }
+
+    #[test]
+    fn it_invalidates_descendants() {
+        let uncached = stix_context();
+        let mut context = stix_context();
+        context.set_layout_cache(Some(LayoutCache::new()));
+
+        let mut mrow = equation();
+        draw(&mrow, &context);
+        let numerator = mrow.child_elements()[1].child_elements()[0].instance_id().clone();
+        let cache = context.layout_cache().unwrap();
+        assert!(cache.entries.borrow().contains_key(&numerator));
+
+        // Color of the row is inherited by the numerator of its fraction
+        mrow.with_math_color(Some(Color::RGB(255, 0, 0)));
+        cache.invalidate(&mrow, mrow.instance_id());
+        assert!(!cache.entries.borrow().contains_key(&numerator));
+        assert_eq!(draw(&mrow, &context), draw(&mrow, &uncached));
+    }
+
+    #[test]
+    fn it_invalidates_ancestors_in_mfenced() {
+        let uncached = stix_context();
+        let mut context = stix_context();
+        context.set_layout_cache(Some(LayoutCache::new()));
+
+        let mut fenced = Mfenced::new();
+        fenced.with_child(Box::new(Mi::new(String::from("x"))))
+            .with_child(Box::new(Mn::new(String::from("2"))));
+        let mut mrow = Mrow::new();
+        mrow.with_child(Box::new(fenced));
+        draw(&mrow, &context);
+
+        let (fenced_id, id) = {
+            let fenced = &mut mrow.children_mut()[0];
+            let fenced_id = fenced.instance_id().clone();
+            let mi = fenced.child_elements_mut().remove(0);
+            mi.as_any_mut().downcast_mut::<Mi>().unwrap().with_text(String::from("xyz"));
+            (fenced_id, mi.instance_id().clone())
+        };
+        let cache = context.layout_cache().unwrap();
+        cache.invalidate(&mrow, &id);
+        assert!(!cache.entries.borrow().contains_key(&fenced_id));
+        assert_eq!(draw(&mrow, &context), draw(&mrow, &uncached));
+    }
+
+    #[test]
+    fn it_reuses_measured_drawables() {
+        let uncached = stix_context();
+        let mut context = stix_context();
+        context.set_layout_cache(Some(LayoutCache::new()));
+
+        // Fences are stretched by the row, measuring them again with the height of the row
+        let mut mrow = equation();
+        mrow.insert_child(1, Box::new(Mo::new(String::from("("))));
+        mrow.insert_child(3, Box::new(Mo::new(String::from(")"))));
+
+        assert_eq!(draw(&mrow, &context), draw(&mrow, &uncached));
+        let measures = context.layout_cache().unwrap().measures();
+        assert!(measures > 0);
+
+        let hits = context.layout_cache().unwrap().hits();
+        assert_eq!(draw(&mrow, &context), draw(&mrow, &uncached));
+        assert!(context.layout_cache().unwrap().hits() > hits);
+        assert_eq!(context.layout_cache().unwrap().measures(), measures);
+    }
+
+    #[test]
+    fn it_refuses_to_change_shared_layouts() {
+        let mut context = stix_context();
+        context.set_layout_cache(Some(LayoutCache::new()));
+
+        let mi = Mi::new(String::from("x"));
+        let mut layout = layout_child(&mi, &context, &Family::new(), &InheritedProps::default(),
+                                      &None);
+        assert!(layout.as_any().is::<MiLayout>());
+        assert!(layout.as_any_mut().is_none());
+    }
+
+    #[test]
+    fn it_prunes_removed_elements() {
+        let cache = LayoutCache::new();
+        let mut context = stix_context();
+        context.set_layout_cache(Some(cache));
+
+        let mut mrow = equation();
+        draw(&mrow, &context);
+        let removed = mrow.remove_child(1);
+        let cache = context.layout_cache().unwrap();
+        assert!(cache.entries.borrow().contains_key(removed.instance_id()));
+        cache.prune(&mrow);
+        assert!(!cache.entries.borrow().contains_key(removed.instance_id()));
+        assert!(cache.entries.borrow().contains_key(mrow.child_elements()[0].instance_id()));
+    }
+}
@@ -19,6 +19,7 @@ use std::any::Any;
 use super::{Element, InstanceId, Miscellaneous, ElementType, InheritedProps, StyleProps, Family};
 use ::layout::{MemptyLayout, Layout};
 use ::platform::Context;
+use ::draw::HitTarget;
 
 pub struct Mempty {
     instance_id: InstanceId
@@ -32,7 +33,9 @@ impl Mempty {
 
 impl Element for Mempty {
     fn layout<'a>(&self, _: &Context, _: &Family<'a>, _: &InheritedProps, _: &Option<&StyleProps>) -> Box<Layout> {
-        Box::new(MemptyLayout::new())
+        Box::new(MemptyLayout {
+            hit_target: Some(HitTarget::new(self.instance_id.clone(), self.type_info())),
+        })
     }
 
     fn type_info(&self) -> ElementType {
@@ -29,6 +29,8 @@ mod instance_id;            pub use self::instance_id::*;
 mod family;                 pub use self::family::*;
 
 mod mempty;                 pub use self::mempty::*;
+mod placeholder;            pub use self::placeholder::*;
+mod layout_cache;           pub use self::layout_cache::*;
 
 use std::any::Any;
 
@@ -42,6 +44,22 @@ pub trait Element {
     fn as_any(&self) -> &Any;
     fn as_any_mut(&mut self) -> &mut Any;
     fn instance_id(&self) -> &InstanceId;
+
+    /// Child elements in reading order, which a caret moves through. Elements whose content is
+    /// not edited child by child have none.
+    fn child_elements(&self) -> Vec<&Element> {
+        Vec::new()
+    }
+
+    /// Slots of `child_elements`, in the same order, for editing the tree in place
+    fn child_elements_mut(&mut self) -> Vec<&mut Box<Element>> {
+        Vec::new()
+    }
+
+    /// Text of token elements
+    fn text(&self) -> Option<&str> {
+        None
+    }
 }
 
 impl PartialEq for Element {
@@ -118,6 +136,7 @@ pub enum EnliveningExpression {
 #[derive(Debug, Copy, Clone, PartialOrd, PartialEq)]
 pub enum Miscellaneous {
     Mempty,
+    Placeholder,
 }
 
 #[derive(Debug, Copy, Clone, PartialOrd, PartialEq)]
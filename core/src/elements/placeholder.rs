@@ -0,0 +1,177 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+
+
+use std::any::Any;
+
+use super::{Element, InstanceId, Miscellaneous, ElementType, InheritedProps, StyleProps, Family,
+            Presentation, PresentationPrivate, SpecifiedPresentationProps, PropertyCalculator,
+            Mempty};
+use ::layout::{PlaceholderLayout, Layout};
+use ::platform::Context;
+
+/// Empty slot of a template, such as a new fraction, drawn as a dashed box for editors to point
+/// at. Unlike `Mempty`, it takes space as a one letter identifier would. It is not MathML, so
+/// trees are cleared of placeholders with [`clear_placeholders`](fn.clear_placeholders.html)
+/// before they are written out.
+pub struct Placeholder {
+    instance_id: InstanceId,
+    presentation_props: SpecifiedPresentationProps,
+}
+
+impl Placeholder {
+    pub fn new() -> Placeholder {
+        Placeholder {
+            instance_id: InstanceId::new(),
+            presentation_props: SpecifiedPresentationProps::default(),
+        }
+    }
+}
+
+impl Element for Placeholder {
+    fn layout<'a>(&self, context: &Context, family: &Family<'a>, inherited: &InheritedProps,
+                  style: &Option<&StyleProps>) -> Box<Layout> {
+        let mut calculator = PropertyCalculator::new(
+            context, self, family, inherited, style.clone());
+
+        Box::new(PlaceholderLayout {
+            presentation_layout: self.layout_presentation(&mut calculator),
+        })
+    }
+
+    fn type_info(&self) -> ElementType {
+        ElementType::Miscellaneous(Miscellaneous::Placeholder)
+    }
+
+    fn as_any(&self) -> &Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut Any {
+        self
+    }
+
+    fn instance_id(&self) -> &InstanceId {
+        &self.instance_id
+    }
+}
+
+impl PresentationPrivate<Placeholder> for Placeholder {
+    fn get_specified_presentation_props(&self) -> &SpecifiedPresentationProps {
+        &self.presentation_props
+    }
+
+    fn get_specified_presentation_props_mut(&mut self) -> &mut SpecifiedPresentationProps {
+        &mut self.presentation_props
+    }
+}
+
+impl Presentation<Placeholder> for Placeholder {}
+
+/// Puts an `Mempty` in the place of every placeholder below `root`, leaving the slots empty as
+/// MathML has them
+pub fn clear_placeholders(root: &mut Element) {
+    for child in root.child_elements_mut() {
+        if child.type_info() == ElementType::Miscellaneous(Miscellaneous::Placeholder) {
+            *child = Box::new(Mempty::new());
+        } else {
+            clear_placeholders(child.as_mut());
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::elements::*;
+    use ::backend::display_list::{DisplayList, DrawCommand};
+    use ::paint::Point;
+    use ::test::fixture::{stix_context, draw_root};
+
+    fn count(element: &Element, element_type: &ElementType) -> usize {
+        let own = if element.type_info() == *element_type { 1 } else { 0 };
+        own + element.child_elements().iter()
+            .map(|child| count(*child, element_type))
+            .sum::<usize>()
+    }
+
+    #[test]
+    fn it_is_cleared_before_writing_out() {
+        let mut mrow = Mrow::new();
+        mrow.with_child(Box::new(Mfrac::new(Box::new(Placeholder::new()),
+                                            Box::new(Msqrt::new(Box::new(Placeholder::new()))))))
+            .with_child(Box::new(Placeholder::new()))
+            .with_child(Box::new(Mi::new(String::from("x"))));
+
+        let placeholder = ElementType::Miscellaneous(Miscellaneous::Placeholder);
+        let mempty = ElementType::Miscellaneous(Miscellaneous::Mempty);
+        assert_eq!(count(&mrow, &placeholder), 3);
+
+        clear_placeholders(&mut mrow);
+        assert_eq!(count(&mrow, &placeholder), 0);
+        assert_eq!(count(&mrow, &mempty), 3);
+        assert_eq!(mrow.children().len(), 3);
+    }
+
+    #[test]
+    fn it_is_sized_by_script_level() {
+        let context = stix_context();
+        let ruler = context.platform().get_math_ruler(32.);
+
+        let placeholder = Placeholder::new();
+        let drawable = draw_root(&placeholder, &context);
+        assert!((drawable.bounding_box().width() - 2. * ruler.axis_height()).abs() < 0.01);
+        assert_eq!(drawable.bounding_box().baseline(), 0.);
+
+        let superscript = Placeholder::new();
+        let superscript_id = superscript.instance_id().clone();
+        let msup = Msup::new(Box::new(Mi::new(String::from("x"))), Box::new(superscript));
+        let drawable = draw_root(&msup, &context);
+        let (_, found) = drawable.locate(&Point::new(0., 0.), &superscript_id)
+            .expect("Superscript not laid out");
+        assert!(found.bounding_box().width() < 2. * ruler.axis_height());
+    }
+
+    #[test]
+    fn it_is_drawn_and_hit() {
+        let context = stix_context();
+
+        let numerator = Placeholder::new();
+        let numerator_id = numerator.instance_id().clone();
+        let mfrac = Mfrac::new(Box::new(numerator), Box::new(Placeholder::new()));
+        let drawable = draw_root(&mfrac, &context);
+
+        let (top_left, found) = drawable.locate(&Point::new(0., 0.), &numerator_id)
+            .expect("Numerator not laid out");
+        let center = &top_left + &Point::new(found.bounding_box().width() / 2.,
+                                             found.bounding_box().height() / 2.);
+        let hit = drawable.hit_test(&Point::new(0., 0.), &center).expect("Placeholder not hit");
+        assert_eq!(hit.instance_id(), &numerator_id);
+        assert_eq!(hit.element_type(), ElementType::Miscellaneous(Miscellaneous::Placeholder));
+
+        let list = DisplayList::new(drawable.bounding_box());
+        drawable.draw(&list, &Point::new(0., 0.));
+        let dashes = list.commands().iter().filter(|command| match **command {
+            DrawCommand::Line { .. } => true,
+            _ => false,
+        }).count();
+
+        // Fraction rule and the dashed edges of both boxes
+        assert!(dashes > 8, "{} lines drawn", dashes);
+    }
+}
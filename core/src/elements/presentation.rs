@@ -17,6 +17,7 @@
 
 use ::props::*;
 use ::layout::PresentationLayout;
+use ::draw::HitTarget;
 use super::{Property, Element, PropertyCalculator, EmptyComputeCtx};
 
 #[derive(Default)]
@@ -44,6 +45,12 @@ pub trait PresentationPrivate<T: Element> {
         writer:                 |v, fork| fork.display_style(v)
     };
 
+    #[allow(const_err)]
+    const PROP_CRAMPED: Property<CrampedStyle, T, EmptyComputeCtx> = Property::Inherited {
+        reader:                 |p| p.cramped(),
+        writer:                 |v, fork| fork.cramped(v)
+    };
+
     #[allow(const_err)]
     const PROP_SCRIPT_LEVEL: Property<ScriptLevel, T, EmptyComputeCtx> = Property::Inherited {
         reader:                 |p| p.script_level(),
@@ -67,6 +74,7 @@ pub trait PresentationPrivate<T: Element> {
 
     fn layout_presentation(&self, calculator: &mut PropertyCalculator<T>) -> PresentationLayout {
         let specified = self.get_specified_presentation_props();
+        let element = calculator.element();
 
         PresentationLayout {
             math_color: calculator.calculate(
@@ -74,9 +82,11 @@ pub trait PresentationPrivate<T: Element> {
             math_background: calculator.calculate(
                 &Self::PROP_MATH_BACKGROUND, specified.math_background.as_ref()),
             display_style: calculator.calculate(&Self::PROP_DISPLAY_STYLE, None),
+            cramped: calculator.calculate(&Self::PROP_CRAMPED, None),
             script_level: calculator.calculate(&Self::PROP_SCRIPT_LEVEL, None),
             script_min_size: calculator.calculate(&Self::PROP_SCRIPT_MIN_SIZE, None),
             script_size_multiplier: calculator.calculate(&Self::PROP_SCRIPT_SIZE_MULTIPLIER, None),
+            hit_target: Some(HitTarget::new(element.instance_id().clone(), element.type_info())),
         }
     }
 }
@@ -141,6 +141,10 @@ impl<'a, 'b, 'c, 'd, 'e, 'f, T: Element> PropertyCalculator<'a, 'b, 'c, 'e, 'f,
         val
     }
 
+    pub fn element(&self) -> &'b T {
+        self.element
+    }
+
     pub fn make_fork(self) -> InheritedPropsCopier {
         self.copier
     }
@@ -18,10 +18,16 @@ use std::any::Any;
 
 use super::super::{Element, ElementType, Family, InheritedProps, StyleProps, InstanceId,
                    ScriptsAndLimits, PropertyCalculator, Presentation, PresentationPrivate,
-                   SpecifiedPresentationProps, EmptyComputeCtx, Property};
+                   SpecifiedPresentationProps, EmptyComputeCtx, Property, layout_child};
 use ::platform::Context;
 use ::layout::{Layout, MmultiscriptLayout};
-use ::props::{Length, MathSize, PropertyModifier};
+use ::props::{Length, MathSize, PropertyModifier, Directionality};
+
+#[allow(const_err)]
+const PROP_DIRECTIONALITY: Property<Directionality, Mmultiscripts, EmptyComputeCtx> = Property::Inherited {
+    reader: |i| i.dir(),
+    writer: |v, fork| fork.dir(v)
+};
 
 #[allow(const_err)]
 const PROP_SUPERSCRIPT_SHIFT: Property<Length, Mmultiscripts, EmptyComputeCtx> = Property::Specified {
@@ -123,6 +129,7 @@ impl Element for Mmultiscripts {
             &PROP_SUPERSCRIPT_SHIFT, self.superscript_shift.as_ref());
         let subscript_shift = calculator.calculate(
             &PROP_SUBSCRIPT_SHIFT, self.subscript_shift.as_ref());
+        let dir = calculator.calculate(&PROP_DIRECTIONALITY, None);
         let base_size = presentation_layout.script_level.get_font_size(context, &MathSize::NORMAL);
 
         let new_family = family.add(self);
@@ -137,22 +144,32 @@ impl Element for Mmultiscripts {
         ));
         let inherited_fork_scripts = inherited_fork_scripts.copy();
 
+        let mut inherited_fork_subscripts = inherited_fork_scripts.copier();
+        inherited_fork_subscripts.cramped(true);
+        let inherited_fork_subscripts = inherited_fork_subscripts.copy();
+
         Box::new(MmultiscriptLayout {
-            base_layout: self.base.layout(context, &new_family, &inherited_fork, style),
+            base_layout: layout_child(self.base.as_ref(), context, &new_family,
+                                      &inherited_fork, style),
             prescript_layout: self.prescripts.iter().map(| multiscript | {
                 (
-                    multiscript.superscript.layout(context, &new_family, &inherited_fork_scripts, style),
-                    multiscript.subscript.layout(context, &new_family, &inherited_fork_scripts, style),
+                    layout_child(multiscript.superscript.as_ref(), context, &new_family,
+                                 &inherited_fork_scripts, style),
+                    layout_child(multiscript.subscript.as_ref(), context, &new_family,
+                                 &inherited_fork_subscripts, style),
                 )
             }).collect(),
             postscript_layout: self.postscripts.iter().map(| multiscript | {
                 (
-                    multiscript.superscript.layout(context, &new_family, &inherited_fork_scripts, style),
-                    multiscript.subscript.layout(context, &new_family, &inherited_fork_scripts, style),
+                    layout_child(multiscript.superscript.as_ref(), context, &new_family,
+                                 &inherited_fork_scripts, style),
+                    layout_child(multiscript.subscript.as_ref(), context, &new_family,
+                                 &inherited_fork_subscripts, style),
                 )
             }).collect(),
             subscript_shift: subscript_shift.get_length_du(context, base_size),
             superscript_shift: superscript_shift.get_length_du(context, base_size),
+            dir,
             presentation_layout,
         })
     }
@@ -172,6 +189,25 @@ impl Element for Mmultiscripts {
     fn instance_id(&self) -> &InstanceId {
         &self.instance_id
     }
+
+    fn child_elements(&self) -> Vec<&Element> {
+        // Subscript and superscript pairs follow the base, postscripts first as in MathML
+        let mut children = vec![self.base.as_ref()];
+        for script in self.postscripts.iter().chain(self.prescripts.iter()) {
+            children.push(script.subscript.as_ref());
+            children.push(script.superscript.as_ref());
+        }
+        children
+    }
+
+    fn child_elements_mut(&mut self) -> Vec<&mut Box<Element>> {
+        let mut children = vec![&mut self.base];
+        for script in self.postscripts.iter_mut().chain(self.prescripts.iter_mut()) {
+            children.push(&mut script.subscript);
+            children.push(&mut script.superscript);
+        }
+        children
+    }
 }
 
 impl PresentationPrivate<Mmultiscripts> for Mmultiscripts {
@@ -192,7 +228,46 @@ mod test {
     use super::*;
     use ::elements::*;
     use ::props::*;
-    use ::test::skia::Snapshot;
+    use ::test::snapshot::Snapshot;
+    use ::backend::display_list::{DisplayList, DrawCommand};
+    use ::paint::Point;
+    use ::test::fixture::stix_context;
+
+    /// Rise of the baseline of text "2" above the baseline of an element
+    fn superscript_rise(context: &Context, element: &Element) -> f32 {
+        let layout = element.layout(context, &Family::new(), &InheritedProps::default(), &None);
+        let drawable = layout.layout(context);
+        let list = DisplayList::new(drawable.bounding_box());
+        drawable.draw(&list, &Point::new(0., 0.));
+
+        let commands = list.commands();
+        let superscript_baseline = commands.iter().filter_map(|command| match *command {
+            DrawCommand::GlyphRun { ref top_left, ref bound, ref run, .. } if run.text() == "2" =>
+                Some(top_left.y() + bound.baseline_pos()),
+            _ => None,
+        }).next().expect("Superscript not drawn");
+
+        drawable.bounding_box().baseline_pos() - superscript_baseline
+    }
+
+    // Base without ink, whose top does not limit the superscript shift
+    fn power() -> Box<Element> {
+        let mut base = Mspace::new();
+        base.with_width(Some(Length::EM(1.)))
+            .with_height(Some(Length::EM(0.)))
+            .with_depth(Some(Length::EM(0.)));
+        Box::new(Msup::new(Box::new(base), Box::new(Mn::new(String::from("2")))))
+    }
+
+    #[test]
+    fn it_lowers_superscripts_in_cramped_style() {
+        let context = stix_context();
+
+        let rise = superscript_rise(&context, power().as_ref());
+        let radicand_rise = superscript_rise(&context, &Msqrt::new(power()));
+
+        assert!(radicand_rise < rise, "{} is not below {}", radicand_rise, rise);
+    }
 
     #[test]
     fn it_works() {
@@ -219,6 +294,25 @@ mod test {
 
         snapshot.snap_element(&script, "mmutliscript_prescript_postscript");
 
+        let mut rtl = Mrow::new();
+        rtl.with_dir(Some(Directionality::RTL))
+            .with_child(Box::new(script));
+        snapshot.snap_element(&rtl, "mmutliscript_prescript_postscript_rtl");
+
+        let mut script = Mmultiscripts::new(Box::new(Mi::new(String::from("x"))));
+        script.with_prescript(
+            Mmultiscript {
+                superscript: Box::new(Mn::new(String::from("1"))),
+                subscript: Box::new(Mn::new(String::from("2")))
+            }
+        );
+        script.with_postscript(
+            Mmultiscript {
+                superscript: Box::new(Mn::new(String::from("3"))),
+                subscript: Box::new(Mn::new(String::from("4")))
+            }
+        );
+
         script.with_math_color(Some(Color::RGB(0, 255, 255)));
         script.with_math_background(Some(Color::RGB(0, 0, 0)));
 
@@ -245,4 +339,38 @@ mod test {
 
         snapshot.snap_element(&script, "mmutliscript_prescript_postscript_diff_height");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn it_mirrors_scripts_in_rtl() {
+        let snapshot = Snapshot::default();
+
+        let base = Mi::new(String::from("x"));
+        let prescript = Mn::new(String::from("1"));
+        let postscript = Mn::new(String::from("3"));
+        let ids = [base.instance_id().clone(), prescript.instance_id().clone(),
+            postscript.instance_id().clone()];
+        let ids = [&ids[0], &ids[1], &ids[2]];
+
+        let mut script = Mmultiscripts::new(Box::new(base));
+        script.with_prescript(Mmultiscript {
+            superscript: Box::new(prescript),
+            subscript: Box::new(Mn::new(String::from("2")))
+        });
+        script.with_postscript(Mmultiscript {
+            superscript: Box::new(postscript),
+            subscript: Box::new(Mn::new(String::from("4")))
+        });
+
+        let mut row = Mrow::new();
+        row.with_child(Box::new(script));
+        let ltr = snapshot.positions(&row, &ids);
+        row.with_dir(Some(Directionality::RTL));
+        let rtl = snapshot.positions(&row, &ids);
+
+        // Prescripts are on the right and postscripts on the left of base in right to left layout
+        assert!(ltr[1].x() < ltr[0].x() && ltr[0].x() < ltr[2].x());
+        assert!(rtl[2].x() < rtl[0].x() && rtl[0].x() < rtl[1].x());
+        assert!((ltr[1].y() - rtl[1].y()).abs() < 0.01);
+        assert!((ltr[2].y() - rtl[2].y()).abs() < 0.01);
+    }
+}
@@ -20,7 +20,7 @@ use super::super::{Mmultiscripts, Mmultiscript, Element, ElementType, InheritedP
                    Family, InstanceId, ScriptsAndLimits, Presentation, PresentationPrivate,
                    SpecifiedPresentationProps};
 use ::platform::Context;
-use ::layout::{Layout};
+use ::layout::{Layout, with_hit_target};
 
 pub struct Msubsup {
     multiscript: Mmultiscripts
@@ -52,7 +52,7 @@ impl Msubsup {
 impl Element for Msubsup {
     fn layout<'a>(&self, context: &Context, family: &Family<'a>, inherited: &InheritedProps,
                   style: &Option<&StyleProps>) -> Box<Layout> {
-        self.multiscript.layout(context, family, inherited, style)
+        with_hit_target(self.multiscript.layout(context, family, inherited, style), self)
     }
 
     fn type_info(&self) -> ElementType {
@@ -70,6 +70,15 @@ impl Element for Msubsup {
     fn instance_id(&self) -> &InstanceId {
         self.multiscript.instance_id()
     }
+
+    fn child_elements(&self) -> Vec<&Element> {
+        let script = &self.multiscript.postscript()[0];
+        vec![self.base().as_ref(), script.subscript.as_ref(), script.superscript.as_ref()]
+    }
+
+    fn child_elements_mut(&mut self) -> Vec<&mut Box<Element>> {
+        self.multiscript.child_elements_mut()
+    }
 }
 
 impl PresentationPrivate<Mmultiscripts> for Msubsup {
@@ -89,7 +98,7 @@ impl Presentation<Mmultiscripts> for Msubsup {}
 mod test {
     use super::*;
     use ::elements::*;
-    use ::test::skia::Snapshot;
+    use ::test::snapshot::Snapshot;
 
     #[test]
     fn it_works() {
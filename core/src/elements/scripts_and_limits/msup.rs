@@ -20,7 +20,7 @@ use super::super::{Mmultiscripts, Mmultiscript, Element, ElementType, InheritedP
                    Family, InstanceId, ScriptsAndLimits, Presentation, PresentationPrivate, Mempty,
                    SpecifiedPresentationProps};
 use ::platform::Context;
-use ::layout::{Layout};
+use ::layout::{Layout, with_hit_target};
 
 pub struct Msup {
     multiscript: Mmultiscripts
@@ -52,7 +52,7 @@ impl Msup {
 impl Element for Msup {
     fn layout<'a>(&self, context: &Context, family: &Family<'a>, inherited: &InheritedProps,
                   style: &Option<&StyleProps>) -> Box<Layout> {
-        self.multiscript.layout(context, family, inherited, style)
+        with_hit_target(self.multiscript.layout(context, family, inherited, style), self)
     }
 
     fn type_info(&self) -> ElementType {
@@ -70,6 +70,16 @@ impl Element for Msup {
     fn instance_id(&self) -> &InstanceId {
         self.multiscript.instance_id()
     }
+
+    fn child_elements(&self) -> Vec<&Element> {
+        vec![self.base().as_ref(), self.multiscript.postscript()[0].superscript.as_ref()]
+    }
+
+    fn child_elements_mut(&mut self) -> Vec<&mut Box<Element>> {
+        let mut children = self.multiscript.child_elements_mut();
+        children.remove(1);
+        children
+    }
 }
 
 impl PresentationPrivate<Mmultiscripts> for Msup {
@@ -89,7 +99,7 @@ impl Presentation<Mmultiscripts> for Msup {}
 mod test {
     use super::*;
     use ::elements::*;
-    use ::test::skia::Snapshot;
+    use ::test::snapshot::Snapshot;
 
     #[test]
     fn it_works() {
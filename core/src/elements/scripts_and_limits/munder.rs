@@ -20,7 +20,7 @@ use super::super::{Munderover, Element, ElementType, InheritedProps, StyleProps,
                    Family, InstanceId, ScriptsAndLimits, Presentation, PresentationPrivate, Mempty,
                    SpecifiedPresentationProps};
 use ::platform::Context;
-use ::layout::{Layout};
+use ::layout::{Layout, with_hit_target};
 use ::props::{Accent, HAlign};
 
 pub struct Munder {
@@ -74,7 +74,7 @@ impl Munder {
 impl Element for Munder {
     fn layout<'a>(&self, context: &Context, family: &Family<'a>, inherited: &InheritedProps,
                   style: &Option<&StyleProps>) -> Box<Layout> {
-        self.munderover.layout(context, family, inherited, style)
+        with_hit_target(self.munderover.layout(context, family, inherited, style), self)
     }
 
     fn type_info(&self) -> ElementType {
@@ -92,6 +92,16 @@ impl Element for Munder {
     fn instance_id(&self) -> &InstanceId {
         self.munderover.instance_id()
     }
+
+    fn child_elements(&self) -> Vec<&Element> {
+        vec![self.munderover.base().as_ref(), self.munderover.underscript().as_ref()]
+    }
+
+    fn child_elements_mut(&mut self) -> Vec<&mut Box<Element>> {
+        let mut children = self.munderover.child_elements_mut();
+        children.truncate(2);
+        children
+    }
 }
 
 impl PresentationPrivate<Munderover> for Munder {
@@ -111,7 +121,46 @@ impl Presentation<Munderover> for Munder {}
 mod test {
     use super::*;
     use ::elements::*;
-    use ::test::skia::Snapshot;
+    use ::test::snapshot::Snapshot;
+    use ::paint::Point;
+    use ::test::fixture::{stix_context, draw_root};
+
+    #[test]
+    fn it_stretches_horizontal_arrows() {
+        let context = stix_context();
+        let base = Mi::new(String::from("xxxxxxxx"));
+        let arrow = Mo::new(String::from("\u{2192}"));
+        let arrow_id = arrow.instance_id().clone();
+        let munder = Munder::new(Box::new(base), Box::new(arrow));
+
+        // Head of the arrow assembly in STIX has a connector shorter than the minimum overlap
+        let drawable = draw_root(&munder, &context);
+        let (position, arrow) = drawable.locate(&Point::new(0., 0.), &arrow_id).unwrap();
+
+        assert!(arrow.bounding_box().width() > 200., "{} is not stretched",
+                arrow.bounding_box().width());
+        assert_inside(drawable.bounding_box().width(), position.x(), arrow.bounding_box().width());
+    }
+
+    #[test]
+    fn it_fits_stretched_underscripts() {
+        let context = stix_context();
+        let underscript = Mo::new(String::from("_"));
+        let underscript_id = underscript.instance_id().clone();
+        let munder = Munder::new(Box::new(Mi::new(String::from("x"))), Box::new(underscript));
+
+        let drawable = draw_root(&munder, &context);
+        let (position, underscript) = drawable.locate(&Point::new(0., 0.), &underscript_id)
+            .unwrap();
+
+        assert_inside(drawable.bounding_box().width(), position.x(),
+                      underscript.bounding_box().width());
+    }
+
+    fn assert_inside(box_width: f32, x: f32, width: f32) {
+        assert!(x >= 0. && x + width <= box_width + 0.001,
+                "Script from {} to {} is outside box of width {}", x, x + width, box_width);
+    }
 
     #[ignore]
     #[test]
@@ -18,12 +18,18 @@ use std::any::Any;
 
 use super::super::{Element, InheritedProps, StyleProps, Family, ElementType, InstanceId,
                    SpecifiedPresentationProps, PresentationPrivate, Presentation, ScriptsAndLimits,
-                   PropertyCalculator, Property, EmptyComputeCtx};
-use ::layout::{Layout, MunderoverLayout, MmultiscriptLayout};
+                   PropertyCalculator, Property, EmptyComputeCtx, layout_child};
+use ::layout::{Layout, MunderoverLayout, MmultiscriptLayout, MoLayout, MiLayout};
 use ::platform::Context;
-use ::props::{HAlign, PropertyModifier, MathSize, Accent};
+use ::props::{HAlign, PropertyModifier, MathSize, Accent, Directionality};
 use ::utils::get_core_mo_layout;
 
+#[allow(const_err)]
+const PROP_DIRECTIONALITY: Property<Directionality, Munderover, EmptyComputeCtx> = Property::Inherited {
+    reader: |i| i.dir(),
+    writer: |v, fork| fork.dir(v)
+};
+
 #[allow(const_err)]
 const PROP_ACCENT: Property<Accent, Munderover, EmptyComputeCtx> = Property::Specified {
     default: || Accent::Automatic,
@@ -139,11 +145,12 @@ impl Element for Munderover {
             &PROP_ACCENT, self.accent.as_ref());
         let accent_under = calculator.calculate(
             &PROP_ACCENT_UNDER, self.accent.as_ref());
+        let dir = calculator.calculate(&PROP_DIRECTIONALITY, None);
 
         let new_family = family.add(self);
         let inherited_base = calculator.make_fork().copy();
 
-        let base_layout = self.base.layout(context, &new_family, &inherited_base, style);
+        let mut base_layout = self.base.layout(context, &new_family, &inherited_base, style);
 
         let (mo_movable_limits, mo_accent) = {
             let core_mo = get_core_mo_layout(&base_layout);
@@ -163,15 +170,23 @@ impl Element for Munderover {
             ));
             let inherited_scripts = inherited_scripts.copy();
 
+            let mut inherited_subscript = inherited_scripts.copier();
+            inherited_subscript.cramped(true);
+            let inherited_subscript = inherited_subscript.copy();
+
             return Box::new(MmultiscriptLayout {
                 base_layout,
                 prescript_layout: Vec::new(),
                 postscript_layout: vec![(
-                    self.overscript.layout(context, &new_family, &inherited_scripts, style),
-                    self.underscript.layout(context, &new_family, &inherited_scripts, style)
+                    layout_child(self.overscript.as_ref(), context, &new_family,
+                                 &inherited_scripts, style),
+                    layout_child(self.underscript.as_ref(), context, &new_family,
+                                 &inherited_subscript,
+                                 style)
                 )],
                 subscript_shift: 0.0,
                 superscript_shift: 0.0,
+                dir,
                 presentation_layout,
             });
         }
@@ -192,6 +207,7 @@ impl Element for Munderover {
 
         let mut inherited_under = inherited_base.copier();
         inherited_under.display_style(false);
+        inherited_under.cramped(true);
         if !accent_under {
             inherited_under.script_level(presentation_layout.script_level.new_level(
                 PropertyModifier::Increment(1), context, &MathSize::NORMAL,
@@ -200,13 +216,23 @@ impl Element for Munderover {
         }
         let inherited_under = inherited_under.copy();
 
+        let mut overscript_layout = self.overscript.layout(context, &new_family, &inherited_over,
+                                                           style);
+        if accent {
+            let base_size = presentation_layout.script_level.get_font_size(context,
+                                                                           &MathSize::NORMAL);
+            fit_accent_over(context, base_size, &mut base_layout, &mut overscript_layout);
+        }
+
         return Box::new(MunderoverLayout {
             accent_over: accent,
             accent_under,
             align,
+            dir,
             base: base_layout,
-            underscript: self.underscript.layout(context, &new_family, &inherited_under, style),
-            overscript: self.overscript.layout(context, &new_family, &inherited_over, style),
+            underscript: layout_child(self.underscript.as_ref(), context, &new_family,
+                                      &inherited_under, style),
+            overscript: overscript_layout,
             presentation_layout,
         })
     }
@@ -226,6 +252,30 @@ impl Element for Munderover {
     fn instance_id(&self) -> &InstanceId {
         &self.instance_id
     }
+
+    fn child_elements(&self) -> Vec<&Element> {
+        vec![self.base.as_ref(), self.underscript.as_ref(), self.overscript.as_ref()]
+    }
+
+    fn child_elements_mut(&mut self) -> Vec<&mut Box<Element>> {
+        vec![&mut self.base, &mut self.underscript, &mut self.overscript]
+    }
+}
+
+// Accent operators over bases taller than the flattened accent base height take their
+// flattened form, and identifiers carrying an accent drop the dots of i and j
+fn fit_accent_over(context: &Context, base_size: f32, base: &mut Box<Layout>,
+                   accent: &mut Box<Layout>) {
+    if let Some(mi) = base.as_any_mut().and_then(|any| any.downcast_mut::<MiLayout>()) {
+        mi.token_element.dotless = true;
+    }
+
+    let base_ascent = base.layout(context).bounding_box().ink_ascent();
+    let ruler = context.platform().get_math_ruler(base_size);
+
+    if let Some(mo) = accent.as_any_mut().and_then(|any| any.downcast_mut::<MoLayout>()) {
+        mo.flattened_accent = base_ascent > ruler.flattened_accent_base_height();
+    }
 }
 
 impl PresentationPrivate<Munderover> for Munderover {
@@ -245,8 +295,94 @@ impl Presentation<Munderover> for Munderover {}
 mod test {
     use super::*;
     use ::elements::*;
-    use ::test::skia::Snapshot;
+    use ::test::snapshot::Snapshot;
+    use ::backend::display_list::{DisplayList, DrawCommand};
+    use ::font::{OpenTypeFont, OpenTypeRuler};
+    use ::font::test::stix_math;
+    use ::paint::{Point, TextRuler, MathRuler};
+    use std::rc::Rc;
+    use ::test::fixture::stix_context;
+
+    /// Indices of glyphs drawn for an element, in drawing order
+    fn drawn_glyphs(context: &Context, font: &OpenTypeFont, element: &Element) -> Vec<u32> {
+        let layout = element.layout(context, &Family::new(), &InheritedProps::default(), &None);
+        let drawable = layout.layout(context);
+        let list = DisplayList::new(drawable.bounding_box());
+        drawable.draw(&list, &Point::new(0., 0.));
+
+        let commands = list.commands();
+        commands.iter().flat_map(|command| match *command {
+            DrawCommand::GlyphRun { ref run, .. } =>
+                run.glyphs().iter().map(|glyph| glyph.glyph_index()).collect(),
+            DrawCommand::Glyph { glyph_index, .. } => vec![glyph_index],
+            DrawCommand::Text { ref text, .. } =>
+                text.chars().filter_map(|c| font.glyph_index(c as u32)).collect(),
+            _ => Vec::new(),
+        }).collect()
+    }
+
+    #[test]
+    fn it_uses_glyph_forms_of_accents_and_scripts() {
+        let font = Rc::new(stix_math());
+        let ruler = OpenTypeRuler::new(font.clone());
+        let glyph = |unicode: u32| font.glyph_index(unicode).unwrap();
+        let context = stix_context();
+
+        let italic_i = glyph(0x1D456);
+        let dot = glyph(0x307);
+        let flat_dot = ruler.flattened_accent_glyph(dot).unwrap();
+        let dotless_i = ruler.dotless_glyph(italic_i).unwrap();
+        let script_x = ruler.script_style_glyph(glyph(0x1D465), 1).unwrap();
+
+        let mut accented = Munderover::new(Box::new(Mi::new(String::from("i"))),
+                                           Box::new(Mo::new(String::from("\u{307}"))),
+                                           Box::new(Mempty::new()));
+        accented.with_accent(Some(Accent::True));
+        assert_eq!(drawn_glyphs(&context, &font, &accented), vec![dot, dotless_i]);
+
+        accented.with_base(Box::new(Mfrac::new(Box::new(Mi::new(String::from("x"))),
+                                               Box::new(Mi::new(String::from("y"))))));
+        assert_eq!(drawn_glyphs(&context, &font, &accented)[0], flat_dot);
+
+        let limits = Munderover::new(Box::new(Mi::new(String::from("i"))),
+                                     Box::new(Mi::new(String::from("x"))),
+                                     Box::new(Mempty::new()));
+        assert_eq!(drawn_glyphs(&context, &font, &limits), vec![script_x, italic_i]);
+    }
+
     
+    /// Rise of the baseline of an overscript above the baseline of identifier `base`
+    fn overscript_rise(context: &Context, base: &str) -> f32 {
+        let element = Munderover::new(Box::new(Mi::new(String::from(base))),
+                                      Box::new(Mn::new(String::from("2"))),
+                                      Box::new(Mempty::new()));
+        let layout = element.layout(context, &Family::new(), &InheritedProps::default(), &None);
+        let drawable = layout.layout(context);
+        let list = DisplayList::new(drawable.bounding_box());
+        drawable.draw(&list, &Point::new(0., 0.));
+
+        // Overscript is drawn before base
+        let commands = list.commands();
+        let baselines: Vec<f32> = commands.iter().filter_map(|command| match *command {
+            DrawCommand::GlyphRun { ref top_left, ref bound, .. } =>
+                Some(top_left.y() + bound.baseline_pos()),
+            _ => None,
+        }).collect();
+        assert_eq!(baselines.len(), 2);
+
+        baselines[1] - baselines[0]
+    }
+
+    #[test]
+    fn it_places_overscripts_above_base_ink() {
+        let context = stix_context();
+
+        let over_short = overscript_rise(&context, "x");
+        let over_tall = overscript_rise(&context, "b");
+
+        assert!(over_short < over_tall, "{} is not below {}", over_short, over_tall);
+    }
+
     #[test]
     fn it_works() {
         let snapshot = Snapshot::default();
@@ -258,4 +394,34 @@ mod test {
 
         snapshot.snap_element(&underover, "munderover_simple");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn it_mirrors_alignment_in_rtl() {
+        let snap = Snapshot::default();
+
+        let base = Mi::new(String::from("xyz"));
+        let overscript = Mi::new(String::from("a"));
+        let underscript = Mi::new(String::from("b"));
+        let ids = [base.instance_id().clone(), overscript.instance_id().clone(),
+            underscript.instance_id().clone()];
+        let ids = [&ids[0], &ids[1], &ids[2]];
+
+        let mut munderover = Munderover::new(Box::new(base), Box::new(overscript),
+                                             Box::new(underscript));
+        munderover.with_align(Some(HAlign::Left));
+        let mut row = Mrow::new();
+        row.with_child(Box::new(munderover));
+
+        // Scripts aligned to the left are aligned to the right in right to left layout
+        let ltr = snap.positions(&row, &ids);
+        row.with_dir(Some(Directionality::RTL));
+        let rtl = snap.positions(&row, &ids);
+        snap.snap_element(&row, "munderover_left_rtl");
+
+        assert!((ltr[1].x() - ltr[0].x()).abs() < 0.01);
+        assert!((ltr[2].x() - ltr[0].x()).abs() < 0.01);
+        assert!(rtl[1].x() > rtl[0].x());
+        assert!(rtl[2].x() > rtl[0].x());
+        assert!((ltr[1].y() - rtl[1].y()).abs() < 0.01);
+    }
+}
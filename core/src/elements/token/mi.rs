@@ -25,6 +25,7 @@ use super::super::{
     SpecifiedPresentationProps, Element, InheritedProps, StyleProps, ElementType, TokenElement,
     Property, InstanceId, Family, EmptyComputeCtx};
 use ::platform::*;
+use ::draw::TextFont;
 
 pub struct Mi {
     instance_id: InstanceId,
@@ -76,6 +77,10 @@ impl Element for Mi {
     fn instance_id(&self) -> &InstanceId {
         &self.instance_id
     }
+
+    fn text(&self) -> Option<&str> {
+        Some(self.get_text())
+    }
 }
 
 impl PresentationPrivate<Mi> for Mi {
@@ -102,6 +107,14 @@ impl TokenPrivate<Mi> for Mi {
         reader:     |s| s.math_variant(),
     };
 
+    fn text_font(&self, context: &Context) -> TextFont {
+        if context.text_font_identifiers() && self.get_text().chars().count() > 1 {
+            TextFont::Text
+        } else {
+            TextFont::Math
+        }
+    }
+
     fn get_specified_token_props(&self) -> &SpecifiedTokenProps {
         &self.token_props
     }
@@ -119,7 +132,7 @@ impl Presentation<Mi> for Mi {}
 #[cfg(test)]
 mod test {
     use super::*;
-    use ::test::skia::Snapshot;
+    use ::test::snapshot::Snapshot;
 
     #[test]
     fn it_works() {
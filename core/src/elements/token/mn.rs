@@ -75,6 +75,10 @@ impl Element for Mn {
     fn instance_id(&self) -> &InstanceId {
         &self.instance_id
     }
+
+    fn text(&self) -> Option<&str> {
+        Some(self.get_text())
+    }
 }
 
 impl PresentationPrivate<Mn> for Mn {
@@ -105,7 +109,7 @@ impl Presentation<Mn> for Mn {}
 #[cfg(test)]
 mod test {
     use super::*;
-    use ::test::skia::Snapshot;
+    use ::test::snapshot::Snapshot;
     use ::props::{Color, MathSize, MathVariant};
 
     #[test]
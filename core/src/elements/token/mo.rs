@@ -23,9 +23,9 @@ use ::layout::{MoLayout, Layout};
 use super::super::{
     TokenPrivate, Token, PresentationPrivate, Presentation, SpecifiedTokenProps, PropertyCalculator,
     SpecifiedPresentationProps, Element, InheritedProps, StyleProps, ElementType, TokenElement, Property,
-    Mrow, InstanceId, Family, EmptyComputeCtx};
+    InstanceId, Family, RowPosition, EmptyComputeCtx};
 use ::platform::*;
-use ::utils::{is_space_like, get_enclosing_embellished_operator};
+use ::utils::get_enclosing_embellished_operator;
 use ::constants::SpaceLevel;
 
 #[derive(Debug)]
@@ -78,10 +78,10 @@ const PROP_FORM: Property<OperatorForm, Mo, EmptyComputeCtx> = Property::Compute
             return None;
         }
 
-        let parent = parent.as_any().downcast_ref::<Mrow>().unwrap();
-        let children = parent.children();
+        let position = embellished_operator.position().cloned()
+            .unwrap_or_else(|| RowPosition::in_row(&parent.child_elements(), elm));
 
-        if children.len() == 1 {
+        if position.len == 1 {
             if let Some(grand_parent_family) = family.grand_parent() {
                 if let Some(grand_parent) = grand_parent_family.parent() {
                     if grand_parent.type_info().is_scrips_or_limits() {
@@ -93,18 +93,15 @@ const PROP_FORM: Property<OperatorForm, Mo, EmptyComputeCtx> = Property::Compute
             return None;
         }
 
-        let num_non_space_like = children.iter()
-            .fold(0, |acc, child| if !is_space_like(child.as_ref()) { acc+1 } else { acc });
-
-        if num_non_space_like < 2 {
+        if position.non_space_like < 2 {
             return None;
         }
 
-        if children.iter().find(|c| !is_space_like(c.as_ref())).unwrap().instance_id() == elm.instance_id() {
+        if position.first {
             return Some(OperatorForm::Prefix);
         }
 
-        if children.iter().rev().find(|c| !is_space_like(c.as_ref())).unwrap().instance_id() == elm.instance_id() {
+        if position.last {
             return Some(OperatorForm::Postfix);
         }
 
@@ -447,6 +444,7 @@ impl Element for Mo {
                 &PROP_MOVABLE_LIMITS, self.movable_limits.as_ref(), &compute_ctx),
             accent: calculator.calculate_contextual(
                 &PROP_ACCENT, self.accent.as_ref(), &compute_ctx),
+            flattened_accent: false,
 
             token_element: token_layout,
         })
@@ -467,6 +465,10 @@ impl Element for Mo {
     fn instance_id(&self) -> &InstanceId {
         &self.instance_id
     }
+
+    fn text(&self) -> Option<&str> {
+        Some(self.get_text())
+    }
 }
 
 impl PresentationPrivate<Mo> for Mo {
@@ -498,7 +500,44 @@ impl Presentation<Mo> for Mo {}
 mod test {
     use super::*;
     use ::elements::*;
-    use ::test::skia::Snapshot;
+    use ::test::snapshot::Snapshot;
+    use ::paint::Point;
+    use ::test::fixture::{stix_context, draw_root};
+
+    #[test]
+    fn it_overlaps_neighbors_with_negative_lspace() {
+        let context = stix_context();
+
+        let row_of = |lspace: f32| {
+            let mut mo = Mo::new(String::from("+"));
+            mo.with_lspace(Some(Length::EM(lspace))).with_rspace(Some(Length::EM(0.)));
+            let a = Mi::new(String::from("a"));
+            let (a_id, mo_id) = (a.instance_id().clone(), mo.instance_id().clone());
+
+            let mut mrow = Mrow::new();
+            mrow.with_child(Box::new(a))
+                .with_child(Box::new(mo))
+                .with_child(Box::new(Mi::new(String::from("b"))));
+
+            let drawable = draw_root(&mrow, &context);
+            let origin = Point::new(0., 0.);
+            let a_width = drawable.locate(&origin, &a_id).unwrap().1.bounding_box().width();
+            let (mo_pos, mo) = drawable.locate(&origin, &mo_id).unwrap();
+            (a_width, mo_pos.x(), mo.bounding_box().width(), drawable.bounding_box().width())
+        };
+
+        // Negative lspace moves the operator and everything after it back over the `a`, as far
+        // as the same positive lspace moves them forward
+        let (a_width, unspaced_x, unspaced_width, unspaced_row) = row_of(0.);
+        let (_, _, spaced_width, _) = row_of(0.5);
+        let (_, kerned_x, kerned_width, kerned_row) = row_of(-0.5);
+        let space = spaced_width - unspaced_width;
+        assert!(space > 0.);
+        assert!((unspaced_x - a_width).abs() < 0.01);
+        assert!((kerned_x - a_width).abs() < 0.01);
+        assert!((kerned_width - (unspaced_width - space)).abs() < 0.01);
+        assert!((kerned_row - (unspaced_row - space)).abs() < 0.01);
+    }
 
     #[test]
     fn it_works() {
@@ -27,6 +27,7 @@ use std::rc::Rc;
 use ::props::{MathVariant, MathSize, Directionality};
 use ::elements::{Element, PresentationPrivate, Property, PropertyCalculator, EmptyComputeCtx};
 use ::layout::{TokenLayout};
+use ::draw::TextFont;
 use ::platform::Context;
 
 pub struct SpecifiedTokenProps {
@@ -57,6 +58,11 @@ pub trait TokenPrivate<T: Element> : PresentationPrivate<T> {
         writer:     |v, fork| fork.dir(v)
     };
 
+    /// Font the token text is set in
+    fn text_font(&self, _context: &Context) -> TextFont {
+        TextFont::Math
+    }
+
     fn get_specified_token_props(&self) -> &SpecifiedTokenProps;
     fn get_specified_token_props_mut(&mut self) -> &mut SpecifiedTokenProps;
 
@@ -75,6 +81,8 @@ pub trait TokenPrivate<T: Element> : PresentationPrivate<T> {
                     &Self::PROP_MATH_SIZE, token_props.math_size.as_ref())
             ),
             dir: calculator.calculate(&Self::PROP_DIR, token_props.dir.as_ref()),
+            text_font: self.text_font(context),
+            dotless: false,
             presentation_element: presentation_layout,
         }
     }
@@ -24,6 +24,7 @@ use super::super::{
     SpecifiedPresentationProps, Element, InheritedProps, StyleProps, ElementType, TokenElement,
     Property, InstanceId, Family, EmptyComputeCtx};
 use ::platform::Context;
+use ::draw::TextFont;
 
 #[allow(const_err)]
 const PROP_LQUOTE: Property<String, Ms, EmptyComputeCtx> = Property::Specified {
@@ -101,6 +102,10 @@ impl Element for Ms {
     fn instance_id(&self) -> &InstanceId {
         &self.instance_id
     }
+
+    fn text(&self) -> Option<&str> {
+        Some(self.get_text())
+    }
 }
 
 impl PresentationPrivate<Ms> for Ms {
@@ -114,6 +119,10 @@ impl PresentationPrivate<Ms> for Ms {
 }
 
 impl TokenPrivate<Ms> for Ms {
+    fn text_font(&self, _context: &Context) -> TextFont {
+        TextFont::Text
+    }
+
     fn get_specified_token_props(&self) -> &SpecifiedTokenProps {
         &self.token_props
     }
@@ -131,7 +140,7 @@ impl Presentation<Ms> for Ms {}
 #[cfg(test)]
 mod test {
     use super::*;
-    use ::test::skia::Snapshot;
+    use ::test::snapshot::Snapshot;
     use ::props::{Color, MathSize, MathVariant};
 
     #[test]
@@ -210,8 +210,54 @@ impl Presentation<Mspace> for Mspace {}
 #[cfg(test)]
 mod test {
     use super::*;
-    use ::test::skia::Snapshot;
+    use ::test::snapshot::Snapshot;
     use ::props::{Color, MathSize};
+    use ::elements::{Mrow, Mi};
+    use ::backend::display_list::{DisplayList, DrawCommand};
+    use ::paint::Point;
+    use ::test::fixture::{stix_context, draw_root};
+
+    #[test]
+    fn it_kerns_with_negative_width() {
+        let context = stix_context();
+
+        let x_positions = |width: f32| {
+            let mut space = Mspace::new();
+            space.with_width(Some(Length::EM(width)))
+                .with_height(Some(Length::EM(0.5)))
+                .with_depth(Some(Length::EM(0.)))
+                .with_math_background(Some(Color::RGB(255, 0, 0)));
+
+            let mut row = Mrow::new();
+            row.with_child(Box::new(Mi::new(String::from("x"))))
+                .with_child(Box::new(space))
+                .with_child(Box::new(Mi::new(String::from("y"))));
+
+            let drawable = draw_root(&row, &context);
+            let list = DisplayList::new(drawable.bounding_box());
+            drawable.draw(&list, &Point::new(0., 0.));
+
+            let commands = list.commands();
+            assert!(commands.iter().all(|command| match *command {
+                DrawCommand::Rect { ref rect, .. } => !rect.is_empty(),
+                _ => true,
+            }), "Background drawn for negative space");
+
+            commands.iter().filter_map(|command| match *command {
+                DrawCommand::GlyphRun { ref top_left, .. } => Some(top_left.x()),
+                _ => None,
+            }).collect::<Vec<f32>>()
+        };
+
+        let unspaced = x_positions(0.);
+        let spaced = x_positions(0.2);
+        let kerned = x_positions(-0.2);
+
+        assert_eq!(kerned.len(), 2);
+        assert!(kerned[1] < unspaced[1]);
+        assert!(((spaced[1] - unspaced[1]) - (unspaced[1] - kerned[1])).abs() < 0.01,
+                "{:?} is not kerned as far as {:?} is spaced", kerned, spaced);
+    }
 
     #[test]
     fn it_works() {
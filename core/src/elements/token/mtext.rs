@@ -24,6 +24,7 @@ use super::super::{
     SpecifiedPresentationProps, Element, InheritedProps, StyleProps, ElementType,
     TokenElement, InstanceId, Family};
 use ::platform::Context;
+use ::draw::TextFont;
 
 pub struct Mtext {
     instance_id: InstanceId,
@@ -75,6 +76,10 @@ impl Element for Mtext {
     fn instance_id(&self) -> &InstanceId {
         &self.instance_id
     }
+
+    fn text(&self) -> Option<&str> {
+        Some(self.get_text())
+    }
 }
 
 impl PresentationPrivate<Mtext> for Mtext {
@@ -88,6 +93,10 @@ impl PresentationPrivate<Mtext> for Mtext {
 }
 
 impl TokenPrivate<Mtext> for Mtext {
+    fn text_font(&self, _context: &Context) -> TextFont {
+        TextFont::Text
+    }
+
     fn get_specified_token_props(&self) -> &SpecifiedTokenProps {
         &self.token_props
     }
@@ -105,8 +114,53 @@ impl Presentation<Mtext> for Mtext {}
 #[cfg(test)]
 mod test {
     use super::*;
-    use ::test::skia::Snapshot;
+    use ::test::snapshot::Snapshot;
     use ::props::{Color, MathSize, MathVariant};
+    use ::elements::{Family, InheritedProps};
+    use ::backend::raster::RasterPlatform;
+    use ::backend::display_list::{DisplayList, DrawCommand};
+    use ::font::FontStack;
+    use ::font::test::{stix_math, stix_text, stix_renamed};
+    use ::paint::{Point, FontSynthesis};
+
+    /// Family and synthesis of text drawn for an element
+    fn drawn_faces(context: &Context, element: &Element) -> Vec<(String, String, FontSynthesis)> {
+        let layout = element.layout(context, &Family::new(), &InheritedProps::default(), &None);
+        let drawable = layout.layout(context);
+        let list = DisplayList::new(drawable.bounding_box());
+        drawable.draw(&list, &Point::new(0., 0.));
+
+        let commands = list.commands();
+        commands.iter().filter_map(|command| match *command {
+            DrawCommand::GlyphRun { ref run, synthesis, .. } =>
+                Some((run.text().to_string(), run.typeface().font_family().to_string(), synthesis)),
+            _ => None,
+        }).collect()
+    }
+
+    #[test]
+    fn it_uses_text_font() {
+        let mut fonts = FontStack::new(stix_math());
+        fonts.with_text_font(stix_text());
+        let context = Context::new(Box::new(RasterPlatform::with_fonts(fonts.clone())), 32.);
+
+        let text = String::from("STIX Two Text");
+        assert_eq!(drawn_faces(&context, &Mtext::new(String::from("if"))),
+                   vec![(String::from("if"), text.clone(), FontSynthesis::none())]);
+        assert_eq!(drawn_faces(&context, Mtext::new(String::from("if"))
+                       .with_math_variant(Some(MathVariant::Bold))),
+                   vec![(String::from("if"), text.clone(), FontSynthesis::new(true, false))]);
+
+        fonts.with_styled_text_font(true, false, stix_renamed("STIX Two Bold"));
+        let context = Context::new(Box::new(RasterPlatform::with_fonts(fonts)), 32.);
+        assert_eq!(drawn_faces(&context, Mtext::new(String::from("if"))
+                       .with_math_variant(Some(MathVariant::Bold))),
+                   vec![(String::from("if"), String::from("STIX Two Bold"),
+                         FontSynthesis::none())]);
+        assert_eq!(drawn_faces(&context, Mtext::new(String::from("if"))
+                       .with_math_variant(Some(MathVariant::DoubleStruck))),
+                   vec![(String::from("\u{1D55A}\u{1D557}"), text, FontSynthesis::none())]);
+    }
 
     #[test]
     fn it_works() {
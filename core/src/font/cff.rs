@@ -0,0 +1,633 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+
+use super::FontError;
+use super::reader::Reader;
+use super::outline::Outline;
+
+const OP_CHAR_STRINGS: u16 = 17;
+const OP_PRIVATE: u16 = 18;
+const OP_SUBRS: u16 = 19;
+const OP_ROS: u16 = 1230;
+const OP_FD_ARRAY: u16 = 1236;
+const OP_FD_SELECT: u16 = 1237;
+
+const MAX_STACK: usize = 48;
+const MAX_SUBR_DEPTH: usize = 10;
+
+/// INDEX structure, stored as byte ranges into the owned table data
+struct Index {
+    ranges: Vec<(usize, usize)>,
+}
+
+impl Index {
+    fn parse(data: &[u8], pos: usize) -> Result<(Index, usize), FontError> {
+        let mut reader = Reader::at(data, pos)?;
+        let count = reader.read_u16()? as usize;
+        if count == 0 {
+            return Ok((Index { ranges: vec![] }, reader.pos()));
+        }
+
+        let off_size = reader.read_u8()?;
+        let mut offsets = Vec::with_capacity(count + 1);
+        for _ in 0..count+1 {
+            offsets.push(reader.read_offset(off_size)? as usize);
+        }
+
+        // Offsets are 1 based, relative to the byte before object data
+        let base = reader.pos() - 1;
+        let mut ranges = Vec::with_capacity(count);
+        for i in 0..count {
+            let start = base + offsets[i];
+            let end = base + offsets[i+1];
+            if start > end || end > data.len() {
+                return Err(FontError::Malformed("CFF INDEX"));
+            }
+            ranges.push((start, end));
+        }
+
+        let end = base + offsets[count];
+        Ok((Index { ranges }, end))
+    }
+
+    fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    fn get<'a>(&self, data: &'a [u8], index: usize) -> Option<&'a [u8]> {
+        self.ranges.get(index).map(|&(start, end)| &data[start..end])
+    }
+}
+
+struct PrivateDict {
+    subrs: Index,
+}
+
+enum FdSelect {
+    Single,
+    Glyphs(Vec<u8>),
+}
+
+pub struct Cff {
+    data: Vec<u8>,
+    char_strings: Index,
+    global_subrs: Index,
+    privates: Vec<PrivateDict>,
+    fd_select: FdSelect,
+}
+
+impl Cff {
+    pub fn parse(data: &[u8]) -> Result<Cff, FontError> {
+        let mut reader = Reader::new(data);
+        let major = reader.read_u8()?;
+        if major != 1 {
+            return Err(FontError::Unsupported("CFF version"));
+        }
+        reader.skip(1)?;
+        let header_size = reader.read_u8()? as usize;
+
+        let (_, pos) = Index::parse(data, header_size)?;
+        let (top_dicts, pos) = Index::parse(data, pos)?;
+        let (_, pos) = Index::parse(data, pos)?;
+        let (global_subrs, _) = Index::parse(data, pos)?;
+
+        let top_dict = top_dicts.get(data, 0).ok_or(FontError::Malformed("CFF top DICT"))?;
+        let top_dict = parse_dict(top_dict)?;
+
+        let char_strings_offset = dict_int(&top_dict, OP_CHAR_STRINGS, 0)?
+            .ok_or(FontError::Malformed("CFF CharStrings"))?;
+        let (char_strings, _) = Index::parse(data, char_strings_offset as usize)?;
+
+        let (privates, fd_select) = if find_operator(&top_dict, OP_ROS).is_some() {
+            let fd_array_offset = dict_int(&top_dict, OP_FD_ARRAY, 0)?
+                .ok_or(FontError::Malformed("CFF FDArray"))?;
+            let fd_select_offset = dict_int(&top_dict, OP_FD_SELECT, 0)?
+                .ok_or(FontError::Malformed("CFF FDSelect"))?;
+
+            let (fd_array, _) = Index::parse(data, fd_array_offset as usize)?;
+            let mut privates = Vec::with_capacity(fd_array.len());
+            for i in 0..fd_array.len() {
+                let font_dict = parse_dict(fd_array.get(data, i).unwrap())?;
+                privates.push(parse_private(data, &font_dict)?);
+            }
+
+            let fd_select = parse_fd_select(data, fd_select_offset as usize,
+                                            char_strings.len())?;
+            (privates, FdSelect::Glyphs(fd_select))
+        } else {
+            (vec![parse_private(data, &top_dict)?], FdSelect::Single)
+        };
+
+        Ok(Cff {
+            data: data.to_vec(),
+            char_strings,
+            global_subrs,
+            privates,
+            fd_select,
+        })
+    }
+
+    pub fn outline(&self, glyph_index: u32) -> Result<Outline, FontError> {
+        let char_string = self.char_strings.get(&self.data, glyph_index as usize)
+            .ok_or(FontError::Malformed("glyph index"))?;
+
+        let fd = match self.fd_select {
+            FdSelect::Single => 0,
+            FdSelect::Glyphs(ref fds) => *fds.get(glyph_index as usize).unwrap_or(&0) as usize,
+        };
+        let private = self.privates.get(fd).ok_or(FontError::Malformed("CFF FDSelect"))?;
+
+        let mut interpreter = CharStringInterpreter {
+            data: &self.data,
+            global_subrs: &self.global_subrs,
+            local_subrs: &private.subrs,
+            stack: Vec::with_capacity(MAX_STACK),
+            outline: Outline::new(),
+            x: 0.,
+            y: 0.,
+            stem_count: 0,
+            width_parsed: false,
+            contour_open: false,
+        };
+
+        interpreter.run(char_string, 0)?;
+        interpreter.close_contour();
+
+        Ok(interpreter.outline)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Operand {
+    Int(i32),
+    Real(f64),
+}
+
+impl Operand {
+    fn as_int(&self) -> i32 {
+        match *self {
+            Operand::Int(v) => v,
+            Operand::Real(v) => v as i32,
+        }
+    }
+}
+
+fn parse_dict(data: &[u8]) -> Result<Vec<(u16, Vec<Operand>)>, FontError> {
+    let mut reader = Reader::new(data);
+    let mut entries = Vec::new();
+    let mut operands = Vec::new();
+
+    while !reader.is_empty() {
+        let b0 = reader.read_u8()?;
+        match b0 {
+            0..=21 => {
+                let operator = if b0 == 12 {
+                    1200 + reader.read_u8()? as u16
+                } else {
+                    b0 as u16
+                };
+                entries.push((operator, operands));
+                operands = Vec::new();
+            },
+            28 => operands.push(Operand::Int(reader.read_i16()? as i32)),
+            29 => operands.push(Operand::Int(reader.read_i32()?)),
+            30 => operands.push(Operand::Real(read_real(&mut reader)?)),
+            32..=246 => operands.push(Operand::Int(b0 as i32 - 139)),
+            247..=250 => {
+                let b1 = reader.read_u8()? as i32;
+                operands.push(Operand::Int((b0 as i32 - 247)*256 + b1 + 108));
+            },
+            251..=254 => {
+                let b1 = reader.read_u8()? as i32;
+                operands.push(Operand::Int(-(b0 as i32 - 251)*256 - b1 - 108));
+            },
+            _ => return Err(FontError::Malformed("CFF DICT")),
+        }
+    }
+
+    Ok(entries)
+}
+
+fn read_real(reader: &mut Reader) -> Result<f64, FontError> {
+    let mut text = String::new();
+
+    'outer: loop {
+        let byte = reader.read_u8()?;
+        for nibble in [byte >> 4, byte & 0x0f].iter() {
+            match *nibble {
+                0..=9 => text.push((b'0' + nibble) as char),
+                0xa => text.push('.'),
+                0xb => text.push('E'),
+                0xc => text.push_str("E-"),
+                0xe => text.push('-'),
+                0xf => break 'outer,
+                _ => {},
+            }
+        }
+    }
+
+    text.parse().map_err(|_| FontError::Malformed("CFF real number"))
+}
+
+fn find_operator(dict: &[(u16, Vec<Operand>)], operator: u16) -> Option<&Vec<Operand>> {
+    dict.iter().find(|entry| entry.0 == operator).map(|entry| &entry.1)
+}
+
+fn dict_int(dict: &[(u16, Vec<Operand>)], operator: u16, index: usize)
+    -> Result<Option<i32>, FontError> {
+    match find_operator(dict, operator) {
+        Some(operands) => operands.get(index).map(|o| Some(o.as_int()))
+            .ok_or(FontError::Malformed("CFF DICT")),
+        None => Ok(None),
+    }
+}
+
+fn parse_private(data: &[u8], dict: &[(u16, Vec<Operand>)]) -> Result<PrivateDict, FontError> {
+    let size = dict_int(dict, OP_PRIVATE, 0)?;
+    let offset = dict_int(dict, OP_PRIVATE, 1)?;
+
+    let (size, offset) = match (size, offset) {
+        (Some(size), Some(offset)) if size >= 0 && offset >= 0 => (size as usize, offset as usize),
+        _ => return Ok(PrivateDict { subrs: Index { ranges: vec![] } }),
+    };
+
+    if offset + size > data.len() {
+        return Err(FontError::Truncated);
+    }
+
+    let private = parse_dict(&data[offset..offset+size])?;
+    let subrs = match dict_int(&private, OP_SUBRS, 0)? {
+        Some(subrs_offset) => Index::parse(data, offset + subrs_offset as usize)?.0,
+        None => Index { ranges: vec![] },
+    };
+
+    Ok(PrivateDict { subrs })
+}
+
+fn parse_fd_select(data: &[u8], offset: usize, num_glyphs: usize) -> Result<Vec<u8>, FontError> {
+    let mut reader = Reader::at(data, offset)?;
+    let format = reader.read_u8()?;
+
+    match format {
+        0 => Ok(reader.read_bytes(num_glyphs)?.to_vec()),
+        3 => {
+            let num_ranges = reader.read_u16()?;
+            let mut fds = vec![0u8; num_glyphs];
+            let mut first = reader.read_u16()? as usize;
+            for _ in 0..num_ranges {
+                let fd = reader.read_u8()?;
+                let next = reader.read_u16()? as usize;
+                for glyph in first..next.min(num_glyphs) {
+                    fds[glyph] = fd;
+                }
+                first = next;
+            }
+            Ok(fds)
+        },
+        _ => Err(FontError::Unsupported("CFF FDSelect format")),
+    }
+}
+
+fn subr_bias(count: usize) -> i32 {
+    if count < 1240 {
+        107
+    } else if count < 33900 {
+        1131
+    } else {
+        32768
+    }
+}
+
+/// Type 2 charstring interpreter. Hints are parsed only to skip hint masks.
+struct CharStringInterpreter<'a> {
+    data: &'a [u8],
+    global_subrs: &'a Index,
+    local_subrs: &'a Index,
+    stack: Vec<f32>,
+    outline: Outline,
+    x: f32,
+    y: f32,
+    stem_count: usize,
+    width_parsed: bool,
+    contour_open: bool,
+}
+
+impl<'a> CharStringInterpreter<'a> {
+    fn run(&mut self, char_string: &[u8], depth: usize) -> Result<bool, FontError> {
+        if depth > MAX_SUBR_DEPTH {
+            return Err(FontError::Malformed("CFF subroutine nesting"));
+        }
+
+        let mut reader = Reader::new(char_string);
+        while !reader.is_empty() {
+            let b0 = reader.read_u8()?;
+            match b0 {
+                // hstem, vstem, hstemhm, vstemhm
+                1 | 3 | 18 | 23 => {
+                    self.parse_width(self.stack.len() % 2 == 1);
+                    self.stem_count += self.stack.len() / 2;
+                    self.stack.clear();
+                },
+                // hintmask, cntrmask
+                19 | 20 => {
+                    self.parse_width(self.stack.len() % 2 == 1);
+                    self.stem_count += self.stack.len() / 2;
+                    self.stack.clear();
+                    reader.skip((self.stem_count + 7) / 8)?;
+                },
+                // rmoveto
+                21 => {
+                    self.parse_width(self.stack.len() > 2);
+                    let dy = self.pop()?;
+                    let dx = self.pop()?;
+                    self.move_to(dx, dy);
+                    self.stack.clear();
+                },
+                // hmoveto
+                22 => {
+                    self.parse_width(self.stack.len() > 1);
+                    let dx = self.pop()?;
+                    self.move_to(dx, 0.);
+                    self.stack.clear();
+                },
+                // vmoveto
+                4 => {
+                    self.parse_width(self.stack.len() > 1);
+                    let dy = self.pop()?;
+                    self.move_to(0., dy);
+                    self.stack.clear();
+                },
+                // rlineto
+                5 => {
+                    let args = self.take_stack();
+                    for pair in args.chunks(2) {
+                        if pair.len() == 2 {
+                            self.line_to(pair[0], pair[1]);
+                        }
+                    }
+                },
+                // hlineto, vlineto
+                6 | 7 => {
+                    let args = self.take_stack();
+                    let mut horizontal = b0 == 6;
+                    for &d in args.iter() {
+                        if horizontal {
+                            self.line_to(d, 0.);
+                        } else {
+                            self.line_to(0., d);
+                        }
+                        horizontal = !horizontal;
+                    }
+                },
+                // rrcurveto
+                8 => {
+                    let args = self.take_stack();
+                    for c in args.chunks(6) {
+                        if c.len() == 6 {
+                            self.curve_to(c[0], c[1], c[2], c[3], c[4], c[5]);
+                        }
+                    }
+                },
+                // callsubr
+                10 => {
+                    let index = self.pop()? as i32 + subr_bias(self.local_subrs.len());
+                    let subr = self.local_subrs.get(self.data, index as usize)
+                        .ok_or(FontError::Malformed("CFF subroutine"))?;
+                    if self.run(subr, depth + 1)? {
+                        return Ok(true);
+                    }
+                },
+                // return
+                11 => return Ok(false),
+                // escape
+                12 => {
+                    let b1 = reader.read_u8()?;
+                    self.flex(b1)?;
+                },
+                // endchar
+                14 => {
+                    self.parse_width(self.stack.len() == 1 || self.stack.len() == 5);
+                    self.stack.clear();
+                    self.close_contour();
+                    return Ok(true);
+                },
+                // rcurveline
+                24 => {
+                    let args = self.take_stack();
+                    if args.len() < 2 {
+                        return Err(FontError::Malformed("CFF rcurveline"));
+                    }
+                    let (curves, line) = args.split_at(args.len() - 2);
+                    for c in curves.chunks(6) {
+                        if c.len() == 6 {
+                            self.curve_to(c[0], c[1], c[2], c[3], c[4], c[5]);
+                        }
+                    }
+                    self.line_to(line[0], line[1]);
+                },
+                // rlinecurve
+                25 => {
+                    let args = self.take_stack();
+                    if args.len() < 6 {
+                        return Err(FontError::Malformed("CFF rlinecurve"));
+                    }
+                    let (lines, c) = args.split_at(args.len() - 6);
+                    for pair in lines.chunks(2) {
+                        if pair.len() == 2 {
+                            self.line_to(pair[0], pair[1]);
+                        }
+                    }
+                    self.curve_to(c[0], c[1], c[2], c[3], c[4], c[5]);
+                },
+                // vvcurveto
+                26 => {
+                    let mut args = &self.take_stack()[..];
+                    let mut dx1 = 0.;
+                    if args.len() % 4 == 1 {
+                        dx1 = args[0];
+                        args = &args[1..];
+                    }
+                    for c in args.chunks(4) {
+                        if c.len() == 4 {
+                            self.curve_to(dx1, c[0], c[1], c[2], 0., c[3]);
+                            dx1 = 0.;
+                        }
+                    }
+                },
+                // hhcurveto
+                27 => {
+                    let mut args = &self.take_stack()[..];
+                    let mut dy1 = 0.;
+                    if args.len() % 4 == 1 {
+                        dy1 = args[0];
+                        args = &args[1..];
+                    }
+                    for c in args.chunks(4) {
+                        if c.len() == 4 {
+                            self.curve_to(c[0], dy1, c[1], c[2], c[3], 0.);
+                            dy1 = 0.;
+                        }
+                    }
+                },
+                // shortint
+                28 => {
+                    let value = reader.read_i16()? as f32;
+                    self.push(value)?;
+                },
+                // callgsubr
+                29 => {
+                    let index = self.pop()? as i32 + subr_bias(self.global_subrs.len());
+                    let subr = self.global_subrs.get(self.data, index as usize)
+                        .ok_or(FontError::Malformed("CFF global subroutine"))?;
+                    if self.run(subr, depth + 1)? {
+                        return Ok(true);
+                    }
+                },
+                // vhcurveto, hvcurveto
+                30 | 31 => {
+                    let args = self.take_stack();
+                    let mut horizontal = b0 == 31;
+                    let mut i = 0;
+                    while i + 4 <= args.len() {
+                        let last = if args.len() - i == 5 { args[i+4] } else { 0. };
+                        if horizontal {
+                            self.curve_to(args[i], 0., args[i+1], args[i+2], last, args[i+3]);
+                        } else {
+                            self.curve_to(0., args[i], args[i+1], args[i+2], args[i+3], last);
+                        }
+                        horizontal = !horizontal;
+                        i += 4;
+                    }
+                },
+                32..=246 => self.push(b0 as f32 - 139.)?,
+                247..=250 => {
+                    let b1 = reader.read_u8()? as f32;
+                    self.push((b0 as f32 - 247.)*256. + b1 + 108.)?;
+                },
+                251..=254 => {
+                    let b1 = reader.read_u8()? as f32;
+                    self.push(-(b0 as f32 - 251.)*256. - b1 - 108.)?;
+                },
+                255 => {
+                    let value = reader.read_i32()? as f32 / 65536.;
+                    self.push(value)?;
+                },
+                _ => return Err(FontError::Malformed("CFF charstring operator")),
+            }
+        }
+
+        Ok(false)
+    }
+
+    fn flex(&mut self, operator: u8) -> Result<(), FontError> {
+        let a = self.take_stack();
+        match operator {
+            // hflex
+            34 if a.len() >= 7 => {
+                self.curve_to(a[0], 0., a[1], a[2], a[3], 0.);
+                self.curve_to(a[4], 0., a[5], -a[2], a[6], 0.);
+            },
+            // flex
+            35 if a.len() >= 12 => {
+                self.curve_to(a[0], a[1], a[2], a[3], a[4], a[5]);
+                self.curve_to(a[6], a[7], a[8], a[9], a[10], a[11]);
+            },
+            // hflex1
+            36 if a.len() >= 9 => {
+                let y = self.y;
+                self.curve_to(a[0], a[1], a[2], a[3], a[4], 0.);
+                let dy = y - (self.y + a[7]);
+                self.curve_to(a[5], 0., a[6], a[7], a[8], dy);
+            },
+            // flex1
+            37 if a.len() >= 11 => {
+                let dx = a[0] + a[2] + a[4] + a[6] + a[8];
+                let dy = a[1] + a[3] + a[5] + a[7] + a[9];
+                self.curve_to(a[0], a[1], a[2], a[3], a[4], a[5]);
+                if dx.abs() > dy.abs() {
+                    self.curve_to(a[6], a[7], a[8], a[9], a[10], -dy);
+                } else {
+                    self.curve_to(a[6], a[7], a[8], a[9], -dx, a[10]);
+                }
+            },
+            // Arithmetic and storage operators are deprecated and not used by math fonts
+            _ => {},
+        }
+
+        Ok(())
+    }
+
+    /// The first stack clearing operator may carry the advance width as an extra argument
+    fn parse_width(&mut self, has_width: bool) {
+        if !self.width_parsed {
+            if has_width && !self.stack.is_empty() {
+                self.stack.remove(0);
+            }
+            self.width_parsed = true;
+        }
+    }
+
+    fn push(&mut self, value: f32) -> Result<(), FontError> {
+        if self.stack.len() >= MAX_STACK {
+            return Err(FontError::Malformed("CFF argument stack"));
+        }
+        self.stack.push(value);
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Result<f32, FontError> {
+        self.stack.pop().ok_or(FontError::Malformed("CFF argument stack"))
+    }
+
+    fn take_stack(&mut self) -> Vec<f32> {
+        self.width_parsed = true;
+        let stack = self.stack.clone();
+        self.stack.clear();
+        stack
+    }
+
+    fn close_contour(&mut self) {
+        if self.contour_open {
+            self.outline.close();
+            self.contour_open = false;
+        }
+    }
+
+    fn move_to(&mut self, dx: f32, dy: f32) {
+        self.close_contour();
+        self.x += dx;
+        self.y += dy;
+        self.outline.move_to(self.x, self.y);
+        self.contour_open = true;
+    }
+
+    fn line_to(&mut self, dx: f32, dy: f32) {
+        self.x += dx;
+        self.y += dy;
+        self.outline.line_to(self.x, self.y);
+    }
+
+    fn curve_to(&mut self, dx1: f32, dy1: f32, dx2: f32, dy2: f32, dx: f32, dy: f32) {
+        let x1 = self.x + dx1;
+        let y1 = self.y + dy1;
+        let x2 = x1 + dx2;
+        let y2 = y1 + dy2;
+        self.x = x2 + dx;
+        self.y = y2 + dy;
+        self.outline.curve_to(x1, y1, x2, y2, self.x, self.y);
+    }
+}
@@ -0,0 +1,199 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+
+use super::FontError;
+use super::reader::Reader;
+
+pub struct Segment {
+    start: u32,
+    end: u32,
+    delta: u16,
+    // Index into glyph id array for `start`, when the segment is not mapped by delta alone
+    glyph_ids_index: Option<usize>,
+}
+
+pub struct Group {
+    start: u32,
+    end: u32,
+    start_glyph: u32,
+}
+
+pub enum Cmap {
+    SegmentMapping { segments: Vec<Segment>, glyph_ids: Vec<u16> },
+    SegmentedCoverage { groups: Vec<Group> },
+}
+
+impl Cmap {
+    /// Picks a unicode subtable, preferring full repertoire ones over BMP only ones
+    pub fn parse(data: &[u8]) -> Result<Cmap, FontError> {
+        let mut reader = Reader::new(data);
+        reader.skip(2)?;
+        let num_tables = reader.read_u16()?;
+
+        let mut best: Option<(u8, usize)> = None;
+        for _ in 0..num_tables {
+            let platform_id = reader.read_u16()?;
+            let encoding_id = reader.read_u16()?;
+            let offset = reader.read_u32()? as usize;
+
+            let rank = match (platform_id, encoding_id) {
+                (3, 10) | (0, 4) | (0, 6) => 3,
+                (0, _) => 2,
+                (3, 1) => 1,
+                _ => continue,
+            };
+
+            let format = Reader::at(data, offset)?.read_u16()?;
+            if format != 4 && format != 12 {
+                continue;
+            }
+
+            if best.map(|(r, _)| rank > r).unwrap_or(true) {
+                best = Some((rank, offset));
+            }
+        }
+
+        let (_, offset) = best.ok_or(FontError::Unsupported("cmap subtable"))?;
+        let mut reader = Reader::at(data, offset)?;
+        match reader.read_u16()? {
+            4 => parse_segment_mapping(reader),
+            _ => parse_segmented_coverage(reader),
+        }
+    }
+
+    pub fn glyph_index(&self, unicode: u32) -> Option<u32> {
+        let glyph = match *self {
+            Cmap::SegmentMapping { ref segments, ref glyph_ids } => {
+                let segment = segments.iter().find(|s| unicode <= s.end)?;
+                if unicode < segment.start {
+                    return None;
+                }
+
+                match segment.glyph_ids_index {
+                    Some(index) => {
+                        let glyph = *glyph_ids.get(index + (unicode - segment.start) as usize)?;
+                        if glyph == 0 {
+                            0
+                        } else {
+                            glyph.wrapping_add(segment.delta) as u32
+                        }
+                    },
+                    None => (unicode as u16).wrapping_add(segment.delta) as u32,
+                }
+            },
+            Cmap::SegmentedCoverage { ref groups } => {
+                let group = groups.iter().find(|g| unicode >= g.start && unicode <= g.end)?;
+                group.start_glyph + unicode - group.start
+            }
+        };
+
+        if glyph == 0 {
+            None
+        } else {
+            Some(glyph)
+        }
+    }
+
+    /// Lowest code point mapped to `glyph_index`, by scanning all mappings
+    pub fn unicode(&self, glyph_index: u32) -> Option<u32> {
+        if glyph_index == 0 {
+            return None;
+        }
+
+        match *self {
+            Cmap::SegmentMapping { ref segments, .. } => segments.iter()
+                .flat_map(|s| s.start..s.end+1)
+                .find(|&c| c != 0xFFFF && self.glyph_index(c) == Some(glyph_index)),
+            Cmap::SegmentedCoverage { ref groups } => groups.iter()
+                .filter(|g| glyph_index >= g.start_glyph &&
+                    glyph_index - g.start_glyph <= g.end - g.start)
+                .map(|g| g.start + glyph_index - g.start_glyph)
+                .min(),
+        }
+    }
+}
+
+fn parse_segment_mapping(mut reader: Reader) -> Result<Cmap, FontError> {
+    let length = reader.read_u16()? as usize;
+    let table_start = reader.pos() - 4;
+    reader.skip(2)?;
+    let seg_count = (reader.read_u16()? / 2) as usize;
+    reader.skip(6)?;
+
+    let mut ends = Vec::with_capacity(seg_count);
+    for _ in 0..seg_count {
+        ends.push(reader.read_u16()?);
+    }
+    reader.skip(2)?;
+
+    let mut starts = Vec::with_capacity(seg_count);
+    for _ in 0..seg_count {
+        starts.push(reader.read_u16()?);
+    }
+
+    let mut deltas = Vec::with_capacity(seg_count);
+    for _ in 0..seg_count {
+        deltas.push(reader.read_u16()?);
+    }
+
+    let range_offsets_pos = reader.pos();
+    let mut range_offsets = Vec::with_capacity(seg_count);
+    for _ in 0..seg_count {
+        range_offsets.push(reader.read_u16()? as usize);
+    }
+
+    // The glyph id array runs until the end of the subtable. Range offsets are relative to their
+    // own position, so they are converted to indexes into this array.
+    let glyph_ids_pos = reader.pos();
+    let mut glyph_ids = Vec::new();
+    while reader.pos() + 2 <= table_start + length && !reader.is_empty() {
+        glyph_ids.push(reader.read_u16()?);
+    }
+
+    let segments = (0..seg_count).map(|i| Segment {
+        start: starts[i] as u32,
+        end: ends[i] as u32,
+        delta: deltas[i],
+        glyph_ids_index: if range_offsets[i] == 0 {
+            None
+        } else {
+            (range_offsets_pos + 2*i + range_offsets[i]).checked_sub(glyph_ids_pos).map(|d| d/2)
+        },
+    }).collect();
+
+    Ok(Cmap::SegmentMapping { segments, glyph_ids })
+}
+
+fn parse_segmented_coverage(mut reader: Reader) -> Result<Cmap, FontError> {
+    reader.skip(10)?;
+    let num_groups = reader.read_u32()?;
+
+    let mut groups = Vec::new();
+    for _ in 0..num_groups {
+        let start = reader.read_u32()?;
+        let end = reader.read_u32()?;
+        let start_glyph = reader.read_u32()?;
+
+        if end < start {
+            return Err(FontError::Malformed("cmap group"));
+        }
+
+        groups.push(Group { start, end, start_glyph });
+    }
+
+    Ok(Cmap::SegmentedCoverage { groups })
+}
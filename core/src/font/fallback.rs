@@ -0,0 +1,140 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use super::{OpenTypeFont, MathConstant};
+
+// Parameters of Computer Modern in em, which TeX lays out math with
+const CM_X_HEIGHT: f32 = 0.430555;
+const CM_AXIS_HEIGHT: f32 = 0.25;
+const CM_RULE_THICKNESS: f32 = 0.04;
+const CM_NUM1: f32 = 0.676508;
+const CM_NUM2: f32 = 0.393732;
+const CM_NUM3: f32 = 0.443731;
+const CM_DENOM1: f32 = 0.685951;
+const CM_DENOM2: f32 = 0.344841;
+const CM_SUP2: f32 = 0.362892;
+const CM_SUP3: f32 = 0.288889;
+const CM_SUB1: f32 = 0.15;
+const CM_SUP_DROP: f32 = 0.386108;
+const CM_SUB_DROP: f32 = 0.05;
+const CM_DELIM2: f32 = 1.01;
+const CM_BIG_OP_SPACING1: f32 = 0.111112;
+const CM_BIG_OP_SPACING2: f32 = 0.166667;
+const CM_BIG_OP_SPACING3: f32 = 0.2;
+const CM_BIG_OP_SPACING4: f32 = 0.6;
+const CM_SCRIPT_SPACE: f32 = 0.05;
+const CM_DISPLAY_OPERATOR_HEIGHT: f32 = 1.4;
+
+/// Math constant in design units for fonts without a MATH table, derived from x-height, cap
+/// height and em size. Values follow the font parameters TeX uses with Computer Modern, with
+/// vertical shifts scaled by the ratio of x-height of the font to that of Computer Modern.
+pub fn synthesized_constant(font: &OpenTypeFont, constant: MathConstant) -> i16 {
+    let em = font.units_per_em() as f32;
+    let x_height = if font.x_height() > 0 { font.x_height() as f32 } else { CM_X_HEIGHT * em };
+    let cap_height = if font.cap_height() > 0 { font.cap_height() as f32 } else { x_height * 1.5 };
+
+    // Em size of Computer Modern with the same x-height as the font
+    let shift = |value: f32| value * x_height / CM_X_HEIGHT;
+    let rule = (CM_RULE_THICKNESS * em).round().max(1.);
+
+    let value = match constant {
+        MathConstant::ScriptPercentScaleDown => 70.,
+        MathConstant::ScriptScriptPercentScaleDown => 50.,
+        MathConstant::DelimitedSubFormulaMinHeight => shift(CM_DELIM2),
+        MathConstant::DisplayOperatorMinHeight => shift(CM_DISPLAY_OPERATOR_HEIGHT),
+        MathConstant::MathLeading => 0.,
+        MathConstant::AxisHeight => shift(CM_AXIS_HEIGHT),
+        MathConstant::AccentBaseHeight => x_height,
+        MathConstant::FlattenedAccentBaseHeight => cap_height,
+        MathConstant::SubscriptShiftDown => shift(CM_SUB1),
+        MathConstant::SubscriptTopMax => x_height * 4. / 5.,
+        MathConstant::SubscriptBaselineDropMin => shift(CM_SUB_DROP),
+        MathConstant::SuperscriptShiftUp => shift(CM_SUP2),
+        MathConstant::SuperscriptShiftUpCramped => shift(CM_SUP3),
+        MathConstant::SuperscriptBottomMin => x_height / 4.,
+        MathConstant::SuperscriptBaselineDropMax => shift(CM_SUP_DROP),
+        MathConstant::SubSuperscriptGapMin => 4. * rule,
+        MathConstant::SuperscriptBottomMaxWithSubscript => x_height * 4. / 5.,
+        MathConstant::SpaceAfterScript => CM_SCRIPT_SPACE * em,
+        MathConstant::UpperLimitGapMin => shift(CM_BIG_OP_SPACING1),
+        MathConstant::UpperLimitBaselineRiseMin => shift(CM_BIG_OP_SPACING3),
+        MathConstant::LowerLimitGapMin => shift(CM_BIG_OP_SPACING2),
+        MathConstant::LowerLimitBaselineDropMin => shift(CM_BIG_OP_SPACING4),
+        MathConstant::StackTopShiftUp => shift(CM_NUM3),
+        MathConstant::StackTopDisplayStyleShiftUp => shift(CM_NUM1),
+        MathConstant::StackBottomShiftDown => shift(CM_DENOM2),
+        MathConstant::StackBottomDisplayStyleShiftDown => shift(CM_DENOM1),
+        MathConstant::StackGapMin => 3. * rule,
+        MathConstant::StackDisplayStyleGapMin => 7. * rule,
+        MathConstant::StretchStackTopShiftUp => shift(CM_BIG_OP_SPACING3),
+        MathConstant::StretchStackBottomShiftDown => shift(CM_BIG_OP_SPACING4),
+        MathConstant::StretchStackGapAboveMin => shift(CM_BIG_OP_SPACING1),
+        MathConstant::StretchStackGapBelowMin => shift(CM_BIG_OP_SPACING2),
+        MathConstant::FractionNumeratorShiftUp => shift(CM_NUM2),
+        MathConstant::FractionNumeratorDisplayStyleShiftUp => shift(CM_NUM1),
+        MathConstant::FractionDenominatorShiftDown => shift(CM_DENOM2),
+        MathConstant::FractionDenominatorDisplayStyleShiftDown => shift(CM_DENOM1),
+        MathConstant::FractionNumeratorGapMin => rule,
+        MathConstant::FractionNumDisplayStyleGapMin => 3. * rule,
+        MathConstant::FractionRuleThickness => rule,
+        MathConstant::FractionDenominatorGapMin => rule,
+        MathConstant::FractionDenomDisplayStyleGapMin => 3. * rule,
+        MathConstant::SkewedFractionHorizontalGap => x_height / 2.,
+        MathConstant::SkewedFractionVerticalGap => 2. * rule,
+        MathConstant::OverbarVerticalGap => 3. * rule,
+        MathConstant::OverbarRuleThickness => rule,
+        MathConstant::OverbarExtraAscender => rule,
+        MathConstant::UnderbarVerticalGap => 3. * rule,
+        MathConstant::UnderbarRuleThickness => rule,
+        MathConstant::UnderbarExtraDescender => rule,
+        MathConstant::RadicalVerticalGap => rule * 5. / 4.,
+        MathConstant::RadicalDisplayStyleVerticalGap => rule + x_height / 4.,
+        MathConstant::RadicalRuleThickness => rule,
+        MathConstant::RadicalExtraAscender => rule,
+        MathConstant::RadicalKernBeforeDegree => em * 5. / 18.,
+        MathConstant::RadicalKernAfterDegree => -em * 10. / 18.,
+        MathConstant::RadicalDegreeBottomRaisePercent => 60.,
+    };
+
+    value.round() as i16
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::test::stix_math;
+
+    #[test]
+    fn it_derives_constants_from_font_metrics() {
+        let font = stix_math();
+        let constant = |constant| synthesized_constant(&font, constant);
+
+        assert_eq!(constant(MathConstant::AccentBaseHeight), 473);
+        assert_eq!(constant(MathConstant::FlattenedAccentBaseHeight), 657);
+        assert_eq!(constant(MathConstant::FractionRuleThickness), 40);
+        assert_eq!(constant(MathConstant::StackGapMin), 120);
+        assert_eq!(constant(MathConstant::AxisHeight), 275);
+        assert_eq!(constant(MathConstant::ScriptPercentScaleDown), 70);
+        assert_eq!(constant(MathConstant::RadicalKernAfterDegree), -556);
+
+        // Close to values of the font's own MATH table
+        let math = font.math().unwrap();
+        for &c in [MathConstant::AxisHeight, MathConstant::StackGapMin,
+                   MathConstant::SubscriptShiftDown, MathConstant::SuperscriptShiftUp].iter() {
+            assert!((constant(c) - math.constant(c)).abs() < 150, "{:?}", c);
+        }
+    }
+}
@@ -0,0 +1,279 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+
+use super::FontError;
+use super::reader::Reader;
+use super::outline::Outline;
+
+const ON_CURVE_POINT: u8 = 0x01;
+const X_SHORT_VECTOR: u8 = 0x02;
+const Y_SHORT_VECTOR: u8 = 0x04;
+const REPEAT_FLAG: u8 = 0x08;
+const X_IS_SAME_OR_POSITIVE: u8 = 0x10;
+const Y_IS_SAME_OR_POSITIVE: u8 = 0x20;
+
+const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+const ARGS_ARE_XY_VALUES: u16 = 0x0002;
+const WE_HAVE_A_SCALE: u16 = 0x0008;
+const MORE_COMPONENTS: u16 = 0x0020;
+const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+
+const MAX_COMPONENT_DEPTH: usize = 8;
+
+/// TrueType outlines located through the loca table. Glyph data is read from the font data
+/// owned by `OpenTypeFont`.
+pub struct Glyf {
+    offsets: Vec<usize>,
+    table_offset: usize,
+    table_length: usize,
+}
+
+impl Glyf {
+    pub fn parse(loca: &[u8], index_to_loc_format: i16, num_glyphs: u16, table_offset: usize,
+                 table_length: usize) -> Result<Glyf, FontError> {
+        let mut reader = Reader::new(loca);
+        let mut offsets = Vec::with_capacity(num_glyphs as usize + 1);
+
+        for _ in 0..num_glyphs as usize + 1 {
+            let offset = match index_to_loc_format {
+                0 => reader.read_u16()? as usize * 2,
+                1 => reader.read_u32()? as usize,
+                _ => return Err(FontError::Unsupported("loca format")),
+            };
+            offsets.push(offset);
+        }
+
+        Ok(Glyf { offsets, table_offset, table_length })
+    }
+
+    pub fn outline(&self, data: &[u8], glyph_index: u32) -> Result<Outline, FontError> {
+        let mut outline = Outline::new();
+        self.append_glyph(data, glyph_index, &mut outline, 0)?;
+        Ok(outline)
+    }
+
+    fn glyph_data<'a>(&self, data: &'a [u8], glyph_index: u32) -> Result<&'a [u8], FontError> {
+        let index = glyph_index as usize;
+        if index + 1 >= self.offsets.len() {
+            return Err(FontError::Malformed("glyph index"));
+        }
+
+        let start = self.offsets[index];
+        let end = self.offsets[index+1];
+        if start > end || end > self.table_length {
+            return Err(FontError::Malformed("loca table"));
+        }
+
+        Ok(&data[self.table_offset+start..self.table_offset+end])
+    }
+
+    fn append_glyph(&self, data: &[u8], glyph_index: u32, outline: &mut Outline, depth: usize)
+        -> Result<(), FontError> {
+        if depth > MAX_COMPONENT_DEPTH {
+            return Err(FontError::Malformed("composite glyph nesting"));
+        }
+
+        let glyph = self.glyph_data(data, glyph_index)?;
+        if glyph.is_empty() {
+            return Ok(());
+        }
+
+        let mut reader = Reader::new(glyph);
+        let num_contours = reader.read_i16()?;
+        reader.skip(8)?;
+
+        if num_contours >= 0 {
+            append_simple_glyph(reader, num_contours as usize, outline)
+        } else {
+            self.append_composite_glyph(data, reader, outline, depth)
+        }
+    }
+
+    fn append_composite_glyph(&self, data: &[u8], mut reader: Reader, outline: &mut Outline,
+                              depth: usize) -> Result<(), FontError> {
+        loop {
+            let flags = reader.read_u16()?;
+            let glyph_index = reader.read_u16()?;
+
+            let (dx, dy) = if flags & ARG_1_AND_2_ARE_WORDS != 0 {
+                (reader.read_i16()? as f32, reader.read_i16()? as f32)
+            } else {
+                (reader.read_i8()? as f32, reader.read_i8()? as f32)
+            };
+
+            let (a, b, c, d) = if flags & WE_HAVE_A_SCALE != 0 {
+                let scale = read_f2dot14(&mut reader)?;
+                (scale, 0., 0., scale)
+            } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+                (read_f2dot14(&mut reader)?, 0., 0., read_f2dot14(&mut reader)?)
+            } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+                (read_f2dot14(&mut reader)?, read_f2dot14(&mut reader)?,
+                 read_f2dot14(&mut reader)?, read_f2dot14(&mut reader)?)
+            } else {
+                (1., 0., 0., 1.)
+            };
+
+            // Point matching placement is rare and needs the hinting machinery, such
+            // components are placed at origin
+            let (dx, dy) = if flags & ARGS_ARE_XY_VALUES != 0 { (dx, dy) } else { (0., 0.) };
+
+            let mut component = Outline::new();
+            self.append_glyph(data, glyph_index as u32, &mut component, depth + 1)?;
+            outline.extend_transformed(&component, |x, y| (a*x + c*y + dx, b*x + d*y + dy));
+
+            if flags & MORE_COMPONENTS == 0 {
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn read_f2dot14(reader: &mut Reader) -> Result<f32, FontError> {
+    Ok(reader.read_i16()? as f32 / 16384.)
+}
+
+fn append_simple_glyph(mut reader: Reader, num_contours: usize, outline: &mut Outline)
+    -> Result<(), FontError> {
+    let mut end_points = Vec::with_capacity(num_contours);
+    for _ in 0..num_contours {
+        end_points.push(reader.read_u16()? as usize);
+    }
+
+    let num_points = match end_points.last() {
+        Some(&last) => last + 1,
+        None => return Ok(()),
+    };
+
+    let instruction_length = reader.read_u16()? as usize;
+    reader.skip(instruction_length)?;
+
+    let mut flags = Vec::with_capacity(num_points);
+    while flags.len() < num_points {
+        let flag = reader.read_u8()?;
+        flags.push(flag);
+        if flag & REPEAT_FLAG != 0 {
+            for _ in 0..reader.read_u8()? {
+                flags.push(flag);
+            }
+        }
+    }
+    flags.truncate(num_points);
+
+    let xs = read_coordinates(&mut reader, &flags, X_SHORT_VECTOR, X_IS_SAME_OR_POSITIVE)?;
+    let ys = read_coordinates(&mut reader, &flags, Y_SHORT_VECTOR, Y_IS_SAME_OR_POSITIVE)?;
+
+    let mut start = 0;
+    for &end in end_points.iter() {
+        if end < start || end >= num_points {
+            return Err(FontError::Malformed("glyph contour"));
+        }
+
+        let points: Vec<(f32, f32, bool)> = (start..end+1)
+            .map(|i| (xs[i], ys[i], flags[i] & ON_CURVE_POINT != 0))
+            .collect();
+        append_contour(&points, outline);
+        start = end + 1;
+    }
+
+    Ok(())
+}
+
+fn read_coordinates(reader: &mut Reader, flags: &[u8], short_flag: u8, same_or_positive: u8)
+    -> Result<Vec<f32>, FontError> {
+    let mut value = 0i32;
+    let mut coordinates = Vec::with_capacity(flags.len());
+
+    for &flag in flags.iter() {
+        if flag & short_flag != 0 {
+            let delta = reader.read_u8()? as i32;
+            value += if flag & same_or_positive != 0 { delta } else { -delta };
+        } else if flag & same_or_positive == 0 {
+            value += reader.read_i16()? as i32;
+        }
+        coordinates.push(value as f32);
+    }
+
+    Ok(coordinates)
+}
+
+/// Converts quadratic B-spline contour to path commands, inserting the implied on curve points
+/// between consecutive off curve points.
+fn append_contour(points: &[(f32, f32, bool)], outline: &mut Outline) {
+    if points.is_empty() {
+        return;
+    }
+
+    let len = points.len();
+    let first_on = points.iter().position(|p| p.2);
+
+    let start = match first_on {
+        Some(i) => (points[i].0, points[i].1),
+        None => midpoint(points[len-1], points[0]),
+    };
+    let offset = first_on.unwrap_or(len - 1);
+
+    outline.move_to(start.0, start.1);
+
+    let mut control: Option<(f32, f32)> = None;
+    let steps = if first_on.is_some() { len } else { len + 1 };
+    for step in 1..steps+1 {
+        let point = points[(offset + step) % len];
+        let is_last = step == steps;
+
+        if point.2 || (is_last && first_on.is_none()) {
+            let target = if point.2 { (point.0, point.1) } else { start };
+            match control.take() {
+                Some(c) => outline.quad_to(c.0, c.1, target.0, target.1),
+                None => outline.line_to(target.0, target.1),
+            }
+        } else {
+            if let Some(c) = control {
+                let mid = midpoint((c.0, c.1, false), point);
+                outline.quad_to(c.0, c.1, mid.0, mid.1);
+            }
+            control = Some((point.0, point.1));
+        }
+    }
+
+    outline.close();
+}
+
+fn midpoint(a: (f32, f32, bool), b: (f32, f32, bool)) -> (f32, f32) {
+    ((a.0 + b.0)/2., (a.1 + b.1)/2.)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::PathCommand;
+
+    #[test]
+    fn it_inserts_implied_on_curve_points() {
+        let mut outline = Outline::new();
+        append_contour(&[(0., 0., true), (10., 0., false), (10., 10., false), (0., 10., true)],
+                       &mut outline);
+
+        assert_eq!(outline.commands(), &[
+            PathCommand::MoveTo(0., 0.),
+            PathCommand::QuadTo(10., 0., 10., 5.),
+            PathCommand::QuadTo(10., 10., 0., 10.),
+            PathCommand::LineTo(0., 0.),
+            PathCommand::Close,
+        ]);
+    }
+}
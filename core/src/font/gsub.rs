@@ -0,0 +1,218 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+
+use std::collections::HashMap;
+
+use super::FontError;
+use super::reader::Reader;
+use super::math::parse_coverage;
+
+/// Glyph substitution features read from GSUB table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GsubFeature {
+    /// `ssty`, optical variants of glyphs in scripts and in scripts of scripts
+    ScriptStyle,
+    /// `flac`, accents flattened to fit over tall bases
+    FlattenedAccent,
+    /// `dtls`, dotless forms of letters carrying accents
+    Dotless,
+}
+
+const FEATURES: [(GsubFeature, &[u8; 4]); 3] = [
+    (GsubFeature::ScriptStyle, b"ssty"),
+    (GsubFeature::FlattenedAccent, b"flac"),
+    (GsubFeature::Dotless, b"dtls"),
+];
+
+const SINGLE_SUBSTITUTION: u16 = 1;
+const ALTERNATE_SUBSTITUTION: u16 = 3;
+const EXTENSION_SUBSTITUTION: u16 = 7;
+
+type Substitutions = HashMap<u32, Vec<u32>>;
+
+/// Single and alternate substitutions of the features in `GsubFeature`, merged over all
+/// scripts and languages. Other features and lookup types are not read.
+pub struct GsubTable {
+    features: HashMap<GsubFeature, Substitutions>,
+}
+
+impl GsubTable {
+    pub fn parse(data: &[u8]) -> Result<GsubTable, FontError> {
+        let mut reader = Reader::new(data);
+        reader.skip(6)?;
+        let feature_list = reader.read_u16()? as usize;
+        let lookup_list = reader.read_u16()? as usize;
+
+        let mut features = HashMap::new();
+        for &(feature, tag) in FEATURES.iter() {
+            let mut substitutions = HashMap::new();
+            for lookup in feature_lookups(data, feature_list, tag)? {
+                parse_lookup(data, lookup_list, lookup, &mut substitutions)?;
+            }
+
+            if !substitutions.is_empty() {
+                features.insert(feature, substitutions);
+            }
+        }
+
+        Ok(GsubTable { features })
+    }
+
+    /// Glyphs substituting `glyph_index` under `feature`. Single substitutions have one glyph,
+    /// alternate substitutions have one per alternate in font order.
+    pub fn alternates(&self, feature: GsubFeature, glyph_index: u32) -> &[u32] {
+        self.features.get(&feature)
+            .and_then(|substitutions| substitutions.get(&glyph_index))
+            .map(|glyphs| &glyphs[..])
+            .unwrap_or(&[])
+    }
+}
+
+/// Indices of lookups of all feature records having `tag`
+fn feature_lookups(data: &[u8], feature_list: usize, tag: &[u8; 4]) -> Result<Vec<u16>, FontError> {
+    let mut reader = Reader::at(data, feature_list)?;
+    let count = reader.read_u16()?;
+
+    let mut lookups = Vec::new();
+    for _ in 0..count {
+        let record_tag = reader.read_bytes(4)?;
+        let offset = reader.read_u16()? as usize;
+        if record_tag != &tag[..] {
+            continue;
+        }
+
+        let mut feature = Reader::at(data, feature_list + offset)?;
+        feature.skip(2)?;
+        let lookup_count = feature.read_u16()?;
+        for _ in 0..lookup_count {
+            let lookup = feature.read_u16()?;
+            if !lookups.contains(&lookup) {
+                lookups.push(lookup);
+            }
+        }
+    }
+
+    Ok(lookups)
+}
+
+fn parse_lookup(data: &[u8], lookup_list: usize, index: u16, substitutions: &mut Substitutions)
+    -> Result<(), FontError> {
+    let mut reader = Reader::at(data, lookup_list)?;
+    if index >= reader.read_u16()? {
+        return Err(FontError::Malformed("lookup index"));
+    }
+    reader.skip(index as usize * 2)?;
+    let lookup = lookup_list + reader.read_u16()? as usize;
+
+    let mut reader = Reader::at(data, lookup)?;
+    let lookup_type = reader.read_u16()?;
+    reader.skip(2)?;
+    let subtable_count = reader.read_u16()?;
+
+    for _ in 0..subtable_count {
+        let mut subtable = lookup + reader.read_u16()? as usize;
+        let mut subtable_type = lookup_type;
+
+        if lookup_type == EXTENSION_SUBSTITUTION {
+            let mut extension = Reader::at(data, subtable)?;
+            extension.skip(2)?;
+            subtable_type = extension.read_u16()?;
+            subtable += extension.read_u32()? as usize;
+        }
+
+        match subtable_type {
+            SINGLE_SUBSTITUTION => parse_single(data, subtable, substitutions)?,
+            ALTERNATE_SUBSTITUTION => parse_alternate(data, subtable, substitutions)?,
+            _ => {},
+        }
+    }
+
+    Ok(())
+}
+
+fn parse_single(data: &[u8], offset: usize, substitutions: &mut Substitutions)
+    -> Result<(), FontError> {
+    let mut reader = Reader::at(data, offset)?;
+    let format = reader.read_u16()?;
+    let coverage = parse_coverage(data, offset + reader.read_u16()? as usize)?;
+
+    match format {
+        1 => {
+            let delta = reader.read_i16()? as i32;
+            for glyph in coverage {
+                let substitute = (glyph as i32 + delta) as u16 as u32;
+                substitutions.entry(glyph).or_insert_with(|| vec![substitute]);
+            }
+        },
+        2 => {
+            let count = reader.read_u16()? as usize;
+            for glyph in coverage.into_iter().take(count) {
+                let substitute = reader.read_u16()? as u32;
+                substitutions.entry(glyph).or_insert_with(|| vec![substitute]);
+            }
+        },
+        _ => return Err(FontError::Unsupported("single substitution format")),
+    }
+
+    Ok(())
+}
+
+fn parse_alternate(data: &[u8], offset: usize, substitutions: &mut Substitutions)
+    -> Result<(), FontError> {
+    let mut reader = Reader::at(data, offset)?;
+    reader.skip(2)?;
+    let coverage = parse_coverage(data, offset + reader.read_u16()? as usize)?;
+    let count = reader.read_u16()? as usize;
+
+    for glyph in coverage.into_iter().take(count) {
+        let mut set = Reader::at(data, offset + reader.read_u16()? as usize)?;
+        let glyph_count = set.read_u16()?;
+
+        let mut alternates = Vec::with_capacity(glyph_count as usize);
+        for _ in 0..glyph_count {
+            alternates.push(set.read_u16()? as u32);
+        }
+
+        substitutions.entry(glyph).or_insert(alternates);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::test::stix_math;
+
+    #[test]
+    fn it_reads_substitutions() {
+        let font = stix_math();
+        let gsub = font.gsub().expect("No GSUB table");
+
+        let i = font.glyph_index('i' as u32).unwrap();
+        let dotless = gsub.alternates(GsubFeature::Dotless, i);
+        assert_eq!(dotless.len(), 1);
+        assert!(dotless[0] != i);
+
+        let circumflex = font.glyph_index(0x302).unwrap();
+        assert_eq!(gsub.alternates(GsubFeature::FlattenedAccent, circumflex).len(), 1);
+        assert_eq!(gsub.alternates(GsubFeature::ScriptStyle, i).len(), 2);
+
+        let x = font.glyph_index('\u{1D465}' as u32).unwrap();
+        assert!(gsub.alternates(GsubFeature::Dotless, x).is_empty());
+    }
+}
@@ -0,0 +1,383 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+
+use std::collections::HashMap;
+
+use super::FontError;
+use super::reader::Reader;
+
+/// Entries of MathConstants table, in table order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MathConstant {
+    ScriptPercentScaleDown,
+    ScriptScriptPercentScaleDown,
+    DelimitedSubFormulaMinHeight,
+    DisplayOperatorMinHeight,
+    MathLeading,
+    AxisHeight,
+    AccentBaseHeight,
+    FlattenedAccentBaseHeight,
+    SubscriptShiftDown,
+    SubscriptTopMax,
+    SubscriptBaselineDropMin,
+    SuperscriptShiftUp,
+    SuperscriptShiftUpCramped,
+    SuperscriptBottomMin,
+    SuperscriptBaselineDropMax,
+    SubSuperscriptGapMin,
+    SuperscriptBottomMaxWithSubscript,
+    SpaceAfterScript,
+    UpperLimitGapMin,
+    UpperLimitBaselineRiseMin,
+    LowerLimitGapMin,
+    LowerLimitBaselineDropMin,
+    StackTopShiftUp,
+    StackTopDisplayStyleShiftUp,
+    StackBottomShiftDown,
+    StackBottomDisplayStyleShiftDown,
+    StackGapMin,
+    StackDisplayStyleGapMin,
+    StretchStackTopShiftUp,
+    StretchStackBottomShiftDown,
+    StretchStackGapAboveMin,
+    StretchStackGapBelowMin,
+    FractionNumeratorShiftUp,
+    FractionNumeratorDisplayStyleShiftUp,
+    FractionDenominatorShiftDown,
+    FractionDenominatorDisplayStyleShiftDown,
+    FractionNumeratorGapMin,
+    FractionNumDisplayStyleGapMin,
+    FractionRuleThickness,
+    FractionDenominatorGapMin,
+    FractionDenomDisplayStyleGapMin,
+    SkewedFractionHorizontalGap,
+    SkewedFractionVerticalGap,
+    OverbarVerticalGap,
+    OverbarRuleThickness,
+    OverbarExtraAscender,
+    UnderbarVerticalGap,
+    UnderbarRuleThickness,
+    UnderbarExtraDescender,
+    RadicalVerticalGap,
+    RadicalDisplayStyleVerticalGap,
+    RadicalRuleThickness,
+    RadicalExtraAscender,
+    RadicalKernBeforeDegree,
+    RadicalKernAfterDegree,
+    RadicalDegreeBottomRaisePercent,
+}
+
+const NUM_MATH_CONSTANTS: usize = MathConstant::RadicalDegreeBottomRaisePercent as usize + 1;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MathGlyphVariant {
+    glyph_index: u32,
+    advance: u16,
+}
+
+impl MathGlyphVariant {
+    pub fn glyph_index(&self) -> u32 {
+        self.glyph_index
+    }
+
+    /// Advance along the construction direction in design units
+    pub fn advance(&self) -> u16 {
+        self.advance
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MathGlyphPart {
+    glyph_index: u32,
+    start_connector_length: u16,
+    end_connector_length: u16,
+    full_advance: u16,
+    is_extender: bool,
+}
+
+impl MathGlyphPart {
+    pub fn glyph_index(&self) -> u32 {
+        self.glyph_index
+    }
+
+    pub fn start_connector_length(&self) -> u16 {
+        self.start_connector_length
+    }
+
+    pub fn end_connector_length(&self) -> u16 {
+        self.end_connector_length
+    }
+
+    pub fn full_advance(&self) -> u16 {
+        self.full_advance
+    }
+
+    pub fn is_extender(&self) -> bool {
+        self.is_extender
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MathGlyphAssembly {
+    italics_correction: i16,
+    parts: Vec<MathGlyphPart>,
+}
+
+impl MathGlyphAssembly {
+    pub fn italics_correction(&self) -> i16 {
+        self.italics_correction
+    }
+
+    pub fn parts(&self) -> &[MathGlyphPart] {
+        &self.parts
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+struct MathGlyphConstruction {
+    variants: Vec<MathGlyphVariant>,
+    assembly: Option<MathGlyphAssembly>,
+}
+
+/// Parsed OpenType MATH table. Values are in design units.
+pub struct MathTable {
+    constants: Vec<i16>,
+    min_connector_overlap: u16,
+    vertical: HashMap<u32, MathGlyphConstruction>,
+    horizontal: HashMap<u32, MathGlyphConstruction>,
+}
+
+impl MathTable {
+    pub fn parse(data: &[u8]) -> Result<MathTable, FontError> {
+        let mut reader = Reader::new(data);
+        reader.skip(4)?;
+        let constants_offset = reader.read_u16()? as usize;
+        reader.skip(2)?;
+        let variants_offset = reader.read_u16()? as usize;
+
+        let constants = if constants_offset == 0 {
+            vec![0; NUM_MATH_CONSTANTS]
+        } else {
+            parse_constants(data, constants_offset)?
+        };
+
+        let (min_connector_overlap, vertical, horizontal) = if variants_offset == 0 {
+            (0, HashMap::new(), HashMap::new())
+        } else {
+            parse_variants(data, variants_offset)?
+        };
+
+        Ok(MathTable { constants, min_connector_overlap, vertical, horizontal })
+    }
+
+    /// Value of a constant in design units, or in percent for the scale down and raise
+    /// percent constants
+    pub fn constant(&self, constant: MathConstant) -> i16 {
+        self.constants[constant as usize]
+    }
+
+    pub fn min_connector_overlap(&self) -> u16 {
+        self.min_connector_overlap
+    }
+
+    pub fn glyph_variants(&self, glyph_index: u32, vertical: bool) -> &[MathGlyphVariant] {
+        self.construction(glyph_index, vertical)
+            .map(|c| &c.variants[..])
+            .unwrap_or(&[])
+    }
+
+    pub fn glyph_assembly(&self, glyph_index: u32, vertical: bool) -> Option<&MathGlyphAssembly> {
+        self.construction(glyph_index, vertical)
+            .and_then(|c| c.assembly.as_ref())
+    }
+
+    fn construction(&self, glyph_index: u32, vertical: bool) -> Option<&MathGlyphConstruction> {
+        if vertical {
+            self.vertical.get(&glyph_index)
+        } else {
+            self.horizontal.get(&glyph_index)
+        }
+    }
+}
+
+fn parse_constants(data: &[u8], offset: usize) -> Result<Vec<i16>, FontError> {
+    let mut reader = Reader::at(data, offset)?;
+    let mut constants = Vec::with_capacity(NUM_MATH_CONSTANTS);
+
+    // Two percentages and two heights are plain values, the rest except the last one are
+    // MathValueRecords whose device table offsets are skipped
+    for _ in 0..4 {
+        constants.push(reader.read_i16()?);
+    }
+    for _ in 4..NUM_MATH_CONSTANTS-1 {
+        constants.push(reader.read_i16()?);
+        reader.skip(2)?;
+    }
+    constants.push(reader.read_i16()?);
+
+    Ok(constants)
+}
+
+type Constructions = HashMap<u32, MathGlyphConstruction>;
+
+fn parse_variants(data: &[u8], offset: usize) -> Result<(u16, Constructions, Constructions), FontError> {
+    let mut reader = Reader::at(data, offset)?;
+    let min_connector_overlap = reader.read_u16()?;
+    let vertical_coverage = reader.read_u16()? as usize;
+    let horizontal_coverage = reader.read_u16()? as usize;
+    let vertical_count = reader.read_u16()? as usize;
+    let horizontal_count = reader.read_u16()? as usize;
+
+    let mut vertical_offsets = Vec::with_capacity(vertical_count);
+    for _ in 0..vertical_count {
+        vertical_offsets.push(reader.read_u16()? as usize);
+    }
+
+    let mut horizontal_offsets = Vec::with_capacity(horizontal_count);
+    for _ in 0..horizontal_count {
+        horizontal_offsets.push(reader.read_u16()? as usize);
+    }
+
+    let vertical = parse_constructions(data, offset, vertical_coverage, &vertical_offsets)?;
+    let horizontal = parse_constructions(data, offset, horizontal_coverage, &horizontal_offsets)?;
+
+    Ok((min_connector_overlap, vertical, horizontal))
+}
+
+fn parse_constructions(data: &[u8], base: usize, coverage_offset: usize, offsets: &[usize])
+    -> Result<Constructions, FontError> {
+    let mut constructions = HashMap::new();
+    if coverage_offset == 0 {
+        return Ok(constructions);
+    }
+
+    let glyphs = parse_coverage(data, base + coverage_offset)?;
+    for (glyph, &construction_offset) in glyphs.iter().zip(offsets.iter()) {
+        if construction_offset == 0 {
+            continue;
+        }
+
+        let construction = parse_construction(data, base + construction_offset)?;
+        constructions.insert(*glyph, construction);
+    }
+
+    Ok(constructions)
+}
+
+fn parse_construction(data: &[u8], offset: usize) -> Result<MathGlyphConstruction, FontError> {
+    let mut reader = Reader::at(data, offset)?;
+    let assembly_offset = reader.read_u16()? as usize;
+    let variant_count = reader.read_u16()?;
+
+    let mut variants = Vec::with_capacity(variant_count as usize);
+    for _ in 0..variant_count {
+        let glyph_index = reader.read_u16()? as u32;
+        let advance = reader.read_u16()?;
+        variants.push(MathGlyphVariant { glyph_index, advance });
+    }
+
+    let assembly = if assembly_offset == 0 {
+        None
+    } else {
+        Some(parse_assembly(data, offset + assembly_offset)?)
+    };
+
+    Ok(MathGlyphConstruction { variants, assembly })
+}
+
+fn parse_assembly(data: &[u8], offset: usize) -> Result<MathGlyphAssembly, FontError> {
+    let mut reader = Reader::at(data, offset)?;
+    let italics_correction = reader.read_i16()?;
+    reader.skip(2)?;
+    let part_count = reader.read_u16()?;
+
+    let mut parts = Vec::with_capacity(part_count as usize);
+    for _ in 0..part_count {
+        parts.push(MathGlyphPart {
+            glyph_index: reader.read_u16()? as u32,
+            start_connector_length: reader.read_u16()?,
+            end_connector_length: reader.read_u16()?,
+            full_advance: reader.read_u16()?,
+            is_extender: reader.read_u16()? & 0x0001 != 0,
+        });
+    }
+
+    Ok(MathGlyphAssembly { italics_correction, parts })
+}
+
+/// Glyphs of a coverage table in coverage index order
+pub(super) fn parse_coverage(data: &[u8], offset: usize) -> Result<Vec<u32>, FontError> {
+    let mut reader = Reader::at(data, offset)?;
+    let format = reader.read_u16()?;
+    let count = reader.read_u16()?;
+    let mut glyphs = Vec::new();
+
+    match format {
+        1 => for _ in 0..count {
+            glyphs.push(reader.read_u16()? as u32);
+        },
+        2 => for _ in 0..count {
+            let start = reader.read_u16()? as u32;
+            let end = reader.read_u16()? as u32;
+            reader.skip(2)?;
+            for glyph in start..end+1 {
+                glyphs.push(glyph);
+            }
+        },
+        _ => return Err(FontError::Unsupported("coverage format")),
+    }
+
+    Ok(glyphs)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::test::stix_math;
+
+    #[test]
+    fn it_reads_math_constants() {
+        let font = stix_math();
+        let math = font.math().expect("No MATH table");
+
+        assert!(math.constant(MathConstant::ScriptPercentScaleDown) > 50);
+        assert!(math.constant(MathConstant::ScriptScriptPercentScaleDown) <
+            math.constant(MathConstant::ScriptPercentScaleDown));
+        assert!(math.constant(MathConstant::AxisHeight) > 200);
+        assert!(math.constant(MathConstant::FractionRuleThickness) > 0);
+        assert!(math.constant(MathConstant::RadicalDegreeBottomRaisePercent) > 0);
+        assert!(math.min_connector_overlap() > 0);
+    }
+
+    #[test]
+    fn it_reads_glyph_constructions() {
+        let font = stix_math();
+        let math = font.math().unwrap();
+
+        let paren = font.glyph_index('(' as u32).unwrap();
+        let variants = math.glyph_variants(paren, true);
+        assert!(variants.len() > 1);
+        assert!(variants.windows(2).all(|v| v[0].advance() <= v[1].advance()));
+
+        let assembly = math.glyph_assembly(paren, true).expect("No assembly for (");
+        assert!(assembly.parts().iter().any(|p| p.is_extender()));
+        assert!(math.glyph_variants(paren, false).is_empty());
+
+        let arrow = font.glyph_index(0x2192).unwrap();
+        assert!(!math.glyph_variants(arrow, false).is_empty());
+    }
+}
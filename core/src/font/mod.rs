@@ -0,0 +1,512 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+
+mod reader;
+mod cmap;
+mod cff;
+mod glyf;
+mod fallback;
+mod gsub;                       pub use self::gsub::*;
+mod math;                       pub use self::math::*;
+mod outline;                    pub use self::outline::*;
+mod ruler;                      pub use self::ruler::*;
+mod stack;                      pub use self::stack::*;
+
+use std::fmt;
+use std::io;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use self::reader::Reader;
+use self::cmap::Cmap;
+use self::cff::Cff;
+use self::glyf::Glyf;
+use ::paint::TypeFace;
+
+#[derive(Debug)]
+pub enum FontError {
+    Io(io::Error),
+    Truncated,
+    MissingTable(&'static str),
+    Unsupported(&'static str),
+    Malformed(&'static str),
+}
+
+impl fmt::Display for FontError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FontError::Io(ref err) => write!(f, "cannot read font: {}", err),
+            FontError::Truncated => write!(f, "font data is truncated"),
+            FontError::MissingTable(tag) => write!(f, "font has no {} table", tag),
+            FontError::Unsupported(what) => write!(f, "unsupported {}", what),
+            FontError::Malformed(what) => write!(f, "malformed {}", what),
+        }
+    }
+}
+
+impl From<io::Error> for FontError {
+    fn from(err: io::Error) -> FontError {
+        FontError::Io(err)
+    }
+}
+
+enum Outlines {
+    Cff(Cff),
+    Glyf(Glyf),
+}
+
+/// OpenType or TrueType font parsed in memory. All metrics are in font design units.
+pub struct OpenTypeFont {
+    data: Vec<u8>,
+    units_per_em: u16,
+    ascender: i16,
+    descender: i16,
+    line_gap: i16,
+    x_height: i16,
+    cap_height: i16,
+    num_glyphs: u16,
+    advances: Vec<u16>,
+    cmap: Cmap,
+    outlines: Outlines,
+    math: Option<MathTable>,
+    gsub: Option<GsubTable>,
+    family_name: String,
+    subfamily_name: String,
+}
+
+const NAME_FONT_FAMILY: u16 = 1;
+const NAME_FONT_SUBFAMILY: u16 = 2;
+
+// OS/2 fsSelection bit asking for typographic metrics over hhea ones
+const USE_TYPO_METRICS: u16 = 1 << 7;
+
+struct Os2 {
+    use_typo_metrics: bool,
+    typo_ascender: i16,
+    typo_descender: i16,
+    typo_line_gap: i16,
+    x_height: Option<i16>,
+    cap_height: Option<i16>,
+}
+
+struct TableRecord {
+    tag: [u8; 4],
+    offset: usize,
+    length: usize,
+}
+
+impl OpenTypeFont {
+    pub fn from_file(path: &Path) -> Result<OpenTypeFont, FontError> {
+        let mut data = Vec::new();
+        File::open(path)?.read_to_end(&mut data)?;
+        OpenTypeFont::from_bytes(data)
+    }
+
+    pub fn from_bytes(data: Vec<u8>) -> Result<OpenTypeFont, FontError> {
+        let tables = read_table_directory(&data)?;
+
+        let head = table(&data, &tables, b"head")?;
+        let mut reader = Reader::at(head, 18)?;
+        let units_per_em = reader.read_u16()?;
+        if units_per_em == 0 {
+            return Err(FontError::Malformed("head table"));
+        }
+        let mut reader = Reader::at(head, 50)?;
+        let index_to_loc_format = reader.read_i16()?;
+
+        let maxp = table(&data, &tables, b"maxp")?;
+        let num_glyphs = Reader::at(maxp, 4)?.read_u16()?;
+
+        let hhea = table(&data, &tables, b"hhea")?;
+        let mut reader = Reader::at(hhea, 4)?;
+        let mut ascender = reader.read_i16()?;
+        let mut descender = reader.read_i16()?;
+        let mut line_gap = reader.read_i16()?;
+        let num_h_metrics = Reader::at(hhea, 34)?.read_u16()?;
+
+        let os2 = match table(&data, &tables, b"OS/2") {
+            Ok(os2) => Some(read_os2(os2)?),
+            Err(_) => None,
+        };
+        if let Some(Os2 { use_typo_metrics: true, typo_ascender, typo_descender, typo_line_gap,
+                          .. }) = os2 {
+            ascender = typo_ascender;
+            descender = typo_descender;
+            line_gap = typo_line_gap;
+        }
+
+        let advances = read_advances(table(&data, &tables, b"hmtx")?, num_h_metrics, num_glyphs)?;
+        let cmap = Cmap::parse(table(&data, &tables, b"cmap")?)?;
+
+        let outlines = if let Ok(cff) = table(&data, &tables, b"CFF ") {
+            Outlines::Cff(Cff::parse(cff)?)
+        } else {
+            let loca = table(&data, &tables, b"loca")?;
+            let glyf = find_table(&tables, b"glyf").ok_or(FontError::MissingTable("glyf"))?;
+            Outlines::Glyf(Glyf::parse(loca, index_to_loc_format, num_glyphs, glyf.offset,
+                                       glyf.length)?)
+        };
+
+        let math = match table(&data, &tables, b"MATH") {
+            Ok(math) => Some(MathTable::parse(math)?),
+            Err(_) => None,
+        };
+
+        let gsub = match table(&data, &tables, b"GSUB") {
+            Ok(gsub) => Some(GsubTable::parse(gsub)?),
+            Err(_) => None,
+        };
+
+        let (family_name, subfamily_name) = match table(&data, &tables, b"name") {
+            Ok(name) => (read_name(name, NAME_FONT_FAMILY)?, read_name(name, NAME_FONT_SUBFAMILY)?),
+            Err(_) => (String::new(), String::new()),
+        };
+
+        let mut font = OpenTypeFont {
+            data,
+            units_per_em,
+            ascender,
+            descender,
+            line_gap,
+            x_height: 0,
+            cap_height: 0,
+            num_glyphs,
+            advances,
+            cmap,
+            outlines,
+            math,
+            gsub,
+            family_name,
+            subfamily_name,
+        };
+
+        // Versions of OS/2 before 2 have no x and cap height, which are measured from glyphs
+        font.x_height = os2.as_ref().and_then(|os2| os2.x_height)
+            .unwrap_or_else(|| font.ink_top('x' as u32));
+        font.cap_height = os2.as_ref().and_then(|os2| os2.cap_height)
+            .unwrap_or_else(|| font.ink_top('H' as u32));
+
+        Ok(font)
+    }
+
+    pub fn units_per_em(&self) -> u16 {
+        self.units_per_em
+    }
+
+    /// Ascender from hhea, or from OS/2 when the font asks for typographic metrics
+    pub fn ascender(&self) -> i16 {
+        self.ascender
+    }
+
+    /// Distance below baseline, negative for most fonts
+    pub fn descender(&self) -> i16 {
+        self.descender
+    }
+
+    pub fn line_gap(&self) -> i16 {
+        self.line_gap
+    }
+
+    pub fn x_height(&self) -> i16 {
+        self.x_height
+    }
+
+    pub fn cap_height(&self) -> i16 {
+        self.cap_height
+    }
+
+    pub fn num_glyphs(&self) -> u16 {
+        self.num_glyphs
+    }
+
+    pub fn glyph_index(&self, unicode: u32) -> Option<u32> {
+        self.cmap.glyph_index(unicode)
+    }
+
+    /// Code point mapped to glyph by cmap, none for glyphs only reachable through layout tables
+    pub fn glyph_unicode(&self, glyph_index: u32) -> Option<u32> {
+        self.cmap.unicode(glyph_index)
+    }
+
+    pub fn advance_width(&self, glyph_index: u32) -> u16 {
+        self.advances.get(glyph_index as usize).cloned().unwrap_or(0)
+    }
+
+    /// Glyph outline in font units with y axis pointing up. Glyphs which cannot be decoded
+    /// yield an empty outline.
+    pub fn glyph_outline(&self, glyph_index: u32) -> Outline {
+        let result = match self.outlines {
+            Outlines::Cff(ref cff) => cff.outline(glyph_index),
+            Outlines::Glyf(ref glyf) => glyf.outline(&self.data, glyph_index),
+        };
+
+        result.unwrap_or_else(|_| Outline::new())
+    }
+
+    pub fn math(&self) -> Option<&MathTable> {
+        self.math.as_ref()
+    }
+
+    pub fn gsub(&self) -> Option<&GsubTable> {
+        self.gsub.as_ref()
+    }
+
+    pub fn family_name(&self) -> &str {
+        &self.family_name
+    }
+
+    pub fn subfamily_name(&self) -> &str {
+        &self.subfamily_name
+    }
+
+    pub fn typeface(&self) -> TypeFace {
+        TypeFace::new(self.family_name.clone(), self.subfamily_name.clone())
+    }
+
+    /// Top of the outline of the glyph for a code point, zero if it has none
+    fn ink_top(&self, unicode: u32) -> i16 {
+        self.glyph_index(unicode)
+            .and_then(|glyph| self.glyph_outline(glyph).bounds())
+            .map(|bounds| bounds.y_max() as i16)
+            .unwrap_or(0)
+    }
+}
+
+fn read_table_directory(data: &[u8]) -> Result<Vec<TableRecord>, FontError> {
+    let mut reader = Reader::new(data);
+    let version = reader.read_u32()?;
+
+    match version {
+        0x00010000 | 0x4F54544F | 0x74727565 => {},
+        0x74746366 => return Err(FontError::Unsupported("font collection")),
+        _ => return Err(FontError::Malformed("table directory")),
+    }
+
+    let num_tables = reader.read_u16()?;
+    reader.skip(6)?;
+
+    let mut tables = Vec::with_capacity(num_tables as usize);
+    for _ in 0..num_tables {
+        let tag = reader.read_bytes(4)?;
+        reader.skip(4)?;
+        let offset = reader.read_u32()? as usize;
+        let length = reader.read_u32()? as usize;
+
+        if offset.checked_add(length).map(|end| end > data.len()).unwrap_or(true) {
+            return Err(FontError::Truncated);
+        }
+
+        tables.push(TableRecord { tag: [tag[0], tag[1], tag[2], tag[3]], offset, length });
+    }
+
+    Ok(tables)
+}
+
+fn find_table<'a>(tables: &'a [TableRecord], tag: &[u8; 4]) -> Option<&'a TableRecord> {
+    tables.iter().find(|t| &t.tag == tag)
+}
+
+fn table<'a>(data: &'a [u8], tables: &[TableRecord], tag: &'static [u8; 4])
+    -> Result<&'a [u8], FontError> {
+    let record = find_table(tables, tag)
+        .ok_or_else(|| FontError::MissingTable(::std::str::from_utf8(tag).unwrap_or("")))?;
+    Ok(&data[record.offset..record.offset+record.length])
+}
+
+fn read_advances(hmtx: &[u8], num_h_metrics: u16, num_glyphs: u16) -> Result<Vec<u16>, FontError> {
+    if num_h_metrics == 0 {
+        return Err(FontError::Malformed("hhea table"));
+    }
+
+    let mut reader = Reader::new(hmtx);
+    let mut advances = Vec::with_capacity(num_glyphs as usize);
+    for _ in 0..num_h_metrics {
+        advances.push(reader.read_u16()?);
+        reader.skip(2)?;
+    }
+
+    let last = *advances.last().unwrap();
+    while advances.len() < num_glyphs as usize {
+        advances.push(last);
+    }
+
+    Ok(advances)
+}
+
+fn read_os2(os2: &[u8]) -> Result<Os2, FontError> {
+    let version = Reader::new(os2).read_u16()?;
+    let fs_selection = Reader::at(os2, 62)?.read_u16()?;
+
+    let mut reader = Reader::at(os2, 68)?;
+    let typo_ascender = reader.read_i16()?;
+    let typo_descender = reader.read_i16()?;
+    let typo_line_gap = reader.read_i16()?;
+
+    let (x_height, cap_height) = if version >= 2 {
+        let mut reader = Reader::at(os2, 86)?;
+        (Some(reader.read_i16()?), Some(reader.read_i16()?))
+    } else {
+        (None, None)
+    };
+
+    Ok(Os2 {
+        use_typo_metrics: fs_selection & USE_TYPO_METRICS != 0,
+        typo_ascender,
+        typo_descender,
+        typo_line_gap,
+        x_height,
+        cap_height,
+    })
+}
+
+/// Reads a name record, preferring Windows unicode records over Macintosh roman ones
+fn read_name(name: &[u8], name_id: u16) -> Result<String, FontError> {
+    let mut reader = Reader::new(name);
+    reader.skip(2)?;
+    let count = reader.read_u16()?;
+    let storage = reader.read_u16()? as usize;
+
+    let mut mac_name = None;
+    for _ in 0..count {
+        let platform_id = reader.read_u16()?;
+        reader.skip(4)?;
+        let id = reader.read_u16()?;
+        let length = reader.read_u16()? as usize;
+        let offset = reader.read_u16()? as usize;
+
+        if id != name_id {
+            continue;
+        }
+
+        let bytes = Reader::at(name, storage + offset)?.read_bytes(length)?;
+        match platform_id {
+            0 | 3 => {
+                let units: Vec<u16> = bytes.chunks(2)
+                    .filter(|c| c.len() == 2)
+                    .map(|c| ((c[0] as u16) << 8) | c[1] as u16)
+                    .collect();
+                return Ok(String::from_utf16_lossy(&units));
+            },
+            1 if mac_name.is_none() => {
+                mac_name = Some(bytes.iter().map(|&b| b as char).collect());
+            },
+            _ => {},
+        }
+    }
+
+    Ok(mac_name.unwrap_or_default())
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use std::path::Path;
+    use super::*;
+
+    const STIX_MATH_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/test/assets/STIX2Math.otf");
+
+    pub fn stix_math() -> OpenTypeFont {
+        OpenTypeFont::from_file(Path::new(STIX_MATH_PATH)).expect("Cannot load STIX2Math.otf")
+    }
+
+    /// STIX with its MATH table hidden, standing in for a text font
+    pub fn stix_without_math() -> OpenTypeFont {
+        let mut data = Vec::new();
+        File::open(STIX_MATH_PATH).and_then(|mut file| file.read_to_end(&mut data))
+            .expect("Cannot load STIX2Math.otf");
+
+        let num_tables = ((data[4] as usize) << 8) | data[5] as usize;
+        let record = (0..num_tables).map(|i| 12 + i * 16)
+            .find(|&offset| &data[offset..offset+4] == b"MATH")
+            .expect("No MATH table in STIX2Math.otf");
+        data[record] = b'X';
+
+        OpenTypeFont::from_bytes(data).unwrap()
+    }
+
+    /// STIX without MATH table renamed to "STIX Two Text", a text face distinct from the math
+    /// face but with the same coverage
+    pub fn stix_text() -> OpenTypeFont {
+        stix_renamed("STIX Two Text")
+    }
+
+    /// STIX without MATH table with family renamed to `family`, as long as the original name
+    pub fn stix_renamed(family: &str) -> OpenTypeFont {
+        let utf16_be = |text: &str| -> Vec<u8> {
+            text.encode_utf16().flat_map(|c| vec![(c >> 8) as u8, c as u8]).collect()
+        };
+        let from = utf16_be("STIX Two Math");
+        let to = utf16_be(family);
+        assert_eq!(from.len(), to.len());
+
+        let mut data = stix_without_math().data;
+
+        let mut offset = 0;
+        while let Some(position) = data[offset..].windows(from.len()).position(|w| w == &from[..]) {
+            let start = offset + position;
+            data[start..start+to.len()].copy_from_slice(&to);
+            offset = start + to.len();
+        }
+
+        OpenTypeFont::from_bytes(data).unwrap()
+    }
+
+    #[test]
+    fn it_reads_metrics() {
+        let font = stix_math();
+
+        assert_eq!(font.units_per_em(), 1000);
+        assert_eq!(font.family_name(), "STIX Two Math");
+        assert!(font.ascender() > 0);
+        assert!(font.descender() < 0);
+        assert_eq!((font.x_height(), font.cap_height()), (473, 657));
+
+        let x = font.glyph_index('x' as u32).expect("No glyph for x");
+        assert!(x > 0);
+        assert!(font.advance_width(x) > 0);
+        assert!(font.glyph_index('\u{1D465}' as u32).is_some());
+        assert_eq!(font.glyph_index(0x10FFFF), None);
+        assert_eq!(font.glyph_unicode(x), Some('x' as u32));
+    }
+
+    #[test]
+    fn it_decodes_cff_outlines() {
+        let font = stix_math();
+
+        let x = font.glyph_index('x' as u32).unwrap();
+        let bounds = font.glyph_outline(x).bounds().expect("Empty outline for x");
+        assert_eq!((bounds.x_min(), bounds.y_min(), bounds.x_max(), bounds.y_max()),
+                   (-2., 0., 482., 473.));
+        assert_eq!(font.advance_width(x), 479);
+
+        let space = font.glyph_index(' ' as u32).unwrap();
+        assert!(font.glyph_outline(space).bounds().is_none());
+    }
+
+    #[test]
+    fn it_loads_fonts_without_math() {
+        let font = stix_without_math();
+        assert!(font.math().is_none());
+        assert!(stix_math().math().is_some());
+    }
+
+    #[test]
+    fn it_rejects_invalid_data() {
+        assert!(OpenTypeFont::from_bytes(vec![]).is_err());
+        assert!(OpenTypeFont::from_bytes(vec![0, 1, 0, 0, 0, 10]).is_err());
+    }
+}
@@ -0,0 +1,168 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathCommand {
+    MoveTo(f32, f32),
+    LineTo(f32, f32),
+    QuadTo(f32, f32, f32, f32),
+    CurveTo(f32, f32, f32, f32, f32, f32),
+    Close,
+}
+
+/// Glyph outline made of closed contours
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Outline {
+    commands: Vec<PathCommand>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutlineBounds {
+    x_min: f32,
+    y_min: f32,
+    x_max: f32,
+    y_max: f32,
+}
+
+impl OutlineBounds {
+    pub fn x_min(&self) -> f32 {
+        self.x_min
+    }
+
+    pub fn y_min(&self) -> f32 {
+        self.y_min
+    }
+
+    pub fn x_max(&self) -> f32 {
+        self.x_max
+    }
+
+    pub fn y_max(&self) -> f32 {
+        self.y_max
+    }
+
+    pub fn width(&self) -> f32 {
+        self.x_max - self.x_min
+    }
+
+    pub fn height(&self) -> f32 {
+        self.y_max - self.y_min
+    }
+}
+
+impl Outline {
+    pub fn new() -> Outline {
+        Outline::default()
+    }
+
+    pub fn commands(&self) -> &[PathCommand] {
+        &self.commands
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    pub fn move_to(&mut self, x: f32, y: f32) {
+        self.commands.push(PathCommand::MoveTo(x, y));
+    }
+
+    pub fn line_to(&mut self, x: f32, y: f32) {
+        self.commands.push(PathCommand::LineTo(x, y));
+    }
+
+    pub fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        self.commands.push(PathCommand::QuadTo(x1, y1, x, y));
+    }
+
+    pub fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.commands.push(PathCommand::CurveTo(x1, y1, x2, y2, x, y));
+    }
+
+    pub fn close(&mut self) {
+        self.commands.push(PathCommand::Close);
+    }
+
+    /// Appends all contours of `other` after mapping each point through `transform`
+    pub fn extend_transformed<F>(&mut self, other: &Outline, transform: F)
+        where F: Fn(f32, f32) -> (f32, f32) {
+        for command in other.commands.iter() {
+            let command = match *command {
+                PathCommand::MoveTo(x, y) => {
+                    let (x, y) = transform(x, y);
+                    PathCommand::MoveTo(x, y)
+                },
+                PathCommand::LineTo(x, y) => {
+                    let (x, y) = transform(x, y);
+                    PathCommand::LineTo(x, y)
+                },
+                PathCommand::QuadTo(x1, y1, x, y) => {
+                    let (x1, y1) = transform(x1, y1);
+                    let (x, y) = transform(x, y);
+                    PathCommand::QuadTo(x1, y1, x, y)
+                },
+                PathCommand::CurveTo(x1, y1, x2, y2, x, y) => {
+                    let (x1, y1) = transform(x1, y1);
+                    let (x2, y2) = transform(x2, y2);
+                    let (x, y) = transform(x, y);
+                    PathCommand::CurveTo(x1, y1, x2, y2, x, y)
+                },
+                PathCommand::Close => PathCommand::Close,
+            };
+
+            self.commands.push(command);
+        }
+    }
+
+    /// Bounds of all on and off curve points. Since control points of font outlines rarely lie
+    /// outside the ink, this is a close approximation of the ink bounds.
+    pub fn bounds(&self) -> Option<OutlineBounds> {
+        let mut bounds: Option<OutlineBounds> = None;
+
+        {
+            let mut add = |x: f32, y: f32| {
+                bounds = Some(match bounds.take() {
+                    Some(b) => OutlineBounds {
+                        x_min: b.x_min.min(x),
+                        y_min: b.y_min.min(y),
+                        x_max: b.x_max.max(x),
+                        y_max: b.y_max.max(y),
+                    },
+                    None => OutlineBounds { x_min: x, y_min: y, x_max: x, y_max: y },
+                });
+            };
+
+            for command in self.commands.iter() {
+                match *command {
+                    PathCommand::MoveTo(x, y) | PathCommand::LineTo(x, y) => add(x, y),
+                    PathCommand::QuadTo(x1, y1, x, y) => {
+                        add(x1, y1);
+                        add(x, y);
+                    },
+                    PathCommand::CurveTo(x1, y1, x2, y2, x, y) => {
+                        add(x1, y1);
+                        add(x2, y2);
+                        add(x, y);
+                    },
+                    PathCommand::Close => {},
+                }
+            }
+        }
+
+        bounds
+    }
+}
@@ -0,0 +1,114 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+
+use super::FontError;
+
+/// Big endian cursor over font data. Every read is bounds checked.
+#[derive(Clone)]
+pub struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Reader<'a> {
+        Reader { data, pos: 0 }
+    }
+
+    pub fn at(data: &'a [u8], pos: usize) -> Result<Reader<'a>, FontError> {
+        if pos > data.len() {
+            return Err(FontError::Truncated);
+        }
+
+        Ok(Reader { data, pos })
+    }
+
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    pub fn seek(&mut self, pos: usize) -> Result<(), FontError> {
+        if pos > self.data.len() {
+            return Err(FontError::Truncated);
+        }
+
+        self.pos = pos;
+        Ok(())
+    }
+
+    pub fn skip(&mut self, len: usize) -> Result<(), FontError> {
+        let pos = self.pos + len;
+        self.seek(pos)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    pub fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], FontError> {
+        if self.pos + len > self.data.len() {
+            return Err(FontError::Truncated);
+        }
+
+        let bytes = &self.data[self.pos..self.pos+len];
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, FontError> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    pub fn read_i8(&mut self) -> Result<i8, FontError> {
+        Ok(self.read_u8()? as i8)
+    }
+
+    pub fn read_u16(&mut self) -> Result<u16, FontError> {
+        let bytes = self.read_bytes(2)?;
+        Ok(((bytes[0] as u16) << 8) | bytes[1] as u16)
+    }
+
+    pub fn read_i16(&mut self) -> Result<i16, FontError> {
+        Ok(self.read_u16()? as i16)
+    }
+
+    pub fn read_u24(&mut self) -> Result<u32, FontError> {
+        let bytes = self.read_bytes(3)?;
+        Ok(((bytes[0] as u32) << 16) | ((bytes[1] as u32) << 8) | bytes[2] as u32)
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32, FontError> {
+        let bytes = self.read_bytes(4)?;
+        Ok(((bytes[0] as u32) << 24) | ((bytes[1] as u32) << 16) | ((bytes[2] as u32) << 8) |
+            bytes[3] as u32)
+    }
+
+    pub fn read_i32(&mut self) -> Result<i32, FontError> {
+        Ok(self.read_u32()? as i32)
+    }
+
+    /// Reads an unsigned integer of `size` bytes, as used by CFF offsets
+    pub fn read_offset(&mut self, size: u8) -> Result<u32, FontError> {
+        match size {
+            1 => Ok(self.read_u8()? as u32),
+            2 => Ok(self.read_u16()? as u32),
+            3 => self.read_u24(),
+            4 => self.read_u32(),
+            _ => Err(FontError::Malformed("offset size")),
+        }
+    }
+}
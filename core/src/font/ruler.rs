@@ -0,0 +1,538 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use super::{OpenTypeFont, MathConstant, GsubFeature};
+use super::fallback::synthesized_constant;
+use ::props::Directionality;
+use ::paint::{
+    MathRuler, TextRuler, Rect, GlyphConstructionDirection, GlyphVariant, GlyphAssembly, TypeFace,
+    GlyphAssemblyPart, GlyphRun, ShapedGlyph, Ink
+};
+
+/// Text and math ruler reading metrics of an `OpenTypeFont` at any size, so that measurements
+/// are identical across platforms. Math constants of fonts without a MATH table are
+/// synthesized from their x-height, cap height and em size, and they have no glyph variants.
+pub struct OpenTypeRuler {
+    font: Rc<OpenTypeFont>,
+    typeface: TypeFace,
+    size: Cell<f32>,
+}
+
+impl OpenTypeRuler {
+    pub fn new(font: Rc<OpenTypeFont>) -> OpenTypeRuler {
+        OpenTypeRuler {
+            typeface: font.typeface(),
+            font,
+            size: Cell::new(0.),
+        }
+    }
+
+    /// Font size in pixels used by all following measurements
+    pub fn set_size(&self, size: f32) {
+        self.size.set(size);
+    }
+
+    fn scale(&self, design_units: f32) -> f32 {
+        design_units * self.size.get() / self.font.units_per_em() as f32
+    }
+
+    fn constant(&self, constant: MathConstant) -> i16 {
+        match self.font.math() {
+            Some(math) => math.constant(constant),
+            None => synthesized_constant(&self.font, constant),
+        }
+    }
+
+    fn advance(&self, glyph_index: u32) -> f32 {
+        self.scale(self.font.advance_width(glyph_index) as f32)
+    }
+
+    fn substitute(&self, feature: GsubFeature, glyph_index: u32, alternate: usize) -> Option<u32> {
+        // Fonts with fewer alternates than asked for use their last one
+        self.font.gsub()
+            .map(|gsub| gsub.alternates(feature, glyph_index))
+            .and_then(|alternates| alternates.get(alternate).or_else(|| alternates.last()))
+            .cloned()
+    }
+}
+
+impl TextRuler for OpenTypeRuler {
+    fn font_size(&self) -> f32 {
+        self.size.get()
+    }
+
+    fn typeface(&self) -> &TypeFace {
+        &self.typeface
+    }
+
+    fn measure(&self, text: &str, dir: &Directionality) -> Rect {
+        Rect::new(self.shape(text, dir, None).width(), self.ascent() - self.descent())
+    }
+
+    fn measure_char(&self, unicode: u32, _: &Directionality) -> Rect {
+        let width = self.advance(self.font.glyph_index(unicode).unwrap_or(0));
+        Rect::new(width, self.ascent() - self.descent())
+    }
+
+    fn measure_glyph(&self, glyph_index: u32, _: &Directionality) -> Rect {
+        let ink_height = self.glyph_ink(glyph_index)
+            .map(|ink| ink.height())
+            .unwrap_or(0.);
+        Rect::new(self.advance(glyph_index), ink_height.max(self.ascent() - self.descent()))
+    }
+
+    // Without GSUB and GPOS tables every character is drawn with its nominal glyph
+    fn shape(&self, text: &str, dir: &Directionality, _: Option<&str>) -> GlyphRun {
+        let mut glyphs: Vec<ShapedGlyph> = text.char_indices()
+            .map(|(cluster, c)| {
+                let glyph_index = self.font.glyph_index(c as u32).unwrap_or(0);
+                ShapedGlyph::new(glyph_index, self.advance(glyph_index), 0., 0., cluster)
+            })
+            .collect();
+        if *dir == Directionality::RTL {
+            glyphs.reverse();
+        }
+
+        GlyphRun::new(text.to_string(), self.typeface.clone(), self.size.get(), dir.clone(), glyphs)
+    }
+
+    fn dotless_glyph(&self, glyph_index: u32) -> Option<u32> {
+        self.substitute(GsubFeature::Dotless, glyph_index, 0)
+    }
+
+    fn glyph_ink(&self, glyph_index: u32) -> Option<Ink> {
+        self.font.glyph_outline(glyph_index).bounds().map(|b| Ink::new(
+            self.scale(b.x_min()), self.scale(b.x_max()),
+            self.scale(b.y_max()), self.scale(b.y_min())))
+    }
+
+    fn ascent(&self) -> f32 {
+        self.scale(self.font.ascender() as f32)
+    }
+
+    fn descent(&self) -> f32 {
+        self.scale(self.font.descender() as f32)
+    }
+}
+
+impl MathRuler for OpenTypeRuler {
+    fn script_percent_scale_down(&self) -> f32 {
+        self.constant(MathConstant::ScriptPercentScaleDown) as f32
+    }
+
+    fn script_script_percent_scale_down(&self) -> f32 {
+        self.constant(MathConstant::ScriptScriptPercentScaleDown) as f32
+    }
+
+    fn delimited_sub_formula_min_height(&self) -> f32 {
+        self.scale(self.constant(MathConstant::DelimitedSubFormulaMinHeight) as f32)
+    }
+
+    fn display_operator_min_height(&self) -> f32 {
+        self.scale(self.constant(MathConstant::DisplayOperatorMinHeight) as f32)
+    }
+
+    fn math_leading(&self) -> f32 {
+        self.scale(self.constant(MathConstant::MathLeading) as f32)
+    }
+
+    fn axis_height(&self) -> f32 {
+        self.scale(self.constant(MathConstant::AxisHeight) as f32)
+    }
+
+    fn accent_base_height(&self) -> f32 {
+        self.scale(self.constant(MathConstant::AccentBaseHeight) as f32)
+    }
+
+    fn flattened_accent_base_height(&self) -> f32 {
+        self.scale(self.constant(MathConstant::FlattenedAccentBaseHeight) as f32)
+    }
+
+    fn subscript_shift_down(&self) -> f32 {
+        self.scale(self.constant(MathConstant::SubscriptShiftDown) as f32)
+    }
+
+    fn subscript_top_max(&self) -> f32 {
+        self.scale(self.constant(MathConstant::SubscriptTopMax) as f32)
+    }
+
+    fn subscript_baseline_drop_min(&self) -> f32 {
+        self.scale(self.constant(MathConstant::SubscriptBaselineDropMin) as f32)
+    }
+
+    fn superscript_shift_up(&self) -> f32 {
+        self.scale(self.constant(MathConstant::SuperscriptShiftUp) as f32)
+    }
+
+    fn superscript_shift_up_cramped(&self) -> f32 {
+        self.scale(self.constant(MathConstant::SuperscriptShiftUpCramped) as f32)
+    }
+
+    fn superscript_bottom_min(&self) -> f32 {
+        self.scale(self.constant(MathConstant::SuperscriptBottomMin) as f32)
+    }
+
+    fn superscript_baseline_drop_max(&self) -> f32 {
+        self.scale(self.constant(MathConstant::SuperscriptBaselineDropMax) as f32)
+    }
+
+    fn sub_superscript_gap_min(&self) -> f32 {
+        self.scale(self.constant(MathConstant::SubSuperscriptGapMin) as f32)
+    }
+
+    fn superscript_bottom_max_with_subscript(&self) -> f32 {
+        self.scale(self.constant(MathConstant::SuperscriptBottomMaxWithSubscript) as f32)
+    }
+
+    fn space_after_script(&self) -> f32 {
+        self.scale(self.constant(MathConstant::SpaceAfterScript) as f32)
+    }
+
+    fn upper_limit_gap_min(&self) -> f32 {
+        self.scale(self.constant(MathConstant::UpperLimitGapMin) as f32)
+    }
+
+    fn upper_limit_baseline_rise_min(&self) -> f32 {
+        self.scale(self.constant(MathConstant::UpperLimitBaselineRiseMin) as f32)
+    }
+
+    fn lower_limit_gap_min(&self) -> f32 {
+        self.scale(self.constant(MathConstant::LowerLimitGapMin) as f32)
+    }
+
+    fn lower_limit_baseline_drop_min(&self) -> f32 {
+        self.scale(self.constant(MathConstant::LowerLimitBaselineDropMin) as f32)
+    }
+
+    fn stack_top_shift_up(&self) -> f32 {
+        self.scale(self.constant(MathConstant::StackTopShiftUp) as f32)
+    }
+
+    fn stack_top_display_style_shift_up(&self) -> f32 {
+        self.scale(self.constant(MathConstant::StackTopDisplayStyleShiftUp) as f32)
+    }
+
+    fn stack_bottom_shift_down(&self) -> f32 {
+        self.scale(self.constant(MathConstant::StackBottomShiftDown) as f32)
+    }
+
+    fn stack_bottom_display_style_shift_down(&self) -> f32 {
+        self.scale(self.constant(MathConstant::StackBottomDisplayStyleShiftDown) as f32)
+    }
+
+    fn stack_gap_min(&self) -> f32 {
+        self.scale(self.constant(MathConstant::StackGapMin) as f32)
+    }
+
+    fn stack_display_style_gap_min(&self) -> f32 {
+        self.scale(self.constant(MathConstant::StackDisplayStyleGapMin) as f32)
+    }
+
+    fn stretch_stack_top_shift_up(&self) -> f32 {
+        self.scale(self.constant(MathConstant::StretchStackTopShiftUp) as f32)
+    }
+
+    fn stretch_stack_bottom_shift_down(&self) -> f32 {
+        self.scale(self.constant(MathConstant::StretchStackBottomShiftDown) as f32)
+    }
+
+    fn stretch_stack_gap_above_min(&self) -> f32 {
+        self.scale(self.constant(MathConstant::StretchStackGapAboveMin) as f32)
+    }
+
+    fn stretch_stack_gap_below_min(&self) -> f32 {
+        self.scale(self.constant(MathConstant::StretchStackGapBelowMin) as f32)
+    }
+
+    fn fraction_numerator_shift_up(&self) -> f32 {
+        self.scale(self.constant(MathConstant::FractionNumeratorShiftUp) as f32)
+    }
+
+    fn fraction_numerator_display_style_shift_up(&self) -> f32 {
+        self.scale(self.constant(MathConstant::FractionNumeratorDisplayStyleShiftUp) as f32)
+    }
+
+    fn fraction_denominator_shift_down(&self) -> f32 {
+        self.scale(self.constant(MathConstant::FractionDenominatorShiftDown) as f32)
+    }
+
+    fn fraction_denominator_display_style_shift_down(&self) -> f32 {
+        self.scale(self.constant(MathConstant::FractionDenominatorDisplayStyleShiftDown) as f32)
+    }
+
+    fn fraction_numerator_gap_min(&self) -> f32 {
+        self.scale(self.constant(MathConstant::FractionNumeratorGapMin) as f32)
+    }
+
+    fn fraction_num_display_style_gap_min(&self) -> f32 {
+        self.scale(self.constant(MathConstant::FractionNumDisplayStyleGapMin) as f32)
+    }
+
+    fn fraction_rule_thickness(&self) -> f32 {
+        self.scale(self.constant(MathConstant::FractionRuleThickness) as f32)
+    }
+
+    fn fraction_denominator_gap_min(&self) -> f32 {
+        self.scale(self.constant(MathConstant::FractionDenominatorGapMin) as f32)
+    }
+
+    fn fraction_denominator_display_style_gap_min(&self) -> f32 {
+        self.scale(self.constant(MathConstant::FractionDenomDisplayStyleGapMin) as f32)
+    }
+
+    fn skewed_fraction_horizontal_gap(&self) -> f32 {
+        self.scale(self.constant(MathConstant::SkewedFractionHorizontalGap) as f32)
+    }
+
+    fn skewed_fraction_vertical_gap(&self) -> f32 {
+        self.scale(self.constant(MathConstant::SkewedFractionVerticalGap) as f32)
+    }
+
+    fn overbar_vertical_gap(&self) -> f32 {
+        self.scale(self.constant(MathConstant::OverbarVerticalGap) as f32)
+    }
+
+    fn overbar_rule_thickness(&self) -> f32 {
+        self.scale(self.constant(MathConstant::OverbarRuleThickness) as f32)
+    }
+
+    fn overbar_extra_ascender(&self) -> f32 {
+        self.scale(self.constant(MathConstant::OverbarExtraAscender) as f32)
+    }
+
+    fn underbar_vertical_gap(&self) -> f32 {
+        self.scale(self.constant(MathConstant::UnderbarVerticalGap) as f32)
+    }
+
+    fn underbar_rule_thickness(&self) -> f32 {
+        self.scale(self.constant(MathConstant::UnderbarRuleThickness) as f32)
+    }
+
+    fn underbar_extra_descender(&self) -> f32 {
+        self.scale(self.constant(MathConstant::UnderbarExtraDescender) as f32)
+    }
+
+    fn radical_vertical_gap(&self) -> f32 {
+        self.scale(self.constant(MathConstant::RadicalVerticalGap) as f32)
+    }
+
+    fn radical_display_style_vertical_gap(&self) -> f32 {
+        self.scale(self.constant(MathConstant::RadicalDisplayStyleVerticalGap) as f32)
+    }
+
+    fn radical_rule_thickness(&self) -> f32 {
+        self.scale(self.constant(MathConstant::RadicalRuleThickness) as f32)
+    }
+
+    fn radical_extra_ascender(&self) -> f32 {
+        self.scale(self.constant(MathConstant::RadicalExtraAscender) as f32)
+    }
+
+    fn radical_kern_before_degree(&self) -> f32 {
+        self.scale(self.constant(MathConstant::RadicalKernBeforeDegree) as f32)
+    }
+
+    fn radical_kern_after_degree(&self) -> f32 {
+        self.scale(self.constant(MathConstant::RadicalKernAfterDegree) as f32)
+    }
+
+    fn radical_degree_bottom_raise_percent(&self) -> f32 {
+        self.constant(MathConstant::RadicalDegreeBottomRaisePercent) as f32 / 100.
+    }
+
+    fn minimum_connector_overlap(&self, _: &GlyphConstructionDirection) -> f32 {
+        self.scale(self.font.math().map(|m| m.min_connector_overlap()).unwrap_or(0) as f32)
+    }
+
+    fn glyph_variants(&self, unicode: u32, direction: &GlyphConstructionDirection) -> Vec<GlyphVariant> {
+        let (math, glyph_index) = match (self.font.math(), self.font.glyph_index(unicode)) {
+            (Some(math), Some(glyph_index)) => (math, glyph_index),
+            _ => return vec![],
+        };
+
+        math.glyph_variants(glyph_index, is_vertical(direction)).iter()
+            .map(|v| GlyphVariant::new(v.glyph_index(), self.scale(v.advance() as f32)))
+            .collect()
+    }
+
+    fn glyph_assembly(&self, unicode: u32, direction: &GlyphConstructionDirection) -> GlyphAssembly {
+        let assembly = self.font.math().and_then(|math| self.font.glyph_index(unicode)
+            .and_then(|glyph_index| math.glyph_assembly(glyph_index, is_vertical(direction))));
+
+        let assembly = match assembly {
+            Some(assembly) => assembly,
+            None => return GlyphAssembly::empty(),
+        };
+
+        let parts = assembly.parts().iter().map(|p| GlyphAssemblyPart::new(
+            p.glyph_index(),
+            self.scale(p.start_connector_length() as f32),
+            self.scale(p.end_connector_length() as f32),
+            self.scale(p.full_advance() as f32),
+            p.is_extender()
+        )).collect();
+
+        GlyphAssembly::new(parts, self.scale(assembly.italics_correction() as f32))
+    }
+
+    fn rtl_mirrored_glyph(&self, _: u32) -> Option<u32> {
+        // rtlm substitutions are not read from GSUB
+        None
+    }
+
+    fn script_style_glyph(&self, glyph_index: u32, script_level: u32) -> Option<u32> {
+        match script_level {
+            0 => None,
+            level => self.substitute(GsubFeature::ScriptStyle, glyph_index,
+                                     level.min(2) as usize - 1)
+        }
+    }
+
+    fn flattened_accent_glyph(&self, glyph_index: u32) -> Option<u32> {
+        self.substitute(GsubFeature::FlattenedAccent, glyph_index, 0)
+    }
+}
+
+fn is_vertical(direction: &GlyphConstructionDirection) -> bool {
+    match *direction {
+        GlyphConstructionDirection::Vertical => true,
+        GlyphConstructionDirection::Horizontal => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::test::{stix_math, stix_without_math};
+
+    fn assert_near(value: f32, expected: f32) {
+        assert!((value - expected).abs() < 1e-3, "{} is not {}", value, expected);
+    }
+
+    #[test]
+    fn it_scales_metrics() {
+        let ruler = OpenTypeRuler::new(Rc::new(stix_math()));
+
+        ruler.set_size(20.);
+        assert_near(ruler.font_size(), 20.);
+        assert_near(ruler.ascent(), 15.24);
+        assert_near(ruler.descent(), -4.76);
+        assert_near(ruler.measure("xy", &Directionality::LTR).width(), 19.24);
+        assert_near(ruler.axis_height(), 5.16);
+        assert_near(ruler.fraction_rule_thickness(), 1.36);
+        assert_near(ruler.script_percent_scale_down(), 70.);
+        assert_near(ruler.radical_degree_bottom_raise_percent(), 0.55);
+        assert_near(ruler.minimum_connector_overlap(&GlyphConstructionDirection::Vertical), 2.);
+
+        ruler.set_size(40.);
+        assert_near(ruler.axis_height(), 10.32);
+        assert_near(ruler.script_percent_scale_down(), 70.);
+    }
+
+    #[test]
+    fn it_shapes_runs() {
+        let font = Rc::new(stix_math());
+        let ruler = OpenTypeRuler::new(font.clone());
+        ruler.set_size(20.);
+
+        let run = ruler.shape("a\u{2211}b", &Directionality::RTL, Some("en"));
+        assert_eq!(run.text(), "a\u{2211}b");
+        assert_eq!(run.typeface(), ruler.typeface());
+        assert_eq!(run.size(), 20.);
+
+        let clusters: Vec<usize> = run.glyphs().iter().map(|glyph| glyph.cluster()).collect();
+        assert_eq!(clusters, vec![4, 1, 0]);
+        assert_eq!(run.glyphs()[2].glyph_index(), font.glyph_index('a' as u32).unwrap());
+        assert!((run.width() - ruler.measure("a\u{2211}b", &Directionality::LTR).width()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn it_measures_ink() {
+        let font = Rc::new(stix_math());
+        let ruler = OpenTypeRuler::new(font.clone());
+        ruler.set_size(20.);
+        let glyph = |unicode: char| font.glyph_index(unicode as u32).unwrap();
+
+        let x = ruler.glyph_ink(glyph('x')).unwrap();
+        let p = ruler.glyph_ink(glyph('p')).unwrap();
+        assert!(x.ascent() < ruler.ascent() && x.descent() > -0.5);
+        assert!(p.descent() < -2.);
+        assert_eq!(ruler.glyph_ink(glyph(' ')), None);
+
+        let run = ruler.shape("x p", &Directionality::LTR, None);
+        let ink = ruler.run_ink(&run).unwrap();
+        assert_near(ink.ascent(), x.ascent().max(p.ascent()));
+        assert_near(ink.descent(), p.descent());
+        assert_near(ink.right(), run.width() - run.glyphs()[2].advance() + p.right());
+    }
+
+    #[test]
+    fn it_substitutes_glyph_forms() {
+        let font = Rc::new(stix_math());
+        let ruler = OpenTypeRuler::new(font.clone());
+        let glyph = |unicode: char| font.glyph_index(unicode as u32).unwrap();
+
+        let x = glyph('\u{1D465}');
+        assert_eq!(ruler.script_style_glyph(x, 0), None);
+        let script = ruler.script_style_glyph(x, 1).expect("No ssty variant");
+        let script_script = ruler.script_style_glyph(x, 2).expect("No ssty variant");
+        assert!(script != x && script_script != script);
+        assert_eq!(ruler.script_style_glyph(x, 5), Some(script_script));
+
+        assert!(ruler.flattened_accent_glyph(glyph('\u{302}')).is_some());
+        assert_eq!(ruler.flattened_accent_glyph(x), None);
+
+        assert!(ruler.dotless_glyph(glyph('i')).expect("No dotless i") != glyph('i'));
+        assert_eq!(ruler.dotless_glyph(glyph('x')), None);
+    }
+
+    #[test]
+    fn it_reads_glyph_constructions() {
+        let ruler = OpenTypeRuler::new(Rc::new(stix_math()));
+        ruler.set_size(20.);
+
+        let variants = ruler.glyph_variants('(' as u32, &GlyphConstructionDirection::Vertical);
+        assert_eq!(variants.len(), 13);
+        assert_near(variants[0].advance(), 18.34);
+        assert!(variants.windows(2).all(|pair| pair[0].advance() < pair[1].advance()));
+
+        let assembly = ruler.glyph_assembly('(' as u32, &GlyphConstructionDirection::Vertical);
+        let extenders: Vec<bool> = assembly.parts().iter().map(|part| part.is_extender()).collect();
+        assert_eq!(extenders, vec![false, true, false]);
+        assert_near(assembly.parts()[0].end_connector_length(), 5.);
+        assert_near(assembly.parts()[1].full_advance(), 25.04);
+
+        assert!(ruler.glyph_variants('x' as u32, &GlyphConstructionDirection::Vertical).is_empty());
+        assert!(ruler.glyph_assembly('x' as u32, &GlyphConstructionDirection::Vertical)
+            .parts().is_empty());
+    }
+
+    #[test]
+    fn it_synthesizes_constants_without_math_table() {
+        let ruler = OpenTypeRuler::new(Rc::new(stix_without_math()));
+        ruler.set_size(20.);
+
+        assert_near(ruler.axis_height(), 5.5);
+        assert_near(ruler.fraction_rule_thickness(), 0.8);
+        assert_near(ruler.script_percent_scale_down(), 70.);
+        assert_near(ruler.radical_degree_bottom_raise_percent(), 0.6);
+        assert!(ruler.glyph_variants('(' as u32, &GlyphConstructionDirection::Vertical).is_empty());
+    }
+}
@@ -0,0 +1,176 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use std::rc::Rc;
+
+use super::OpenTypeFont;
+use ::paint::TypeFace;
+
+/// Fonts of a platform in order of preference: the math font, the text font used for `mtext`
+/// and `ms` with its bold and italic faces, and fallbacks for characters none of them has.
+/// Text font is the math font unless set.
+#[derive(Clone)]
+pub struct FontStack {
+    fonts: Vec<Rc<OpenTypeFont>>,
+    typefaces: Vec<TypeFace>,
+    text_index: usize,
+    // Bold and italic flags of styled text faces, with their position in fonts
+    styled_text: Vec<(bool, bool, usize)>,
+    fallback_start: usize,
+}
+
+impl FontStack {
+    pub fn new(math_font: OpenTypeFont) -> FontStack {
+        FontStack {
+            typefaces: vec![math_font.typeface()],
+            fonts: vec![Rc::new(math_font)],
+            text_index: 0,
+            styled_text: Vec::new(),
+            fallback_start: 1,
+        }
+    }
+
+    pub fn with_text_font(&mut self, font: OpenTypeFont) -> &mut FontStack {
+        let typeface = font.typeface();
+        let font = Rc::new(font);
+
+        if self.text_index == 0 {
+            self.insert(1, font, typeface);
+            self.text_index = 1;
+        } else {
+            self.fonts[1] = font;
+            self.typefaces[1] = typeface;
+        }
+        self
+    }
+
+    /// Face of the text font used for bold and italic text instead of synthesizing the style
+    pub fn with_styled_text_font(&mut self, bold: bool, italic: bool, font: OpenTypeFont)
+        -> &mut FontStack {
+        let typeface = font.typeface();
+        let font = Rc::new(font);
+
+        match self.styled_text.iter().position(|&(b, i, _)| b == bold && i == italic) {
+            Some(position) => {
+                let index = self.styled_text[position].2;
+                self.fonts[index] = font;
+                self.typefaces[index] = typeface;
+            },
+            None => {
+                let index = self.fallback_start;
+                self.insert(index, font, typeface);
+                self.styled_text.push((bold, italic, index));
+            }
+        }
+        self
+    }
+
+    pub fn with_fallback_font(&mut self, font: OpenTypeFont) -> &mut FontStack {
+        self.typefaces.push(font.typeface());
+        self.fonts.push(Rc::new(font));
+        self
+    }
+
+    pub fn math_font(&self) -> &Rc<OpenTypeFont> {
+        &self.fonts[0]
+    }
+
+    pub fn text_font(&self) -> &Rc<OpenTypeFont> {
+        &self.fonts[self.text_index]
+    }
+
+    /// All fonts, math font first and fallbacks last
+    pub fn fonts(&self) -> &[Rc<OpenTypeFont>] {
+        &self.fonts
+    }
+
+    pub fn math_typeface(&self) -> &TypeFace {
+        &self.typefaces[0]
+    }
+
+    pub fn text_typeface(&self) -> &TypeFace {
+        &self.typefaces[self.text_index]
+    }
+
+    /// Face of the text font with the style, the regular text face when neither bold nor italic
+    pub fn styled_text_typeface(&self, bold: bool, italic: bool) -> Option<&TypeFace> {
+        if !bold && !italic {
+            return Some(self.text_typeface());
+        }
+
+        self.styled_text.iter()
+            .find(|&&(b, i, _)| b == bold && i == italic)
+            .map(|&(_, _, index)| &self.typefaces[index])
+    }
+
+    pub fn fallback_typefaces(&self) -> &[TypeFace] {
+        &self.typefaces[self.fallback_start..]
+    }
+
+    /// Position of font with `typeface` in `fonts()`
+    pub fn index_of(&self, typeface: &TypeFace) -> Option<usize> {
+        self.typefaces.iter().position(|face| face == typeface)
+    }
+
+    /// Font with `typeface`, falling back to the math font for faces not in the stack
+    pub fn font(&self, typeface: &TypeFace) -> &Rc<OpenTypeFont> {
+        &self.fonts[self.index_of(typeface).unwrap_or(0)]
+    }
+
+    /// Inserts a font before the fallbacks, moving positions of the fonts after it
+    fn insert(&mut self, index: usize, font: Rc<OpenTypeFont>, typeface: TypeFace) {
+        self.fonts.insert(index, font);
+        self.typefaces.insert(index, typeface);
+        self.fallback_start += 1;
+        for styled in self.styled_text.iter_mut() {
+            if styled.2 >= index {
+                styled.2 += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::test::{stix_math, stix_text};
+
+    #[test]
+    fn it_orders_fonts() {
+        let mut fonts = FontStack::new(stix_math());
+        assert_eq!(fonts.text_typeface(), fonts.math_typeface());
+        assert!(fonts.fallback_typefaces().is_empty());
+
+        fonts.with_fallback_font(stix_text()).with_text_font(stix_text());
+        assert_eq!(fonts.fonts().len(), 3);
+        assert_eq!(fonts.math_typeface().font_family(), "STIX Two Math");
+        assert_eq!(fonts.text_typeface().font_family(), "STIX Two Text");
+        assert_eq!(fonts.fallback_typefaces().len(), 1);
+        assert!(fonts.text_font().math().is_none());
+
+        assert_eq!(fonts.styled_text_typeface(false, false), Some(fonts.text_typeface()));
+        assert_eq!(fonts.styled_text_typeface(true, false), None);
+        fonts.with_styled_text_font(true, false, stix_text());
+        assert_eq!(fonts.fonts().len(), 4);
+        assert_eq!(fonts.styled_text_typeface(true, false).unwrap().font_family(), "STIX Two Text");
+        assert_eq!(fonts.fallback_typefaces().len(), 1);
+
+        let unknown = TypeFace::new(String::from("Unknown"), String::from("Regular"));
+        assert_eq!(fonts.index_of(&unknown), None);
+        assert!(Rc::ptr_eq(fonts.font(&unknown), fonts.math_font()));
+        assert!(Rc::ptr_eq(fonts.font(&fonts.text_typeface().clone()), fonts.text_font()));
+    }
+}
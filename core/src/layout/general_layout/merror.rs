@@ -29,7 +29,7 @@ pub struct MerrorLayout {
 }
 
 impl Layout for MerrorLayout {
-    fn layout<'a>(&'a self, context: &Context) -> Box<Drawable + 'a> {
+    fn layout(&self, context: &Context) -> Box<Drawable> {
         let mut wrapper = self.presentation_layout.layout(context);
 
         let child_drawable = self.child_layout.layout(context);
@@ -47,7 +47,7 @@ impl Layout for MerrorLayout {
         self
     }
 
-    fn as_any_mut(&mut self) -> &mut Any {
-        self
+    fn as_any_mut(&mut self) -> Option<&mut Any> {
+        Some(self)
     }
 }
\ No newline at end of file
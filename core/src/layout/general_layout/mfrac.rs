@@ -19,9 +19,10 @@ use std::any::Any;
 
 use super::super::{Layout, ElementGroup, ConcreteLayout, PresentationLayout};
 use ::platform::Context;
-use ::draw::{Drawable, AbsoluteLayout, Wrapper, MeasureMode, Line, LineParam, AbsoluteLayoutParams};
+use ::draw::{Drawable, AbsoluteLayout, Wrapper, MeasureMode, Line, LineParam, AbsoluteLayoutParams,
+             BoundingBox};
 use ::props::{Directionality, Color, HAlign, MathSize, LineThickness};
-use ::paint::{Point};
+use ::paint::{Point, MathRuler};
 
 pub struct MfracLayout {
     pub(crate) numerator: Box<Layout>,
@@ -37,7 +38,7 @@ pub struct MfracLayout {
 }
 
 impl Layout for MfracLayout {
-    fn layout<'a>(&'a self, context: &Context) -> Box<Drawable + 'a> {
+    fn layout(&self, context: &Context) -> Box<Drawable> {
         Box::new(ConcreteLayout::layout(self, context))
     }
 
@@ -45,8 +46,8 @@ impl Layout for MfracLayout {
         self
     }
 
-    fn as_any_mut(&mut self) -> &mut Any {
-        self
+    fn as_any_mut(&mut self) -> Option<&mut Any> {
+        Some(self)
     }
 }
 
@@ -56,8 +57,8 @@ impl ElementGroup for MfracLayout {
     }
 }
 
-impl<'a> ConcreteLayout<'a, Wrapper<'a, PresentationLayout, AbsoluteLayout<'a>>> for MfracLayout {
-    fn layout(&'a self, context: &Context) -> Wrapper<'a, PresentationLayout, AbsoluteLayout<'a>> {
+impl ConcreteLayout<Wrapper<AbsoluteLayout>> for MfracLayout {
+    fn layout(&self, context: &Context) -> Wrapper<AbsoluteLayout> {
         let layout = if self.bevelled {
             self.layout_bevelled(context)
         } else {
@@ -98,9 +99,11 @@ impl MfracLayout {
         }
     }
 
-    pub fn layout_normal<'a>(&'a self, context: &Context) -> AbsoluteLayout<'a> {
+    pub fn layout_normal(&self, context: &Context) -> AbsoluteLayout {
         let mut ll = AbsoluteLayout::new();
         ll.should_calculate_child_bounds(false);
+        // Numerator and denominator alignments are mirrored in right to left layout
+        ll.set_dir(self.dir.clone());
 
         let num_layout = self.numerator.layout(context);
         let denom_layout = self.denominator.layout(context);
@@ -112,15 +115,67 @@ impl MfracLayout {
         let rule_thickness = self.line_thickness.get_thickness_du(
             context, font_size, ruler.fraction_rule_thickness());
 
-        let mut line = Line::new(
-            LineParam::Horizontal { y: 0. },
-            self,
-            rule_thickness,
-            |e| &e.presentation_element.math_color
-        );
-
         let display_style = self.presentation_element.display_style;
+        let axis_height = ruler.axis_height();
+
+        // Fractions without rule are stacks, such as binomial coefficients
+        let (num_baseline_shift_from_frac_axis, denom_baseline_shift_from_frac_axis) =
+            if rule_thickness > 0. {
+                MfracLayout::get_fraction_shifts(ruler, display_style, rule_thickness,
+                                                 num_layout.bounding_box(),
+                                                 denom_layout.bounding_box())
+            } else {
+                MfracLayout::get_stack_shifts(ruler, display_style, num_layout.bounding_box(),
+                                              denom_layout.bounding_box())
+            };
+
+        let frac_width = num_layout.bounding_box().width().max(denom_layout.bounding_box().width());
+
+        let num_x_pos = MfracLayout::get_aligned_x_pos(
+            frac_width, num_layout.bounding_box().width(), &self.num_align);
+        let num_y_pos = 0f32;
+
+        // Shifts are measured from the fraction axis at the center of the rule
+        let line_y_pos = num_y_pos+num_layout.bounding_box().baseline_pos()+
+            num_baseline_shift_from_frac_axis-
+            (rule_thickness/2f32);
+
+        let denom_x_pos = MfracLayout::get_aligned_x_pos(
+            frac_width, denom_layout.bounding_box().width(), &self.denom_align);
+        let denom_y_pos = line_y_pos+
+            (rule_thickness/2f32)+
+            denom_baseline_shift_from_frac_axis-
+            (denom_layout.bounding_box().baseline_pos());
+
+        let axis = denom_y_pos-line_y_pos +
+            denom_layout.bounding_box().height() -
+            (rule_thickness/2f32);
+
+        ll.set_axis(Some(axis));
+        ll.set_baseline(Some(axis-axis_height));
+
+        ll.add_child(num_layout, AbsoluteLayoutParams::new(Point::new(num_x_pos, num_y_pos)));
+
+        if rule_thickness > 0. {
+            let mut line = Line::new(
+                LineParam::Horizontal { y: 0. },
+                self,
+                rule_thickness,
+                |e| &e.presentation_element.math_color
+            );
+            line.calculate(context, &MeasureMode::UpTo(frac_width), &MeasureMode::Wrap);
+            ll.add_child(Box::new(line), AbsoluteLayoutParams::new(Point::new(0., line_y_pos)));
+        }
+
+        ll.add_child(denom_layout, AbsoluteLayoutParams::new(Point::new(denom_x_pos, denom_y_pos)));
 
+        ll
+    }
+
+    // Shifts of numerator baseline above and denominator baseline below the fraction axis, with
+    // gaps to the rule measured to the ink of numerator and denominator
+    fn get_fraction_shifts(ruler: &MathRuler, display_style: bool, rule_thickness: f32,
+                           num_box: &BoundingBox, denom_box: &BoundingBox) -> (f32, f32) {
         let num_gap_min = if display_style {
             ruler.fraction_num_display_style_gap_min()
         } else {
@@ -142,49 +197,38 @@ impl MfracLayout {
         let denom_shift_down = if display_style {
             ruler.fraction_denominator_display_style_shift_down()
         } else {
-            ruler.fraction_denominator_display_style_shift_down()
+            ruler.fraction_denominator_shift_down()
         };
 
         let axis_height = ruler.axis_height();
 
-        let num_baseline_shift_from_frac_axis =
-            (num_shift_up-axis_height)
-                .max(num_layout.bounding_box().baseline()+num_gap_min+(rule_thickness/2.0));
-
-        let denom_baseline_shift_from_frac_axis =
-            (axis_height+denom_shift_down)
-                .max(denom_gap_min+denom_layout.bounding_box().baseline_pos()+(rule_thickness/2.0));
-
-        let frac_width = num_layout.bounding_box().width().max(denom_layout.bounding_box().width());
-
-        let num_x_pos = MfracLayout::get_aligned_x_pos(
-            frac_width, num_layout.bounding_box().width(), &self.num_align);
-        let num_y_pos = 0f32;
+        let num_shift = (num_shift_up-axis_height)
+            .max(-num_box.ink_descent()+num_gap_min+(rule_thickness/2.0));
 
-        let line_y_pos = num_y_pos+num_layout.bounding_box().baseline_pos()+
-            num_baseline_shift_from_frac_axis;
+        let denom_shift = (axis_height+denom_shift_down)
+            .max(denom_gap_min+denom_box.ink_ascent()+(rule_thickness/2.0));
 
-        let denom_x_pos = MfracLayout::get_aligned_x_pos(
-            frac_width, denom_layout.bounding_box().width(), &self.denom_align);
-        let denom_y_pos = line_y_pos+
-            rule_thickness+
-            denom_baseline_shift_from_frac_axis-
-            (denom_layout.bounding_box().baseline_pos());
-
-        let axis = denom_y_pos-line_y_pos +
-            denom_layout.bounding_box().height() -
-            (rule_thickness/2f32);
+        (num_shift, denom_shift)
+    }
 
-        ll.set_axis(Some(axis));
-        ll.set_baseline(Some(axis-axis_height));
+    // Shifts of numerator and denominator baselines from the axis of a stack, which are moved
+    // apart equally when the gap between them is below the minimum
+    fn get_stack_shifts(ruler: &MathRuler, display_style: bool, num_box: &BoundingBox,
+                        denom_box: &BoundingBox) -> (f32, f32) {
+        let (top_shift_up, bottom_shift_down, gap_min) = if display_style {
+            (ruler.stack_top_display_style_shift_up(),
+             ruler.stack_bottom_display_style_shift_down(),
+             ruler.stack_display_style_gap_min())
+        } else {
+            (ruler.stack_top_shift_up(), ruler.stack_bottom_shift_down(), ruler.stack_gap_min())
+        };
 
-        line.calculate(context, &MeasureMode::UpTo(frac_width), &MeasureMode::Wrap);
+        let gap = (top_shift_up+num_box.ink_descent()) - (denom_box.ink_ascent()-bottom_shift_down);
+        let delta = ((gap_min-gap)/2.).max(0.);
 
-        ll.add_child(num_layout, AbsoluteLayoutParams::new(Point::new(num_x_pos, num_y_pos)));
-        ll.add_child(Box::new(line), AbsoluteLayoutParams::new(Point::new(0., line_y_pos)));
-        ll.add_child(denom_layout, AbsoluteLayoutParams::new(Point::new(denom_x_pos, denom_y_pos)));
+        let axis_height = ruler.axis_height();
 
-        ll
+        (top_shift_up+delta-axis_height, axis_height+bottom_shift_down+delta)
     }
 
     fn get_aligned_x_pos(frac_width: f32, box_width: f32, alignment: &HAlign) -> f32 {
@@ -195,9 +239,10 @@ impl MfracLayout {
         }
     }
 
-    pub fn layout_bevelled<'a>(&'a self, context: &Context) -> AbsoluteLayout<'a> {
+    pub fn layout_bevelled(&self, context: &Context) -> AbsoluteLayout {
         let mut ll = AbsoluteLayout::new();
         ll.should_calculate_child_bounds(false);
+        ll.set_dir(self.dir.clone());
 
         let num_layout = self.numerator.layout(context);
         let denom_layout = self.denominator.layout(context);
@@ -220,15 +265,11 @@ impl MfracLayout {
             vertical_gap +
             denom_layout.bounding_box().height();
 
-        let line_p0 = Point::new(
-            0f32,
-            frac_height
-        );
-
-        let line_p1 = Point::new(
-            horizontal_gap,
-            0f32
-        );
+        // Slant of the line is reversed in right to left layout along with the positions
+        let (line_p0, line_p1) = match self.dir {
+            Directionality::LTR => (Point::new(0f32, frac_height), Point::new(horizontal_gap, 0f32)),
+            Directionality::RTL => (Point::new(horizontal_gap, frac_height), Point::new(0f32, 0f32)),
+        };
 
         let line_x_pos = num_x_pos + num_layout.bounding_box().width();
         let line_y_pos = 0f32;
@@ -20,8 +20,9 @@ use std::any::Any;
 use super::super::{Layout, PresentationLayout, ConcreteLayout};
 use ::props::{PseudoLength, PropertyModifier, MathSize};
 use ::platform::Context;
-use ::draw::{Drawable, BoundingBox, MeasureMode};
+use ::draw::{Drawable, BoundingBox, MeasureMode, HitResult, Highlight, BoxSnapshot};
 use ::paint::{Point, Rect, Canvas};
+use ::elements::InstanceId;
 
 pub struct MpaddedLayout {
     pub(crate) width: PropertyModifier<PseudoLength>,
@@ -37,7 +38,7 @@ pub struct MpaddedLayout {
 
 
 impl Layout for MpaddedLayout {
-    fn layout<'a>(&'a self, context: &Context) -> Box<Drawable + 'a> {
+    fn layout(&self, context: &Context) -> Box<Drawable> {
         let child_drawable = self.child_layout.layout(context);
         let mut wrapper = self.presentation_layout.layout(context);
 
@@ -48,9 +49,9 @@ impl Layout for MpaddedLayout {
         let font_size = self.presentation_layout.script_level
             .get_font_size(context, &MathSize::NORMAL);
 
+        // Negative widths move the pen backwards, so that the following content overlaps
         let layout_width = self.width.value(PseudoLength::DU(child_width))
-            .get_length_du(context, font_size, child_width, child_height, child_depth)
-            .max(0f32);
+            .get_length_du(context, font_size, child_width, child_height, child_depth);
         let layout_height = self.height.value(PseudoLength::DU(child_height))
             .get_length_du(context, font_size, child_width, child_height, child_depth)
             .max(0f32);
@@ -65,6 +66,9 @@ impl Layout for MpaddedLayout {
         let layout_voffset = self.voffset.value(PseudoLength::DU(0f32))
             .get_length_du(context, font_size, child_width, child_height, child_depth);
 
+        let ink = child_drawable.bounding_box().ink_in_parent(
+            &Point::new(layout_lspace, layout_voffset), layout_height);
+
         wrapper.wrap(MpaddedDrawable {
             content: child_drawable,
             lspace: layout_lspace,
@@ -73,7 +77,7 @@ impl Layout for MpaddedLayout {
                 Rect::new(layout_width, layout_height+layout_depth),
                 layout_depth,
                 layout_axis,
-            ),
+            ).with_ink(ink),
         });
 
         Box::new(wrapper)
@@ -83,13 +87,13 @@ impl Layout for MpaddedLayout {
         self
     }
 
-    fn as_any_mut(&mut self) -> &mut Any {
-        self
+    fn as_any_mut(&mut self) -> Option<&mut Any> {
+        Some(self)
     }
 }
 
-struct MpaddedDrawable<'a> {
-    content: Box<Drawable + 'a>,
+struct MpaddedDrawable {
+    content: Box<Drawable>,
 
     lspace: f32,
     voffset: f32,
@@ -97,11 +101,17 @@ struct MpaddedDrawable<'a> {
     bounding_box: BoundingBox
 }
 
-impl<'a> Drawable for MpaddedDrawable<'a> {
+impl Drawable for MpaddedDrawable {
     fn draw(&self, canvas: &Canvas, pen_pos: &Point) {
         self.content.draw(canvas,&(pen_pos+&Point::new(self.lspace, self.voffset)));
     }
 
+    fn draw_highlighted(&self, canvas: &Canvas, pen_pos: &Point, highlight: &Highlight,
+                        selected: bool) {
+        self.content.draw_highlighted(canvas, &(pen_pos+&Point::new(self.lspace, self.voffset)),
+                                      highlight, selected);
+    }
+
     fn calculate(&mut self, _: &Context, _: &MeasureMode, _: &MeasureMode) {
         // do nothing
     }
@@ -109,4 +119,17 @@ impl<'a> Drawable for MpaddedDrawable<'a> {
     fn bounding_box(&self) -> &BoundingBox {
         &self.bounding_box
     }
+
+    fn hit_test(&self, pen_pos: &Point, point: &Point) -> Option<HitResult> {
+        self.content.hit_test(&(pen_pos+&Point::new(self.lspace, self.voffset)), point)
+    }
+
+    fn locate(&self, pen_pos: &Point, instance_id: &InstanceId) -> Option<(Point, &Drawable)> {
+        self.content.locate(&(pen_pos+&Point::new(self.lspace, self.voffset)), instance_id)
+    }
+
+    fn to_snapshot(&self) -> BoxSnapshot {
+        let content = (Point::new(self.lspace, self.voffset), self.content.to_snapshot());
+        BoxSnapshot::group(self.bounding_box.clone(), vec![content])
+    }
 }
\ No newline at end of file
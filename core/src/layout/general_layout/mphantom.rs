@@ -27,7 +27,7 @@ pub struct MphatomLayout {
 }
 
 impl Layout for MphatomLayout {
-    fn layout<'a>(&'a self, context: &Context) -> Box<Drawable + 'a> {
+    fn layout(&self, context: &Context) -> Box<Drawable> {
         let mut wrapper = self.presentation_layout.layout(context);
         let mut phantom = Phantom::new();
 
@@ -45,7 +45,7 @@ impl Layout for MphatomLayout {
         self
     }
 
-    fn as_any_mut(&mut self) -> &mut Any {
-        self
+    fn as_any_mut(&mut self) -> Option<&mut Any> {
+        Some(self)
     }
 }
\ No newline at end of file
@@ -34,7 +34,7 @@ pub struct MrootLayout {
 }
 
 impl Layout for MrootLayout {
-    fn layout<'a>(&'a self, context: &Context) -> Box<Drawable + 'a> {
+    fn layout(&self, context: &Context) -> Box<Drawable> {
         Box::new(ConcreteLayout::layout(self, context))
     }
 
@@ -42,15 +42,16 @@ impl Layout for MrootLayout {
         self
     }
 
-    fn as_any_mut(&mut self) -> &mut Any {
-        self
+    fn as_any_mut(&mut self) -> Option<&mut Any> {
+        Some(self)
     }
 }
 
-impl<'a> ConcreteLayout<'a, Wrapper<'a, PresentationLayout, AbsoluteLayout<'a>>> for MrootLayout {
-    fn layout(&'a self, context: &Context) -> Wrapper<'a, PresentationLayout, AbsoluteLayout<'a>> {
+impl ConcreteLayout<Wrapper<AbsoluteLayout>> for MrootLayout {
+    fn layout(&self, context: &Context) -> Wrapper<AbsoluteLayout> {
         let mut ll = AbsoluteLayout::new();
         ll.should_calculate_child_bounds(false);
+        ll.set_dir(self.dir.clone());
 
         let ruler = context.platform().get_math_ruler(self.base_size);
 
@@ -69,7 +70,13 @@ impl<'a> ConcreteLayout<'a, Wrapper<'a, PresentationLayout, AbsoluteLayout<'a>>>
         let degree_layout = self.degree.layout(context);
         let child_layout = self.child.layout(context);
 
-        let root_height = child_layout.bounding_box().height() + vertical_gap;
+        let child_box = child_layout.bounding_box().clone();
+        let degree_box = degree_layout.bounding_box().clone();
+
+        // Positions are first measured down from the radicand baseline. The rule is a gap above
+        // the ink of the radicand and the surd is stretched to reach from it to the ink bottom.
+        let rule_top = -child_box.ink_ascent() - vertical_gap - rule_thickness;
+        let root_height = -child_box.ink_descent() - rule_top;
 
         let mut surd = Symbol::new(
             self,
@@ -84,26 +91,33 @@ impl<'a> ConcreteLayout<'a, Wrapper<'a, PresentationLayout, AbsoluteLayout<'a>>>
         );
         surd.calculate(context, &MeasureMode::Wrap, &MeasureMode::UpTo(root_height));
 
-        let root_height = surd.bounding_box().height().max(root_height);
+        // The rule continues the top of the surd ink
+        let surd_top = rule_top -
+            (surd.bounding_box().baseline_pos() - surd.bounding_box().ink_ascent());
+        let surd_bottom = surd_top + surd.bounding_box().height();
+
+        let degree_bottom = surd_bottom - surd.bounding_box().height()*degree_bottom_raise_pc;
+        let degree_top = degree_bottom - degree_box.height();
+
+        let child_top = -child_box.baseline_pos();
+
+        let top = (rule_top - extra_ascender).min(surd_top).min(degree_top).min(child_top);
+        let bottom = surd_bottom.max(degree_bottom).max(child_top + child_box.height());
 
         let degree_pos_x = kern_before_degree;
-        let degree_pos_y = ((root_height*degree_bottom_raise_pc)-degree_layout.bounding_box().height())
-            .max(vertical_gap);
+        let degree_pos_y = degree_top - top;
 
-        let surd_pos_x = (degree_pos_x + degree_layout.bounding_box().width() + kern_after_degree).max(0f32);
-        let surd_pos_y = degree_pos_y +
-            degree_layout.bounding_box().height() +
-            (root_height * degree_bottom_raise_pc) -
-            root_height;
+        let surd_pos_x = (degree_pos_x + degree_box.width() + kern_after_degree).max(0f32);
+        let surd_pos_y = surd_top - top;
 
         let rule_pos_x = surd_pos_x + surd.bounding_box().width();
-        let rule_pos_y = surd_pos_y;
+        let rule_pos_y = rule_top - top;
 
         let child_pos_x = rule_pos_x;
-        let child_pos_y = rule_pos_y + rule_thickness + extra_ascender;
+        let child_pos_y = child_top - top;
 
         let mut rule_line = Line::new(
-            LineParam::Horizontal { y: rule_thickness/2.0 },
+            LineParam::Horizontal { y: 0. },
             self,
             rule_thickness,
             |e| &e.presentation_element.math_color
@@ -111,13 +125,13 @@ impl<'a> ConcreteLayout<'a, Wrapper<'a, PresentationLayout, AbsoluteLayout<'a>>>
 
         rule_line.calculate(
             context,
-            &MeasureMode::UpTo(child_layout.bounding_box().width()),
+            &MeasureMode::UpTo(child_box.width()),
             &MeasureMode::Wrap
         );
 
-        let height = surd_pos_y+surd.bounding_box().height();
-        ll.set_baseline(Some(height-(child_pos_y+child_layout.bounding_box().baseline_pos())));
-        ll.set_axis(Some(height-(child_pos_y+child_layout.bounding_box().axis_pos())));
+        let height = bottom - top;
+        ll.set_baseline(Some(height-(child_pos_y+child_box.baseline_pos())));
+        ll.set_axis(Some(height-(child_pos_y+child_box.axis_pos())));
 
         ll.add_child(degree_layout, AbsoluteLayoutParams::new(Point::new(degree_pos_x, degree_pos_y)));
         ll.add_child(Box::new(surd), AbsoluteLayoutParams::new(Point::new(surd_pos_x, surd_pos_y)));
@@ -29,7 +29,7 @@ pub struct MrowLayout {
 }
 
 impl Layout for MrowLayout {
-    fn layout<'a>(&'a self, context: &Context) -> Box<Drawable + 'a> {
+    fn layout(&self, context: &Context) -> Box<Drawable> {
         Box::new(ConcreteLayout::layout(self, context))
     }
 
@@ -37,8 +37,8 @@ impl Layout for MrowLayout {
         self
     }
 
-    fn as_any_mut(&mut self) -> &mut Any {
-        self
+    fn as_any_mut(&mut self) -> Option<&mut Any> {
+        Some(self)
     }
 }
 
@@ -48,9 +48,9 @@ impl ElementGroup for MrowLayout {
     }
 }
 
-impl<'a> ConcreteLayout<'a, Wrapper<'a, PresentationLayout, LinearLayout<'a>>> for MrowLayout {
-    fn layout(&'a self, context: &Context) -> Wrapper<'a, PresentationLayout, LinearLayout<'a>> {
-        let mut layout: LinearLayout<'a> = LinearLayout::new();
+impl ConcreteLayout<Wrapper<LinearLayout>> for MrowLayout {
+    fn layout(&self, context: &Context) -> Wrapper<LinearLayout> {
+        let mut layout: LinearLayout = LinearLayout::new();
         layout.gravity = Gravity::Horizontal;
         layout.layout_align = Align::Baseline;
 
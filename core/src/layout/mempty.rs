@@ -18,26 +18,30 @@ use std::any::Any;
 
 use super::{Layout};
 use ::platform::Context;
-use ::draw::{Drawable, Empty};
+use ::draw::{Drawable, Empty, HitTarget};
 
-pub struct MemptyLayout {}
+pub struct MemptyLayout {
+    pub(crate) hit_target: Option<HitTarget>,
+}
 
 impl MemptyLayout {
     pub fn new() -> MemptyLayout {
-        MemptyLayout {}
+        MemptyLayout { hit_target: None }
     }
 }
 
 impl Layout for MemptyLayout {
-    fn layout<'a>(&'a self, _: &Context) -> Box<Drawable + 'a> {
-        Box::new(Empty::new())
+    fn layout(&self, _: &Context) -> Box<Drawable> {
+        let mut empty = Empty::new();
+        empty.set_hit_target(self.hit_target.clone());
+        Box::new(empty)
     }
 
     fn as_any(&self) -> &Any {
         self
     }
 
-    fn as_any_mut(&mut self) -> &mut Any {
-        self
+    fn as_any_mut(&mut self) -> Option<&mut Any> {
+        Some(self)
     }
 }
\ No newline at end of file
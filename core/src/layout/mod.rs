@@ -24,22 +24,52 @@ mod action;                     pub use self::action::*;
 mod tabluar_math;               pub use self::tabluar_math::*;
 
 mod mempty;                     pub use self::mempty::*;
+mod placeholder;                pub use self::placeholder::*;
 
 use std::any::Any;
 
 use ::platform::Context;
-use ::draw::Drawable;
+use ::draw::{Drawable, BoxSnapshot, HitTarget};
+use ::elements::Element;
 
 pub trait Layout {
-    fn layout<'a>(&'a self, context: &Context) -> Box<Drawable + 'a>;
+    /// Measures the layout into an owned drawable, with colors, sizes and text read from the
+    /// layout, which can be kept after the layout is dropped, measured again and drawn any number
+    /// of times
+    fn layout(&self, context: &Context) -> Box<Drawable>;
     fn as_any(&self) -> &Any;
-    fn as_any_mut(&mut self) -> &mut Any;
+
+    /// Layout to downcast and change, `None` for layouts shared with other parents, which cannot
+    /// be changed
+    fn as_any_mut(&mut self) -> Option<&mut Any>;
+
+    /// Measures the layout and takes a snapshot of the drawable, which can be cloned and sent to
+    /// other threads
+    fn snapshot(&self, context: &Context) -> BoxSnapshot {
+        self.layout(context).to_snapshot()
+    }
 }
 
-pub trait ConcreteLayout<'a, T: Drawable + 'a> {
-    fn layout(&'a self, context: &Context) -> T;
+pub trait ConcreteLayout<T: Drawable> {
+    fn layout(&self, context: &Context) -> T;
 }
 
 pub trait ElementGroup : Layout {
     fn children(&self) -> &[Box<Layout>];
-}
\ No newline at end of file
+}
+/// Makes hits on `layout` report `element`, for elements laid out as another element
+pub(crate) fn with_hit_target(mut layout: Box<Layout>, element: &Element) -> Box<Layout> {
+    let hit_target = Some(HitTarget::new(element.instance_id().clone(), element.type_info()));
+
+    if let Some(any) = layout.as_any_mut() {
+        if let Some(layout) = any.downcast_mut::<MrootLayout>() {
+            layout.presentation_element.hit_target = hit_target;
+        } else if let Some(layout) = any.downcast_mut::<MunderoverLayout>() {
+            layout.presentation_layout.hit_target = hit_target;
+        } else if let Some(layout) = any.downcast_mut::<MmultiscriptLayout>() {
+            layout.presentation_layout.hit_target = hit_target;
+        }
+    }
+
+    layout
+}
@@ -0,0 +1,113 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+
+
+use std::any::Any;
+
+use super::{Layout, PresentationLayout, ConcreteLayout};
+use ::props::{Color, MathSize};
+use ::platform::Context;
+use ::draw::{Drawable, BoundingBox, MeasureMode};
+use ::paint::{Canvas, Point, Rect};
+
+pub struct PlaceholderLayout {
+    pub(crate) presentation_layout: PresentationLayout,
+}
+
+impl Layout for PlaceholderLayout {
+    fn layout(&self, context: &Context) -> Box<Drawable> {
+        let font_size = self.presentation_layout.script_level
+            .get_font_size(context, &MathSize::NORMAL);
+        let ruler = context.platform().get_math_ruler(font_size);
+
+        // Square standing on the baseline and centered on the math axis, outlined as thick as
+        // fraction rules
+        let side = 2. * ruler.axis_height();
+        let mut wrapper = self.presentation_layout.layout(context);
+        wrapper.wrap(PlaceholderDrawable {
+            color: self.presentation_layout.math_color.clone(),
+            stroke_width: ruler.fraction_rule_thickness(),
+            bounding_box: BoundingBox::new(Rect::new(side, side), 0., side / 2.),
+        });
+        wrapper.calculate(context, &MeasureMode::Wrap, &MeasureMode::Wrap);
+
+        Box::new(wrapper)
+    }
+
+    fn as_any(&self) -> &Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> Option<&mut Any> {
+        Some(self)
+    }
+}
+
+struct PlaceholderDrawable {
+    color: Color,
+    stroke_width: f32,
+
+    bounding_box: BoundingBox,
+}
+
+impl PlaceholderDrawable {
+    // Dashes from `start` to `end`, starting and ending with a dash
+    fn draw_dashes(&self, canvas: &Canvas, start: &Point, end: &Point) {
+        let (dx, dy) = (end.x() - start.x(), end.y() - start.y());
+        let length = (dx * dx + dy * dy).sqrt();
+        let dash = 2. * self.stroke_width;
+        if length <= 0. || dash <= 0. {
+            return;
+        }
+
+        // Odd number of segments alternating dash and gap, each close to the dash length
+        let segments = ((length / dash).floor() as usize).max(1) | 1;
+        let segment = length / segments as f32;
+        let point_at = |distance: f32| Point::new(start.x() + dx * distance / length,
+                                                  start.y() + dy * distance / length);
+
+        for index in (0..segments).step_by(2) {
+            let from = index as f32 * segment;
+            canvas.draw_line(&point_at(from), &point_at(from + segment), &self.color,
+                             self.stroke_width);
+        }
+    }
+}
+
+impl Drawable for PlaceholderDrawable {
+    fn draw(&self, canvas: &Canvas, pen_pos: &Point) {
+        // Strokes are kept inside the box
+        let inset = self.stroke_width / 2.;
+        let left = pen_pos.x() + inset;
+        let top = pen_pos.y() + inset;
+        let right = pen_pos.x() + self.bounding_box.width() - inset;
+        let bottom = pen_pos.y() + self.bounding_box.height() - inset;
+
+        self.draw_dashes(canvas, &Point::new(left, top), &Point::new(right, top));
+        self.draw_dashes(canvas, &Point::new(right, top), &Point::new(right, bottom));
+        self.draw_dashes(canvas, &Point::new(right, bottom), &Point::new(left, bottom));
+        self.draw_dashes(canvas, &Point::new(left, bottom), &Point::new(left, top));
+    }
+
+    fn calculate(&mut self, _: &Context, _: &MeasureMode, _: &MeasureMode) {
+        // do nothing
+    }
+
+    fn bounding_box(&self) -> &BoundingBox {
+        &self.bounding_box
+    }
+}
@@ -15,30 +15,34 @@
 */
 
 
-use ::props::{Color, DisplayStyle, ScriptLevel, ScriptMinSize, ScriptSizeMultiplier};
+use ::props::{Color, DisplayStyle, CrampedStyle, ScriptLevel, ScriptMinSize, ScriptSizeMultiplier};
 use super::ConcreteLayout;
 use ::platform::Context;
-use ::draw::{Drawable, Wrapper};
+use ::draw::{Drawable, Wrapper, HitTarget};
 
 pub struct PresentationLayout {
     pub(crate) math_color: Color,
     pub(crate) math_background: Color,
     pub(crate) display_style: DisplayStyle,
+    pub(crate) cramped: CrampedStyle,
     pub(crate) script_level: ScriptLevel,
     pub(crate) script_min_size: ScriptMinSize,
     pub(crate) script_size_multiplier: ScriptSizeMultiplier,
+    pub(crate) hit_target: Option<HitTarget>,
 }
 
 fn math_background_reader(element: &PresentationLayout) -> &Color {
     &element.math_background
 }
 
-impl<'a, U: Drawable + 'a> ConcreteLayout<'a, Wrapper<'a, PresentationLayout, U>> for PresentationLayout {
-    fn layout(&'a self, _: &Context) -> Wrapper<'a, PresentationLayout, U> {
-        Wrapper::<'a, PresentationLayout, U>::new(
+impl<U: Drawable> ConcreteLayout<Wrapper<U>> for PresentationLayout {
+    fn layout(&self, _: &Context) -> Wrapper<U> {
+        let mut wrapper = Wrapper::<U>::new(
             self,
             math_background_reader
-        )
+        );
+        wrapper.set_hit_target(self.hit_target.clone());
+        wrapper
     }
 }
 
@@ -47,9 +51,9 @@ impl<'a, U: Drawable + 'a> ConcreteLayout<'a, Wrapper<'a, PresentationLayout, U>
 impl PresentationLayout {
     pub fn new(math_color: Color, math_background: Color) -> PresentationLayout {
         PresentationLayout {
-            math_color, math_background, display_style: false,
+            math_color, math_background, display_style: false, cramped: false,
             script_level: ScriptLevel::new(0, 12.),
-            script_min_size: 0.0, script_size_multiplier: 0.0
+            script_min_size: 0.0, script_size_multiplier: None, hit_target: None
         }
     }
 }
\ No newline at end of file
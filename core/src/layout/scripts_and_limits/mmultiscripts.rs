@@ -17,10 +17,11 @@
 use std::any::Any;
 
 use super::super::{Layout, PresentationLayout, ConcreteLayout};
-use ::draw::{Drawable, BoundingBox, MeasureMode};
+use ::draw::{Drawable, BoundingBox, MeasureMode, HitResult, Highlight, BoxSnapshot};
 use ::platform::Context;
-use ::paint::{Canvas, Point, MathRuler, Rect};
-use ::props::{DisplayStyle, MathSize};
+use ::paint::{Canvas, Point, MathRuler, Rect, Ink};
+use ::props::{CrampedStyle, MathSize, Directionality};
+use ::elements::InstanceId;
 
 pub struct MmultiscriptLayout {
     pub(crate) base_layout: Box<Layout>,
@@ -28,12 +29,13 @@ pub struct MmultiscriptLayout {
     pub(crate) postscript_layout: Vec<(Box<Layout>, Box<Layout>)>,
     pub(crate) subscript_shift: f32,
     pub(crate) superscript_shift: f32,
+    pub(crate) dir: Directionality,
 
     pub(crate) presentation_layout: PresentationLayout,
 }
 
 impl Layout for MmultiscriptLayout {
-    fn layout<'a>(&'a self, context: &Context) -> Box<Drawable + 'a> {
+    fn layout(&self, context: &Context) -> Box<Drawable> {
         let mut wrapper = self.presentation_layout.layout(context);
 
         wrapper.wrap(MultiscriptDrawable {
@@ -57,7 +59,8 @@ impl Layout for MmultiscriptLayout {
             }).collect(),
             superscript_shift: self.superscript_shift,
             subscript_shift: self.subscript_shift,
-            display_style: self.presentation_layout.display_style,
+            dir: self.dir.clone(),
+            cramped: self.presentation_layout.cramped,
             base_size: self.presentation_layout.script_level.get_font_size(context, &MathSize::NORMAL),
             bounding_box: BoundingBox::default(),
         });
@@ -71,31 +74,32 @@ impl Layout for MmultiscriptLayout {
         self
     }
 
-    fn as_any_mut(&mut self) -> &mut Any {
-        self
+    fn as_any_mut(&mut self) -> Option<&mut Any> {
+        Some(self)
     }
 }
 
 
-struct MultiscriptDrawable<'a> {
-    base: Box<Drawable + 'a>,
+struct MultiscriptDrawable {
+    base: Box<Drawable>,
     base_pos: Point,
 
-    prescripts: Vec<MultiscriptDrawableChild<'a>>,
-    postscripts: Vec<MultiscriptDrawableChild<'a>>,
+    prescripts: Vec<MultiscriptDrawableChild>,
+    postscripts: Vec<MultiscriptDrawableChild>,
 
     superscript_shift: f32,
     subscript_shift: f32,
-    display_style: DisplayStyle,
+    dir: Directionality,
+    cramped: CrampedStyle,
     base_size: f32,
 
     bounding_box: BoundingBox,
 }
 
-struct MultiscriptDrawableChild<'a> {
-    subscript: Box<Drawable + 'a>,
+struct MultiscriptDrawableChild {
+    subscript: Box<Drawable>,
     subscript_pos: Point,
-    superscript: Box<Drawable + 'a>,
+    superscript: Box<Drawable>,
     superscript_pos: Point,
 }
 
@@ -115,8 +119,8 @@ impl ScriptPosition {
     }
 }
 
-impl<'a> MultiscriptDrawable<'a> {
-    fn find_script_y_pos(&self, child: &MultiscriptDrawableChild<'a>, ruler: &MathRuler) -> ScriptPosition {
+impl MultiscriptDrawable {
+    fn find_script_y_pos(&self, child: &MultiscriptDrawableChild, ruler: &MathRuler) -> ScriptPosition {
 
         let base = &self.base;
         let subscript = &child.subscript;
@@ -131,10 +135,10 @@ impl<'a> MultiscriptDrawable<'a> {
         let base_y_pos = if has_superscript {
             // Recommended shift between baseline of base and baseline of superscript
             let superscript_shift = if self.superscript_shift.is_nan() {
-                if self.display_style {
-                    ruler.superscript_shift_up()
-                } else {
+                if self.cramped {
                     ruler.superscript_shift_up_cramped()
+                } else {
+                    ruler.superscript_shift_up()
                 }
             } else {
                 self.superscript_shift
@@ -228,7 +232,7 @@ impl<'a> MultiscriptDrawable<'a> {
         }
     }
 
-    fn find_max_script_y_pos(&self, scripts: &Vec<MultiscriptDrawableChild<'a>>, ruler: &MathRuler) -> ScriptPosition {
+    fn find_max_script_y_pos(&self, scripts: &[MultiscriptDrawableChild], ruler: &MathRuler) -> ScriptPosition {
         let mut script_pos = ScriptPosition {
             base_baseline_pos: 0.0,
             superscript_baseline_pos: 0.0,
@@ -245,14 +249,41 @@ impl<'a> MultiscriptDrawable<'a> {
         return script_pos;
     }
 
-    fn draw_scripts(&self, scripts: &Vec<MultiscriptDrawableChild<'a>>, canvas: &Canvas, pen_pos: &Point) {
+    fn draw_scripts(&self, scripts: &[MultiscriptDrawableChild], canvas: &Canvas,
+                    pen_pos: &Point, highlight: &Highlight, selected: bool) {
         for script in scripts.iter() {
-            script.superscript.draw(canvas, &(pen_pos+&script.superscript_pos));
-            script.subscript.draw(canvas, &(pen_pos+&script.subscript_pos));
+            script.superscript.draw_highlighted(canvas, &(pen_pos+&script.superscript_pos),
+                                                highlight, selected);
+            script.subscript.draw_highlighted(canvas, &(pen_pos+&script.subscript_pos),
+                                              highlight, selected);
         }
     }
 
-    fn set_script_positions(scripts: &mut Vec<MultiscriptDrawableChild<'a>>,
+    fn hit_test_scripts(scripts: &[MultiscriptDrawableChild], pen_pos: &Point, point: &Point)
+        -> Option<HitResult> {
+        scripts.iter().rev()
+            .filter_map(|script| {
+                let subscript_pos = pen_pos+&script.subscript_pos;
+                let superscript_pos = pen_pos+&script.superscript_pos;
+                script.subscript.hit_test(&subscript_pos, point)
+                    .or_else(|| script.superscript.hit_test(&superscript_pos, point))
+            })
+            .next()
+    }
+
+    fn locate_in_scripts<'b>(scripts: &'b [MultiscriptDrawableChild], pen_pos: &Point,
+                             instance_id: &InstanceId) -> Option<(Point, &'b Drawable)> {
+        scripts.iter()
+            .filter_map(|script| {
+                let subscript_pos = pen_pos+&script.subscript_pos;
+                let superscript_pos = pen_pos+&script.superscript_pos;
+                script.subscript.locate(&subscript_pos, instance_id)
+                    .or_else(|| script.superscript.locate(&superscript_pos, instance_id))
+            })
+            .next()
+    }
+
+    fn set_script_positions(scripts: &mut [MultiscriptDrawableChild],
                             script_pos: &ScriptPosition, current_pen_x: f32, space: f32, is_post_script: bool) -> Rect {
         let mut pen_x = current_pen_x;
         let mut y_max = 0f32;
@@ -288,11 +319,16 @@ impl<'a> MultiscriptDrawable<'a> {
     }
 }
 
-impl<'a> Drawable for MultiscriptDrawable<'a> {
+impl Drawable for MultiscriptDrawable {
     fn draw(&self, canvas: &Canvas, pen_pos: &Point) {
-        self.draw_scripts(&self.prescripts, canvas, pen_pos);
-        self.base.draw(canvas, &(pen_pos+&self.base_pos));
-        self.draw_scripts(&self.postscripts, canvas, pen_pos);
+        self.draw_highlighted(canvas, pen_pos, &Highlight::none(), false);
+    }
+
+    fn draw_highlighted(&self, canvas: &Canvas, pen_pos: &Point, highlight: &Highlight,
+                        selected: bool) {
+        self.draw_scripts(&self.prescripts, canvas, pen_pos, highlight, selected);
+        self.base.draw_highlighted(canvas, &(pen_pos+&self.base_pos), highlight, selected);
+        self.draw_scripts(&self.postscripts, canvas, pen_pos, highlight, selected);
     }
 
     fn calculate(&mut self, context: &Context, width_mode: &MeasureMode, height_mode: &MeasureMode) {
@@ -322,18 +358,70 @@ impl<'a> Drawable for MultiscriptDrawable<'a> {
             &mut self.postscripts, &script_pos, pen_x, space_after_script, true);
         pen_x += postscript_bounds.width();
 
+        // Prescripts are on the right and postscripts on the left of base in right to left layout
+        if self.dir == Directionality::RTL {
+            let width = pen_x;
+            let mirror = |pos: &Point, drawable: &Box<Drawable>|
+                Point::new(width - pos.x() - drawable.bounding_box().width(), pos.y());
+
+            self.base_pos = mirror(&self.base_pos, &self.base);
+
+            for script in self.prescripts.iter_mut().chain(self.postscripts.iter_mut()) {
+                script.subscript_pos = mirror(&script.subscript_pos, &script.subscript);
+                script.superscript_pos = mirror(&script.superscript_pos, &script.superscript);
+            }
+        }
+
         let height = prescript_bounds.height()
             .max(postscript_bounds.height())
             .max(self.base.bounding_box().height());
 
+        let mut children = vec![(&self.base, &self.base_pos)];
+        for script in self.prescripts.iter().chain(self.postscripts.iter()) {
+            children.push((&script.subscript, &script.subscript_pos));
+            children.push((&script.superscript, &script.superscript_pos));
+        }
+        let ink = Ink::union_all(children.into_iter().filter_map(|(child, pos)|
+            child.bounding_box().ink_in_parent(pos, base_baseline_pos)));
+
         self.bounding_box = BoundingBox::new(
             Rect::new(pen_x, height),
             height - (base_baseline_pos),
             height - (self.base.bounding_box().axis_pos() - self.base.bounding_box().baseline_pos() + base_baseline_pos),
-        );
+        ).with_ink(ink);
     }
 
     fn bounding_box(&self) -> &BoundingBox {
         &self.bounding_box
     }
+
+    fn hit_test(&self, pen_pos: &Point, point: &Point) -> Option<HitResult> {
+        MultiscriptDrawable::hit_test_scripts(&self.postscripts, pen_pos, point)
+            .or_else(|| self.base.hit_test(&(pen_pos+&self.base_pos), point))
+            .or_else(|| MultiscriptDrawable::hit_test_scripts(&self.prescripts, pen_pos, point))
+    }
+
+    fn locate(&self, pen_pos: &Point, instance_id: &InstanceId) -> Option<(Point, &Drawable)> {
+        self.base.locate(&(pen_pos+&self.base_pos), instance_id)
+            .or_else(|| MultiscriptDrawable::locate_in_scripts(&self.postscripts, pen_pos,
+                                                               instance_id))
+            .or_else(|| MultiscriptDrawable::locate_in_scripts(&self.prescripts, pen_pos,
+                                                               instance_id))
+    }
+
+    fn to_snapshot(&self) -> BoxSnapshot {
+        let scripts = |scripts: &[MultiscriptDrawableChild]| -> Vec<(Point, BoxSnapshot)> {
+            scripts.iter()
+                .flat_map(|script| vec![
+                    (script.superscript_pos.clone(), script.superscript.to_snapshot()),
+                    (script.subscript_pos.clone(), script.subscript.to_snapshot()),
+                ])
+                .collect()
+        };
+
+        let mut children = scripts(&self.prescripts);
+        children.push((self.base_pos.clone(), self.base.to_snapshot()));
+        children.extend(scripts(&self.postscripts));
+        BoxSnapshot::group(self.bounding_box.clone(), children)
+    }
 }
\ No newline at end of file
@@ -17,15 +17,17 @@
 use std::any::Any;
 
 use super::super::{Layout, PresentationLayout, ConcreteLayout};
-use ::props::{HAlign, MathSize};
+use ::props::{HAlign, MathSize, Directionality};
 use ::platform::Context;
-use ::draw::{Drawable, BoundingBox, MeasureMode};
-use ::paint::{Canvas, Point, Rect};
+use ::draw::{Drawable, BoundingBox, MeasureMode, HitResult, Highlight, BoxSnapshot};
+use ::paint::{Canvas, Point, Rect, Ink};
+use ::elements::InstanceId;
 
 pub struct MunderoverLayout {
     pub(crate) accent_over: bool,
     pub(crate) accent_under: bool,
     pub(crate) align: HAlign,
+    pub(crate) dir: Directionality,
 
     pub(crate) base: Box<Layout>,
     pub(crate) underscript: Box<Layout>,
@@ -35,13 +37,14 @@ pub struct MunderoverLayout {
 }
 
 impl Layout for MunderoverLayout {
-    fn layout<'a>(&'a self, context: &Context) -> Box<Drawable + 'a> {
+    fn layout(&self, context: &Context) -> Box<Drawable> {
         let mut wrapper = self.presentation_layout.layout(context);
 
         wrapper.wrap(MunderoverDrawable {
             accent_over: self.accent_over,
             accent_under: self.accent_under,
             align: self.align.clone(),
+            dir: self.dir.clone(),
             base_size: self.presentation_layout.script_level.get_font_size(context, &MathSize::NORMAL),
             base: self.base.layout(context),
             underscript: self.underscript.layout(context),
@@ -61,21 +64,22 @@ impl Layout for MunderoverLayout {
         self
     }
 
-    fn as_any_mut(&mut self) -> &mut Any {
-        self
+    fn as_any_mut(&mut self) -> Option<&mut Any> {
+        Some(self)
     }
 }
 
 
-struct MunderoverDrawable<'a> {
+struct MunderoverDrawable {
     accent_over: bool,
     accent_under: bool,
     align: HAlign,
+    dir: Directionality,
     base_size: f32,
 
-    base: Box<Drawable + 'a>,
-    underscript: Box<Drawable + 'a>,
-    overscript: Box<Drawable + 'a>,
+    base: Box<Drawable>,
+    underscript: Box<Drawable>,
+    overscript: Box<Drawable>,
 
     bounding_box: BoundingBox,
     base_pos: Point,
@@ -83,80 +87,87 @@ struct MunderoverDrawable<'a> {
     overscript_pos: Point,
 }
 
-impl<'a> MunderoverDrawable<'a> {
+impl MunderoverDrawable {
+    // Alignment is mirrored in right to left layout, so that left aligns to the start
     fn get_x_pos_aligned(&self, layout_width: f32, child_width: f32) -> f32 {
-        match self.align {
+        let x_pos = match self.align {
             HAlign::Left => 0f32,
             HAlign::Center => (layout_width-child_width)/2f32,
             HAlign::Right => (layout_width-child_width),
+        };
+
+        match self.dir {
+            Directionality::LTR => x_pos,
+            Directionality::RTL => layout_width-child_width-x_pos,
         }
     }
 }
 
-impl<'a> Drawable for MunderoverDrawable<'a> {
+impl Drawable for MunderoverDrawable {
     fn draw(&self, canvas: &Canvas, pen_pos: &Point) {
         self.overscript.draw(canvas, &(pen_pos+&self.overscript_pos));
         self.base.draw(canvas, &(pen_pos+&self.base_pos));
         self.underscript.draw(canvas, &(pen_pos+&self.underscript_pos));
     }
 
+    fn draw_highlighted(&self, canvas: &Canvas, pen_pos: &Point, highlight: &Highlight,
+                        selected: bool) {
+        self.overscript.draw_highlighted(canvas, &(pen_pos+&self.overscript_pos), highlight,
+                                         selected);
+        self.base.draw_highlighted(canvas, &(pen_pos+&self.base_pos), highlight, selected);
+        self.underscript.draw_highlighted(canvas, &(pen_pos+&self.underscript_pos), highlight,
+                                          selected);
+    }
+
     fn calculate(&mut self, context: &Context, width_mode: &MeasureMode, height_mode: &MeasureMode) {
         let ruler = context.platform().get_math_ruler(self.base_size);
 
         self.base.calculate(context, width_mode, height_mode);
 
-        let has_overscript = self.overscript.bounding_box().width() > 0f32 || self.overscript.bounding_box().height() > 0f32;
-        let has_underscript = self.underscript.bounding_box().width() > 0f32 || self.underscript.bounding_box().height() > 0f32;
-
-        // Fix y position of overscript
-        let overscript_y_pos = 0f32;
-
-        let base_y_pos = if has_overscript {
-            // Minimum shift between top of base and baseline of overscript
-            let upper_limit_baseline_rise_min = ruler.upper_limit_baseline_rise_min();
-
-            let mut base_y_pos = overscript_y_pos +
-                self.overscript.bounding_box().baseline_pos() +
-                upper_limit_baseline_rise_min;
-
-            // Minimum shift between top of base and bottom of overscript
-            let upper_limit_gap_min = ruler.upper_limit_gap_min();
+        let stretch_width = self.base.bounding_box().width()
+            .max(self.overscript.bounding_box().width())
+            .max(self.underscript.bounding_box().width());
 
-            base_y_pos = base_y_pos.max(
-                overscript_y_pos + self.overscript.bounding_box().height() + upper_limit_gap_min);
+        self.overscript.calculate(context, &MeasureMode::UpTo(stretch_width), &MeasureMode::Wrap);
+        self.underscript.calculate(context, &MeasureMode::UpTo(stretch_width), &MeasureMode::Wrap);
 
-            base_y_pos
-        } else {
-            0f32
-        };
+        let has_overscript = self.overscript.bounding_box().width() > 0f32 || self.overscript.bounding_box().height() > 0f32;
+        let has_underscript = self.underscript.bounding_box().width() > 0f32 || self.underscript.bounding_box().height() > 0f32;
 
-        let underscript_y_pos = if has_underscript {
-            // Minimum shift between bottom of base and baseline of underscript
-            let lower_limit_baseline_drop_min = ruler.lower_limit_baseline_drop_min();
+        let base_box = self.base.bounding_box().clone();
 
-            let mut underscript_y_pos = base_y_pos +
-                self.base.bounding_box().height() +
-                lower_limit_baseline_drop_min -
-                self.underscript.bounding_box().baseline_pos();
+        // Baselines of scripts from the base baseline, with gaps measured to the ink of base
+        // and scripts
+        let overscript_baseline = -(base_box.ink_ascent() + ruler.upper_limit_baseline_rise_min()
+            .max(ruler.upper_limit_gap_min() - self.overscript.bounding_box().ink_descent()));
+        let underscript_baseline = -base_box.ink_descent() + ruler.lower_limit_baseline_drop_min()
+            .max(ruler.lower_limit_gap_min() + self.underscript.bounding_box().ink_ascent());
 
-            // Minimum shift between bottom of base and top of underscript
-            let lower_limit_gap_min = ruler.lower_limit_gap_min();
+        let base_top = -base_box.baseline_pos();
+        let overscript_top = overscript_baseline - self.overscript.bounding_box().baseline_pos();
+        let underscript_top = underscript_baseline - self.underscript.bounding_box().baseline_pos();
 
-            underscript_y_pos = underscript_y_pos.max(
-                base_y_pos + self.base.bounding_box().height() + lower_limit_gap_min);
+        let mut top = base_top;
+        let mut bottom = base_top + base_box.height();
+        if has_overscript {
+            top = top.min(overscript_top);
+            bottom = bottom.max(overscript_top + self.overscript.bounding_box().height());
+        }
+        if has_underscript {
+            top = top.min(underscript_top);
+            bottom = bottom.max(underscript_top + self.underscript.bounding_box().height());
+        }
 
-            underscript_y_pos
-        } else {
-            base_y_pos+self.base.bounding_box().height()
-        };
+        let overscript_y_pos = overscript_top - top;
+        let base_y_pos = base_top - top;
+        let underscript_y_pos = underscript_top - top;
 
+        // Stretched scripts can be wider than the width they are stretched to, when parts of a
+        // glyph assembly can not overlap enough to fit it
         let layout_width = self.base.bounding_box().width()
             .max(self.overscript.bounding_box().width())
             .max(self.underscript.bounding_box().width());
 
-        self.overscript.calculate(context, &MeasureMode::UpTo(layout_width), &MeasureMode::Wrap);
-        self.underscript.calculate(context, &MeasureMode::UpTo(layout_width), &MeasureMode::Wrap);
-
         let overscript_x_pos = self.get_x_pos_aligned(layout_width, self.overscript.bounding_box().width());
         let base_x_pos = self.get_x_pos_aligned(layout_width, self.base.bounding_box().width());
         let underscript_x_pos = self.get_x_pos_aligned(layout_width, self.underscript.bounding_box().width());
@@ -165,16 +176,47 @@ impl<'a> Drawable for MunderoverDrawable<'a> {
         self.base_pos = Point::new(base_x_pos, base_y_pos);
         self.underscript_pos = Point::new(underscript_x_pos, underscript_y_pos);
 
-        let layout_height = underscript_y_pos + self.underscript.bounding_box().height();
+        let layout_height = bottom - top;
+        let baseline_pos = base_y_pos + base_box.baseline_pos();
+
+        let mut children = vec![(&self.base, &self.base_pos)];
+        if has_overscript {
+            children.push((&self.overscript, &self.overscript_pos));
+        }
+        if has_underscript {
+            children.push((&self.underscript, &self.underscript_pos));
+        }
+        let ink = Ink::union_all(children.into_iter().filter_map(|(child, pos)|
+            child.bounding_box().ink_in_parent(pos, baseline_pos)));
 
         self.bounding_box = BoundingBox::new(
             Rect::new(layout_width, layout_height),
-            layout_height-(base_y_pos+self.base.bounding_box().baseline_pos()),
-            layout_height-(base_y_pos+self.base.bounding_box().axis_pos()),
-        );
+            layout_height-baseline_pos,
+            layout_height-(base_y_pos+base_box.axis_pos()),
+        ).with_ink(ink);
     }
 
     fn bounding_box(&self) -> &BoundingBox {
         &self.bounding_box
     }
+
+    fn hit_test(&self, pen_pos: &Point, point: &Point) -> Option<HitResult> {
+        self.underscript.hit_test(&(pen_pos+&self.underscript_pos), point)
+            .or_else(|| self.base.hit_test(&(pen_pos+&self.base_pos), point))
+            .or_else(|| self.overscript.hit_test(&(pen_pos+&self.overscript_pos), point))
+    }
+
+    fn locate(&self, pen_pos: &Point, instance_id: &InstanceId) -> Option<(Point, &Drawable)> {
+        self.base.locate(&(pen_pos+&self.base_pos), instance_id)
+            .or_else(|| self.underscript.locate(&(pen_pos+&self.underscript_pos), instance_id))
+            .or_else(|| self.overscript.locate(&(pen_pos+&self.overscript_pos), instance_id))
+    }
+
+    fn to_snapshot(&self) -> BoxSnapshot {
+        BoxSnapshot::group(self.bounding_box.clone(), vec![
+            (self.overscript_pos.clone(), self.overscript.to_snapshot()),
+            (self.base_pos.clone(), self.base.to_snapshot()),
+            (self.underscript_pos.clone(), self.underscript.to_snapshot()),
+        ])
+    }
 }
\ No newline at end of file
@@ -16,7 +16,7 @@
 
 use std::any::Any;
 
-use super::{TokenLayout, PresentationLayout};
+use super::TokenLayout;
 use super::super::{Layout, ConcreteLayout};
 use ::props::{MathVariant, Directionality, Color};
 use ::platform::Context;
@@ -43,7 +43,7 @@ impl MiLayout {
 }
 
 impl Layout for MiLayout {
-    fn layout<'a>(&'a self, context: &Context) -> Box<Drawable + 'a> {
+    fn layout(&self, context: &Context) -> Box<Drawable> {
         Box::new(ConcreteLayout::layout(self, context))
     }
 
@@ -51,14 +51,14 @@ impl Layout for MiLayout {
         self
     }
 
-    fn as_any_mut(&mut self) -> &mut Any {
-        self
+    fn as_any_mut(&mut self) -> Option<&mut Any> {
+        Some(self)
     }
 }
 
-impl<'a> ConcreteLayout<'a, Wrapper<'a, PresentationLayout, Text<'a, TokenLayout>>> for MiLayout {
-    fn layout(&'a self, context: &Context) -> Wrapper<'a, PresentationLayout, Text<'a, TokenLayout>> {
-        <TokenLayout as ConcreteLayout<'a, Wrapper<'a, PresentationLayout, Text<'a, TokenLayout>>>>
+impl ConcreteLayout<Wrapper<Text>> for MiLayout {
+    fn layout(&self, context: &Context) -> Wrapper<Text> {
+        <TokenLayout as ConcreteLayout<Wrapper<Text>>>
         ::layout(&self.token_element, context)
     }
 }
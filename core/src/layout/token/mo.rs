@@ -22,7 +22,7 @@ use ::props::{
     Color
 };
 use super::{Layout, ConcreteLayout, TokenLayout};
-use ::draw::{PaddingBox, Drawable, Symbol, MeasureMode};
+use ::draw::{PaddingBox, Drawable, Symbol, MeasureMode, Wrapper};
 use ::platform::Context;
 
 pub struct MoLayout {
@@ -35,12 +35,14 @@ pub struct MoLayout {
     pub(crate) large_op: bool,
     pub(crate) movable_limits: bool,
     pub(crate) accent: bool,
+    /// Accent over a base taller than the flattened accent base height
+    pub(crate) flattened_accent: bool,
 
     pub(crate) token_element: TokenLayout,
 }
 
 impl Layout for MoLayout {
-    fn layout<'a>(&'a self, context: &Context) -> Box<Drawable + 'a> {
+    fn layout(&self, context: &Context) -> Box<Drawable> {
         Box::new(ConcreteLayout::layout(self, context))
     }
 
@@ -48,13 +50,16 @@ impl Layout for MoLayout {
         self
     }
 
-    fn as_any_mut(&mut self) -> &mut Any {
-        self
+    fn as_any_mut(&mut self) -> Option<&mut Any> {
+        Some(self)
     }
 }
 
-impl<'a> ConcreteLayout<'a, PaddingBox<'a, MoLayout, Symbol<'a, MoLayout>>> for MoLayout {
-    fn layout(&'a self, context: &Context) -> PaddingBox<'a, MoLayout, Symbol<'a, MoLayout>> {
+type MoDrawable =
+    Wrapper<PaddingBox<Symbol>>;
+
+impl ConcreteLayout<MoDrawable> for MoLayout {
+    fn layout(&self, context: &Context) -> MoDrawable {
         let mut padding_box = PaddingBox::new(
             self,
             |s| s.lspace,
@@ -63,7 +68,7 @@ impl<'a> ConcreteLayout<'a, PaddingBox<'a, MoLayout, Symbol<'a, MoLayout>>> for
             |_| 0.,
         );
 
-        let symbol = Symbol::new(
+        let mut symbol = Symbol::new(
             self,
             |s| &s.token_element.text,
             |s| &s.token_element.math_variant,
@@ -75,11 +80,18 @@ impl<'a> ConcreteLayout<'a, PaddingBox<'a, MoLayout, Symbol<'a, MoLayout>>> for
             |s| &s.token_element.presentation_element.math_color,
         );
 
+        symbol.set_script_level(self.token_element.presentation_element.script_level
+            .get_current_level());
+        symbol.set_flattened_accent(self.flattened_accent);
+
         padding_box.wrap(symbol);
 
-        padding_box.calculate(context, &MeasureMode::Wrap, &MeasureMode::Wrap);
+        let mut wrapper = self.token_element.presentation_element.layout(context);
+        wrapper.wrap(padding_box);
+
+        wrapper.calculate(context, &MeasureMode::Wrap, &MeasureMode::Wrap);
 
-        padding_box
+        wrapper
     }
 }
 
@@ -113,6 +125,7 @@ impl MoLayout {
             large_op,
             movable_limits,
             accent,
+            flattened_accent: false,
 
             token_element: TokenLayout::new(
                 text,
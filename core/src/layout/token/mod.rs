@@ -26,7 +26,7 @@ use std::rc::Rc;
 use std::any::Any;
 
 use super::{ConcreteLayout, Layout, PresentationLayout};
-use ::draw::{Text, Drawable, Wrapper, MeasureMode};
+use ::draw::{Text, TextFont, Drawable, Wrapper, MeasureMode};
 use ::platform::{Context};
 use ::props::{MathVariant, Directionality, Color};
 
@@ -36,12 +36,15 @@ pub struct TokenLayout {
     pub(crate) math_variant: MathVariant,
     pub(crate) math_size: f32,
     pub(crate) dir: Directionality,
+    pub(crate) text_font: TextFont,
+    /// Base carrying an accent, whose letters are drawn dotless
+    pub(crate) dotless: bool,
 
     pub(crate) presentation_element: PresentationLayout
 }
 
 impl Layout for TokenLayout {
-    fn layout<'a>(&'a self, _context: &Context) -> Box<Drawable + 'a> {
+    fn layout(&self, _context: &Context) -> Box<Drawable> {
         unimplemented!()
     }
 
@@ -49,17 +52,20 @@ impl Layout for TokenLayout {
         self
     }
 
-    fn as_any_mut(&mut self) -> &mut Any {
-        self
+    fn as_any_mut(&mut self) -> Option<&mut Any> {
+        Some(self)
     }
 }
 
-impl<'a> ConcreteLayout<'a, Wrapper<'a, PresentationLayout, Text<'a, TokenLayout>>> for TokenLayout {
-    fn layout(&'a self, context: &Context) -> Wrapper<'a, PresentationLayout, Text<'a, TokenLayout>> {
+impl ConcreteLayout<Wrapper<Text>> for TokenLayout {
+    fn layout(&self, context: &Context) -> Wrapper<Text> {
         let mut layout = self.presentation_element.layout(context);
 
-        let text = Text::new(self, text_reader, math_size_reader,
-                             math_variant_reader, dir_reader, math_color_reader);
+        let mut text = Text::new(self, text_reader, math_size_reader,
+                                 math_variant_reader, dir_reader, math_color_reader);
+        text.set_font(self.text_font);
+        text.set_script_level(self.presentation_element.script_level.get_current_level());
+        text.set_dotless(self.dotless);
 
         layout.wrap(text);
         layout.calculate(context, &MeasureMode::Wrap, &MeasureMode::Wrap);
@@ -76,6 +82,8 @@ impl TokenLayout {
             math_variant,
             math_size,
             dir,
+            text_font: TextFont::Math,
+            dotless: false,
             presentation_element: PresentationLayout::new(math_color, math_background),
         }
     }
@@ -16,7 +16,7 @@
 
 use std::any::Any;
 
-use super::{TokenLayout, PresentationLayout};
+use super::TokenLayout;
 use super::super::{Layout, ConcreteLayout};
 use ::platform::Context;
 use ::draw::{Drawable, Wrapper, Text, LinearLayout, Gravity, Align, LinearLayoutParams, MeasureMode};
@@ -28,7 +28,7 @@ pub struct MsLayout {
 }
 
 impl Layout for MsLayout {
-    fn layout<'a>(&'a self, context: &Context) -> Box<Drawable + 'a> {
+    fn layout(&self, context: &Context) -> Box<Drawable> {
         Box::new(ConcreteLayout::layout(self, context))
     }
 
@@ -36,18 +36,18 @@ impl Layout for MsLayout {
         self
     }
 
-    fn as_any_mut(&mut self) -> &mut Any {
-        self
+    fn as_any_mut(&mut self) -> Option<&mut Any> {
+        Some(self)
     }
 }
 
-impl<'a> ConcreteLayout<'a, Wrapper<'a, MsLayout, LinearLayout<'a>>> for MsLayout {
-    fn layout(&'a self, context: &Context) -> Wrapper<'a, MsLayout, LinearLayout<'a>> {
+impl ConcreteLayout<Wrapper<LinearLayout>> for MsLayout {
+    fn layout(&self, context: &Context) -> Wrapper<LinearLayout> {
         let token_layout =
-            <TokenLayout as ConcreteLayout<'a, Wrapper<'a, PresentationLayout, Text<'a, TokenLayout>>>>
+            <TokenLayout as ConcreteLayout<Wrapper<Text>>>
             ::layout(&self.token_element, context);
 
-        let lquote_layout = Text::new(
+        let mut lquote_layout = Text::new(
             self,
             |ms| &ms.lquote,
             |ms| ms.token_element.math_size,
@@ -56,7 +56,7 @@ impl<'a> ConcreteLayout<'a, Wrapper<'a, MsLayout, LinearLayout<'a>>> for MsLayou
             |ms| &ms.token_element.presentation_element.math_color
         );
 
-        let rquote_layout = Text::new(
+        let mut rquote_layout = Text::new(
             self,
             |ms| &ms.rquote,
             |ms| ms.token_element.math_size,
@@ -65,6 +65,13 @@ impl<'a> ConcreteLayout<'a, Wrapper<'a, MsLayout, LinearLayout<'a>>> for MsLayou
             |ms| &ms.token_element.presentation_element.math_color
         );
 
+        lquote_layout.set_font(self.token_element.text_font);
+        rquote_layout.set_font(self.token_element.text_font);
+
+        let script_level = self.token_element.presentation_element.script_level.get_current_level();
+        lquote_layout.set_script_level(script_level);
+        rquote_layout.set_script_level(script_level);
+
         let mut layout = LinearLayout::new();
         layout.gravity = Gravity::Horizontal;
         layout.layout_align = Align::Baseline;
@@ -77,6 +84,7 @@ impl<'a> ConcreteLayout<'a, Wrapper<'a, MsLayout, LinearLayout<'a>>> for MsLayou
             self,
             |ms| &ms.token_element.presentation_element.math_background,
         );
+        wrapper.set_hit_target(self.token_element.presentation_element.hit_target.clone());
 
         wrapper.wrap(layout);
 
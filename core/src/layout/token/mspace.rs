@@ -32,7 +32,7 @@ pub struct MspaceLayout {
 }
 
 impl Layout for MspaceLayout {
-    fn layout<'a>(&'a self, context: &Context) -> Box<Drawable + 'a> {
+    fn layout(&self, context: &Context) -> Box<Drawable> {
         Box::new(ConcreteLayout::layout(self, context))
     }
 
@@ -40,18 +40,19 @@ impl Layout for MspaceLayout {
         self
     }
 
-    fn as_any_mut(&mut self) -> &mut Any {
-        self
+    fn as_any_mut(&mut self) -> Option<&mut Any> {
+        Some(self)
     }
 }
 
-impl<'a> ConcreteLayout<'a, Wrapper<'a, MspaceLayout, Space>> for MspaceLayout {
-    fn layout(&'a self, context: &Context) -> Wrapper<'a, MspaceLayout, Space> {
+impl ConcreteLayout<Wrapper<Space>> for MspaceLayout {
+    fn layout(&self, context: &Context) -> Wrapper<Space> {
         let space = Space::new(self.width, self.height+self.depth, self.depth, self.depth);
         let mut wrapper = Wrapper::new(
             self,
             |mspace| &mspace.presentation_layout.math_background
         );
+        wrapper.set_hit_target(self.presentation_layout.hit_target.clone());
 
         wrapper.wrap(space);
 
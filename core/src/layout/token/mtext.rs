@@ -16,11 +16,11 @@
 
 use std::any::Any;
 
-use super::{TokenLayout, PresentationLayout};
+use super::TokenLayout;
 use super::super::{Layout, ConcreteLayout};
 use ::props::{MathVariant, Directionality, Color};
 use ::platform::Context;
-use ::draw::{Drawable, Wrapper, Text};
+use ::draw::{Drawable, Wrapper, Text, TextFont};
 
 pub struct MtextLayout {
     pub(crate) token_element: TokenLayout
@@ -29,21 +29,18 @@ pub struct MtextLayout {
 impl MtextLayout {
     pub fn new(text: String, math_variant: MathVariant, math_size: f32, dir: Directionality,
                math_color: Color, math_background: Color) -> MtextLayout {
+        let mut token_element = TokenLayout::new(text, math_variant, math_size, dir, math_color,
+                                                 math_background);
+        token_element.text_font = TextFont::Text;
+
         MtextLayout {
-            token_element: TokenLayout::new(
-                text,
-                math_variant,
-                math_size,
-                dir,
-                math_color,
-                math_background,
-            )
+            token_element
         }
     }
 }
 
 impl Layout for MtextLayout {
-    fn layout<'a>(&'a self, context: &Context) -> Box<Drawable + 'a> {
+    fn layout(&self, context: &Context) -> Box<Drawable> {
         Box::new(ConcreteLayout::layout(self, context))
     }
 
@@ -51,14 +48,14 @@ impl Layout for MtextLayout {
         self
     }
 
-    fn as_any_mut(&mut self) -> &mut Any {
-        self
+    fn as_any_mut(&mut self) -> Option<&mut Any> {
+        Some(self)
     }
 }
 
-impl<'a> ConcreteLayout<'a, Wrapper<'a, PresentationLayout, Text<'a, TokenLayout>>> for MtextLayout {
-    fn layout(&'a self, context: &Context) -> Wrapper<'a, PresentationLayout, Text<'a, TokenLayout>> {
-        <TokenLayout as ConcreteLayout<'a, Wrapper<'a, PresentationLayout, Text<'a, TokenLayout>>>>
+impl ConcreteLayout<Wrapper<Text>> for MtextLayout {
+    fn layout(&self, context: &Context) -> Wrapper<Text> {
+        <TokenLayout as ConcreteLayout<Wrapper<Text>>>
         ::layout(&self.token_element, context)
     }
 }
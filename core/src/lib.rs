@@ -21,14 +21,15 @@
 pub extern crate akriti_constants;
 pub use akriti_constants as constants;
 
+pub mod backend;
 pub mod draw;
+pub mod editor;
 pub mod elements;
+pub mod font;
 pub mod layout;
 pub mod paint;
 pub mod props;
 pub mod platform;
 pub mod utils;
 
-#[cfg(test)] pub extern crate akriti_measure;
-#[cfg(test)] pub extern crate skia_sys;
 #[cfg(test)] pub mod test;
\ No newline at end of file
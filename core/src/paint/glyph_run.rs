@@ -0,0 +1,100 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use super::typeface::TypeFace;
+use ::props::Directionality;
+
+/// Positioned glyph of a shaped run. Offsets move the glyph from its pen position, with y
+/// growing upwards as in font design space.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShapedGlyph {
+    glyph_index: u32,
+    advance: f32,
+    x_offset: f32,
+    y_offset: f32,
+    cluster: usize,
+}
+
+impl ShapedGlyph {
+    pub fn new(glyph_index: u32, advance: f32, x_offset: f32, y_offset: f32, cluster: usize)
+        -> ShapedGlyph {
+        ShapedGlyph { glyph_index, advance, x_offset, y_offset, cluster }
+    }
+
+    pub fn glyph_index(&self) -> u32 {
+        self.glyph_index
+    }
+
+    pub fn advance(&self) -> f32 {
+        self.advance
+    }
+
+    pub fn x_offset(&self) -> f32 {
+        self.x_offset
+    }
+
+    pub fn y_offset(&self) -> f32 {
+        self.y_offset
+    }
+
+    /// Byte offset in the run text of the first character this glyph was shaped from
+    pub fn cluster(&self) -> usize {
+        self.cluster
+    }
+}
+
+/// Text shaped by a `TextRuler` into glyphs of one face and size. Glyphs are in visual order,
+/// left to right, so that canvases draw them without shaping again.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlyphRun {
+    text: String,
+    typeface: TypeFace,
+    size: f32,
+    dir: Directionality,
+    glyphs: Vec<ShapedGlyph>,
+}
+
+impl GlyphRun {
+    pub fn new(text: String, typeface: TypeFace, size: f32, dir: Directionality,
+               glyphs: Vec<ShapedGlyph>) -> GlyphRun {
+        GlyphRun { text, typeface, size, dir, glyphs }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn typeface(&self) -> &TypeFace {
+        &self.typeface
+    }
+
+    pub fn size(&self) -> f32 {
+        self.size
+    }
+
+    pub fn dir(&self) -> &Directionality {
+        &self.dir
+    }
+
+    pub fn glyphs(&self) -> &[ShapedGlyph] {
+        &self.glyphs
+    }
+
+    /// Sum of glyph advances
+    pub fn width(&self) -> f32 {
+        self.glyphs.iter().map(|glyph| glyph.advance).sum()
+    }
+}
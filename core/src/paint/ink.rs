@@ -0,0 +1,90 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+
+/// Extents of the painted part of a glyph or drawable around its origin on the baseline. Left
+/// and right are measured along the advance, ascent above the baseline and descent below it,
+/// negative below the baseline like font descents.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ink {
+    left: f32,
+    right: f32,
+    ascent: f32,
+    descent: f32,
+}
+
+impl Ink {
+    pub fn new(left: f32, right: f32, ascent: f32, descent: f32) -> Ink {
+        Ink { left, right, ascent, descent }
+    }
+
+    pub fn left(&self) -> f32 {
+        self.left
+    }
+
+    pub fn right(&self) -> f32 {
+        self.right
+    }
+
+    pub fn ascent(&self) -> f32 {
+        self.ascent
+    }
+
+    pub fn descent(&self) -> f32 {
+        self.descent
+    }
+
+    pub fn width(&self) -> f32 {
+        self.right - self.left
+    }
+
+    pub fn height(&self) -> f32 {
+        self.ascent - self.descent
+    }
+
+    /// Ink moved right by `dx` and up by `dy`
+    pub fn translate(&self, dx: f32, dy: f32) -> Ink {
+        Ink::new(self.left + dx, self.right + dx, self.ascent + dy, self.descent + dy)
+    }
+
+    pub fn union(&self, other: &Ink) -> Ink {
+        Ink::new(self.left.min(other.left), self.right.max(other.right),
+                 self.ascent.max(other.ascent), self.descent.min(other.descent))
+    }
+
+    /// Smallest ink covering all of `inks`, `None` when there is nothing painted
+    pub fn union_all<I: Iterator<Item=Ink>>(inks: I) -> Option<Ink> {
+        inks.fold(None, |union, ink| Some(match union {
+            Some(union) => union.union(&ink),
+            None => ink,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_unites_translated_ink() {
+        let dot = Ink::new(1., 3., 10., 8.);
+        let bar = Ink::new(0., 4., 1., -1.).translate(2., -1.);
+
+        assert_eq!(bar, Ink::new(2., 6., 0., -2.));
+        assert_eq!(Ink::union_all(vec![dot, bar].into_iter()), Some(Ink::new(1., 6., 10., -2.)));
+        assert_eq!(Ink::union_all(Vec::new().into_iter()), None);
+    }
+}
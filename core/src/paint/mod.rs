@@ -16,8 +16,10 @@
 
 
 mod canvas;                     pub use self::canvas::Canvas;
+mod glyph_run;                  pub use self::glyph_run::{GlyphRun, ShapedGlyph};
+mod ink;                        pub use self::ink::Ink;
 mod point;                      pub use self::point::Point;
 mod rect;                       pub use self::rect::Rect;
 mod ruler;                      pub use self::ruler::*;
 mod style;                      pub use self::style::Style;
-mod typeface;                   pub use self::typeface::TypeFace;
\ No newline at end of file
+mod typeface;                   pub use self::typeface::{TypeFace, FontSynthesis};
\ No newline at end of file
@@ -15,7 +15,9 @@
 */
 
 
-#[derive(Debug, Clone)]
+/// Width and height of a box. Either can be negative, such as for negative spaces, in which case
+/// the box moves the pen backwards and has no area of its own.
+#[derive(Debug, Clone, PartialEq)]
 pub struct Rect {
     width: f32,
     height: f32
@@ -23,8 +25,6 @@ pub struct Rect {
 
 impl Rect {
     pub fn new(width: f32, height: f32) -> Rect {
-        assert!(width >= 0.0, "Width of rectangle should be greater than zero");
-        assert!(height >= 0.0, "Height of rectangle should be greater than zero");
         Rect {
             width,
             height
@@ -38,4 +38,8 @@ impl Rect {
     pub fn width(&self) -> f32 {
         self.width
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.width <= 0. || self.height <= 0.
+    }
 }
\ No newline at end of file
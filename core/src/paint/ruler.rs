@@ -17,6 +17,8 @@
 
 use super::typeface::TypeFace;
 use super::rect::Rect;
+use super::glyph_run::GlyphRun;
+use super::ink::Ink;
 use ::props::Directionality;
 
 pub trait TextRuler {
@@ -26,6 +28,23 @@ pub trait TextRuler {
     fn measure(&self, text: &str, dir: &Directionality) -> Rect;
     fn measure_char(&self, unicode: u32, dir: &Directionality) -> Rect;
     fn measure_glyph(&self, glyph_index: u32, dir: &Directionality) -> Rect;
+    /// Glyphs drawing `text` in `language`, a BCP 47 tag when known
+    fn shape(&self, text: &str, dir: &Directionality, language: Option<&str>) -> GlyphRun;
+    /// Dotless form of a letter carrying an accent, from the `dtls` feature
+    fn dotless_glyph(&self, glyph_index: u32) -> Option<u32>;
+    /// Ink of a glyph around its origin, `None` for glyphs without outline such as spaces
+    fn glyph_ink(&self, glyph_index: u32) -> Option<Ink>;
+
+    /// Ink of all glyphs of a shaped run around the origin of its first glyph
+    fn run_ink(&self, run: &GlyphRun) -> Option<Ink> {
+        let mut pen = 0f32;
+        Ink::union_all(run.glyphs().iter().filter_map(|glyph| {
+            let ink = self.glyph_ink(glyph.glyph_index())
+                .map(|ink| ink.translate(pen + glyph.x_offset(), glyph.y_offset()));
+            pen += glyph.advance();
+            ink
+        }))
+    }
 
     fn ascent(&self) -> f32;
     fn descent(&self) -> f32;
@@ -91,6 +110,13 @@ pub trait MathRuler : TextRuler {
     fn minimum_connector_overlap(&self, direction: &GlyphConstructionDirection) -> f32;
     fn glyph_variants(&self, unicode: u32, direction: &GlyphConstructionDirection) -> Vec<GlyphVariant>;
     fn glyph_assembly(&self, unicode: u32, direction: &GlyphConstructionDirection) -> GlyphAssembly;
+    fn rtl_mirrored_glyph(&self, unicode: u32) -> Option<u32>;
+    /// Optical variant drawn at `script_level` from the `ssty` feature, none at level zero.
+    /// Levels past two share the variant of level two.
+    fn script_style_glyph(&self, glyph_index: u32, script_level: u32) -> Option<u32>;
+    /// Accent flattened for bases taller than `flattened_accent_base_height`, from the `flac`
+    /// feature
+    fn flattened_accent_glyph(&self, glyph_index: u32) -> Option<u32>;
 }
 
 #[derive(Debug, Clone, PartialOrd, PartialEq)]
@@ -15,7 +15,8 @@
 */
 
 
-#[derive(Debug)]
+/// Font face identified by its family and style names, such as "STIX Two Math" and "Regular"
+#[derive(Debug, Clone, PartialEq, Default)]
 pub struct TypeFace {
     font_family: String,
     font_style: String
@@ -28,4 +29,57 @@ impl TypeFace {
             font_family: family,
         }
     }
+
+    pub fn font_family(&self) -> &str {
+        &self.font_family
+    }
+
+    pub fn font_style(&self) -> &str {
+        &self.font_style
+    }
+}
+
+/// Style to be synthesized while drawing text, used when the font has no glyph for the styled
+/// or mirrored character.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FontSynthesis {
+    bold: bool,
+    italic: bool,
+    mirror: bool,
+}
+
+impl FontSynthesis {
+    pub fn new(bold: bool, italic: bool) -> FontSynthesis {
+        FontSynthesis { bold, italic, mirror: false }
+    }
+
+    /// Glyph flipped horizontally, for right to left text
+    pub fn mirrored() -> FontSynthesis {
+        FontSynthesis { bold: false, italic: false, mirror: true }
+    }
+
+    pub fn none() -> FontSynthesis {
+        FontSynthesis::default()
+    }
+
+    pub fn with_mirror(mut self, mirror: bool) -> FontSynthesis {
+        self.mirror = mirror;
+        self
+    }
+
+    pub fn bold(&self) -> bool {
+        self.bold
+    }
+
+    pub fn italic(&self) -> bool {
+        self.italic
+    }
+
+    pub fn mirror(&self) -> bool {
+        self.mirror
+    }
+
+    pub fn is_none(&self) -> bool {
+        !self.bold && !self.italic && !self.mirror
+    }
 }
\ No newline at end of file
@@ -18,14 +18,19 @@
 use super::Platform;
 use ::constants::{
     OperatorDictionary, 
-    MathVariantsDictionary
+    MathVariantsDictionary,
+    BidiMirroringDictionary
 };
+use ::elements::LayoutCache;
 
 pub struct Context {
     platform: Box<Platform>,
     operator_dictionary: OperatorDictionary,
     math_variants_dictionary: MathVariantsDictionary,
-    font_size: f32
+    bidi_mirroring_dictionary: BidiMirroringDictionary,
+    font_size: f32,
+    text_font_identifiers: bool,
+    layout_cache: Option<LayoutCache>,
 }
 
 impl Context {
@@ -34,10 +39,28 @@ impl Context {
             platform,
             operator_dictionary: OperatorDictionary::new(),
             math_variants_dictionary: MathVariantsDictionary::new(),
-            font_size
+            bidi_mirroring_dictionary: BidiMirroringDictionary::new(),
+            font_size,
+            text_font_identifiers: false,
+            layout_cache: None,
         }
     }
 
+    /// Sets multi-letter identifiers like function names in the text font, as `mtext`
+    pub fn set_text_font_identifiers(&mut self, enabled: bool) {
+        self.text_font_identifiers = enabled;
+    }
+
+    /// Keeps layouts of elements between layouts with `cache`. Settings of the context are not
+    /// part of the cache keys, so the cache must be cleared when they change.
+    pub fn set_layout_cache(&mut self, cache: Option<LayoutCache>) {
+        self.layout_cache = cache;
+    }
+
+    pub fn layout_cache(&self) -> Option<&LayoutCache> {
+        self.layout_cache.as_ref()
+    }
+
     pub fn platform(&self) -> &Platform {
         self.platform.as_ref()
     }
@@ -50,7 +73,15 @@ impl Context {
         &self.math_variants_dictionary
     }
 
+    pub fn bidi_mirroring_dictionary(&self) -> &BidiMirroringDictionary {
+        &self.bidi_mirroring_dictionary
+    }
+
     pub fn font_size(&self) -> f32 {
         self.font_size
     }
+
+    pub fn text_font_identifiers(&self) -> bool {
+        self.text_font_identifiers
+    }
 }
\ No newline at end of file
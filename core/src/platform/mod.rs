@@ -21,16 +21,44 @@ use std::any::Any;
 
 use ::paint::{
     TextRuler, 
-    MathRuler
+    MathRuler,
+    TypeFace,
 };
 
 pub trait Platform {
     fn get_text_ruler(&self, size: f32) -> &TextRuler;
     fn get_math_ruler(&self, size: f32) -> &MathRuler;
+    /// Ruler of any face of the font stack, the math face for faces the platform does not have
+    fn get_typeface_ruler(&self, typeface: &TypeFace, size: f32) -> &TextRuler;
+
+    fn math_typeface(&self) -> &TypeFace;
+    fn text_typeface(&self) -> &TypeFace;
+    /// Bold or italic face of the text font, none when the style has to be synthesized
+    fn styled_text_typeface(&self, bold: bool, italic: bool) -> Option<&TypeFace>;
+    fn fallback_typefaces(&self) -> &[TypeFace];
+    fn typeface_has_glyph(&self, typeface: &TypeFace, unicode: u32) -> bool;
+
     fn px_to_du(&self, px: f32) -> f32;
     fn sp_to_du(&self, sp: f32) -> f32;
     fn dp_to_du(&self, dp: f32) -> f32;
     fn as_any(&self) -> &Any;
+
+    fn has_glyph(&self, unicode: u32) -> bool {
+        self.typeface_has_glyph(self.math_typeface(), unicode)
+    }
+
+    /// Face drawing `unicode`, which is `preferred` if it has the glyph and otherwise the first
+    /// face having it among the math face, the text face and the fallbacks
+    fn typeface_for_char(&self, preferred: &TypeFace, unicode: u32) -> Option<TypeFace> {
+        if self.typeface_has_glyph(preferred, unicode) {
+            return Some(preferred.clone());
+        }
+
+        let stack = [self.math_typeface(), self.text_typeface()];
+        stack.iter().cloned().chain(self.fallback_typefaces().iter())
+            .find(|typeface| self.typeface_has_glyph(typeface, unicode))
+            .cloned()
+    }
 }
 
 #[cfg(test)] pub mod test;
\ No newline at end of file
@@ -1,7 +1,7 @@
 use std::any::Any;
 
 use ::platform::{Platform, Context};
-use ::paint::{TextRuler, MathRuler};
+use ::paint::{TextRuler, MathRuler, TypeFace};
 
 pub struct MockPlatform {}
 
@@ -14,6 +14,30 @@ impl Platform for MockPlatform {
         unimplemented!()
     }
 
+    fn get_typeface_ruler(&self, _: &TypeFace, _: f32) -> &TextRuler {
+        unimplemented!()
+    }
+
+    fn math_typeface(&self) -> &TypeFace {
+        unimplemented!()
+    }
+
+    fn text_typeface(&self) -> &TypeFace {
+        unimplemented!()
+    }
+
+    fn styled_text_typeface(&self, _: bool, _: bool) -> Option<&TypeFace> {
+        unimplemented!()
+    }
+
+    fn fallback_typefaces(&self) -> &[TypeFace] {
+        unimplemented!()
+    }
+
+    fn typeface_has_glyph(&self, _: &TypeFace, _: u32) -> bool {
+        unimplemented!()
+    }
+
     fn px_to_du(&self, _: f32) -> f32 {
         unimplemented!()
     }
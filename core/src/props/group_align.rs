@@ -15,7 +15,7 @@
 */
 
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum GroupAlign {
     Left,
     Right,
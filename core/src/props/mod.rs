@@ -38,5 +38,8 @@ mod valign;                 pub use self::valign::*;
 mod table_side;             pub use self::table_side::*;
 
 pub type DisplayStyle = bool;
+/// Style of denominators, subscripts, radicands and underscripts, which shifts superscripts less
+pub type CrampedStyle = bool;
 pub type ScriptMinSize = f32;
-pub type ScriptSizeMultiplier = f32;
+/// Multiplier of font size per script level, none when the scale down of the math font is used
+pub type ScriptSizeMultiplier = Option<f32>;
@@ -17,8 +17,12 @@
 
 use super::{PropertyModifier, ScriptSizeMultiplier, ScriptMinSize, MathSize};
 use ::platform::Context;
+use ::paint::MathRuler;
 
-#[derive(Debug, Clone)]
+/// Multiplier for levels past those scaled down by the math font
+pub const DEFAULT_SCRIPT_SIZE_MULTIPLIER: f32 = 0.707;
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct ScriptLevel {
     level: u32,
     current_font_size: f32,
@@ -40,8 +44,14 @@ impl ScriptLevel {
 
         let diff = new_level - (self.level as i32);
         let current_size = self.get_font_size(context, math_size);
-        let new_size =
-            (current_size * script_size_multiplier.powi(diff)).max(script_min_size);
+        let scale = match script_size_multiplier {
+            Some(multiplier) => multiplier.powi(diff),
+            None => {
+                let ruler = context.platform().get_math_ruler(current_size);
+                level_scale(ruler, new_level as u32) / level_scale(ruler, self.level)
+            }
+        };
+        let new_size = (current_size * scale).max(script_min_size);
 
         ScriptLevel {
             level: new_level as u32,
@@ -56,4 +66,67 @@ impl ScriptLevel {
     pub fn get_font_size(&self, context: &Context, math_size: &MathSize) -> f32 {
         math_size.get_math_size_du(context, self.current_font_size)
     }
-}
\ No newline at end of file
+}
+
+// Font size at `level` relative to level zero. Levels one and two use the scale down percents of
+// the math font and deeper levels the default multiplier.
+fn level_scale(ruler: &MathRuler, level: u32) -> f32 {
+    let script = scale_down(ruler.script_percent_scale_down(), DEFAULT_SCRIPT_SIZE_MULTIPLIER);
+    let script_script = scale_down(ruler.script_script_percent_scale_down(),
+                                   script * DEFAULT_SCRIPT_SIZE_MULTIPLIER);
+
+    match level {
+        0 => 1.,
+        1 => script,
+        _ => script_script * DEFAULT_SCRIPT_SIZE_MULTIPLIER.powi(level as i32 - 2),
+    }
+}
+
+// Percent constants left as zero by the font take the multiplier
+fn scale_down(percent: f32, fallback: f32) -> f32 {
+    if percent > 0. {
+        percent / 100.
+    } else {
+        fallback
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::backend::raster::RasterPlatform;
+    use ::font::test::stix_math;
+
+    fn font_size(context: &Context, level: &ScriptLevel, increment: i32,
+                 multiplier: ScriptSizeMultiplier, min_size: f32) -> f32 {
+        level.new_level(PropertyModifier::Increment(increment), context, &MathSize::NORMAL,
+                        multiplier, min_size)
+            .get_font_size(context, &MathSize::NORMAL)
+    }
+
+    fn assert_near(value: f32, expected: f32) {
+        assert!((value - expected).abs() < 1e-3, "{} is not {}", value, expected);
+    }
+
+    #[test]
+    fn it_scales_down_with_math_font_percents() {
+        let context = Context::new(Box::new(RasterPlatform::new(stix_math())), 20.);
+        let ruler = context.platform().get_math_ruler(20.);
+        let script = ruler.script_percent_scale_down() / 100.;
+        let script_script = ruler.script_script_percent_scale_down() / 100.;
+        let base = ScriptLevel::new(0, 20.);
+
+        assert_near(font_size(&context, &base, 1, None, 0.), 20. * script);
+        assert_near(font_size(&context, &base, 2, None, 0.), 20. * script_script);
+        assert_near(font_size(&context, &base, 3, None, 0.),
+                    20. * script_script * DEFAULT_SCRIPT_SIZE_MULTIPLIER);
+
+        let script_level = base.new_level(PropertyModifier::Increment(1), &context,
+                                          &MathSize::NORMAL, None, 0.);
+        assert_near(font_size(&context, &script_level, 1, None, 0.), 20. * script_script);
+        assert_near(font_size(&context, &script_level, -1, None, 0.), 20.);
+
+        assert_near(font_size(&context, &base, 2, Some(0.5), 0.), 5.);
+        assert_near(font_size(&context, &base, 2, None, 16.), 16.);
+    }
+}
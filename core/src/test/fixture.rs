@@ -0,0 +1,38 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+
+use ::elements::{Element, Family, InheritedProps};
+use ::layout::Layout;
+use ::draw::Drawable;
+use ::platform::Context;
+use ::backend::raster::RasterPlatform;
+use ::font::test::stix_math;
+
+/// Context laying out with the bundled STIX math font at 32 pixels
+pub fn stix_context() -> Context {
+    Context::new(Box::new(RasterPlatform::new(stix_math())), 32.)
+}
+
+/// Layout of `element` as the root of a tree
+pub fn layout_root(element: &Element, context: &Context) -> Box<Layout> {
+    element.layout(context, &Family::new(), &InheritedProps::default(), &None)
+}
+
+/// Drawable measured for `element` laid out as the root of a tree
+pub fn draw_root(element: &Element, context: &Context) -> Box<Drawable> {
+    layout_root(element, context).layout(context)
+}
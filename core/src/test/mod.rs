@@ -15,4 +15,5 @@
 */
 
 
-pub mod skia;
\ No newline at end of file
+pub mod snapshot;
+pub mod fixture;
\ No newline at end of file
@@ -0,0 +1,90 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+
+use std::path::Path;
+
+use ::paint::Point;
+use ::layout::Layout;
+use ::elements::{Element, InheritedProps, Family, InstanceId};
+use ::platform::Context;
+use ::draw::{Drawable, MeasureMode};
+use ::backend::raster::RasterPlatform;
+use ::font::OpenTypeFont;
+
+/// Renders elements with the bundled math font and writes PNG files to `target`
+pub struct Snapshot {
+    context: Context,
+}
+
+impl Snapshot {
+    pub fn new(font: &str, font_size: f32) -> Snapshot {
+        Snapshot::with_font(OpenTypeFont::from_file(Path::new(font)).expect("Cannot load font"),
+                            font_size)
+    }
+
+    pub fn with_font(font: OpenTypeFont, font_size: f32) -> Snapshot {
+        Snapshot {
+            context: Context::new(Box::new(RasterPlatform::new(font)), font_size),
+        }
+    }
+
+    pub fn snap_element(&self, element: &Element, name: &str) {
+        let inherited_props = InheritedProps::default();
+        let layout = element.layout(&self.context, &Family::new(), &inherited_props, &None);
+        self.snap_layout(layout.as_ref(), name);
+    }
+
+    /// Positions of the elements with `ids` in laid out `element` drawn at origin. Panics if
+    /// an element is not found.
+    pub fn positions(&self, element: &Element, ids: &[&InstanceId]) -> Vec<Point> {
+        let inherited_props = InheritedProps::default();
+        let layout = element.layout(&self.context, &Family::new(), &inherited_props, &None);
+        let drawable = layout.layout(&self.context);
+
+        ids.iter()
+            .map(|id| drawable.locate(&Point::new(0., 0.), id).expect("Element is not laid out").0)
+            .collect()
+    }
+
+    pub fn snap_layout(&self, layout: &Layout, name: &str) {
+        let mut drawable = layout.layout(&self.context);
+        self.snap(drawable.as_mut(), name);
+    }
+
+    pub fn snap_drawable(&self, drawable: &mut Drawable, width_mode: &MeasureMode,
+                         height_mode: &MeasureMode, name: &str) {
+        drawable.calculate(&self.context, width_mode, height_mode);
+        self.snap(drawable, name);
+    }
+
+    fn snap(&self, drawable: &Drawable, name: &str) {
+        let platform = self.context.platform().as_any().downcast_ref::<RasterPlatform>().unwrap();
+        let canvas = platform.new_canvas(drawable.bounding_box().width(),
+                                         drawable.bounding_box().height());
+
+        drawable.draw(&canvas, &Point::new(0., 0.));
+
+        canvas.write_png(Path::new(env!("CARGO_MANIFEST_DIR")).join("target")
+            .join(format!("{}.png", name)).as_ref()).expect("Cannot snap");
+    }
+}
+
+impl Default for Snapshot {
+    fn default() -> Snapshot {
+        Snapshot::new(concat!(env!("CARGO_MANIFEST_DIR"), "/src/test/assets/STIX2Math.otf"), 64.)
+    }
+}
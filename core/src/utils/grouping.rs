@@ -0,0 +1,518 @@
+/*
+ * Copyright 2017 Sreejith Krishnan R
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+*/
+
+use ::elements::{Element, Mrow, Mo, Token, Mmultiscripts, Munderover, Msub, Msup, Msubsup, Munder};
+use ::constants::{OperatorDictionary, OperatorForm, Operator};
+use super::is_space_like;
+
+// Priority of invisible times (U+2062). Used to group adjacent operands which are not
+// separated by an operator, like 2x
+const JUXTAPOSITION_PRIORITY: u32 = 390;
+
+enum Lexeme {
+    Operand,
+    Operator { text: String, form: Option<OperatorForm>, fence: Option<bool> },
+}
+
+struct Item {
+    index: usize,
+    lexeme: Lexeme,
+    // Space like elements that follow this item. They are kept with the item while grouping.
+    trailing: Vec<usize>,
+}
+
+enum Node {
+    Leaf(usize),
+    Group(Vec<Node>),
+    Empty,
+}
+
+enum OperatorRole {
+    Prefix,
+    OpeningFence,
+    ClosingFence,
+    Infix(u32),
+    Postfix(u32),
+    // Operator which can only start an expression, placed after an operand. Operands are
+    // grouped as if an invisible times is present between them.
+    Juxtaposition,
+}
+
+struct Parser<'a> {
+    items: &'a Vec<Item>,
+    dictionary: &'a OperatorDictionary,
+    pos: usize,
+}
+
+/// Inferred row structure of a list of elements, see [`infer_groups`](fn.infer_groups.html)
+#[derive(Debug, PartialEq, Clone)]
+pub enum Grouping {
+    /// Element at the index in the grouped list
+    Child(usize),
+    /// Inferred row of groups
+    Row(Vec<Grouping>),
+}
+
+/// Groups a flat list of elements into nested `Mrow`s according to operator priority and form.
+///
+/// Follows the inferred structure recommended in section 3.3.1.3.1 of MathML3 spec. Operators of
+/// same priority are kept in the same row, prefix operators are grouped with their operand,
+/// postfix operators with the preceding operand and fences with their contents. Child `Mrow`s are
+/// grouped recursively.
+pub fn group_by_precedence(elements: Vec<Box<Element>>, dictionary: &OperatorDictionary) -> Vec<Box<Element>> {
+    let mut elements = elements;
+
+    for element in elements.iter_mut() {
+        if element.type_info().is_mrow() {
+            element.as_any_mut().downcast_mut::<Mrow>().unwrap().infer_grouping(dictionary);
+        }
+    }
+
+    let groups = {
+        let children: Vec<&Element> = elements.iter().map(|element| element.as_ref()).collect();
+        infer_groups(&children, dictionary)
+    };
+
+    let mut slots: Vec<Option<Box<Element>>> = elements.into_iter().map(Some).collect();
+    let mut result = Vec::new();
+
+    for grouping in groups {
+        emit(grouping, &mut slots, &mut result);
+    }
+
+    result
+}
+
+/// Infers the row structure of `elements` without changing them.
+///
+/// Uses the same rules as [`group_by_precedence`](fn.group_by_precedence.html), but only
+/// describes the resulting rows with indices into `elements`. Child `Mrow`s are treated as
+/// operands and are not looked into.
+pub fn infer_groups(elements: &[&Element], dictionary: &OperatorDictionary) -> Vec<Grouping> {
+    let mut leading: Vec<usize> = Vec::new();
+    let mut items: Vec<Item> = Vec::new();
+
+    for (index, element) in elements.iter().enumerate() {
+        if is_space_like(*element) {
+            if let Some(item) = items.last_mut() {
+                item.trailing.push(index);
+            } else {
+                leading.push(index);
+            }
+            continue;
+        }
+
+        let lexeme = match get_core_operator(*element) {
+            Some(mo) => Lexeme::Operator {
+                text: String::from(mo.get_text()),
+                form: mo.get_form().cloned(),
+                fence: mo.get_fence().cloned(),
+            },
+            None => Lexeme::Operand,
+        };
+
+        items.push(Item { index, lexeme, trailing: Vec::new() });
+    }
+
+    let root = {
+        let mut parser = Parser { items: &items, dictionary, pos: 0 };
+        let mut nodes = Vec::new();
+
+        while parser.pos < items.len() {
+            nodes.push(parser.parse_expression(0, false));
+        }
+
+        group(nodes)
+    };
+
+    let mut result: Vec<Grouping> = leading.into_iter().map(Grouping::Child).collect();
+
+    match root {
+        Node::Group(children) => for child in children {
+            collect(child, &items, &mut result);
+        },
+        node => collect(node, &items, &mut result),
+    }
+
+    result
+}
+
+fn collect(node: Node, items: &[Item], output: &mut Vec<Grouping>) {
+    match node {
+        Node::Leaf(index) => {
+            let item = &items[index];
+            output.push(Grouping::Child(item.index));
+            output.extend(item.trailing.iter().map(|index| Grouping::Child(*index)));
+        },
+        Node::Group(children) => {
+            let mut row = Vec::new();
+
+            for child in children {
+                collect(child, items, &mut row);
+            }
+
+            output.push(Grouping::Row(row));
+        },
+        Node::Empty => {}
+    }
+}
+
+fn emit(grouping: Grouping, slots: &mut Vec<Option<Box<Element>>>, output: &mut Vec<Box<Element>>) {
+    match grouping {
+        Grouping::Child(index) => {
+            output.push(slots[index].take().expect("Element is already grouped"));
+        },
+        Grouping::Row(children) => {
+            let mut row = Mrow::new();
+            let mut row_children = Vec::new();
+
+            for child in children {
+                emit(child, slots, &mut row_children);
+            }
+
+            for child in row_children {
+                row.with_child(child);
+            }
+
+            output.push(Box::new(row));
+        },
+    }
+}
+
+// Creates a group from nodes discarding missing operands. A group with single node is
+// replaced by the node itself so that no redundant rows are created.
+fn group(nodes: Vec<Node>) -> Node {
+    let mut nodes: Vec<Node> = nodes.into_iter()
+        .filter(|node| if let Node::Empty = *node { false } else { true })
+        .collect();
+
+    match nodes.len() {
+        0 => Node::Empty,
+        1 => nodes.pop().unwrap(),
+        _ => Node::Group(nodes),
+    }
+}
+
+// Embellished operator as defined in section 3.2.5.7.1 of MathML3 spec, limited to the
+// elements for which the core operator can be found without layout.
+fn get_core_operator(element: &Element) -> Option<&Mo> {
+    let any = element.as_any();
+
+    if let Some(mo) = any.downcast_ref::<Mo>() {
+        return Some(mo);
+    }
+
+    if let Some(mrow) = any.downcast_ref::<Mrow>() {
+        let mrow: &Mrow = mrow;
+        let mut non_space_like = mrow.children().iter().filter(|c| !is_space_like(c.as_ref()));
+
+        return match (non_space_like.next(), non_space_like.next()) {
+            (Some(child), None) => get_core_operator(child.as_ref()),
+            _ => None
+        };
+    }
+
+    let base = if let Some(scripts) = any.downcast_ref::<Mmultiscripts>() {
+        scripts.base()
+    } else if let Some(underover) = any.downcast_ref::<Munderover>() {
+        underover.base()
+    } else if let Some(msub) = any.downcast_ref::<Msub>() {
+        msub.base()
+    } else if let Some(msup) = any.downcast_ref::<Msup>() {
+        msup.base()
+    } else if let Some(msubsup) = any.downcast_ref::<Msubsup>() {
+        msubsup.base()
+    } else if let Some(munder) = any.downcast_ref::<Munder>() {
+        munder.base()
+    } else {
+        return None;
+    };
+
+    get_core_operator(base.as_ref())
+}
+
+impl<'a> Parser<'a> {
+    fn parse_expression(&mut self, min_priority: u32, in_fence: bool) -> Node {
+        let mut left = self.parse_operand(in_fence);
+
+        while self.pos < self.items.len() {
+            let role = self.operator_role(in_fence);
+
+            let priority = match role {
+                OperatorRole::ClosingFence => break,
+                OperatorRole::Postfix(priority) => {
+                    if priority < min_priority {
+                        break;
+                    }
+
+                    let operator = Node::Leaf(self.pos);
+                    self.pos += 1;
+                    left = group(vec![left, operator]);
+                    continue;
+                },
+                OperatorRole::Infix(priority) => priority,
+                OperatorRole::Juxtaposition => JUXTAPOSITION_PRIORITY,
+                OperatorRole::Prefix | OperatorRole::OpeningFence => unreachable!(),
+            };
+
+            if priority < min_priority {
+                break;
+            }
+
+            // Operators with same priority are kept in the same row, ie a+b-c is a single row
+            let mut operands = vec![left];
+            while self.pos < self.items.len() {
+                match self.operator_role(in_fence) {
+                    OperatorRole::Infix(p) if p == priority => {
+                        operands.push(Node::Leaf(self.pos));
+                        self.pos += 1;
+                    },
+                    OperatorRole::Juxtaposition if priority == JUXTAPOSITION_PRIORITY => {},
+                    _ => break,
+                }
+
+                operands.push(self.parse_expression(priority+1, in_fence));
+            }
+
+            left = group(operands);
+        }
+
+        left
+    }
+
+    fn parse_operand(&mut self, in_fence: bool) -> Node {
+        if self.pos >= self.items.len() {
+            return Node::Empty;
+        }
+
+        let (text, form, fence) = match self.items[self.pos].lexeme {
+            Lexeme::Operand => {
+                self.pos += 1;
+                return Node::Leaf(self.pos-1);
+            },
+            Lexeme::Operator { ref text, ref form, ref fence } => (text, form, fence),
+        };
+
+        let forms = self.dictionary.operator_forms(text);
+        let has_form = |f: &OperatorForm| forms.map(|forms| forms.contains_key(f)).unwrap_or(false);
+
+        // Closing fence of an empty fenced expression, like ()
+        if in_fence && form.is_none() && !has_form(&OperatorForm::Prefix) &&
+            self.is_fence(text, &OperatorForm::Postfix, fence) {
+            return Node::Empty;
+        }
+
+        let form = form.clone().unwrap_or(OperatorForm::Prefix);
+
+        if form == OperatorForm::Prefix && self.is_fence(text, &form, fence) {
+            let open = Node::Leaf(self.pos);
+            self.pos += 1;
+
+            let content = self.parse_expression(0, true);
+
+            let close = if self.pos < self.items.len() {
+                self.pos += 1;
+                Node::Leaf(self.pos-1)
+            } else {
+                Node::Empty
+            };
+
+            return group(vec![open, content, close]);
+        }
+
+        let priority = self.priority(text, &form);
+        let operator = Node::Leaf(self.pos);
+        self.pos += 1;
+
+        let operand = self.parse_expression(priority+1, in_fence);
+
+        group(vec![operator, operand])
+    }
+
+    fn operator_role(&self, in_fence: bool) -> OperatorRole {
+        let (text, form, fence) = match self.items[self.pos].lexeme {
+            Lexeme::Operand => return OperatorRole::Juxtaposition,
+            Lexeme::Operator { ref text, ref form, ref fence } => (text, form, fence),
+        };
+
+        let form = match *form {
+            Some(ref form) => form.clone(),
+            None => {
+                let forms = self.dictionary.operator_forms(text);
+                let has_form = |f: &OperatorForm| forms.map(|forms| forms.contains_key(f)).unwrap_or(false);
+
+                if in_fence && has_form(&OperatorForm::Postfix) &&
+                    self.is_fence(text, &OperatorForm::Postfix, fence) {
+                    OperatorForm::Postfix
+                } else if has_form(&OperatorForm::Infix) || forms.is_none() {
+                    OperatorForm::Infix
+                } else if has_form(&OperatorForm::Postfix) {
+                    OperatorForm::Postfix
+                } else {
+                    return OperatorRole::Juxtaposition;
+                }
+            }
+        };
+
+        match form {
+            OperatorForm::Infix => OperatorRole::Infix(self.priority(text, &form)),
+            OperatorForm::Prefix => if self.is_fence(text, &form, fence) {
+                OperatorRole::OpeningFence
+            } else {
+                OperatorRole::Prefix
+            },
+            OperatorForm::Postfix => if in_fence && self.is_fence(text, &form, fence) {
+                OperatorRole::ClosingFence
+            } else {
+                OperatorRole::Postfix(self.priority(text, &form))
+            },
+        }.into_operator_position()
+    }
+
+    fn attrs(&self, text: &str, form: &OperatorForm) -> Operator {
+        self.dictionary.operator_attrs_approx(text, form).cloned().unwrap_or(Operator::default())
+    }
+
+    fn priority(&self, text: &str, form: &OperatorForm) -> u32 {
+        self.attrs(text, form).priority()
+    }
+
+    fn is_fence(&self, text: &str, form: &OperatorForm, specified: &Option<bool>) -> bool {
+        specified.unwrap_or_else(|| self.attrs(text, form).properties().fence())
+    }
+}
+
+impl OperatorRole {
+    // Prefix operators after an operand are separated from it by an implicit invisible times
+    fn into_operator_position(self) -> OperatorRole {
+        match self {
+            OperatorRole::Prefix | OperatorRole::OpeningFence => OperatorRole::Juxtaposition,
+            role => role,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::elements::{Mi, Mn, Mtext};
+
+    fn mi(text: &str) -> Box<Element> {
+        Box::new(Mi::new(String::from(text)))
+    }
+
+    fn mn(text: &str) -> Box<Element> {
+        Box::new(Mn::new(String::from(text)))
+    }
+
+    fn mo(text: &str) -> Box<Element> {
+        Box::new(Mo::new(String::from(text)))
+    }
+
+    // Serializes element tree, using brackets for rows
+    fn structure(element: &Element) -> String {
+        if let Some(mrow) = element.as_any().downcast_ref::<Mrow>() {
+            let mrow: &Mrow = mrow;
+            return format!("[{}]", mrow.children().iter()
+                .map(|c| structure(c.as_ref()))
+                .collect::<Vec<String>>()
+                .join(" "));
+        }
+
+        if let Some(mo) = element.as_any().downcast_ref::<Mo>() {
+            return String::from(mo.get_text());
+        }
+
+        if let Some(mi) = element.as_any().downcast_ref::<Mi>() {
+            return String::from(mi.get_text());
+        }
+
+        if let Some(mn) = element.as_any().downcast_ref::<Mn>() {
+            return String::from(mn.get_text());
+        }
+
+        String::from("?")
+    }
+
+    fn grouped(elements: Vec<Box<Element>>) -> String {
+        let mut row = Mrow::new();
+        for element in elements {
+            row.with_child(element);
+        }
+        row.infer_grouping(&OperatorDictionary::new());
+        structure(&row)
+    }
+
+    #[test]
+    fn it_groups_by_priority() {
+        assert_eq!(grouped(vec![mi("a"), mo("+"), mi("b"), mo("\u{d7}"), mi("c")]), "[a + [b \u{d7} c]]");
+        assert_eq!(grouped(vec![mi("a"), mo("\u{d7}"), mi("b"), mo("+"), mi("c")]), "[[a \u{d7} b] + c]");
+        assert_eq!(grouped(vec![mi("x"), mo("="), mi("a"), mo("+"), mi("b")]), "[x = [a + b]]");
+    }
+
+    #[test]
+    fn it_keeps_same_priority_in_one_row() {
+        assert_eq!(grouped(vec![mi("a"), mo("+"), mi("b"), mo("-"), mi("c")]), "[a + b - c]");
+        assert_eq!(grouped(vec![mi("a"), mo("+"), mi("b"), mo("="), mi("c"), mo("-"), mi("d")]),
+                   "[[a + b] = [c - d]]");
+    }
+
+    #[test]
+    fn it_groups_prefix_and_postfix_operators() {
+        assert_eq!(grouped(vec![mo("-"), mi("x"), mo("+"), mi("y")]), "[[- x] + y]");
+        assert_eq!(grouped(vec![mi("n"), mo("!"), mo("+"), mn("1")]), "[[n !] + 1]");
+        assert_eq!(grouped(vec![mo("-"), mo("-"), mi("x")]), "[- [- x]]");
+    }
+
+    #[test]
+    fn it_groups_fences() {
+        assert_eq!(grouped(vec![mo("("), mi("a"), mo("+"), mi("b"), mo(")"), mo("\u{d7}"), mi("c")]),
+                   "[[( [a + b] )] \u{d7} c]");
+        assert_eq!(grouped(vec![mo("("), mo(")")]), "[( )]");
+        assert_eq!(grouped(vec![mo("|"), mi("x"), mo("|")]), "[| x |]");
+        assert_eq!(grouped(vec![mo("("), mi("a"), mo(","), mi("b")]), "[( [a , b]]");
+    }
+
+    #[test]
+    fn it_groups_juxtaposed_operands() {
+        assert_eq!(grouped(vec![mn("2"), mi("x"), mo("+"), mn("1")]), "[[2 x] + 1]");
+        assert_eq!(grouped(vec![mi("f"), mo("("), mi("x"), mo(")")]), "[f [( x )]]");
+    }
+
+    #[test]
+    fn it_groups_nested_rows() {
+        let mut inner = Mrow::new();
+        inner.with_child(mi("a"))
+            .with_child(mo("+"))
+            .with_child(mi("b"))
+            .with_child(mo("\u{d7}"))
+            .with_child(mi("c"));
+
+        assert_eq!(grouped(vec![Box::new(inner), mo("="), mi("d")]), "[[a + [b \u{d7} c]] = d]");
+    }
+
+    #[test]
+    fn it_infers_groups_without_moving_elements() {
+        let elements = [mi("a"), mo("+"), mo("-"), mi("b"), Box::new(Mtext::new(String::from(" ")))];
+        let children: Vec<&Element> = elements.iter().map(|element| element.as_ref()).collect();
+
+        assert_eq!(infer_groups(&children, &OperatorDictionary::new()), vec![
+            Grouping::Child(0),
+            Grouping::Child(1),
+            Grouping::Row(vec![Grouping::Child(2), Grouping::Child(3), Grouping::Child(4)]),
+        ]);
+    }
+}
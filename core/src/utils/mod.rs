@@ -14,6 +14,7 @@
  * limitations under the License.
 */
 
+mod grouping;               pub use self::grouping::*;
 
 use ::elements::{Element, Mrow, Family, Mphantom, Mmultiscripts, Mo, Munderover};
 use ::layout::{ElementGroup, Layout, MrowLayout, MoLayout, MmultiscriptLayout, MunderoverLayout, MfracLayout,
@@ -41,7 +42,7 @@ pub fn is_space_like(element: &Element) -> bool {
     }
 
     if element_type.is_mpadded() {
-        unimplemented!();
+        return element.child_elements().iter().all(|child| is_space_like(*child));
     }
 
 
@@ -82,12 +83,16 @@ pub fn get_enclosing_embellished_operator<'a>(family: &'a Family<'a>, base_op: &
     // or one of the elements mstyle, mphantom, or mpadded, such that an mrow containing the
     // same arguments would be an embellished operator
     let is_embellished_operator = if parent_type.is_mrow() {
-        let mrow: &Mrow = parent.as_any().downcast_ref::<Mrow>().unwrap();
-        let children = mrow.children();
-        let num_space_like = children.iter().fold(
-            0, |acc, child| if is_space_like(child.as_ref()) { acc+1 } else { acc });
+        if let Some(position) = family.position() {
+            position.non_space_like == 1
+        } else {
+            let mrow: &Mrow = parent.as_any().downcast_ref::<Mrow>().unwrap();
+            let children = mrow.children();
+            let num_space_like = children.iter().fold(
+                0, |acc, child| if is_space_like(child.as_ref()) { acc+1 } else { acc });
 
-        children.len()-num_space_like == 1
+            children.len()-num_space_like == 1
+        }
     } else if parent_type.is_scrips_or_limits() {
         if let Some(scripts) = parent.as_any().downcast_ref::<Mmultiscripts>() {
             let scripts: &Mmultiscripts = scripts;